@@ -0,0 +1,74 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use num_bigint::BigInt;
+use zk_starter_kit::bench::Workload;
+use zk_starter_kit::field::FieldElement;
+
+/// The synthetic circuits benchmarked through witness verification, proving and verification.
+const WORKLOADS: [Workload; 6] = [
+    Workload::MulChain(100),
+    Workload::MulChain(1000),
+    Workload::MerklePath(4),
+    Workload::MerklePath(8),
+    Workload::PoseidonChain(1),
+    Workload::PoseidonChain(4),
+];
+
+/// Benchmarks the field operations every other phase is built on.
+fn field_operations(c: &mut Criterion) {
+    let a = FieldElement::new(BigInt::from(123_456_789));
+    let b = FieldElement::new(BigInt::from(987_654_321));
+    let exponent = BigInt::from(1_000_000_005);
+    let mut group = c.benchmark_group("field");
+    group.bench_function("add", |bench| bench.iter(|| black_box(&a).add(black_box(&b))));
+    group.bench_function("mul", |bench| bench.iter(|| black_box(&a).mul(black_box(&b))));
+    group.bench_function("pow", |bench| bench.iter(|| black_box(&a).pow(black_box(&exponent))));
+    group.bench_function("inv", |bench| bench.iter(|| black_box(&a).inv()));
+    group.finish();
+}
+
+/// Benchmarks building each circuit, which assigns every wire.
+fn witness_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("witness_generation");
+    for workload in &WORKLOADS {
+        group.bench_with_input(BenchmarkId::from_parameter(workload), workload, |bench, workload| {
+            bench.iter(|| workload.build())
+        });
+    }
+    group.finish();
+}
+
+/// Benchmarks checking a witness against the constraint system.
+fn witness_verification(c: &mut Criterion) {
+    let mut group = c.benchmark_group("witness_verification");
+    for workload in &WORKLOADS {
+        let r1cs = workload.build().to_r1cs();
+        let witness = r1cs.generate_witness();
+        group.bench_with_input(BenchmarkId::from_parameter(workload), &witness, |bench, witness| {
+            bench.iter(|| assert!(r1cs.verify_witness(witness)))
+        });
+    }
+    group.finish();
+}
+
+/// Benchmarks proving and verifying each circuit.
+fn proving(c: &mut Criterion) {
+    let mut group = c.benchmark_group("proving");
+    // Proving the larger circuits takes seconds, so take fewer samples
+    group.sample_size(10);
+    for workload in &WORKLOADS {
+        let circuit = workload.build();
+        let r1cs = circuit.to_r1cs();
+        let witness = r1cs.generate_witness();
+        group.bench_with_input(BenchmarkId::new("prove", workload), &witness, |bench, witness| {
+            bench.iter(|| r1cs.generate_proof(witness))
+        });
+        let proof = r1cs.generate_proof(&witness);
+        group.bench_with_input(BenchmarkId::new("verify", workload), &proof, |bench, proof| {
+            bench.iter(|| assert!(circuit.verify(proof)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, field_operations, witness_generation, witness_verification, proving);
+criterion_main!(benches);
@@ -0,0 +1,22 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=proto");
+
+    // Generate the protobuf types with a vendored protoc, so no system install is needed
+    #[cfg(feature = "protobuf")]
+    {
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("No vendored protoc for this platform");
+        std::env::set_var("PROTOC", protoc);
+
+        // The generated client relies on the 2021 prelude, so only the server is built
+        #[cfg(feature = "grpc")]
+        tonic_build::configure()
+            .build_client(false)
+            .compile_protos(&["proto/types.proto", "proto/zk.proto"], &["proto"])
+            .expect("Failed to compile the protobuf definitions");
+
+        #[cfg(not(feature = "grpc"))]
+        prost_build::compile_protos(&["proto/types.proto"], &["proto"])
+            .expect("Failed to compile the protobuf definitions");
+    }
+}
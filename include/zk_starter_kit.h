@@ -0,0 +1,45 @@
+#ifndef ZK_STARTER_KIT_H
+#define ZK_STARTER_KIT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define ZK_OK 0
+#define ZK_ERR_NULL -1
+#define ZK_ERR_UTF8 -2
+#define ZK_ERR_SERIALIZATION -3
+#define ZK_ERR_COMPILE -4
+#define ZK_ERR_INPUT -5
+#define ZK_ERR_IO -6
+#define ZK_ERR_PANIC -7
+#define ZK_ERR_EXTERNAL -8
+#define ZK_ERR_CANCELLED -9
+#define ZK_ERR_VERSION -10
+
+/* A byte buffer owned by the library, released with zk_buffer_free. */
+typedef struct ZkBuffer {
+    uint8_t *data;
+    size_t len;
+} ZkBuffer;
+
+/* Compiles a JSON circuit description. Returns ZK_OK or a negative error code. */
+int32_t zk_compile(const char *circuit_json, ZkBuffer *out);
+
+/* Proves a compiled circuit with a JSON array or object of named inputs. Returns ZK_OK or a negative error code. */
+int32_t zk_prove(const uint8_t *compiled, size_t compiled_len, const char *inputs_json, ZkBuffer *out);
+
+/* Verifies a proof. Returns 1 if valid, 0 if invalid or without a witness to verify, or a negative error code. */
+int32_t zk_verify(const uint8_t *compiled, size_t compiled_len, const uint8_t *proof, size_t proof_len);
+
+/* Releases a buffer returned by zk_compile or zk_prove. */
+void zk_buffer_free(ZkBuffer buffer);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ZK_STARTER_KIT_H */
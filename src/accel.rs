@@ -0,0 +1,51 @@
+use std::sync::OnceLock;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+
+/// The accelerator registered with `register`, if any.
+static ACCELERATOR: OnceLock<Box<dyn Accelerator>> = OnceLock::new();
+
+/// Represents a hardware backend, such as a SIMD, CUDA or Metal implementation,
+/// for the heavy proving kernels.
+///
+/// Every kernel may decline an input it does not support, for instance a
+/// field that does not fit its word size, by returning `None`; the crate then
+/// falls back to its scalar implementation.
+pub trait Accelerator: Send + Sync {
+    /// Gets the name of the backend, for diagnostics.
+    ///
+    /// # Returns
+    /// - `&str`: The name of the backend.
+    fn name(&self) -> &str;
+
+    /// Computes the number-theoretic transform of `values`: their evaluations
+    /// at `1, root, root^2, ...`, in that order.
+    ///
+    /// # Parameters
+    /// - `values`: The coefficients to transform, whose length is a power of two.
+    /// - `root`: A primitive root of unity of order `values.len()`.
+    ///
+    /// # Returns
+    /// - `Option<Vec<FieldElement>>`: The evaluations, or `None` to fall back to the scalar kernel.
+    fn ntt(&self, values: &[FieldElement], root: &FieldElement) -> Option<Vec<FieldElement>>;
+}
+
+/// Registers the accelerator used by the kernels for the rest of the process.
+///
+/// # Parameters
+/// - `accelerator`: The backend to use.
+///
+/// # Returns
+/// - `Result<()>`: An error if an accelerator was already registered.
+pub fn register(accelerator: Box<dyn Accelerator>) -> Result<()> {
+    ACCELERATOR.set(accelerator)
+        .map_err(|rejected| Error::External(format!("Cannot register {}: an accelerator is already registered", rejected.name())))
+}
+
+/// Gets the registered accelerator.
+///
+/// # Returns
+/// - `Option<&'static dyn Accelerator>`: The accelerator, or `None` if the kernels run on the scalar code.
+pub fn accelerator() -> Option<&'static dyn Accelerator> {
+    ACCELERATOR.get().map(|accelerator| accelerator.as_ref())
+}
@@ -0,0 +1,314 @@
+use std::collections::BTreeMap;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{Num, One, Zero};
+use serde::Deserialize;
+use serde_json::Value;
+use crate::circom::{CircomCircuit, CircomConstraint, LinearCombination};
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+
+/// The BN254 scalar field prime, over which Noir programs are compiled.
+const BN254_PRIME: &str = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+/// Represents an ACIR expression `Σ q·a·b + Σ q·w + q_c`.
+#[derive(Clone, Debug)]
+pub struct Expression {
+    /// The quadratic terms `(q, a, b)`.
+    pub mul_terms: Vec<(BigInt, u32, u32)>,
+    /// The linear terms `(q, w)`.
+    pub linear_combinations: Vec<(BigInt, u32)>,
+    /// The constant term.
+    pub q_c: BigInt,
+}
+
+/// Represents the ACIR opcodes this reader can lower.
+#[derive(Clone, Debug)]
+pub enum AcirOpcode {
+    /// Asserts that the expression evaluates to zero.
+    AssertZero(Expression),
+    /// Asserts that a witness fits in the given number of bits.
+    Range {
+        /// The witness being range checked.
+        witness: u32,
+        /// The number of bits.
+        num_bits: u32,
+    },
+}
+
+/// Represents an ACIR circuit, such as the `main` function of a compiled Noir program.
+#[derive(Clone, Debug)]
+pub struct AcirCircuit {
+    /// The highest witness index used by the circuit.
+    pub current_witness_index: u32,
+    /// The opcodes of the circuit.
+    pub opcodes: Vec<AcirOpcode>,
+    /// The private parameters of the circuit.
+    pub private_parameters: Vec<u32>,
+    /// The public parameters of the circuit.
+    pub public_parameters: Vec<u32>,
+    /// The witnesses returned by the circuit, which are public.
+    pub return_values: Vec<u32>,
+}
+
+/// Mirrors the serde layout of an ACIR circuit, leaving the opcodes to be matched by hand.
+#[derive(Deserialize)]
+struct RawCircuit {
+    current_witness_index: u32,
+    opcodes: Vec<Value>,
+    #[serde(default)]
+    private_parameters: Vec<u32>,
+    #[serde(default)]
+    public_parameters: Vec<u32>,
+    #[serde(default)]
+    return_values: Vec<u32>,
+}
+
+/// Mirrors the serde layout of an ACIR expression, with hexadecimal field elements.
+#[derive(Deserialize)]
+struct RawExpression {
+    mul_terms: Vec<(String, u32, u32)>,
+    linear_combinations: Vec<(String, u32)>,
+    q_c: String,
+}
+
+impl AcirCircuit {
+    /// Reads an ACIR circuit from the JSON serialization of ACIR's `Program` or `Circuit`.
+    ///
+    /// For a program, the first function is taken as `main`. Only `AssertZero`
+    /// (formerly `Arithmetic`) opcodes and `RANGE` black box calls are supported.
+    ///
+    /// # Parameters
+    /// - `json`: The serialized program or circuit.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The circuit, or an error naming the first unsupported opcode.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let mut value: Value = serde_json::from_str(json)?;
+        if let Some(functions) = value.get_mut("functions").and_then(Value::as_array_mut) {
+            if functions.is_empty() {
+                return Err(Error::Input("The ACIR program has no functions".to_string()));
+            }
+            value = functions.swap_remove(0);
+        }
+        let raw: RawCircuit = serde_json::from_value(value)?;
+
+        Ok(AcirCircuit {
+            current_witness_index: raw.current_witness_index,
+            opcodes: raw.opcodes.iter().map(parse_opcode).collect::<Result<Vec<_>>>()?,
+            private_parameters: raw.private_parameters,
+            public_parameters: raw.public_parameters,
+            return_values: raw.return_values,
+        })
+    }
+
+    /// Lowers the circuit into `A * B = C` constraints over the BN254 scalar field.
+    ///
+    /// Wire 0 is the constant one, followed by the return values as public
+    /// outputs, the public parameters as public inputs and the remaining
+    /// witnesses in index order. Expressions with several quadratic terms and
+    /// range checks allocate auxiliary wires after them.
+    ///
+    /// # Parameters
+    /// - `witness`: The values of the ACIR witnesses, as solved by the Noir executor, or
+    ///   `None` to lower the constraints only.
+    ///
+    /// # Returns
+    /// - `Result<(CircomCircuit, Option<Vec<FieldElement>>)>`: The constraint system and,
+    ///   given a witness, the full wire assignment starting with the constant one.
+    pub fn lower(&self, witness: Option<&BTreeMap<u32, FieldElement>>) -> Result<(CircomCircuit, Option<Vec<FieldElement>>)> {
+        let prime = BigInt::from_str_radix(BN254_PRIME, 10).expect("valid prime");
+        let mut lowering = Lowering {
+            prime: prime.clone(),
+            wires: BTreeMap::new(),
+            num_wires: 1,
+            constraints: Vec::new(),
+            values: witness.map(|_| vec![BigInt::one()]),
+        };
+
+        // Lay out the public witnesses first, as circom does
+        let mut public: Vec<u32> = Vec::new();
+        for index in self.return_values.iter().chain(&self.public_parameters) {
+            if !public.contains(index) {
+                public.push(*index);
+            }
+        }
+        let num_public_outputs = public.iter().filter(|index| self.return_values.contains(index)).count();
+        let private = (0..=self.current_witness_index).filter(|index| !public.contains(index));
+        for index in public.iter().copied().chain(private) {
+            let value = match witness {
+                Some(witness) => {
+                    let value = witness.get(&index)
+                        .ok_or_else(|| Error::Input(format!("ACIR witness {} is not assigned", index)))?;
+                    if *value.get_modulus() != prime {
+                        return Err(Error::Input(format!("ACIR witness {} is not in the BN254 scalar field", index)));
+                    }
+                    Some(value.get_value())
+                },
+                None => None,
+            };
+            let wire = lowering.allocate(value);
+            lowering.wires.insert(index, wire);
+        }
+
+        for opcode in &self.opcodes {
+            match opcode {
+                AcirOpcode::AssertZero(expression) => lowering.assert_zero(expression)?,
+                AcirOpcode::Range { witness, num_bits } => lowering.range(*witness, *num_bits)?,
+            }
+        }
+
+        let circuit = CircomCircuit {
+            prime: prime.clone(),
+            num_wires: lowering.num_wires,
+            num_public_outputs,
+            num_public_inputs: public.len() - num_public_outputs,
+            num_private_inputs: self.private_parameters.len(),
+            constraints: lowering.constraints,
+        };
+        let witness = lowering.values.map(|values| values.into_iter()
+            .map(|value| FieldElement::new_with_modulus(value, prime.clone()))
+            .collect());
+        Ok((circuit, witness))
+    }
+}
+
+/// Tracks the wires, constraints and values produced while lowering a circuit.
+struct Lowering {
+    /// The prime of the field.
+    prime: BigInt,
+    /// The wire of each ACIR witness.
+    wires: BTreeMap<u32, usize>,
+    /// The number of wires allocated so far, including the constant one.
+    num_wires: usize,
+    /// The constraints produced so far.
+    constraints: Vec<CircomConstraint>,
+    /// The values of the wires, when lowering with a witness.
+    values: Option<Vec<BigInt>>,
+}
+
+impl Lowering {
+    /// Allocates a wire with an optional value.
+    fn allocate(&mut self, value: Option<BigInt>) -> usize {
+        if let (Some(values), Some(value)) = (self.values.as_mut(), value) {
+            values.push(value);
+        }
+        self.num_wires += 1;
+        self.num_wires - 1
+    }
+
+    /// Gets the wire of an ACIR witness.
+    fn wire(&self, index: u32) -> Result<usize> {
+        self.wires.get(&index).copied()
+            .ok_or_else(|| Error::Input(format!("ACIR witness {} exceeds current_witness_index", index)))
+    }
+
+    /// Gets the value of a wire, when lowering with a witness.
+    fn value(&self, wire: usize) -> Option<BigInt> {
+        self.values.as_ref().map(|values| values[wire].clone())
+    }
+
+    /// Lowers `expression == 0`, with one auxiliary wire per quadratic term beyond the first.
+    fn assert_zero(&mut self, expression: &Expression) -> Result<()> {
+        // Everything but the first quadratic term moves to the output side, negated
+        let mut c: LinearCombination = vec![(0, (-&expression.q_c).mod_floor(&self.prime))];
+        for (q, w) in &expression.linear_combinations {
+            c.push((self.wire(*w)?, (-q).mod_floor(&self.prime)));
+        }
+        for (q, a, b) in expression.mul_terms.iter().skip(1) {
+            let product = self.product(self.wire(*a)?, self.wire(*b)?);
+            c.push((product, (-q).mod_floor(&self.prime)));
+        }
+
+        // Without a quadratic term, `1 * 0 = -(linear part)` keeps the constraint rank-1
+        let (a, b) = match expression.mul_terms.first() {
+            Some((q, a, b)) => (vec![(self.wire(*a)?, q.mod_floor(&self.prime))], vec![(self.wire(*b)?, BigInt::one())]),
+            None => (vec![(0, BigInt::one())], Vec::new()),
+        };
+        self.constraints.push(CircomConstraint { a, b, c });
+        Ok(())
+    }
+
+    /// Allocates a wire holding `a * b` and constrains it.
+    fn product(&mut self, a: usize, b: usize) -> usize {
+        let value = self.value(a).zip(self.value(b)).map(|(a, b)| (a * b).mod_floor(&self.prime));
+        let output = self.allocate(value);
+        self.constraints.push(CircomConstraint {
+            a: vec![(a, BigInt::one())],
+            b: vec![(b, BigInt::one())],
+            c: vec![(output, BigInt::one())],
+        });
+        output
+    }
+
+    /// Lowers a range check into boolean bit wires that recompose the witness.
+    fn range(&mut self, index: u32, num_bits: u32) -> Result<()> {
+        if u64::from(num_bits) > self.prime.bits() {
+            return Err(Error::Input(format!("Range check of {} bits exceeds the field size", num_bits)));
+        }
+        let wire = self.wire(index)?;
+        let value = self.value(wire);
+        let mut recomposed: LinearCombination = Vec::new();
+        for bit in 0..num_bits {
+            let bit_value = value.as_ref().map(|value| (value >> bit as usize) & BigInt::one());
+            let bit_wire = self.allocate(bit_value);
+            // b * b = b holds only for 0 and 1
+            self.constraints.push(CircomConstraint {
+                a: vec![(bit_wire, BigInt::one())],
+                b: vec![(bit_wire, BigInt::one())],
+                c: vec![(bit_wire, BigInt::one())],
+            });
+            recomposed.push((bit_wire, BigInt::one() << bit as usize));
+        }
+        self.constraints.push(CircomConstraint {
+            a: recomposed,
+            b: vec![(0, BigInt::one())],
+            c: vec![(wire, BigInt::one())],
+        });
+        Ok(())
+    }
+}
+
+/// Parses an opcode from its serde JSON form.
+fn parse_opcode(opcode: &Value) -> Result<AcirOpcode> {
+    let unsupported = || Error::Input(format!("Unsupported ACIR opcode {}", opcode));
+    let (name, body) = opcode.as_object()
+        .and_then(|object| object.iter().next())
+        .ok_or_else(unsupported)?;
+    match name.as_str() {
+        "AssertZero" | "Arithmetic" => {
+            let raw: RawExpression = serde_json::from_value(body.clone())?;
+            Ok(AcirOpcode::AssertZero(Expression {
+                mul_terms: raw.mul_terms.iter()
+                    .map(|(q, a, b)| Ok((parse_field(q)?, *a, *b)))
+                    .collect::<Result<Vec<_>>>()?,
+                linear_combinations: raw.linear_combinations.iter()
+                    .map(|(q, w)| Ok((parse_field(q)?, *w)))
+                    .collect::<Result<Vec<_>>>()?,
+                q_c: parse_field(&raw.q_c)?,
+            }))
+        },
+        "BlackBoxFuncCall" => {
+            let input = body.get("RANGE").and_then(|range| range.get("input")).ok_or_else(unsupported)?;
+            // Older ACIR stores `{ witness, num_bits }`, newer `{ input: { Witness }, num_bits }`
+            let witness = input.get("witness")
+                .or_else(|| input.get("input").and_then(|input| input.get("Witness")))
+                .and_then(Value::as_u64);
+            let num_bits = input.get("num_bits").and_then(Value::as_u64);
+            match (witness, num_bits) {
+                (Some(witness), Some(num_bits)) => Ok(AcirOpcode::Range { witness: witness as u32, num_bits: num_bits as u32 }),
+                _ => Err(unsupported()),
+            }
+        },
+        _ => Err(unsupported()),
+    }
+}
+
+/// Parses a field element serialized by ACIR as hexadecimal, with or without `0x`.
+fn parse_field(hex: &str) -> Result<BigInt> {
+    let digits = hex.strip_prefix("0x").unwrap_or(hex);
+    if digits.is_empty() {
+        return Ok(BigInt::zero());
+    }
+    BigInt::from_str_radix(digits, 16).map_err(|_| Error::Input(format!("Invalid ACIR field element {}", hex)))
+}
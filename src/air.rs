@@ -0,0 +1,349 @@
+use alloc::vec::Vec;
+use num_traits::Zero;
+use crate::context::ProverContext;
+use crate::field::FieldElement;
+use crate::plonkish::Expression;
+use crate::qap::Polynomial;
+
+/// Represents the execution trace of an iterative computation: one column per
+/// register and one row per step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceTable {
+    /// The number of columns.
+    width: usize,
+    /// The values of the registers at every step.
+    rows: Vec<Vec<FieldElement>>,
+}
+
+impl TraceTable {
+    /// Creates an empty trace.
+    ///
+    /// # Parameters
+    /// - `width`: The number of columns.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `TraceTable` struct.
+    pub fn new(width: usize) -> Self {
+        TraceTable { width, rows: Vec::new() }
+    }
+
+    /// Builds a trace by running a step function from an initial row.
+    ///
+    /// # Parameters
+    /// - `initial`: The first row.
+    /// - `steps`: The number of rows in the trace, including the first one.
+    /// - `step`: Computes the next row from the current one.
+    ///
+    /// # Returns
+    /// - `Self`: The trace, with the width of the initial row.
+    ///
+    /// # Panics
+    /// - If the step function returns a row of another width.
+    pub fn build(initial: &[FieldElement], steps: usize, mut step: impl FnMut(&[FieldElement]) -> Vec<FieldElement>) -> Self {
+        let mut trace = TraceTable::new(initial.len());
+        let mut row = initial.to_vec();
+        for _ in 1..steps {
+            let next = step(&row);
+            trace.push_row(&row);
+            row = next;
+        }
+        if steps > 0 {
+            trace.push_row(&row);
+        }
+        trace
+    }
+
+    /// Appends a row.
+    ///
+    /// # Parameters
+    /// - `row`: The values of the registers, one per column.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the row.
+    ///
+    /// # Panics
+    /// - If the row does not have one value per column.
+    pub fn push_row(&mut self, row: &[FieldElement]) -> usize {
+        assert_eq!(row.len(), self.width, "Expected a row of {} values, got {}", self.width, row.len());
+        self.rows.push(row.to_vec());
+        self.rows.len() - 1
+    }
+
+    /// Gets the number of columns.
+    ///
+    /// # Returns
+    /// - `usize`: The width of the trace.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Gets the number of rows.
+    ///
+    /// # Returns
+    /// - `usize`: The length of the trace.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Checks whether the trace has no rows.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the trace is empty.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Gets the rows of the trace.
+    ///
+    /// # Returns
+    /// - `&[Vec<FieldElement>]`: The rows, in step order.
+    pub fn rows(&self) -> &[Vec<FieldElement>] {
+        &self.rows
+    }
+
+    /// Gets the values of a column.
+    ///
+    /// # Parameters
+    /// - `column`: The index of the column.
+    ///
+    /// # Returns
+    /// - `Vec<FieldElement>`: The values of the register at every step.
+    pub fn column(&self, column: usize) -> Vec<FieldElement> {
+        self.rows.iter().map(|row| row[column].clone()).collect()
+    }
+
+    /// Interpolates every column over the evaluation domain of the trace
+    /// length, the trace polynomials a STARK prover commits to.
+    ///
+    /// # Parameters
+    /// - `context`: The context caching the domain.
+    ///
+    /// # Returns
+    /// - `Vec<Polynomial>`: One polynomial per column, taking the value of row `i` at the `i`-th point of the domain.
+    ///
+    /// # Panics
+    /// - If the trace is empty.
+    pub fn interpolate(&self, context: &mut ProverContext) -> Vec<Polynomial> {
+        let domain = context.domain(self.rows.len());
+        (0..self.width).map(|column| domain.interpolate(&self.column(column))).collect()
+    }
+}
+
+/// Constrains a register to hold a value at a given step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundaryConstraint {
+    /// The index of the row.
+    pub row: usize,
+    /// The index of the column.
+    pub column: usize,
+    /// The value the register must hold.
+    pub value: FieldElement,
+}
+
+/// Describes a constraint that a trace does not satisfy.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AirFailure {
+    /// The trace does not have the width of the AIR.
+    Width {
+        /// The number of columns of the AIR.
+        expected: usize,
+        /// The number of columns of the trace.
+        actual: usize,
+    },
+    /// A transition constraint does not vanish between a row and the next one.
+    Transition {
+        /// The index of the transition constraint.
+        constraint: usize,
+        /// The index of the current row.
+        row: usize,
+    },
+    /// A boundary constraint does not hold, or refers to a cell outside the trace.
+    Boundary {
+        /// The index of the boundary constraint.
+        constraint: usize,
+    },
+}
+
+/// Represents an algebraic intermediate representation: transition
+/// constraints relating every row of a trace to the next one, and boundary
+/// constraints pinning registers at given steps.
+///
+/// Transition constraints are `plonkish::Expression`s that must vanish on
+/// every pair of adjacent rows, reading the current row with
+/// `Expression::cell` and the next one with `Expression::next`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Air {
+    /// The number of columns of the traces.
+    width: usize,
+    /// The transition constraints.
+    transitions: Vec<Expression>,
+    /// The boundary constraints.
+    boundaries: Vec<BoundaryConstraint>,
+}
+
+impl Air {
+    /// Creates an AIR without constraints.
+    ///
+    /// # Parameters
+    /// - `width`: The number of columns of the traces.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Air` struct.
+    pub fn new(width: usize) -> Self {
+        Air { width, transitions: Vec::new(), boundaries: Vec::new() }
+    }
+
+    /// Adds a transition constraint.
+    ///
+    /// # Parameters
+    /// - `constraint`: The expression over the current and next rows that must vanish.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the constraint.
+    pub fn add_transition(&mut self, constraint: Expression) -> usize {
+        self.transitions.push(constraint);
+        self.transitions.len() - 1
+    }
+
+    /// Adds a boundary constraint.
+    ///
+    /// # Parameters
+    /// - `row`: The index of the row.
+    /// - `column`: The index of the column.
+    /// - `value`: The value the register must hold.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the constraint.
+    pub fn add_boundary(&mut self, row: usize, column: usize, value: FieldElement) -> usize {
+        self.boundaries.push(BoundaryConstraint { row, column, value });
+        self.boundaries.len() - 1
+    }
+
+    /// Gets the transition constraints.
+    ///
+    /// # Returns
+    /// - `&[Expression]`: The constraints, by index.
+    pub fn transitions(&self) -> &[Expression] {
+        &self.transitions
+    }
+
+    /// Gets the boundary constraints.
+    ///
+    /// # Returns
+    /// - `&[BoundaryConstraint]`: The constraints, by index.
+    pub fn boundaries(&self) -> &[BoundaryConstraint] {
+        &self.boundaries
+    }
+
+    /// Checks a trace against every constraint.
+    ///
+    /// # Parameters
+    /// - `trace`: The trace to check.
+    ///
+    /// # Returns
+    /// - `Vec<AirFailure>`: The failed constraints, empty if the trace satisfies the AIR.
+    pub fn check(&self, trace: &TraceTable) -> Vec<AirFailure> {
+        if trace.width() != self.width {
+            return alloc::vec![AirFailure::Width { expected: self.width, actual: trace.width() }];
+        }
+        let rows = trace.rows();
+        let mut failures = Vec::new();
+        for (constraint, transition) in self.transitions.iter().enumerate() {
+            for row in 0..rows.len().saturating_sub(1) {
+                if !transition.evaluate(rows, row).is_some_and(|value| value.get_value().is_zero()) {
+                    failures.push(AirFailure::Transition { constraint, row });
+                }
+            }
+        }
+        for (constraint, boundary) in self.boundaries.iter().enumerate() {
+            if rows.get(boundary.row).and_then(|row| row.get(boundary.column)) != Some(&boundary.value) {
+                failures.push(AirFailure::Boundary { constraint });
+            }
+        }
+        failures
+    }
+
+    /// Checks whether a trace satisfies every constraint.
+    ///
+    /// # Parameters
+    /// - `trace`: The trace to check.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if no constraint fails.
+    pub fn is_satisfied(&self, trace: &TraceTable) -> bool {
+        self.check(trace).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use num_bigint::BigInt;
+    use super::*;
+
+    /// Creates an element of the default field.
+    fn element(value: i64) -> FieldElement {
+        FieldElement::new(BigInt::from(value))
+    }
+
+    /// Builds the AIR of the Fibonacci sequence over two registers, starting
+    /// from `(1, 1)` and reaching 34 in the second register at row 7.
+    fn fibonacci() -> Air {
+        let mut air = Air::new(2);
+        air.add_transition(Expression::next(0) - Expression::cell(1));
+        air.add_transition(Expression::next(1) - Expression::cell(0) - Expression::cell(1));
+        air.add_boundary(0, 0, element(1));
+        air.add_boundary(0, 1, element(1));
+        air.add_boundary(7, 1, element(34));
+        air
+    }
+
+    /// Runs the Fibonacci step function for eight rows from a given start.
+    fn trace(first: i64, second: i64) -> TraceTable {
+        TraceTable::build(&[element(first), element(second)], 8, |row| vec![row[1].clone(), row[0].add(&row[1])])
+    }
+
+    #[test]
+    fn built_traces_satisfy_their_air() {
+        let trace = trace(1, 1);
+        assert_eq!(trace.len(), 8);
+        assert_eq!(trace.column(1)[7], element(34));
+        assert!(fibonacci().is_satisfied(&trace));
+    }
+
+    #[test]
+    fn broken_transitions_are_reported_by_row() {
+        let honest = trace(1, 1);
+        let mut tampered = TraceTable::new(2);
+        for (index, row) in honest.rows().iter().enumerate() {
+            match index {
+                4 => tampered.push_row(&[row[0].clone(), row[1].add(&element(1))]),
+                _ => tampered.push_row(row),
+            };
+        }
+        assert_eq!(fibonacci().check(&tampered), vec![
+            AirFailure::Transition { constraint: 0, row: 4 },
+            AirFailure::Transition { constraint: 1, row: 3 },
+            AirFailure::Transition { constraint: 1, row: 4 },
+        ]);
+    }
+
+    #[test]
+    fn broken_boundaries_are_reported() {
+        // Every transition holds, but the sequence starts elsewhere
+        assert_eq!(fibonacci().check(&trace(1, 2)), vec![
+            AirFailure::Boundary { constraint: 1 },
+            AirFailure::Boundary { constraint: 2 },
+        ]);
+        // A boundary past the last row fails rather than passing vacuously
+        let mut air = fibonacci();
+        let beyond = air.add_boundary(8, 0, element(34));
+        assert_eq!(air.check(&trace(1, 1)), vec![AirFailure::Boundary { constraint: beyond }]);
+    }
+
+    #[test]
+    fn traces_of_another_width_are_rejected() {
+        let trace = TraceTable::build(&[element(1)], 8, |row| vec![row[0].clone()]);
+        assert_eq!(fibonacci().check(&trace), vec![AirFailure::Width { expected: 2, actual: 1 }]);
+    }
+}
@@ -0,0 +1,153 @@
+use serde_json::Value;
+use crate::circuit::Circuit;
+use crate::compiler::CompiledCircuit;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::inputs::{self, Inputs};
+use crate::proof::Proof;
+use crate::verification::VerificationReport;
+
+/// Compiles a circuit from its JSON description into bytes.
+///
+/// # Parameters
+/// - `circuit_json`: The circuit description, see `CircuitSource`.
+///
+/// # Returns
+/// - `Result<Vec<u8>>`: The encoded compiled circuit.
+pub fn compile_circuit(circuit_json: &str) -> Result<Vec<u8>> {
+    CompiledCircuit::from_json(circuit_json)?.to_bytes()
+}
+
+/// Assigns inputs to a compiled circuit and evaluates every gate, producing the witness.
+///
+/// # Parameters
+/// - `compiled`: The encoded compiled circuit.
+/// - `inputs_json`: A JSON array with one value per input, as numbers or strings, or an
+///   object of named public and private inputs, see `Inputs`.
+///
+/// # Returns
+/// - `Result<Circuit>`: The circuit with every wire assigned.
+pub fn assign(compiled: &[u8], inputs_json: &str) -> Result<Circuit> {
+    let compiled = CompiledCircuit::from_bytes(compiled)?;
+    if let Value::Object(_) = serde_json::from_str(inputs_json)? {
+        return compiled.assign_inputs(&Inputs::from_json(inputs_json)?);
+    }
+    compiled.assign(&parse_inputs(inputs_json)?)
+}
+
+/// Proves a compiled circuit with the given inputs.
+///
+/// # Parameters
+/// - `compiled`: The encoded compiled circuit.
+/// - `inputs_json`: A JSON array with one value per input, as numbers or strings, or an
+///   object of named public and private inputs, see `Inputs`.
+///
+/// # Returns
+/// - `Result<Vec<u8>>`: The encoded proof.
+pub fn prove(compiled: &[u8], inputs_json: &str) -> Result<Vec<u8>> {
+    prove_circuit(&assign(compiled, inputs_json)?)
+}
+
+/// Proves a compiled circuit with already parsed inputs.
+///
+/// # Parameters
+/// - `compiled`: The encoded compiled circuit.
+/// - `inputs`: One value per input, in declaration order.
+///
+/// # Returns
+/// - `Result<Vec<u8>>`: The encoded proof.
+pub fn prove_with_inputs(compiled: &[u8], inputs: &[FieldElement]) -> Result<Vec<u8>> {
+    prove_circuit(&CompiledCircuit::from_bytes(compiled)?.assign(inputs)?)
+}
+
+/// Proves a circuit whose wires are all assigned.
+///
+/// # Parameters
+/// - `circuit`: The assigned circuit.
+///
+/// # Returns
+/// - `Result<Vec<u8>>`: The encoded proof.
+pub fn prove_circuit(circuit: &Circuit) -> Result<Vec<u8>> {
+    Ok(bincode::serialize(&circuit.prove())?)
+}
+
+/// Verifies a proof against a compiled circuit.
+///
+/// An encoded proof carries no witness, and the built-in proof system cannot
+/// check the constraints without it, so the proof is unverifiable and this
+/// returns `false`; `verify_report_with_inputs` checks it with its inputs.
+///
+/// # Parameters
+/// - `compiled`: The encoded compiled circuit.
+/// - `proof`: The encoded proof.
+///
+/// # Returns
+/// - `Result<bool>`: `true` if the proof is valid, otherwise `false`.
+pub fn verify(compiled: &[u8], proof: &[u8]) -> Result<bool> {
+    Ok(verify_report(compiled, proof)?.is_valid())
+}
+
+/// Verifies a proof against a compiled circuit, reporting every check.
+///
+/// # Parameters
+/// - `compiled`: The encoded compiled circuit.
+/// - `proof`: The encoded proof.
+///
+/// # Returns
+/// - `Result<VerificationReport>`: The outcome of each check, unverifiable since the proof has no witness.
+pub fn verify_report(compiled: &[u8], proof: &[u8]) -> Result<VerificationReport> {
+    let proof: Proof = bincode::deserialize(proof)?;
+    CompiledCircuit::from_bytes(compiled)?.verify_report(&proof)
+}
+
+/// Verifies a proof against a compiled circuit and the inputs it was proven
+/// with, as an auditor holding the private inputs does: the witness of the
+/// inputs is restored into the proof, whose commitment must open to it, and
+/// every gate and constraint is checked on it.
+///
+/// # Parameters
+/// - `compiled`: The encoded compiled circuit.
+/// - `proof`: The encoded proof.
+/// - `inputs_json`: The inputs, in any format `assign` accepts.
+///
+/// # Returns
+/// - `Result<VerificationReport>`: The outcome of each check, or an error if the inputs do not fit the circuit.
+pub fn verify_report_with_inputs(compiled: &[u8], proof: &[u8], inputs_json: &str) -> Result<VerificationReport> {
+    let mut proof: Proof = bincode::deserialize(proof)?;
+    proof.witness = assign(compiled, inputs_json)?.to_r1cs().generate_witness().iter().map(FieldElement::get_value).collect();
+    CompiledCircuit::from_bytes(compiled)?.verify_report(&proof)
+}
+
+/// Reads the public outputs of a proof, named after the wires of a compiled circuit.
+///
+/// The outputs are only meaningful once the proof is verified.
+///
+/// # Parameters
+/// - `compiled`: The encoded compiled circuit.
+/// - `proof`: The encoded proof.
+///
+/// # Returns
+/// - `Result<Vec<(String, FieldElement)>>`: The name and value of each output, or an error if
+///   the proof does not have one value per output of the circuit.
+pub fn public_outputs(compiled: &[u8], proof: &[u8]) -> Result<Vec<(String, FieldElement)>> {
+    let proof: Proof = bincode::deserialize(proof)?;
+    let compiled = CompiledCircuit::from_bytes(compiled)?;
+    if proof.outputs.len() != compiled.public_outputs.len() {
+        return Err(Error::Input(format!("Expected {} outputs, got {}", compiled.public_outputs.len(), proof.outputs.len())));
+    }
+    Ok(compiled.public_outputs.iter().zip(proof.public_outputs())
+        .map(|(&wire, value)| (compiled.wire_names[wire].clone(), value))
+        .collect())
+}
+
+/// Parses circuit inputs from a JSON array of numbers or numeric strings.
+///
+/// # Parameters
+/// - `inputs_json`: The JSON array.
+///
+/// # Returns
+/// - `Result<Vec<FieldElement>>`: The parsed inputs.
+pub fn parse_inputs(inputs_json: &str) -> Result<Vec<FieldElement>> {
+    let values: Vec<Value> = serde_json::from_str(inputs_json)?;
+    values.iter().map(inputs::parse_value).collect()
+}
@@ -0,0 +1,469 @@
+pub use ark_bn254::{Bn254, Fr};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::Groth16;
+pub use ark_groth16::{Proof as Groth16Proof, ProvingKey, VerifyingKey};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use std::path::Path;
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use crate::circom::CircomCircuit;
+use crate::circuit::{Circuit, Gate};
+use crate::container::{Compression, Container, PROVING_KEY_MAGIC, VERIFYING_KEY_MAGIC};
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::fingerprint::CircuitId;
+use crate::progress::CancellationToken;
+use crate::r1cs::{Operation, R1CS};
+use crate::rng::{self, CryptoRng, OsRng, RngCore};
+
+/// Represents a wire referenced by a converted constraint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wire {
+    /// The constant one.
+    One,
+    /// The wire with the given index.
+    Index(usize),
+}
+
+/// Represents a constraint `A * B = C` over arkworks field elements.
+#[derive(Clone, Debug)]
+pub struct ArkConstraint<F: PrimeField> {
+    /// The linear combination `A`.
+    pub a: Vec<(Wire, F)>,
+    /// The linear combination `B`.
+    pub b: Vec<(Wire, F)>,
+    /// The linear combination `C`.
+    pub c: Vec<(Wire, F)>,
+}
+
+/// Represents a constraint system converted for arkworks.
+///
+/// The first `num_public` wires become public inputs and the rest private
+/// witnesses. The circuit can be synthesized without a witness for setup.
+#[derive(Clone, Debug)]
+pub struct ArkCircuit<F: PrimeField> {
+    /// The number of wires, excluding the constant one.
+    pub num_wires: usize,
+    /// The number of leading wires exposed as public inputs.
+    pub num_public: usize,
+    /// The constraints of the circuit.
+    pub constraints: Vec<ArkConstraint<F>>,
+    /// The values of the wires, or `None` when only the shape is known.
+    pub witness: Option<Vec<F>>,
+}
+
+impl<F: PrimeField> ArkCircuit<F> {
+    /// Converts an R1CS and its witness, with one wire per R1CS variable.
+    ///
+    /// Addition constraints become `(left + right) * 1 = output` and
+    /// multiplication constraints `left * right = output`.
+    ///
+    /// # Parameters
+    /// - `r1cs`: The constraint system.
+    /// - `witness`: The values of the variables, in `F`'s field.
+    /// - `num_public`: The number of leading variables exposed as public inputs.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The converted circuit, or an error for hash constraints or a field mismatch.
+    pub fn from_r1cs(r1cs: &R1CS, witness: &[FieldElement], num_public: usize) -> Result<Self> {
+        let terms = |side: &[(crate::r1cs::Variable, BigInt)]| -> Vec<(Wire, F)> {
+            side.iter().map(|(variable, coeff)| (Wire::Index(variable.index), coefficient(coeff))).collect()
+        };
+
+        let constraints = r1cs.constraints.iter().map(|constraint| match constraint.operation {
+            Operation::Add => {
+                let mut a = terms(&constraint.left);
+                a.extend(terms(&constraint.right));
+                Ok(ArkConstraint { a, b: vec![(Wire::One, F::one())], c: terms(&constraint.output) })
+            },
+            Operation::Mul => Ok(ArkConstraint {
+                a: terms(&constraint.left),
+                b: terms(&constraint.right),
+                c: terms(&constraint.output),
+            }),
+            Operation::Hash => Err(Error::Input("Hash constraints cannot be converted to arkworks".to_string())),
+        }).collect::<Result<Vec<_>>>()?;
+
+        Ok(ArkCircuit {
+            num_wires: r1cs.variables.len(),
+            num_public,
+            constraints,
+            witness: Some(witness.iter().map(to_ark).collect::<Result<Vec<F>>>()?),
+        })
+    }
+
+    /// Converts a circuit built from gates, with one wire per circuit wire.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit, whose wires must hold elements of `F`'s field.
+    /// - `num_public`: The number of leading wires exposed as public inputs.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The converted circuit, or an error on a field mismatch.
+    pub fn from_circuit(circuit: &Circuit, num_public: usize) -> Result<Self> {
+        let one = || vec![(Wire::One, F::one())];
+        let constraints = circuit.gates().iter().map(|gate| Ok(match gate {
+            Gate::Add(a, b, output) => ArkConstraint {
+                a: vec![(Wire::Index(*a), F::one()), (Wire::Index(*b), F::one())],
+                b: one(),
+                c: vec![(Wire::Index(*output), F::one())],
+            },
+            Gate::Mul(a, b, output) => ArkConstraint {
+                a: vec![(Wire::Index(*a), F::one())],
+                b: vec![(Wire::Index(*b), F::one())],
+                c: vec![(Wire::Index(*output), F::one())],
+            },
+            Gate::Linear(terms, constant, output) => {
+                let mut a = terms.iter()
+                    .map(|(wire, coeff)| Ok((Wire::Index(*wire), to_ark(coeff)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                a.push((Wire::One, to_ark(constant)?));
+                ArkConstraint { a, b: one(), c: vec![(Wire::Index(*output), F::one())] }
+            },
+        })).collect::<Result<Vec<_>>>()?;
+
+        Ok(ArkCircuit {
+            num_wires: circuit.wires().len(),
+            num_public,
+            constraints,
+            witness: Some(circuit.wires().iter().map(to_ark).collect::<Result<Vec<F>>>()?),
+        })
+    }
+
+    /// Converts a circom constraint system and its witness.
+    ///
+    /// Wire 0 maps to the constant one and the public outputs and inputs
+    /// become the public inputs, in circom's order.
+    ///
+    /// # Parameters
+    /// - `circom`: The circom constraint system.
+    /// - `witness`: The full circom witness, starting with the constant one.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The converted circuit, or an error on a field mismatch.
+    pub fn from_circom(circom: &CircomCircuit, witness: &[FieldElement]) -> Result<Self> {
+        if witness.len() != circom.num_wires {
+            return Err(Error::Input(format!("Expected {} witness values, got {}", circom.num_wires, witness.len())));
+        }
+        let terms = |lc: &[(usize, BigInt)]| -> Vec<(Wire, F)> {
+            lc.iter().map(|(wire, coeff)| {
+                let wire = if *wire == 0 { Wire::One } else { Wire::Index(wire - 1) };
+                (wire, coefficient(coeff))
+            }).collect()
+        };
+
+        Ok(ArkCircuit {
+            num_wires: circom.num_wires - 1,
+            num_public: circom.num_public_outputs + circom.num_public_inputs,
+            constraints: circom.constraints.iter()
+                .map(|constraint| ArkConstraint { a: terms(&constraint.a), b: terms(&constraint.b), c: terms(&constraint.c) })
+                .collect(),
+            witness: Some(witness[1..].iter().map(to_ark).collect::<Result<Vec<F>>>()?),
+        })
+    }
+
+    /// Gets the values of the public inputs.
+    ///
+    /// # Returns
+    /// - `Vec<F>`: The public inputs, or an empty vector without a witness.
+    pub fn public_inputs(&self) -> Vec<F> {
+        self.witness.as_ref().map(|witness| witness[..self.num_public].to_vec()).unwrap_or_default()
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for ArkCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> std::result::Result<(), SynthesisError> {
+        let witness = self.witness.as_ref();
+        let mut variables = Vec::with_capacity(self.num_wires);
+        for wire in 0..self.num_wires {
+            let value = || witness.and_then(|w| w.get(wire).copied()).ok_or(SynthesisError::AssignmentMissing);
+            let variable = if wire < self.num_public {
+                cs.new_input_variable(value)?
+            } else {
+                cs.new_witness_variable(value)?
+            };
+            variables.push(variable);
+        }
+
+        let lc = |terms: &[(Wire, F)]| terms.iter().fold(LinearCombination::zero(), |lc, (wire, coeff)| {
+            let variable = match wire {
+                Wire::One => Variable::One,
+                Wire::Index(index) => variables[*index],
+            };
+            lc + (*coeff, variable)
+        });
+
+        for constraint in &self.constraints {
+            cs.enforce_constraint(lc(&constraint.a), lc(&constraint.b), lc(&constraint.c))?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs the circuit-specific Groth16 setup over BN254, sampling the toxic waste from `OsRng`.
+///
+/// # Parameters
+/// - `circuit`: The circuit, whose witness is ignored.
+///
+/// # Returns
+/// - `Result<(ProvingKey<Bn254>, VerifyingKey<Bn254>)>`: The proving and verifying keys.
+pub fn setup(circuit: &ArkCircuit<Fr>) -> Result<(ProvingKey<Bn254>, VerifyingKey<Bn254>)> {
+    setup_with_rng(circuit, &mut OsRng)
+}
+
+/// Runs the circuit-specific Groth16 setup over BN254, sampling the toxic waste from a given generator.
+///
+/// # Parameters
+/// - `circuit`: The circuit, whose witness is ignored.
+/// - `rng`: The source of the toxic waste, which anyone replaying it can recover.
+///
+/// # Returns
+/// - `Result<(ProvingKey<Bn254>, VerifyingKey<Bn254>)>`: The proving and verifying keys.
+pub fn setup_with_rng<R: RngCore + CryptoRng>(circuit: &ArkCircuit<Fr>, rng: &mut R) -> Result<(ProvingKey<Bn254>, VerifyingKey<Bn254>)> {
+    setup_with_cancel(circuit, rng, &CancellationToken::new())
+}
+
+/// Runs the circuit-specific Groth16 setup over BN254, stopping early once the token is cancelled.
+///
+/// The token is checked before the keys are generated and before they are
+/// returned; arkworks runs the key generation itself without interruption, so
+/// a cancelled setup discards its keys rather than stopping midway.
+///
+/// # Parameters
+/// - `circuit`: The circuit, whose witness is ignored.
+/// - `rng`: The source of the toxic waste, which anyone replaying it can recover.
+/// - `cancel`: The token checked at phase boundaries.
+///
+/// # Returns
+/// - `Result<(ProvingKey<Bn254>, VerifyingKey<Bn254>)>`: The proving and verifying keys, or
+///   `Error::Cancelled` if the token was cancelled.
+pub fn setup_with_cancel<R: RngCore + CryptoRng>(circuit: &ArkCircuit<Fr>, rng: &mut R, cancel: &CancellationToken) -> Result<(ProvingKey<Bn254>, VerifyingKey<Bn254>)> {
+    cancel.check()?;
+    let shape = ArkCircuit { witness: None, ..circuit.clone() };
+    let keys = Groth16::<Bn254>::circuit_specific_setup(shape, rng).map_err(backend_error)?;
+    cancel.check()?;
+    Ok(keys)
+}
+
+/// Proves a circuit with Groth16 over BN254, sampling the blinding factors from `OsRng`.
+///
+/// # Parameters
+/// - `proving_key`: The proving key from `setup`.
+/// - `circuit`: The circuit with its witness.
+///
+/// # Returns
+/// - `Result<Groth16Proof<Bn254>>`: The Groth16 proof.
+pub fn prove(proving_key: &ProvingKey<Bn254>, circuit: &ArkCircuit<Fr>) -> Result<Groth16Proof<Bn254>> {
+    prove_with_rng(proving_key, circuit, &mut OsRng)
+}
+
+/// Proves a circuit with Groth16 over BN254, sampling the blinding factors from a given generator.
+///
+/// # Parameters
+/// - `proving_key`: The proving key from `setup`.
+/// - `circuit`: The circuit with its witness.
+/// - `rng`: The source of the blinding factors `r` and `s`.
+///
+/// # Returns
+/// - `Result<Groth16Proof<Bn254>>`: The Groth16 proof.
+pub fn prove_with_rng<R: RngCore + CryptoRng>(proving_key: &ProvingKey<Bn254>, circuit: &ArkCircuit<Fr>, rng: &mut R) -> Result<Groth16Proof<Bn254>> {
+    Groth16::<Bn254>::prove(proving_key, circuit.clone(), rng).map_err(backend_error)
+}
+
+/// Proves a circuit with Groth16 over BN254, deriving the blinding factors
+/// from a prover secret, the witness and the circuit id with
+/// `rng::deterministic`, so the same secret and witness always give the same
+/// proof bytes.
+///
+/// The secret must be uniformly random and stay private: it is all that
+/// keeps the blinding factors, and so the witness, hidden from anyone who
+/// holds the proving key.
+///
+/// # Parameters
+/// - `proving_key`: The proving key from `setup`.
+/// - `circuit`: The circuit with its witness.
+/// - `circuit_id`: The id of the constraint system the circuit was converted from.
+/// - `secret`: The prover's private, uniformly random 32-byte secret.
+///
+/// # Returns
+/// - `Result<Groth16Proof<Bn254>>`: The Groth16 proof, or an error if the circuit has no witness.
+pub fn prove_deterministic(proving_key: &ProvingKey<Bn254>, circuit: &ArkCircuit<Fr>, circuit_id: &CircuitId, secret: &[u8; 32]) -> Result<Groth16Proof<Bn254>> {
+    let witness = circuit.witness.as_ref().ok_or_else(|| backend_error(SynthesisError::AssignmentMissing))?;
+    let values: Vec<BigInt> = witness.iter().map(|value| from_ark(value).get_value()).collect();
+    prove_with_rng(proving_key, circuit, &mut rng::deterministic(secret, circuit_id, &values))
+}
+
+/// Verifies a Groth16 proof over BN254.
+///
+/// # Parameters
+/// - `verifying_key`: The verifying key from `setup`.
+/// - `public_inputs`: The values of the public wires, in the BN254 scalar field.
+/// - `proof`: The Groth16 proof.
+///
+/// # Returns
+/// - `Result<bool>`: `true` if the proof is valid, otherwise `false`.
+pub fn verify(verifying_key: &VerifyingKey<Bn254>, public_inputs: &[FieldElement], proof: &Groth16Proof<Bn254>) -> Result<bool> {
+    let inputs = public_inputs.iter().map(to_ark).collect::<Result<Vec<Fr>>>()?;
+    Groth16::<Bn254>::verify(verifying_key, &inputs, proof).map_err(backend_error)
+}
+
+/// Saves a proving key to a binary file, wrapped in a `ZKPK` container.
+///
+/// # Parameters
+/// - `proving_key`: The proving key from `setup`.
+/// - `filename`: The path of the file to save the key to.
+/// - `compression`: The compression applied to the file.
+///
+/// # Returns
+/// - `Result<()>`: The result of the file operation.
+pub fn save_proving_key(proving_key: &ProvingKey<Bn254>, filename: impl AsRef<Path>, compression: Compression) -> Result<()> {
+    let mut payload = Vec::new();
+    proving_key.serialize_compressed(&mut payload).map_err(|err| Error::Serialization(err.to_string()))?;
+    Container::new(PROVING_KEY_MAGIC, modulus::<Fr>(), payload).write_to_file(filename, compression)
+}
+
+/// Loads a proving key from a binary file written by `save_proving_key`,
+/// detecting compression automatically.
+///
+/// # Parameters
+/// - `filename`: The path of the file to load the key from.
+///
+/// # Returns
+/// - `Result<ProvingKey<Bn254>>`: The proving key, or an error if the container or
+///   any of its points is invalid.
+pub fn load_proving_key(filename: impl AsRef<Path>) -> Result<ProvingKey<Bn254>> {
+    let container = Container::read_from_file(PROVING_KEY_MAGIC, filename)?;
+    container.expect_modulus(&modulus::<Fr>())?;
+    ProvingKey::deserialize_compressed(container.payload.as_slice()).map_err(|err| Error::Serialization(err.to_string()))
+}
+
+/// Saves a verifying key to a binary file, wrapped in a `ZKVK` container.
+///
+/// # Parameters
+/// - `verifying_key`: The verifying key from `setup`.
+/// - `filename`: The path of the file to save the key to.
+///
+/// # Returns
+/// - `Result<()>`: The result of the file operation.
+pub fn save_verifying_key(verifying_key: &VerifyingKey<Bn254>, filename: impl AsRef<Path>) -> Result<()> {
+    let mut payload = Vec::new();
+    verifying_key.serialize_compressed(&mut payload).map_err(|err| Error::Serialization(err.to_string()))?;
+    Container::new(VERIFYING_KEY_MAGIC, modulus::<Fr>(), payload).write_to_file(filename, Compression::None)
+}
+
+/// Loads a verifying key from a binary file written by `save_verifying_key`.
+///
+/// # Parameters
+/// - `filename`: The path of the file to load the key from.
+///
+/// # Returns
+/// - `Result<VerifyingKey<Bn254>>`: The verifying key, or an error if the container or
+///   any of its points is invalid.
+pub fn load_verifying_key(filename: impl AsRef<Path>) -> Result<VerifyingKey<Bn254>> {
+    let container = Container::read_from_file(VERIFYING_KEY_MAGIC, filename)?;
+    container.expect_modulus(&modulus::<Fr>())?;
+    VerifyingKey::deserialize_compressed(container.payload.as_slice()).map_err(|err| Error::Serialization(err.to_string()))
+}
+
+/// Gets the modulus of an arkworks prime field.
+///
+/// # Returns
+/// - `BigInt`: The modulus of `F`.
+pub fn modulus<F: PrimeField>() -> BigInt {
+    BigInt::from_bytes_le(Sign::Plus, &F::MODULUS.to_bytes_le())
+}
+
+/// Converts a field element to arkworks.
+///
+/// # Parameters
+/// - `element`: The field element, whose modulus must be the modulus of `F`.
+///
+/// # Returns
+/// - `Result<F>`: The arkworks field element, or an error on a field mismatch.
+pub fn to_ark<F: PrimeField>(element: &FieldElement) -> Result<F> {
+    if *element.get_modulus() != modulus::<F>() {
+        return Err(Error::Input(format!("Field element modulus {} does not match the arkworks field", element.get_modulus())));
+    }
+    Ok(F::from_le_bytes_mod_order(&element.get_value().to_bytes_le().1))
+}
+
+/// Converts an arkworks field element back to a field element.
+///
+/// # Parameters
+/// - `value`: The arkworks field element.
+///
+/// # Returns
+/// - `FieldElement`: The field element, with the modulus of `F`.
+pub fn from_ark<F: PrimeField>(value: &F) -> FieldElement {
+    let value = BigInt::from_bytes_le(Sign::Plus, &value.into_bigint().to_bytes_le());
+    FieldElement::new_with_modulus(value, modulus::<F>())
+}
+
+/// Converts an integer coefficient, reducing it into `F`.
+fn coefficient<F: PrimeField>(coeff: &BigInt) -> F {
+    F::from_le_bytes_mod_order(&coeff.mod_floor(&modulus::<F>()).to_bytes_le().1)
+}
+
+/// Wraps an arkworks error.
+fn backend_error(err: SynthesisError) -> Error {
+    Error::External(format!("Groth16 backend failed: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds the circuit `x * x = y` with `y` public, for the given `x`.
+    fn square(x: u64) -> ArkCircuit<Fr> {
+        let x = Fr::from(x);
+        ArkCircuit {
+            num_wires: 2,
+            num_public: 1,
+            constraints: vec![ArkConstraint {
+                a: vec![(Wire::Index(1), Fr::from(1u64))],
+                b: vec![(Wire::Index(1), Fr::from(1u64))],
+                c: vec![(Wire::Index(0), Fr::from(1u64))],
+            }],
+            witness: Some(vec![x * x, x]),
+        }
+    }
+
+    #[test]
+    fn deterministic_proofs_depend_on_the_secret() {
+        let circuit = square(3);
+        let (proving_key, verifying_key) = setup_with_rng(&circuit, &mut rng::seeded(1)).unwrap();
+        let id = CircuitId([7; 32]);
+        let public = [from_ark(&Fr::from(9u64))];
+
+        let first = prove_deterministic(&proving_key, &circuit, &id, &[1; 32]).unwrap();
+        let second = prove_deterministic(&proving_key, &circuit, &id, &[2; 32]).unwrap();
+        assert_eq!(first, prove_deterministic(&proving_key, &circuit, &id, &[1; 32]).unwrap());
+        assert_ne!(first, second);
+        assert!(verify(&verifying_key, &public, &first).unwrap());
+        assert!(verify(&verifying_key, &public, &second).unwrap());
+    }
+
+    #[test]
+    fn deterministic_proofs_depend_on_the_witness_and_circuit() {
+        let circuit = square(3);
+        let (proving_key, verifying_key) = setup_with_rng(&circuit, &mut rng::seeded(1)).unwrap();
+        let id = CircuitId([7; 32]);
+        let first = prove_deterministic(&proving_key, &circuit, &id, &[1; 32]).unwrap();
+        assert_ne!(first, prove_deterministic(&proving_key, &circuit, &CircuitId([8; 32]), &[1; 32]).unwrap());
+
+        let other = prove_deterministic(&proving_key, &square(4), &id, &[1; 32]).unwrap();
+        assert_ne!(first, other);
+        assert!(verify(&verifying_key, &[from_ark(&Fr::from(16u64))], &other).unwrap());
+        assert!(!verify(&verifying_key, &[from_ark(&Fr::from(9u64))], &other).unwrap());
+    }
+
+    #[test]
+    fn deterministic_proving_needs_a_witness() {
+        let mut circuit = square(3);
+        let (proving_key, _) = setup_with_rng(&circuit, &mut rng::seeded(1)).unwrap();
+        circuit.witness = None;
+        assert!(prove_deterministic(&proving_key, &circuit, &CircuitId([7; 32]), &[1; 32]).is_err());
+    }
+}
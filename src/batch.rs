@@ -0,0 +1,229 @@
+use alloc::vec::Vec;
+use num_bigint::BigInt;
+use serde::{Deserialize, Serialize};
+use crate::context::ProverContext;
+use crate::ct::{self, Choice, ConstantTimeEq};
+use crate::field::{Accumulator, FieldElement};
+use crate::proof::Proof;
+use crate::r1cs::{sides, R1CS};
+use crate::trace::trace_phase;
+use crate::transcript::{PoseidonTranscript, Transcript};
+
+/// The domain of the transcript shared by the statements of a batch.
+const BATCH_DOMAIN: &[u8] = b"zk-starter-kit batch proof";
+
+/// Represents one proof of several independent statements, each an R1CS with
+/// its witness, possibly of different circuits.
+///
+/// Every statement is committed to as in a single `Proof`, then one transcript
+/// absorbs the circuit id, version, outputs and commitment of every statement
+/// in order and yields the aggregate commitment and the challenge `r`. The
+/// verifier checks all constraints of all statements in one call, as a
+/// single random combination: the `k`-th constraint `A * B = C` of the batch
+/// contributes `r^k * (A * B - C)`, and the sums over each field must vanish.
+/// A false constraint survives with probability at most the number of
+/// constraints over the size of the field.
+#[derive(Serialize, Deserialize)]
+pub struct BatchProof {
+    /// The proofs of the statements, in order, each committing to its witness.
+    pub proofs: Vec<Proof>,
+    /// The aggregate commitment, squeezed from the transcript of every statement.
+    pub commitment: BigInt,
+}
+
+impl BatchProof {
+    /// Proves several statements at once.
+    ///
+    /// This builds the Poseidon parameters on every call; use a
+    /// `ProverContext` to reuse them across batches.
+    ///
+    /// # Parameters
+    /// - `statements`: The constraint systems with their witnesses, in order.
+    ///
+    /// # Returns
+    /// - `BatchProof`: The aggregate proof.
+    pub fn generate(statements: &[(&R1CS, &[FieldElement])]) -> BatchProof {
+        Self::generate_with(&ProverContext::new(), statements)
+    }
+
+    /// Proves several statements at once with cached parameters.
+    ///
+    /// # Parameters
+    /// - `context`: The precomputed parameters.
+    /// - `statements`: The constraint systems with their witnesses, in order.
+    ///
+    /// # Returns
+    /// - `BatchProof`: The aggregate proof.
+    ///
+    /// # Panics
+    /// - If an output variable is missing from its witness.
+    pub fn generate_with(context: &ProverContext, statements: &[(&R1CS, &[FieldElement])]) -> BatchProof {
+        trace_phase!("batch", statements = statements.len());
+        let proofs: Vec<Proof> = statements.iter()
+            .map(|(r1cs, witness)| Proof::generate_proof_with(context, r1cs, witness))
+            .collect();
+        let commitment = transcript(context, &proofs).challenge(b"commitment").get_value();
+        BatchProof { proofs, commitment }
+    }
+
+    /// Verifies the proof against the constraint systems of its statements.
+    ///
+    /// # Parameters
+    /// - `circuits`: The constraint systems, in the order they were proven.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if every statement is proven, otherwise `false`.
+    pub fn verify(&self, circuits: &[&R1CS]) -> bool {
+        self.verify_with(&ProverContext::new(), circuits)
+    }
+
+    /// Verifies the proof against the constraint systems of its statements
+    /// with cached parameters.
+    ///
+    /// Each statement is checked like `Proof::verify_proof_with` up to its
+    /// constraints, then the aggregate commitment is recomputed, then every
+    /// constraint of the batch is checked in one random combination.
+    ///
+    /// # Parameters
+    /// - `context`: The precomputed parameters.
+    /// - `circuits`: The constraint systems, in the order they were proven.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if every statement is proven, otherwise `false`.
+    pub fn verify_with(&self, context: &ProverContext, circuits: &[&R1CS]) -> bool {
+        trace_phase!("verify", statements = circuits.len());
+        if self.proofs.len() != circuits.len() {
+            return false;
+        }
+        let bound = self.proofs.iter().zip(circuits)
+            .fold(Choice::from(1), |bound, (proof, r1cs)| bound & proof.binding(context, r1cs));
+
+        let mut transcript = transcript(context, &self.proofs);
+        let commitment = transcript.challenge(b"commitment").get_value();
+        let committed = ct::bigint_eq(&commitment, &self.commitment, ct::field_width(&context.poseidon().modulus));
+        let challenge = transcript.challenge(b"combination").get_value();
+
+        // The powers of the challenge run on across statements, each reduced into the field of its statement
+        let mut offset = 0u64;
+        let satisfied = self.proofs.iter().zip(circuits).fold(Choice::from(1), |satisfied, (proof, r1cs)| {
+            let modulus = r1cs.modulus();
+            let challenge = FieldElement::new_with_modulus(challenge.clone(), modulus.clone());
+            let mut power = challenge.pow(&BigInt::from(offset));
+            offset += r1cs.constraints.len() as u64;
+            let witness = proof.field_witness(&modulus);
+            let mut sum = Accumulator::new(modulus.clone());
+            for (index, constraint) in r1cs.constraints.iter().enumerate() {
+                let Ok((left, right, output)) = sides(index, constraint, &witness, &modulus) else {
+                    return Choice::from(0); // A variable is missing from the witness
+                };
+                sum.add_product(&left.mul(&right).sub(&output), &power);
+                power = power.mul(&challenge);
+            }
+            satisfied & sum.finish().ct_eq(&FieldElement::new_with_modulus(BigInt::from(0), modulus))
+        });
+
+        // Every check runs before deciding, so the time taken does not tell which one failed
+        bool::from(bound & committed & satisfied)
+    }
+
+    /// Gets the number of statements.
+    ///
+    /// # Returns
+    /// - `usize`: The number of statements.
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    /// Checks whether the batch has no statement.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the batch is empty, otherwise `false`.
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+}
+
+/// Builds the transcript absorbing every statement of a batch, in order.
+fn transcript(context: &ProverContext, proofs: &[Proof]) -> PoseidonTranscript {
+    let poseidon = context.poseidon();
+    let element = |value: BigInt| FieldElement::new_with_modulus(value, poseidon.modulus.clone());
+    let mut transcript = PoseidonTranscript::with_poseidon(poseidon.clone(), BATCH_DOMAIN);
+    transcript.append(b"statements", &element(BigInt::from(proofs.len())));
+    for proof in proofs {
+        // Four bytes at a time, so distinct ids stay distinct in small fields
+        for chunk in proof.circuit_id.0.chunks(4) {
+            transcript.append(b"circuit id", &element(BigInt::from(u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))));
+        }
+        for part in [proof.version.major, proof.version.minor, proof.version.patch] {
+            transcript.append(b"version", &element(BigInt::from(part)));
+        }
+        transcript.append(b"outputs", &element(BigInt::from(proof.outputs.len())));
+        for output in &proof.outputs {
+            transcript.append(b"output", &element(output.clone()));
+        }
+        transcript.append(b"commitment", &element(proof.commitment.clone()));
+    }
+    transcript
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use num_traits::One;
+    use super::*;
+
+    /// Creates an element of the default field.
+    fn element(value: i64) -> FieldElement {
+        FieldElement::new(BigInt::from(value))
+    }
+
+    /// Builds an R1CS proving `a * b = c` over its witness, with `c` as output.
+    fn product(a: i64, b: i64) -> (R1CS, Vec<FieldElement>) {
+        let mut r1cs = R1CS::new();
+        let witness = vec![element(a), element(b), element(a * b)];
+        for value in &witness {
+            r1cs.add_variable(value.clone());
+        }
+        r1cs.add_constraints(&[(vec![(0, element(1))], vec![(1, element(1))], vec![(2, element(1))])]);
+        r1cs.add_output(2);
+        (r1cs, witness)
+    }
+
+    /// Builds an R1CS proving `(a + b) * 1 = s` and `s * s = d` over its
+    /// witness, which starts with the constant one.
+    fn square_of_sum(a: i64, b: i64) -> (R1CS, Vec<FieldElement>) {
+        let mut r1cs = R1CS::new();
+        let witness = vec![element(1), element(a), element(b), element(a + b), element((a + b) * (a + b))];
+        for value in &witness {
+            r1cs.add_variable(value.clone());
+        }
+        r1cs.add_constraints(&[
+            (vec![(1, element(1)), (2, element(1))], vec![(0, element(1))], vec![(3, element(1))]),
+            (vec![(3, element(1))], vec![(3, element(1))], vec![(4, element(1))]),
+        ]);
+        (r1cs, witness)
+    }
+
+    #[test]
+    fn batches_of_different_circuits_verify_in_one_call() {
+        let (first, first_witness) = product(3, 7);
+        let (second, second_witness) = square_of_sum(2, 5);
+        let batch = BatchProof::generate(&[(&first, &first_witness), (&second, &second_witness), (&first, &first_witness)]);
+        assert_eq!(batch.len(), 3);
+        assert!(batch.verify(&[&first, &second, &first]));
+        assert!(!batch.verify(&[&second, &first, &first]));
+        assert!(!batch.verify(&[&first, &second]));
+    }
+
+    #[test]
+    fn batches_reject_false_statements_and_tampering() {
+        let (first, first_witness) = product(3, 7);
+        let (second, mut second_witness) = square_of_sum(2, 5);
+        second_witness[4] = element(50);
+        let batch = BatchProof::generate(&[(&first, &first_witness), (&second, &second_witness)]);
+        assert!(!batch.verify(&[&first, &second]));
+
+        let mut batch = BatchProof::generate(&[(&first, &first_witness)]);
+        batch.commitment += BigInt::one();
+        assert!(!batch.verify(&[&first]));
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+use num_bigint::BigInt;
+use crate::circuit::Circuit;
+use crate::field::FieldElement;
+use crate::gadgets;
+use crate::poseidon::Poseidon;
+
+/// Represents a parameterized synthetic circuit to benchmark.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Workload {
+    /// A chain of the given number of multiplication gates, each multiplying the previous product by the input.
+    MulChain(usize),
+    /// A Poseidon Merkle path of the given depth, recomputing the root from a leaf.
+    MerklePath(usize),
+    /// A chain of the given number of Poseidon permutations, each permuting the previous state.
+    PoseidonChain(usize),
+}
+
+impl Workload {
+    /// Builds the circuit of the workload, assigning every wire along the way.
+    ///
+    /// # Returns
+    /// - `Circuit`: The synthesized circuit.
+    pub fn build(&self) -> Circuit {
+        let mut circuit = Circuit::new();
+        match *self {
+            Workload::MulChain(length) => {
+                let input = circuit.add_input(FieldElement::new(BigInt::from(3)));
+                let mut product = input;
+                for _ in 0..length {
+                    product = circuit.alloc_mul(product, input);
+                }
+            },
+            Workload::MerklePath(depth) => {
+                let leaf = circuit.add_input(FieldElement::new(BigInt::from(1)));
+                let path: Vec<(FieldElement, bool)> = (0..depth)
+                    .map(|level| (FieldElement::new(BigInt::from(level + 2)), level % 2 == 0))
+                    .collect();
+                gadgets::merkle::merkle_root(&mut circuit, &Poseidon::default(), leaf, &path);
+            },
+            Workload::PoseidonChain(length) => {
+                let poseidon = Poseidon::default();
+                let mut state: Vec<usize> = (0..poseidon.width)
+                    .map(|position| circuit.add_input(FieldElement::new(BigInt::from(position + 1))))
+                    .collect();
+                for _ in 0..length {
+                    state = gadgets::poseidon::permute(&mut circuit, &poseidon, &state);
+                }
+            },
+        }
+        circuit
+    }
+}
+
+impl fmt::Display for Workload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Workload::MulChain(length) => write!(f, "mul chain ({})", length),
+            Workload::MerklePath(depth) => write!(f, "Merkle path (depth {})", depth),
+            Workload::PoseidonChain(length) => write!(f, "Poseidon chain ({})", length),
+        }
+    }
+}
+
+/// Represents the timings of a benchmarked workload.
+#[derive(Clone, Debug)]
+pub struct BenchReport {
+    /// The benchmarked workload.
+    pub workload: Workload,
+    /// The number of constraints of the circuit.
+    pub constraints: usize,
+    /// The time taken to build the circuit and compute its wire values.
+    pub witness_generation: Duration,
+    /// The time taken to build the R1CS from the circuit.
+    pub constraint_generation: Duration,
+    /// The time taken to generate the proof.
+    pub proving: Duration,
+    /// The time taken to verify the proof.
+    pub verification: Duration,
+    /// Whether the proof verified, which should always be `true`.
+    pub valid: bool,
+}
+
+/// Benchmarks a workload through witness generation, constraint generation,
+/// proving and verification.
+///
+/// # Parameters
+/// - `workload`: The synthetic circuit to benchmark.
+///
+/// # Returns
+/// - `BenchReport`: The timings of each phase.
+pub fn run(workload: Workload) -> BenchReport {
+    let start = Instant::now();
+    let circuit = workload.build();
+    let witness_generation = start.elapsed();
+
+    let start = Instant::now();
+    let r1cs = circuit.to_r1cs();
+    let constraint_generation = start.elapsed();
+
+    let start = Instant::now();
+    let proof = r1cs.generate_proof(&r1cs.generate_witness());
+    let proving = start.elapsed();
+
+    let start = Instant::now();
+    let valid = circuit.verify(&proof);
+    let verification = start.elapsed();
+
+    BenchReport {
+        workload,
+        constraints: circuit.gates().len(),
+        witness_generation,
+        constraint_generation,
+        proving,
+        verification,
+        valid,
+    }
+}
@@ -0,0 +1,129 @@
+/// The initialization vector of Blake2s, the first 32 bits of the fractional
+/// parts of the square roots of the first eight primes.
+pub const IV: [u32; 8] = [
+    0x6A09_E667, 0xBB67_AE85, 0x3C6E_F372, 0xA54F_F53A, 0x510E_527F, 0x9B05_688C, 0x1F83_D9AB, 0x5BE0_CD19,
+];
+
+/// The message word permutation of every round.
+pub const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// The indices of the state words mixed by the eight `G` calls of a round.
+pub const MIX: [[usize; 4]; 8] = [
+    [0, 4, 8, 12], [1, 5, 9, 13], [2, 6, 10, 14], [3, 7, 11, 15],
+    [0, 5, 10, 15], [1, 6, 11, 12], [2, 7, 8, 13], [3, 4, 9, 14],
+];
+
+/// The size of a message block in bytes.
+pub const BLOCK_BYTES: usize = 64;
+
+/// Computes the unkeyed Blake2s-256 digest of a message, the reference the
+/// Blake2s gadget is checked against.
+///
+/// # Parameters
+/// - `input`: The message.
+///
+/// # Returns
+/// - `[u8; 32]`: The digest.
+pub fn hash(input: &[u8]) -> [u8; 32] {
+    let mut state = initial_state();
+    let blocks = input.len().div_ceil(BLOCK_BYTES).max(1);
+    for index in 0..blocks {
+        let start = index * BLOCK_BYTES;
+        let end = (start + BLOCK_BYTES).min(input.len());
+        let mut block = [0u8; BLOCK_BYTES];
+        block[..end - start].copy_from_slice(&input[start..end]);
+        let words: [u32; 16] = core::array::from_fn(|i| u32::from_le_bytes([block[4 * i], block[4 * i + 1], block[4 * i + 2], block[4 * i + 3]]));
+        compress(&mut state, &words, end as u64, index + 1 == blocks);
+    }
+    let mut digest = [0u8; 32];
+    for (chunk, word) in digest.chunks_mut(4).zip(&state) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    digest
+}
+
+/// Gets the state before the first block: the initialization vector mixed
+/// with the parameter block of an unkeyed 32-byte digest.
+///
+/// # Returns
+/// - `[u32; 8]`: The initial chaining value.
+pub fn initial_state() -> [u32; 8] {
+    let mut state = IV;
+    state[0] ^= 0x0101_0020;
+    state
+}
+
+/// Runs the compression function on one block.
+///
+/// # Parameters
+/// - `state`: The chaining value, updated in place.
+/// - `block`: The sixteen little-endian message words.
+/// - `counter`: The number of message bytes hashed so far, including this block.
+/// - `last`: Whether this is the final block.
+pub fn compress(state: &mut [u32; 8], block: &[u32; 16], counter: u64, last: bool) {
+    let mut v = [0u32; 16];
+    v[..8].copy_from_slice(state);
+    v[8..].copy_from_slice(&IV);
+    v[12] ^= counter as u32;
+    v[13] ^= (counter >> 32) as u32;
+    if last {
+        v[14] = !v[14];
+    }
+    for sigma in &SIGMA {
+        for (call, [a, b, c, d]) in MIX.iter().enumerate() {
+            let (x, y) = (block[sigma[2 * call]], block[sigma[2 * call + 1]]);
+            v[*a] = v[*a].wrapping_add(v[*b]).wrapping_add(x);
+            v[*d] = (v[*d] ^ v[*a]).rotate_right(16);
+            v[*c] = v[*c].wrapping_add(v[*d]);
+            v[*b] = (v[*b] ^ v[*c]).rotate_right(12);
+            v[*a] = v[*a].wrapping_add(v[*b]).wrapping_add(y);
+            v[*d] = (v[*d] ^ v[*a]).rotate_right(8);
+            v[*c] = v[*c].wrapping_add(v[*d]);
+            v[*b] = (v[*b] ^ v[*c]).rotate_right(7);
+        }
+    }
+    for (i, word) in state.iter_mut().enumerate() {
+        *word ^= v[i] ^ v[i + 8];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().fold(String::new(), |mut out, byte| {
+            write!(out, "{:02x}", byte).unwrap();
+            out
+        })
+    }
+
+    #[test]
+    fn digests_match_rfc_7693() {
+        // Appendix B of RFC 7693
+        assert_eq!(hex(&hash(b"abc")), "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982");
+    }
+
+    #[test]
+    fn digests_match_the_reference_implementation() {
+        // The unkeyed vectors of the BLAKE2 reference implementation, for the empty message,
+        // exactly one block and two blocks
+        assert_eq!(hex(&hash(b"")), "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9");
+        let message: Vec<u8> = (0..100).collect();
+        assert_eq!(hex(&hash(&message[..64])), "56f34e8b96557e90c1f24b52d0c89d51086acf1b00f634cf1dde9233b8eaaa3e");
+        assert_eq!(hex(&hash(&message)), "81dcc3a505eace3f879d8f702776770f9df50e521d1428a85daf04f9ad2150e0");
+    }
+}
@@ -0,0 +1,295 @@
+use ark_bn254::{Bn254, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::Pairing;
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ec::{AffineRepr, CurveGroup, Group, VariableBaseMSM};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use ark_serialize::CanonicalSerialize;
+use sha2::{Digest, Sha256};
+use crate::error::{Error, Result};
+use crate::kzg::KzgParams;
+use crate::rng::{CryptoRng, OsRng, RngCore};
+
+/// The label hashed into every challenge of a contribution proof.
+const CHALLENGE_LABEL: &[u8] = b"zk-starter-kit powers of tau contribution";
+
+/// Represents one participant's update of a powers-of-tau ceremony, which
+/// multiplies the secret `τ` by a secret `s` of their own, with a Schnorr
+/// proof that they know `s`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contribution {
+    /// `[τ]₁` after the update.
+    pub tau_g1: G1Affine,
+    /// `[τ]₂` after the update.
+    pub tau_g2: G2Affine,
+    /// `[s]₁`, the public key of the participant's secret.
+    pub key_g1: G1Affine,
+    /// `[s]₂`, the same secret in G2.
+    pub key_g2: G2Affine,
+    /// The commitment `[k]₁` of the proof of knowledge of `s`.
+    pub proof_commitment: G1Affine,
+    /// The response `k + c·s` of the proof, with `c` hashed from the state before the update and the keys.
+    pub proof_response: Fr,
+}
+
+/// Represents the transcript of a powers-of-tau ceremony producing KZG
+/// parameters: the contributions in order and the resulting powers.
+///
+/// The ceremony starts from `τ = 1`, and each participant multiplies every
+/// power by their own secret, so the final `τ` is the product of all secrets
+/// and stays unknown as long as one participant destroyed theirs.
+/// `verify_contribution_chain` audits a transcript.
+#[derive(Clone, Debug)]
+pub struct CeremonyTranscript {
+    /// The contributions, in order.
+    pub contributions: Vec<Contribution>,
+    /// The parameters after the last contribution.
+    pub params: KzgParams,
+}
+
+impl CeremonyTranscript {
+    /// Starts a ceremony at `τ = 1`, before any contribution.
+    ///
+    /// # Parameters
+    /// - `max_degree`: The largest degree of a committed polynomial.
+    /// - `max_points`: The largest number of points opened in one proof.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `CeremonyTranscript` struct.
+    ///
+    /// # Panics
+    /// - If `max_degree` or `max_points` is zero, leaving no power of `τ` to update.
+    pub fn new(max_degree: usize, max_points: usize) -> Self {
+        assert!(max_degree > 0 && max_points > 0, "A ceremony needs at least one power of tau in each group");
+        CeremonyTranscript {
+            contributions: Vec::new(),
+            params: KzgParams {
+                powers_g1: vec![G1Affine::generator(); max_degree + 1],
+                powers_g2: vec![G2Affine::generator(); max_points + 1],
+            },
+        }
+    }
+
+    /// Adds a contribution from a secret sampled from `OsRng`, which is dropped afterwards.
+    ///
+    /// # Returns
+    /// - `&Contribution`: The published contribution.
+    pub fn contribute(&mut self) -> &Contribution {
+        self.contribute_with_rng(&mut OsRng)
+    }
+
+    /// Adds a contribution from a secret sampled from a given generator.
+    ///
+    /// # Parameters
+    /// - `rng`: The source of the secret and of the proof nonce, which anyone replaying it can recover.
+    ///
+    /// # Returns
+    /// - `&Contribution`: The published contribution.
+    pub fn contribute_with_rng<R: RngCore + CryptoRng>(&mut self, rng: &mut R) -> &Contribution {
+        let secret = loop {
+            let secret = Fr::rand(rng);
+            if !secret.is_zero() {
+                break secret;
+            }
+        };
+        let (key_g1, key_g2) = ((G1Projective::generator() * secret).into_affine(), (G2Projective::generator() * secret).into_affine());
+        let nonce = Fr::rand(rng);
+        let proof_commitment = (G1Projective::generator() * nonce).into_affine();
+        let challenge = challenge(self.contributions.len(), &self.params.powers_g1[1], &self.params.powers_g2[1], &key_g1, &key_g2, &proof_commitment);
+
+        self.params = KzgParams {
+            powers_g1: G1Projective::normalize_batch(&scale_powers(&self.params.powers_g1, secret)),
+            powers_g2: G2Projective::normalize_batch(&scale_powers(&self.params.powers_g2, secret)),
+        };
+        self.contributions.push(Contribution {
+            tau_g1: self.params.powers_g1[1],
+            tau_g2: self.params.powers_g2[1],
+            key_g1,
+            key_g2,
+            proof_commitment,
+            proof_response: nonce + challenge * secret,
+        });
+        self.contributions.last().expect("A contribution was just added")
+    }
+}
+
+/// Audits a ceremony: every contribution must prove knowledge of a nonzero
+/// secret and multiply the `τ` left by the previous one by it in both groups,
+/// and the final parameters must be consistent powers of the last `τ`.
+///
+/// The powers are checked with random linear combinations drawn from `OsRng`,
+/// so a single pairing equation covers each group.
+///
+/// # Parameters
+/// - `transcript`: The transcript to audit.
+///
+/// # Returns
+/// - `Result<()>`: `Ok` if the transcript is valid, or an `Input` error naming the first failed check.
+pub fn verify_contribution_chain(transcript: &CeremonyTranscript) -> Result<()> {
+    let params = &transcript.params;
+    if params.powers_g1.len() < 2 || params.powers_g2.len() < 2 {
+        return Err(Error::Input("The parameters hold no power of tau".to_string()));
+    }
+    if transcript.contributions.is_empty() {
+        return Err(Error::Input("The ceremony has no contributions, so tau is 1".to_string()));
+    }
+
+    let (g1, g2) = (G1Affine::generator(), G2Affine::generator());
+    let (mut tau_g1, mut tau_g2) = (g1, g2);
+    for (index, contribution) in transcript.contributions.iter().enumerate() {
+        let fail = |reason: &str| Err(Error::Input(format!("Contribution {} {}", index, reason)));
+        let points_valid = in_group(&contribution.tau_g1) && in_group(&contribution.key_g1) && in_group(&contribution.proof_commitment)
+            && in_group(&contribution.tau_g2) && in_group(&contribution.key_g2);
+        if !points_valid {
+            return fail("holds a point outside the group");
+        }
+        if contribution.key_g1.is_zero() {
+            return fail("has a zero secret");
+        }
+        if Bn254::pairing(contribution.key_g1, g2) != Bn254::pairing(g1, contribution.key_g2) {
+            return fail("has keys for different secrets");
+        }
+        // The challenge binds the proof to the state it updated, so a contribution cannot be replayed elsewhere
+        let challenge = challenge(index, &tau_g1, &tau_g2, &contribution.key_g1, &contribution.key_g2, &contribution.proof_commitment);
+        if g1 * contribution.proof_response != contribution.proof_commitment + contribution.key_g1 * challenge {
+            return fail("does not prove knowledge of its secret");
+        }
+        let multiplied = Bn254::pairing(contribution.tau_g1, g2) == Bn254::pairing(tau_g1, contribution.key_g2)
+            && Bn254::pairing(g1, contribution.tau_g2) == Bn254::pairing(contribution.key_g1, tau_g2);
+        if !multiplied {
+            return fail("does not multiply tau by its secret");
+        }
+        (tau_g1, tau_g2) = (contribution.tau_g1, contribution.tau_g2);
+    }
+
+    if params.powers_g1[1] != tau_g1 || params.powers_g2[1] != tau_g2 {
+        return Err(Error::Input("The parameters do not match the last contribution".to_string()));
+    }
+    if !powers_consistent(params, &mut OsRng) {
+        return Err(Error::Input("The parameters are not consecutive powers of tau".to_string()));
+    }
+    Ok(())
+}
+
+/// Checks that the parameters start at the generators, lie in their groups
+/// and are consecutive powers of the same `τ`, with one random linear
+/// combination per group: `e(Σ ρᵢ·[τ^(i+1)]₁, [1]₂) = e(Σ ρᵢ·[τ^i]₁, [τ]₂)`
+/// and `e([τ]₁, Σ ρⱼ·[τ^j]₂) = e([1]₁, Σ ρⱼ·[τ^(j+1)]₂)`.
+fn powers_consistent<R: RngCore + CryptoRng>(params: &KzgParams, rng: &mut R) -> bool {
+    let (powers_g1, powers_g2) = (&params.powers_g1, &params.powers_g2);
+    if powers_g1[0] != G1Affine::generator() || powers_g2[0] != G2Affine::generator() {
+        return false;
+    }
+    if !powers_g1.iter().all(in_group) || !powers_g2.iter().all(in_group) {
+        return false;
+    }
+    let weights_g1: Vec<Fr> = (1..powers_g1.len()).map(|_| Fr::rand(rng)).collect();
+    let weights_g2: Vec<Fr> = (1..powers_g2.len()).map(|_| Fr::rand(rng)).collect();
+    let (lower_g1, upper_g1) = (
+        G1Projective::msm_unchecked(&powers_g1[..powers_g1.len() - 1], &weights_g1),
+        G1Projective::msm_unchecked(&powers_g1[1..], &weights_g1),
+    );
+    let (lower_g2, upper_g2) = (
+        G2Projective::msm_unchecked(&powers_g2[..powers_g2.len() - 1], &weights_g2),
+        G2Projective::msm_unchecked(&powers_g2[1..], &weights_g2),
+    );
+    Bn254::pairing(upper_g1, powers_g2[0]) == Bn254::pairing(lower_g1, powers_g2[1])
+        && Bn254::pairing(powers_g1[1], lower_g2) == Bn254::pairing(powers_g1[0], upper_g2)
+}
+
+/// Checks that a point is on its curve and in the prime-order subgroup.
+fn in_group<P: SWCurveConfig>(point: &Affine<P>) -> bool {
+    point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()
+}
+
+/// Multiplies the `i`-th point by `secret^i`.
+fn scale_powers<G: AffineRepr<ScalarField = Fr>>(powers: &[G], secret: Fr) -> Vec<G::Group> {
+    let mut factor = Fr::ONE;
+    powers.iter()
+        .map(|power| {
+            let scaled = *power * factor;
+            factor *= secret;
+            scaled
+        })
+        .collect()
+}
+
+/// Hashes the position of a contribution, the state it updates and its keys
+/// and proof commitment into the challenge of its proof of knowledge.
+fn challenge(index: usize, tau_g1: &G1Affine, tau_g2: &G2Affine, key_g1: &G1Affine, key_g2: &G2Affine, commitment: &G1Affine) -> Fr {
+    let mut hasher = Sha256::new();
+    hasher.update(CHALLENGE_LABEL);
+    hasher.update((index as u64).to_le_bytes());
+    let mut bytes = Vec::new();
+    for point in [tau_g1, key_g1, commitment] {
+        point.serialize_compressed(&mut bytes).expect("Serializing into a vector cannot fail");
+    }
+    for point in [tau_g2, key_g2] {
+        point.serialize_compressed(&mut bytes).expect("Serializing into a vector cannot fail");
+    }
+    hasher.update(&bytes);
+    Fr::from_le_bytes_mod_order(&hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::{DenseUVPolynomial, Polynomial};
+    use super::*;
+    use crate::rng;
+
+    /// Runs a ceremony with the given number of participants.
+    fn ceremony(participants: u64) -> CeremonyTranscript {
+        let mut transcript = CeremonyTranscript::new(4, 2);
+        for seed in 0..participants {
+            transcript.contribute_with_rng(&mut rng::seeded(seed));
+        }
+        transcript
+    }
+
+    #[test]
+    fn honest_ceremonies_verify_and_yield_working_parameters() {
+        let transcript = ceremony(3);
+        assert_eq!(transcript.contributions.len(), 3);
+        verify_contribution_chain(&transcript).unwrap();
+
+        let polynomial = DensePolynomial::from_coefficients_vec((1..=5u64).map(Fr::from).collect());
+        let point = Fr::from(7u64);
+        let commitment = transcript.params.commit(&polynomial).unwrap();
+        let proof = transcript.params.open(&polynomial, &[point]).unwrap();
+        assert!(transcript.params.verify(&commitment, &[point], &[polynomial.evaluate(&point)], &proof));
+    }
+
+    #[test]
+    fn ceremonies_without_contributions_are_rejected() {
+        assert!(verify_contribution_chain(&ceremony(0)).is_err());
+    }
+
+    #[test]
+    fn forged_contributions_are_rejected() {
+        // A response that does not open the commitment
+        let mut transcript = ceremony(2);
+        transcript.contributions[1].proof_response += Fr::from(1u64);
+        assert!(verify_contribution_chain(&transcript).is_err());
+
+        // A contribution replayed at another position
+        let mut transcript = ceremony(2);
+        transcript.contributions.swap(0, 1);
+        assert!(verify_contribution_chain(&transcript).is_err());
+
+        // A tau that is not the previous one times the secret
+        let mut transcript = ceremony(2);
+        transcript.contributions[0].tau_g1 = (transcript.contributions[0].tau_g1 + G1Affine::generator()).into_affine();
+        assert!(verify_contribution_chain(&transcript).is_err());
+    }
+
+    #[test]
+    fn inconsistent_parameters_are_rejected() {
+        let mut transcript = ceremony(2);
+        transcript.params.powers_g1[3] = (transcript.params.powers_g1[3] + G1Affine::generator()).into_affine();
+        assert!(verify_contribution_chain(&transcript).is_err());
+
+        let mut transcript = ceremony(2);
+        transcript.params.powers_g2[2] = G2Affine::generator();
+        assert!(verify_contribution_chain(&transcript).is_err());
+    }
+}
@@ -0,0 +1,596 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use num_bigint::{BigInt, Sign};
+use num_traits::{One, Zero};
+use crate::error::{Error, Result};
+use crate::field::{is_probable_prime, FieldElement, ModulusPolicy};
+use crate::proof::Proof;
+use crate::r1cs::{Constraint, Operation, R1CS};
+
+/// The magic bytes opening a circom `.r1cs` file.
+const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+/// The magic bytes opening a circom `.wtns` file.
+const WTNS_MAGIC: &[u8; 4] = b"wtns";
+
+/// Represents a linear combination of wires, as `(wire, coefficient)` pairs.
+pub type LinearCombination = Vec<(usize, BigInt)>;
+
+/// Represents a circom constraint `A * B = C` over the wires.
+#[derive(Clone, Debug)]
+pub struct CircomConstraint {
+    /// The linear combination `A`.
+    pub a: LinearCombination,
+    /// The linear combination `B`.
+    pub b: LinearCombination,
+    /// The linear combination `C`.
+    pub c: LinearCombination,
+}
+
+/// Represents a constraint system read from a circom `.r1cs` file.
+///
+/// Wire 0 always holds the constant one, followed by the public outputs,
+/// the public inputs and the private inputs.
+#[derive(Clone, Debug)]
+pub struct CircomCircuit {
+    /// The prime of the field the circuit is defined over.
+    pub prime: BigInt,
+    /// The number of wires, including the constant one.
+    pub num_wires: usize,
+    /// The number of public outputs.
+    pub num_public_outputs: usize,
+    /// The number of public inputs.
+    pub num_public_inputs: usize,
+    /// The number of private inputs.
+    pub num_private_inputs: usize,
+    /// The constraints of the circuit.
+    pub constraints: Vec<CircomConstraint>,
+}
+
+/// Describes a wire whose values differ between two witnesses of a circom circuit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WitnessMismatch {
+    /// The index of the wire.
+    pub wire: usize,
+    /// The role of the wire, such as `public input` or `internal`.
+    pub role: &'static str,
+    /// The value in this crate's witness, or `None` if it is too short.
+    pub ours: Option<FieldElement>,
+    /// The value in the other witness, such as one computed by snarkjs, or `None` if it is too short.
+    pub theirs: Option<FieldElement>,
+}
+
+impl fmt::Display for WitnessMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |value: &Option<FieldElement>| value.as_ref().map_or_else(|| "nothing".to_string(), |value| value.get_value().to_string());
+        write!(f, "Wire {} ({}): ours {}, theirs {}", self.wire, self.role, show(&self.ours), show(&self.theirs))
+    }
+}
+
+/// Represents the artifacts produced by compiling a circom source.
+#[derive(Clone, Debug)]
+pub struct CircomArtifacts {
+    /// The constraint system of the circuit.
+    pub circuit: CircomCircuit,
+    /// The path of the WebAssembly witness generator.
+    pub wasm: PathBuf,
+    /// The path of the JavaScript wrapper that runs the witness generator.
+    pub witness_generator: PathBuf,
+}
+
+/// Represents a driver invoking the circom toolchain.
+#[derive(Clone, Debug)]
+pub struct CircomDriver {
+    /// The circom compiler executable.
+    pub circom: PathBuf,
+    /// The Node.js executable used to run witness generators.
+    pub node: PathBuf,
+    /// The directory the artifacts are written to.
+    pub out_dir: PathBuf,
+    /// The requirements on the prime of compiled circuits and generated witnesses.
+    pub policy: ModulusPolicy,
+}
+
+impl CircomDriver {
+    /// Creates a driver using `circom` and `node` from the `PATH`, accepting
+    /// primes allowed by the default `ModulusPolicy`.
+    ///
+    /// # Parameters
+    /// - `out_dir`: The directory the artifacts are written to.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `CircomDriver` struct.
+    pub fn new(out_dir: impl Into<PathBuf>) -> Self {
+        CircomDriver {
+            circom: PathBuf::from("circom"),
+            node: PathBuf::from("node"),
+            out_dir: out_dir.into(),
+            policy: ModulusPolicy::default(),
+        }
+    }
+
+    /// Compiles a circom source into its constraint system and witness generator.
+    ///
+    /// # Parameters
+    /// - `source`: The path of the `.circom` file.
+    ///
+    /// # Returns
+    /// - `Result<CircomArtifacts>`: The compiled artifacts.
+    pub fn compile(&self, source: &Path) -> Result<CircomArtifacts> {
+        let name = source.file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| Error::Input(format!("Invalid circom source {}", source.display())))?;
+        fs::create_dir_all(&self.out_dir)?;
+
+        run(Command::new(&self.circom)
+            .arg(source)
+            .args(["--r1cs", "--wasm", "-o"])
+            .arg(&self.out_dir))?;
+
+        let js_dir = self.out_dir.join(format!("{}_js", name));
+        Ok(CircomArtifacts {
+            circuit: CircomCircuit::read_r1cs(&self.out_dir.join(format!("{}.r1cs", name)), &self.policy)?,
+            wasm: js_dir.join(format!("{}.wasm", name)),
+            witness_generator: js_dir.join("generate_witness.js"),
+        })
+    }
+
+    /// Runs the witness generator of compiled artifacts on the given inputs.
+    ///
+    /// # Parameters
+    /// - `artifacts`: The compiled artifacts.
+    /// - `input_json`: The circuit inputs as a JSON object, in circom's input format.
+    ///
+    /// # Returns
+    /// - `Result<Vec<FieldElement>>`: The full witness, starting with the constant one.
+    pub fn generate_witness(&self, artifacts: &CircomArtifacts, input_json: &str) -> Result<Vec<FieldElement>> {
+        let input_file = self.out_dir.join("input.json");
+        let witness_file = self.out_dir.join("witness.wtns");
+        fs::write(&input_file, input_json)?;
+
+        run(Command::new(&self.node)
+            .arg(&artifacts.witness_generator)
+            .arg(&artifacts.wasm)
+            .arg(&input_file)
+            .arg(&witness_file))?;
+
+        read_witness(&witness_file, &self.policy)
+    }
+
+    /// Compiles a circom source, generates the witness and proves it in one call.
+    ///
+    /// # Parameters
+    /// - `source`: The path of the `.circom` file.
+    /// - `input_json`: The circuit inputs as a JSON object, in circom's input format.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, or an error if the witness does not satisfy the constraints.
+    pub fn prove(&self, source: &Path, input_json: &str) -> Result<Proof> {
+        let artifacts = self.compile(source)?;
+        let witness = self.generate_witness(&artifacts, input_json)?;
+        if !artifacts.circuit.is_satisfied(&witness) {
+            return Err(Error::Input("The witness does not satisfy the circom constraints".to_string()));
+        }
+        Ok(artifacts.circuit.to_r1cs(&witness).generate_proof(&witness))
+    }
+}
+
+impl CircomCircuit {
+    /// Reads a constraint system from a circom `.r1cs` file.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the file.
+    /// - `policy`: The requirements on the prime of the circuit.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The constraint system, or an error if its prime fails the policy.
+    pub fn read_r1cs(path: &Path, policy: &ModulusPolicy) -> Result<Self> {
+        Self::parse_r1cs(&fs::read(path)?, policy)
+    }
+
+    /// Parses a constraint system in circom's binary `.r1cs` format.
+    ///
+    /// # Parameters
+    /// - `data`: The contents of the file.
+    /// - `policy`: The requirements on the prime of the circuit.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The constraint system, or an error if its prime fails the policy.
+    pub fn parse_r1cs(data: &[u8], policy: &ModulusPolicy) -> Result<Self> {
+        let sections = read_sections(data, R1CS_MAGIC)?;
+
+        let mut header = Reader::new(section(&sections, 1)?);
+        let field_size = header.u32()? as usize;
+        let prime = header.prime(field_size)?;
+        policy.check(&prime)?;
+        let num_wires = header.u32()? as usize;
+        let num_public_outputs = header.u32()? as usize;
+        let num_public_inputs = header.u32()? as usize;
+        let num_private_inputs = header.u32()? as usize;
+        let _num_labels = header.u64()?;
+        let num_constraints = header.u32()? as usize;
+
+        let mut body = Reader::new(section(&sections, 2)?);
+        let mut constraints = Vec::new();
+        for _ in 0..num_constraints {
+            let constraint = CircomConstraint {
+                a: body.linear_combination(field_size)?,
+                b: body.linear_combination(field_size)?,
+                c: body.linear_combination(field_size)?,
+            };
+            let mut wires = constraint.a.iter().chain(&constraint.b).chain(&constraint.c);
+            if wires.any(|(wire, _)| *wire >= num_wires) {
+                return Err(Error::Serialization("Constraint refers to an unknown wire".to_string()));
+            }
+            constraints.push(constraint);
+        }
+
+        Ok(CircomCircuit {
+            prime,
+            num_wires,
+            num_public_outputs,
+            num_public_inputs,
+            num_private_inputs,
+            constraints,
+        })
+    }
+
+    /// Checks whether a witness satisfies every constraint.
+    ///
+    /// # Parameters
+    /// - `witness`: The full witness, starting with the constant one.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if `A * B = C` holds for every constraint, otherwise `false`.
+    pub fn is_satisfied(&self, witness: &[FieldElement]) -> bool {
+        if witness.len() != self.num_wires {
+            return false;
+        }
+        let evaluate = |lc: &LinearCombination| lc.iter()
+            .fold(BigInt::zero(), |acc, (wire, coeff)| acc + coeff * witness[*wire].get_value());
+
+        self.constraints.iter().all(|constraint| {
+            let lhs = evaluate(&constraint.a) * evaluate(&constraint.b);
+            (lhs - evaluate(&constraint.c)) % &self.prime == BigInt::zero()
+        })
+    }
+
+    /// Checks the prime of the circuit against a modulus policy, so circuits
+    /// compiled with a small prime such as circom's `-p goldilocks` are only
+    /// accepted with the toy field opt-in.
+    ///
+    /// `read_r1cs` and `parse_r1cs` already apply their policy; this checks
+    /// circuits built in memory, such as lowered ACIR programs.
+    ///
+    /// # Parameters
+    /// - `policy`: The requirements on the prime.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the prime is too small for the policy.
+    pub fn check_field(&self, policy: &ModulusPolicy) -> Result<()> {
+        policy.check(&self.prime)
+    }
+
+    /// Gets the role of a wire from its position in circom's ordering.
+    ///
+    /// # Parameters
+    /// - `wire`: The index of the wire.
+    ///
+    /// # Returns
+    /// - `&'static str`: `one`, `public output`, `public input`, `private input` or `internal`.
+    pub fn wire_role(&self, wire: usize) -> &'static str {
+        let outputs = 1 + self.num_public_outputs;
+        let public = outputs + self.num_public_inputs;
+        let private = public + self.num_private_inputs;
+        match wire {
+            0 => "one",
+            _ if wire < outputs => "public output",
+            _ if wire < public => "public input",
+            _ if wire < private => "private input",
+            _ => "internal",
+        }
+    }
+
+    /// Checks that a witness follows circom's wire ordering for this circuit:
+    /// one value per wire, with the constant one first.
+    ///
+    /// # Parameters
+    /// - `witness`: The full witness.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error naming the first difference from the expected layout.
+    pub fn check_witness_layout(&self, witness: &[FieldElement]) -> Result<()> {
+        if witness.len() != self.num_wires {
+            return Err(Error::Input(format!("The witness holds {} values, but the circuit has {} wires", witness.len(), self.num_wires)));
+        }
+        if !witness[0].get_value().is_one() {
+            return Err(Error::Input(format!("Wire 0 must hold the constant one, not {}", witness[0].get_value())));
+        }
+        Ok(())
+    }
+
+    /// Writes a witness as a `.wtns` file, which snarkjs reads for `wtns check`
+    /// and proving, and `wtns export json` converts to `witness_to_json`'s output.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the file.
+    /// - `witness`: The full witness, in wire order.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the witness does not follow the wire ordering or the file cannot be written.
+    pub fn write_witness(&self, path: &Path, witness: &[FieldElement]) -> Result<()> {
+        self.check_witness_layout(witness)?;
+        Ok(fs::write(path, serialize_witness(witness, &self.prime))?)
+    }
+
+    /// Renders a witness exactly like `snarkjs wtns export json`: an array of
+    /// decimal strings indented by one space, so the two files diff cleanly.
+    ///
+    /// # Parameters
+    /// - `witness`: The full witness, in wire order.
+    ///
+    /// # Returns
+    /// - `Result<String>`: The JSON document, or an error if the witness does not follow the wire ordering.
+    pub fn witness_to_json(&self, witness: &[FieldElement]) -> Result<String> {
+        self.check_witness_layout(witness)?;
+        let values: Vec<String> = witness.iter().map(|value| format!(" \"{}\"", value.get_value())).collect();
+        Ok(format!("[\n{}\n]", values.join(",\n")))
+    }
+
+    /// Compares two witnesses wire by wire, such as this crate's and one
+    /// computed by snarkjs, to find where two toolchains start to disagree.
+    ///
+    /// # Parameters
+    /// - `ours`: This crate's witness, in wire order.
+    /// - `theirs`: The other witness, in wire order.
+    ///
+    /// # Returns
+    /// - `Vec<WitnessMismatch>`: The wires whose values differ or are missing from one witness, in order.
+    pub fn diff_witness(&self, ours: &[FieldElement], theirs: &[FieldElement]) -> Vec<WitnessMismatch> {
+        (0..ours.len().max(theirs.len()))
+            .map(|wire| (wire, ours.get(wire), theirs.get(wire)))
+            .filter(|(_, ours, theirs)| match (ours, theirs) {
+                (Some(ours), Some(theirs)) => (ours.get_value() - theirs.get_value()) % &self.prime != BigInt::zero(),
+                _ => true,
+            })
+            .map(|(wire, ours, theirs)| WitnessMismatch { wire, role: self.wire_role(wire), ours: ours.cloned(), theirs: theirs.cloned() })
+            .collect()
+    }
+
+    /// Adapts the constraint system and a witness into this crate's R1CS.
+    ///
+    /// # Parameters
+    /// - `witness`: The full witness, starting with the constant one.
+    ///
+    /// # Returns
+    /// - `R1CS`: The R1CS with one variable per wire, one multiplication constraint per circom
+    ///   constraint, and circom's public inputs and outputs.
+    pub fn to_r1cs(&self, witness: &[FieldElement]) -> R1CS {
+        let mut r1cs = R1CS::new();
+        for value in witness {
+            r1cs.add_variable(value.clone());
+        }
+
+        for constraint in &self.constraints {
+            let terms = |lc: &LinearCombination| lc.iter()
+                .map(|(wire, coeff)| (r1cs.variables[*wire].clone(), coeff.clone()))
+                .collect();
+            let adapted = Constraint {
+                left: terms(&constraint.a),
+                right: terms(&constraint.b),
+                output: terms(&constraint.c),
+                operation: Operation::Mul,
+            };
+            r1cs.constraints.push(adapted);
+        }
+        let outputs = 1..1 + self.num_public_outputs;
+        for wire in outputs.end..outputs.end + self.num_public_inputs {
+            r1cs.add_public_input(wire);
+        }
+        for wire in outputs {
+            r1cs.add_output(wire);
+        }
+        r1cs
+    }
+}
+
+/// Reads a witness from a circom `.wtns` file.
+///
+/// # Parameters
+/// - `path`: The path of the file.
+/// - `policy`: The requirements on the prime of the witness.
+///
+/// # Returns
+/// - `Result<Vec<FieldElement>>`: The witness values, or an error if their prime fails the policy.
+pub fn read_witness(path: &Path, policy: &ModulusPolicy) -> Result<Vec<FieldElement>> {
+    parse_witness(&fs::read(path)?, policy)
+}
+
+/// Parses a witness in circom's binary `.wtns` format.
+///
+/// # Parameters
+/// - `data`: The contents of the file.
+/// - `policy`: The requirements on the prime of the witness.
+///
+/// # Returns
+/// - `Result<Vec<FieldElement>>`: The witness values, in the circuit's field, or an error
+///   if the prime fails the policy.
+pub fn parse_witness(data: &[u8], policy: &ModulusPolicy) -> Result<Vec<FieldElement>> {
+    let sections = read_sections(data, WTNS_MAGIC)?;
+
+    let mut header = Reader::new(section(&sections, 1)?);
+    let field_size = header.u32()? as usize;
+    let prime = header.prime(field_size)?;
+    policy.check(&prime)?;
+    let num_values = header.u32()? as usize;
+
+    let mut values = Reader::new(section(&sections, 2)?);
+    (0..num_values)
+        .map(|_| Ok(FieldElement::new_with_modulus(values.field(field_size)?, prime.clone())))
+        .collect()
+}
+
+/// Serializes a witness in circom's binary `.wtns` format, version 2.
+///
+/// # Parameters
+/// - `witness`: The witness values, in wire order.
+/// - `prime`: The prime of the field, which sets the size of every value.
+///
+/// # Returns
+/// - `Vec<u8>`: The contents of the file.
+pub fn serialize_witness(witness: &[FieldElement], prime: &BigInt) -> Vec<u8> {
+    // Values take a whole number of 64-bit words, as in circom's own files
+    let field_size = (prime.bits() as usize).div_ceil(64) * 8;
+    let field = |value: &BigInt| {
+        let mut bytes = value.to_bytes_le().1;
+        bytes.resize(field_size, 0);
+        bytes
+    };
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&(field_size as u32).to_le_bytes());
+    header.extend_from_slice(&field(prime));
+    header.extend_from_slice(&(witness.len() as u32).to_le_bytes());
+    let values: Vec<u8> = witness.iter().flat_map(|value| field(&value.get_value())).collect();
+
+    let mut data = WTNS_MAGIC.to_vec();
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(&2u32.to_le_bytes());
+    for (kind, contents) in [(1u32, header), (2, values)] {
+        data.extend_from_slice(&kind.to_le_bytes());
+        data.extend_from_slice(&(contents.len() as u64).to_le_bytes());
+        data.extend_from_slice(&contents);
+    }
+    data
+}
+
+/// Runs an external command, turning a failure into an error.
+fn run(command: &mut Command) -> Result<()> {
+    let output = command.output()
+        .map_err(|err| Error::External(format!("Could not run {:?}: {}", command.get_program(), err)))?;
+    if !output.status.success() {
+        return Err(Error::External(format!(
+            "{:?} failed: {}",
+            command.get_program(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Splits an iden3 binary file into its `(type, contents)` sections after checking the magic.
+fn read_sections<'a>(data: &'a [u8], magic: &[u8; 4]) -> Result<Vec<(u32, &'a [u8])>> {
+    let mut reader = Reader::new(data);
+    if reader.bytes(4)? != magic {
+        return Err(Error::Serialization(format!("Not a circom {} file", String::from_utf8_lossy(magic))));
+    }
+    let _version = reader.u32()?;
+    let num_sections = reader.u32()?;
+
+    let mut sections = Vec::new();
+    for _ in 0..num_sections {
+        let kind = reader.u32()?;
+        let size = reader.u64()? as usize;
+        sections.push((kind, reader.bytes(size)?));
+    }
+    Ok(sections)
+}
+
+/// Finds the section of the given type.
+fn section<'a>(sections: &[(u32, &'a [u8])], kind: u32) -> Result<&'a [u8]> {
+    sections.iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, contents)| *contents)
+        .ok_or_else(|| Error::Serialization(format!("Missing section {}", kind)))
+}
+
+/// Represents a cursor over little-endian binary data.
+struct Reader<'a> {
+    /// The data being read.
+    data: &'a [u8],
+    /// The current offset.
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Creates a reader at the start of the data.
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data, position: 0 }
+    }
+
+    /// Reads the next `len` bytes.
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.position.checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| Error::Serialization("Unexpected end of circom file".to_string()))?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    /// Reads a little-endian `u32`.
+    fn u32(&mut self) -> Result<u32> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.bytes(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    /// Reads a little-endian `u64`.
+    fn u64(&mut self) -> Result<u64> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.bytes(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Reads a little-endian field element of `size` bytes.
+    fn field(&mut self, size: usize) -> Result<BigInt> {
+        Ok(BigInt::from_bytes_le(Sign::Plus, self.bytes(size)?))
+    }
+
+    /// Reads the prime of a header, rejecting a composite one as corrupt.
+    fn prime(&mut self, size: usize) -> Result<BigInt> {
+        let prime = self.field(size)?;
+        if !is_probable_prime(&prime) {
+            return Err(Error::Serialization(format!("The field modulus {} is not prime", prime)));
+        }
+        Ok(prime)
+    }
+
+    /// Reads a sparse linear combination.
+    fn linear_combination(&mut self, field_size: usize) -> Result<LinearCombination> {
+        let count = self.u32()?;
+        (0..count).map(|_| Ok((self.u32()? as usize, self.field(field_size)?))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Field;
+
+    fn witness(prime: &BigInt) -> Vec<FieldElement> {
+        [1, 3, 9].iter().map(|value| FieldElement::new_with_modulus(BigInt::from(*value), prime.clone())).collect()
+    }
+
+    #[test]
+    fn witnesses_round_trip_under_their_policy() {
+        let bn254 = Field::Bn254.modulus();
+        let values = witness(&bn254);
+        assert_eq!(parse_witness(&serialize_witness(&values, &bn254), &ModulusPolicy::default()).unwrap(), values);
+
+        let toy = FieldElement::default_modulus();
+        let values = witness(&toy);
+        assert_eq!(parse_witness(&serialize_witness(&values, &toy), &ModulusPolicy::toy()).unwrap(), values);
+    }
+
+    #[test]
+    fn witnesses_over_small_or_composite_primes_are_rejected() {
+        let toy = FieldElement::default_modulus();
+        assert!(parse_witness(&serialize_witness(&witness(&toy), &toy), &ModulusPolicy::default()).is_err());
+
+        let composite = Field::Bn254.modulus() + 1;
+        assert!(parse_witness(&serialize_witness(&witness(&composite), &composite), &ModulusPolicy::default()).is_err());
+        assert!(parse_witness(&serialize_witness(&witness(&composite), &composite), &ModulusPolicy::toy()).is_err());
+    }
+}
@@ -1,14 +1,112 @@
+use std::path::PathBuf;
+use std::time::Instant;
 use num_bigint::BigInt;
+use num_traits::One;
+use serde::{Deserialize, Serialize};
+use crate::constraint_system::ConstraintSystem;
+use crate::gadgets;
 use crate::r1cs::R1CS;
-use crate::field::FieldElement;
+use crate::field::{FieldElement, ModulusPolicy};
+use crate::fingerprint::CircuitVersion;
+use crate::parallel::cfg_iter;
 use crate::proof::Proof;
+use crate::qap::Terms;
+use crate::simplify::Simplified;
+use crate::trace::trace_phase;
+use crate::verification::{FailedGate, VerificationReport};
+use crate::error::{Error, Result};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Represents the files a proof is written to and verified from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Artifacts {
+    /// The path of the R1CS file.
+    pub r1cs: PathBuf,
+    /// The path of the proof file.
+    pub proof: PathBuf,
+}
+
+impl Artifacts {
+    /// Creates artifact paths for a named circuit, `<name>.r1cs.bin` and
+    /// `<name>.proof.bin` in the given directory.
+    ///
+    /// # Parameters
+    /// - `dir`: The directory holding the artifacts.
+    /// - `name`: The name of the circuit.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Artifacts` struct.
+    pub fn new(dir: impl Into<PathBuf>, name: &str) -> Self {
+        let dir = dir.into();
+        Artifacts {
+            r1cs: dir.join(format!("{}.r1cs.bin", name)),
+            proof: dir.join(format!("{}.proof.bin", name)),
+        }
+    }
+}
 
 /// Represents a gate in the circuit.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Gate {
     /// Addition gate: input1, input2, output
     Add(usize, usize, usize),
     /// Multiplication gate: input1, input2, output
     Mul(usize, usize, usize),
+    /// Linear gate: weighted inputs, constant term, output
+    Linear(Vec<(usize, FieldElement)>, FieldElement, usize),
+}
+
+impl Gate {
+    /// Evaluates the gate on the current wire values.
+    ///
+    /// # Parameters
+    /// - `wires`: The values of the wires the gate reads from.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The value the gate assigns to its output wire.
+    pub fn evaluate(&self, wires: &[FieldElement]) -> FieldElement {
+        self.evaluate_with(|wire| &wires[wire])
+    }
+
+    /// Evaluates the gate, looking up the value of each input wire.
+    ///
+    /// # Parameters
+    /// - `wire`: Returns the value of a wire from its index.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The value the gate assigns to its output wire.
+    pub fn evaluate_with<'a>(&self, wire: impl Fn(usize) -> &'a FieldElement) -> FieldElement {
+        match self {
+            Gate::Add(a, b, _) => wire(*a).add(wire(*b)),
+            Gate::Mul(a, b, _) => wire(*a).mul(wire(*b)),
+            Gate::Linear(terms, constant, _) => terms.iter().fold(constant.clone(), |acc, (input, coeff)| {
+                acc.add(&coeff.mul(wire(*input)))
+            }),
+        }
+    }
+
+    /// Gets the indices of the wires the gate reads from.
+    ///
+    /// # Returns
+    /// - `impl Iterator<Item = usize>`: The input wires, possibly with repetitions.
+    pub fn inputs(&self) -> impl Iterator<Item = usize> + '_ {
+        let (pair, terms) = match self {
+            Gate::Add(a, b, _) | Gate::Mul(a, b, _) => (Some([*a, *b]), &[][..]),
+            Gate::Linear(terms, _, _) => (None, &terms[..]),
+        };
+        pair.into_iter().flatten().chain(terms.iter().map(|(wire, _)| *wire))
+    }
+
+    /// Gets the index of the gate's output wire.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the output wire.
+    pub fn output(&self) -> usize {
+        match self {
+            Gate::Add(_, _, output) | Gate::Mul(_, _, output) | Gate::Linear(_, _, output) => *output,
+        }
+    }
 }
 
 /// Represents a circuit with inputs, gates, and outputs.
@@ -19,8 +117,20 @@ pub struct Circuit {
     gates: Vec<Gate>,
     /// The outputs of the circuit, stored as FieldElements.
     outputs: Vec<FieldElement>,
+    /// The wires exposed as public outputs of the proof, in order.
+    output_wires: Vec<usize>,
+    /// The wires allocated as public inputs, in order.
+    public_inputs: Vec<usize>,
     /// The modulus used for FieldElements.
     modulus: BigInt,
+    /// The version of the circuit, set by its author.
+    version: CircuitVersion,
+}
+
+impl Default for Circuit {
+    fn default() -> Self {
+        Circuit::new()
+    }
 }
 
 impl Circuit {
@@ -34,7 +144,10 @@ impl Circuit {
             inputs: Vec::new(),
             gates: Vec::new(),
             outputs: Vec::new(),
+            output_wires: Vec::new(),
+            public_inputs: Vec::new(),
             modulus: default_modulus,
+            version: CircuitVersion::default(),
         }
     }
 
@@ -67,6 +180,108 @@ impl Circuit {
         self.outputs.push(value);
     }
 
+    /// Exposes a wire as a public output, so every proof of the circuit reveals its value.
+    ///
+    /// # Parameters
+    /// - `wire`: The index of the wire.
+    ///
+    /// # Panics
+    /// - If the wire does not exist.
+    pub fn expose_output(&mut self, wire: usize) {
+        assert!(wire < self.inputs.len(), "Output wire {} does not exist", wire);
+        self.output_wires.push(wire);
+    }
+
+    /// Exposes a wire as a public input, so every proof of the circuit carries its value.
+    ///
+    /// # Parameters
+    /// - `wire`: The index of the wire.
+    ///
+    /// # Panics
+    /// - If the wire does not exist.
+    pub fn expose_input(&mut self, wire: usize) {
+        assert!(wire < self.inputs.len(), "Public input wire {} does not exist", wire);
+        self.public_inputs.push(wire);
+    }
+
+    /// Gets the wires exposed as public outputs.
+    ///
+    /// # Returns
+    /// - `&[usize]`: The output wires, in the order their values appear in proofs.
+    pub fn output_wires(&self) -> &[usize] {
+        &self.output_wires
+    }
+
+    /// Gets the wires allocated as public inputs with `ConstraintSystem::alloc_public` or `expose_input`.
+    ///
+    /// # Returns
+    /// - `&[usize]`: The public input wires, in the order their values appear in proofs.
+    pub fn public_inputs(&self) -> &[usize] {
+        &self.public_inputs
+    }
+
+    /// Sets the version of the circuit, which its R1CS and proofs carry.
+    ///
+    /// # Parameters
+    /// - `version`: The version, bumped by the author on each release.
+    pub fn set_version(&mut self, version: CircuitVersion) {
+        self.version = version;
+    }
+
+    /// Gets the version of the circuit.
+    ///
+    /// # Returns
+    /// - `CircuitVersion`: The version, `0.0.0` unless set.
+    pub fn version(&self) -> CircuitVersion {
+        self.version
+    }
+
+    /// Allocates a new wire holding `a * b` and constrains it with a multiplication gate.
+    ///
+    /// # Parameters
+    /// - `a`: The index of the first factor.
+    /// - `b`: The index of the second factor.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the product wire.
+    pub fn alloc_mul(&mut self, a: usize, b: usize) -> usize {
+        ConstraintSystem::alloc_mul(self, a, b)
+    }
+
+    /// Allocates a new wire holding a weighted sum of wires plus a constant,
+    /// and constrains it with a linear gate.
+    ///
+    /// # Parameters
+    /// - `terms`: The `(wire, coefficient)` pairs of the sum.
+    /// - `constant`: The constant term.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the result wire.
+    pub fn alloc_linear(&mut self, terms: &[(usize, FieldElement)], constant: FieldElement) -> usize {
+        ConstraintSystem::alloc_linear(self, terms, constant)
+    }
+
+    /// Constrains two wires to hold the same value.
+    ///
+    /// # Parameters
+    /// - `a`: The index of the first wire.
+    /// - `b`: The index of the second wire.
+    pub fn enforce_equal(&mut self, a: usize, b: usize) {
+        ConstraintSystem::enforce_equal(self, a, b)
+    }
+
+    /// Allocates a boolean wire holding 1 if a wire is zero and 0 otherwise,
+    /// with the gates of `gadgets::equality::is_zero`.
+    ///
+    /// # Parameters
+    /// - `wire`: The index of the wire to test.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the result wire.
+    pub fn is_zero(&mut self, wire: usize) -> usize {
+        gadgets::equality::is_zero(self, wire)
+    }
+
     /// Retrieves an input value by index, if it exists.
     ///
     /// # Parameters
@@ -78,16 +293,29 @@ impl Circuit {
         self.inputs.get(index)
     }
 
-    /// Generates the proof and checks constraint satisfaction, then saves it to a binary file.
+    /// Gets the values of all wires.
     ///
-    /// # Parameters
-    /// - `proof_file`: The name of the file to save the proof to.
-    pub fn generate_proof(&self, proof_file: &str) {
-        // Ensure inputs are added before generating proof
-        if self.inputs.is_empty() {
-            panic!("No inputs available to generate proof.");
-        }
+    /// # Returns
+    /// - `&[FieldElement]`: The wire values, indexed by wire.
+    pub fn wires(&self) -> &[FieldElement] {
+        &self.inputs
+    }
 
+    /// Gets the gates of the circuit.
+    ///
+    /// # Returns
+    /// - `&[Gate]`: The gates, in insertion order.
+    pub fn gates(&self) -> &[Gate] {
+        &self.gates
+    }
+
+    /// Builds the R1CS for the circuit, with one variable per wire followed by the
+    /// constant one, and one `A * B = C` constraint per gate.
+    ///
+    /// # Returns
+    /// - `R1CS`: The constraint system of the circuit.
+    pub fn to_r1cs(&self) -> R1CS {
+        trace_phase!("constraints", wires = self.inputs.len(), gates = self.gates.len());
         let mut r1cs = R1CS::new();
 
         // Add variables to R1CS
@@ -95,73 +323,173 @@ impl Circuit {
             r1cs.add_variable(input.clone()); // input is of type FieldElement
         }
 
-        // Process each gate and add constraints to R1CS
-        for gate in &self.gates {
-            match gate {
-                Gate::Add(a, b, output) => {
-                    r1cs.add_constraint(
-                        &[
-                            (r1cs.variables[*a].index, FieldElement::new(BigInt::from(1))), // Extract index
-                        ],
-                        &[
-                            (r1cs.variables[*b].index, FieldElement::new(BigInt::from(1))), // Extract index
-                        ],
-                        &[
-                            (r1cs.variables[*output].index, FieldElement::new(BigInt::from(1))), // Extract index
-                        ],
-                        &self.modulus, // Pass modulus dynamically
-                    );
-                },
-                Gate::Mul(a, b, output) => {
-                    r1cs.add_constraint(
-                        &[
-                            (r1cs.variables[*a].index, FieldElement::new(BigInt::from(1))), // Extract index
-                        ],
-                        &[
-                            (r1cs.variables[*b].index, FieldElement::new(BigInt::from(1))), // Extract index
-                        ],
-                        &[
-                            (r1cs.variables[*output].index, FieldElement::new(BigInt::from(1))), // Extract index
-                        ],
-                        &self.modulus, // Pass modulus dynamically
-                    );
-                },
-            }
+        // A trailing variable holds the constant one, so additions and constants fold into linear combinations
+        let one_wire = r1cs.add_variable(FieldElement::new_with_modulus(BigInt::one(), self.modulus.clone()));
+        let one = || FieldElement::new(BigInt::one());
+        let rows: Vec<(Terms, Terms, Terms)> = cfg_iter!(self.gates).map(|gate| match gate {
+            // (a + b) * 1 = output
+            Gate::Add(a, b, output) => (vec![(*a, one()), (*b, one())], vec![(one_wire, one())], vec![(*output, one())]),
+            // a * b = output
+            Gate::Mul(a, b, output) => (vec![(*a, one())], vec![(*b, one())], vec![(*output, one())]),
+            // (terms + constant) * 1 = output
+            Gate::Linear(terms, constant, output) => {
+                let mut combination = terms.clone();
+                combination.push((one_wire, constant.clone()));
+                (combination, vec![(one_wire, one())], vec![(*output, one())])
+            },
+        }).collect();
+        r1cs.add_constraints(&rows);
+        for wire in &self.public_inputs {
+            r1cs.add_public_input(*wire);
+        }
+        for wire in &self.output_wires {
+            r1cs.add_output(*wire);
         }
+        r1cs.version = self.version;
+
+        r1cs
+    }
+
+    /// Builds the R1CS for the circuit like `to_r1cs`, then simplifies it with
+    /// `R1CS::simplify`, keeping the public inputs, the outputs and the constant one.
+    ///
+    /// # Returns
+    /// - `Simplified`: The simplified system and the wires its variables stand for.
+    pub fn to_simplified_r1cs(&self) -> Simplified {
+        self.to_r1cs().simplify(self.inputs.len(), &self.public_inputs)
+    }
+
+    /// Generates an in-memory proof for the current wire assignment.
+    ///
+    /// # Returns
+    /// - `Proof`: The generated proof.
+    ///
+    /// # Panics
+    /// - If the circuit has no inputs.
+    pub fn prove(&self) -> Proof {
+        // Ensure inputs are added before generating proof
+        if self.inputs.is_empty() {
+            panic!("No inputs available to generate proof.");
+        }
+
+        let r1cs = self.to_r1cs();
+        let witness = r1cs.generate_witness();
+        r1cs.generate_proof(&witness)
+    }
+
+    /// Verifies an in-memory proof, which still holds its witness, against the circuit's constraints.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to verify.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the proof is valid, otherwise `false`.
+    pub fn verify(&self, proof: &Proof) -> bool {
+        self.verify_report(proof).is_valid()
+    }
+
+    /// Verifies an in-memory proof, reporting every check instead of stopping at the first failure.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to verify.
+    ///
+    /// # Returns
+    /// - `VerificationReport`: The outcome of the witness length, commitment, public value, gate and constraint checks.
+    pub fn verify_report(&self, proof: &Proof) -> VerificationReport {
+        trace_phase!("verify", wires = self.inputs.len(), gates = self.gates.len());
+        let start = Instant::now();
+        // The witness holds every wire followed by the constant one of the R1CS
+        let mut report = VerificationReport::new(self.inputs.len() + 1, self.version, proof);
+        let r1cs = self.to_r1cs();
+        report.circuit_id = Some(r1cs.circuit_id());
+        report.public_inputs_valid = proof.public_inputs_match(&self.public_inputs);
+        report.outputs_valid = proof.outputs_match(&self.output_wires);
+        // The gates and constraints can only be evaluated on a witness assigning every wire
+        if report.expected_wires == Some(report.witness_len) {
+            let witness: Vec<FieldElement> = proof.witness.iter()
+                .map(|value| FieldElement::new_with_modulus(value.clone(), self.modulus.clone()))
+                .collect();
+            report.failed_gates = cfg_iter!(self.gates).enumerate()
+                .filter_map(|(index, gate)| {
+                    let expected = gate.evaluate(&witness);
+                    let actual = &witness[gate.output()];
+                    (expected != *actual).then(|| FailedGate { index, output: gate.output(), expected, actual: actual.clone() })
+                })
+                .collect();
+            report.unsatisfied_constraints = r1cs.check_witness(&witness).unwrap_or_default();
+        }
+        report.duration = start.elapsed();
+        report
+    }
+
+    /// Generates the proof, then saves it and the R1CS to the artifact paths.
+    ///
+    /// # Parameters
+    /// - `artifacts`: The paths to save the R1CS and the proof to.
+    ///
+    /// # Returns
+    /// - `Result<()>`: The result of the file operations, or an `Input` error if the circuit has no inputs.
+    pub fn generate_proof(&self, artifacts: &Artifacts) -> Result<()> {
+        // Ensure inputs are added before generating proof
+        if self.inputs.is_empty() {
+            return Err(Error::Input("No inputs available to generate proof".to_string()));
+        }
+
+        let r1cs = self.to_r1cs();
 
         // Save the R1CS to a binary file
-        r1cs.save_to_binary("r1cs_file.bin");
+        r1cs.save_to_binary(&artifacts.r1cs)?;
 
         // Generate the witness and proof
         let witness = r1cs.generate_witness();
         let proof = r1cs.generate_proof(&witness);
 
         // Save the proof to a specified file
-        proof.save_to_binary(proof_file).expect("failed to save the proof");
+        proof.save_to_binary(&artifacts.proof)
     }
 
-    /// Verifies the proof by reading from a binary file.
+    /// Verifies the proof by reading it and the R1CS from the artifact paths.
+    ///
+    /// The loaded proof has no witness, and the built-in proof system cannot
+    /// be verified without one, so the wires of this circuit are restored as
+    /// its witness: the proof is only valid if its commitment opens to them
+    /// and they satisfy the loaded constraints.
     ///
     /// # Parameters
-    /// - `proof_file`: The name of the file to read the proof from.
+    /// - `artifacts`: The paths written by `generate_proof`.
     ///
     /// # Returns
-    /// - `bool`: `true` if the proof is valid, otherwise `false`.
-    pub fn verify_proof(&self, proof_file: &str) -> bool {
-        let proof_data = std::fs::read(proof_file).expect("Could not read proof file");
+    /// - `Result<bool>`: `true` if the proof is valid, otherwise `false`, or an error if either file
+    ///   cannot be loaded.
+    pub fn verify_proof(&self, artifacts: &Artifacts) -> Result<bool> {
+        let mut proof = Proof::load_from_binary(&artifacts.proof)?;
+        // Proofs are over the default field, which only loads as a toy field
+        let r1cs = R1CS::load_from_binary(&artifacts.r1cs, &ModulusPolicy::toy())?;
+        proof.witness = self.to_r1cs().generate_witness().iter().map(FieldElement::get_value).collect();
+        Ok(Proof::verify_proof(&proof, &r1cs))
+    }
+}
 
-        let proof = bincode::deserialize::<Proof>(&proof_data).expect("Failed to deserialize proof");
+impl ConstraintSystem for Circuit {
+    fn alloc_private(&mut self, value: FieldElement) -> usize {
+        self.add_input(value)
+    }
 
-        // Ensure that witness is Vec<FieldElement> and not Vec<BigInt>
-        let witness: Vec<FieldElement> = proof.witness.iter()
-            .map(|value| FieldElement::new(value.clone()))
-            .collect();
+    fn alloc_public(&mut self, value: FieldElement) -> usize {
+        let wire = self.add_input(value);
+        self.public_inputs.push(wire);
+        wire
+    }
 
-        let r1cs = R1CS::load_from_binary("r1cs_file.bin");
+    fn value(&self, variable: usize) -> &FieldElement {
+        &self.inputs[variable]
+    }
 
-        let is_valid = r1cs.verify_witness(&witness);
+    fn enforce_mul(&mut self, a: usize, b: usize, output: usize) {
+        self.add_gate(Gate::Mul(a, b, output));
+    }
 
-        println!("Proof verification result: {}", is_valid);
-        is_valid
+    fn enforce_linear(&mut self, terms: &[(usize, FieldElement)], constant: FieldElement, output: usize) {
+        self.add_gate(Gate::Linear(terms.to_vec(), constant, output));
     }
-}
\ No newline at end of file
+}
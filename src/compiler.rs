@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::time::Instant;
+use num_bigint::BigInt;
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+use crate::circuit::{Circuit, Gate};
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::fingerprint::{CircuitId, CircuitVersion};
+use crate::inputs::Inputs;
+use crate::proof::Proof;
+use crate::trace::trace_phase;
+use crate::verification::VerificationReport;
+
+/// Represents a circuit description as written in JSON.
+///
+/// Wires are referred to by name. The inputs are declared up front and every
+/// gate defines a new wire, so each name is assigned exactly once. Inputs
+/// listed in `public` are public and the others private, and the wires listed
+/// in `outputs` are revealed by every proof. The optional `version` is the
+/// semantic version of the circuit, carried by its keys and proofs:
+///
+/// ```json
+/// {
+///   "version": "1.0.0",
+///   "inputs": ["x", "y"],
+///   "public": ["x"],
+///   "outputs": ["out"],
+///   "gates": [
+///     { "op": "mul", "left": "x", "right": "y", "output": "xy" },
+///     { "op": "linear", "terms": [["xy", "1"], ["x", "2"]], "constant": "5", "output": "out" }
+///   ]
+/// }
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CircuitSource {
+    /// The names of the input wires, in the order their values are supplied.
+    pub inputs: Vec<String>,
+    /// The names of the public inputs.
+    #[serde(default)]
+    pub public: Vec<String>,
+    /// The names of the wires exposed as public outputs.
+    #[serde(default)]
+    pub outputs: Vec<String>,
+    /// The gates of the circuit, evaluated in order.
+    pub gates: Vec<GateSource>,
+    /// The version of the circuit as `major.minor.patch`, `0.0.0` if omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// Represents a gate in a circuit description.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum GateSource {
+    /// Addition gate: `output = left + right`.
+    Add { left: String, right: String, output: String },
+    /// Multiplication gate: `output = left * right`.
+    Mul { left: String, right: String, output: String },
+    /// Linear gate: `output = sum(coefficient * wire) + constant`.
+    Linear {
+        terms: Vec<(String, String)>,
+        #[serde(default)]
+        constant: Option<String>,
+        output: String,
+    },
+}
+
+/// Represents a circuit compiled from its description, ready to be assigned inputs.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompiledCircuit {
+    /// The names of the wires, indexed by wire.
+    pub wire_names: Vec<String>,
+    /// The number of input wires, which come first.
+    pub num_inputs: usize,
+    /// The indices of the public input wires.
+    pub public_inputs: Vec<usize>,
+    /// The gates of the circuit, with each output wire following its inputs.
+    pub gates: Vec<Gate>,
+    /// The indices of the wires exposed as public outputs.
+    pub public_outputs: Vec<usize>,
+    /// The version of the circuit.
+    pub version: CircuitVersion,
+}
+
+impl CompiledCircuit {
+    /// Compiles a circuit from its JSON description.
+    ///
+    /// # Parameters
+    /// - `json`: The circuit description.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The compiled circuit, or an error if the description is invalid.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let source: CircuitSource = serde_json::from_str(json)?;
+        Self::compile(&source)
+    }
+
+    /// Compiles a parsed circuit description.
+    ///
+    /// # Parameters
+    /// - `source`: The circuit description.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The compiled circuit, or an error if a wire is undefined or defined twice
+    ///   or the version is malformed.
+    pub fn compile(source: &CircuitSource) -> Result<Self> {
+        trace_phase!("compile", inputs = source.inputs.len(), gates = source.gates.len());
+        let version = match &source.version {
+            Some(version) => version.parse().map_err(|_| Error::Compile(format!("Invalid version `{}`, expected major.minor.patch", version)))?,
+            None => CircuitVersion::default(),
+        };
+        let mut wires: HashMap<String, usize> = HashMap::new();
+        let mut wire_names = Vec::new();
+
+        for name in &source.inputs {
+            define(&mut wires, &mut wire_names, name)?;
+        }
+        let public_inputs = source.public.iter()
+            .map(|name| match wires.get(name) {
+                Some(index) => Ok(*index),
+                None => Err(Error::Compile(format!("Public input `{}` is not declared as an input", name))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut gates = Vec::with_capacity(source.gates.len());
+        for gate in &source.gates {
+            let compiled = match gate {
+                GateSource::Add { left, right, output } => {
+                    let (a, b) = (lookup(&wires, left)?, lookup(&wires, right)?);
+                    Gate::Add(a, b, define(&mut wires, &mut wire_names, output)?)
+                },
+                GateSource::Mul { left, right, output } => {
+                    let (a, b) = (lookup(&wires, left)?, lookup(&wires, right)?);
+                    Gate::Mul(a, b, define(&mut wires, &mut wire_names, output)?)
+                },
+                GateSource::Linear { terms, constant, output } => {
+                    let terms = terms.iter()
+                        .map(|(wire, coeff)| Ok((lookup(&wires, wire)?, parse_constant(coeff)?)))
+                        .collect::<Result<Vec<_>>>()?;
+                    let constant = match constant {
+                        Some(constant) => parse_constant(constant)?,
+                        None => FieldElement::new(BigInt::zero()),
+                    };
+                    Gate::Linear(terms, constant, define(&mut wires, &mut wire_names, output)?)
+                },
+            };
+            gates.push(compiled);
+        }
+        let public_outputs = source.outputs.iter()
+            .map(|name| match wires.get(name) {
+                Some(index) => Ok(*index),
+                None => Err(Error::Compile(format!("Output `{}` is not a wire of the circuit", name))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(CompiledCircuit {
+            wire_names,
+            num_inputs: source.inputs.len(),
+            public_inputs,
+            gates,
+            public_outputs,
+            version,
+        })
+    }
+
+    /// Assigns values to the inputs and evaluates every gate.
+    ///
+    /// # Parameters
+    /// - `inputs`: The values of the input wires, in declaration order.
+    ///
+    /// # Returns
+    /// - `Result<Circuit>`: The circuit with every wire assigned, or an error if the
+    ///   number of inputs does not match.
+    pub fn assign(&self, inputs: &[FieldElement]) -> Result<Circuit> {
+        self.assign_with(inputs, |_| Ok(()))
+    }
+
+    /// Assigns values to the inputs and evaluates every gate, calling back after each gate.
+    ///
+    /// # Parameters
+    /// - `inputs`: The values of the input wires, in declaration order.
+    /// - `after_gate`: Called with the number of gates evaluated so far; an error stops the evaluation.
+    ///
+    /// # Returns
+    /// - `Result<Circuit>`: The circuit with every wire assigned, or an error if the
+    ///   number of inputs does not match or the callback failed.
+    pub(crate) fn assign_with(&self, inputs: &[FieldElement], mut after_gate: impl FnMut(usize) -> Result<()>) -> Result<Circuit> {
+        trace_phase!("witness", gates = self.gates.len());
+        if inputs.len() != self.num_inputs {
+            return Err(Error::Input(format!("Expected {} inputs, got {}", self.num_inputs, inputs.len())));
+        }
+
+        let mut circuit = Circuit::new();
+        circuit.set_version(self.version);
+        for input in inputs {
+            circuit.add_input(input.clone());
+        }
+        for wire in &self.public_inputs {
+            circuit.expose_input(*wire);
+        }
+        let mut values = inputs.to_vec();
+        for (index, gate) in self.gates.iter().enumerate() {
+            let value = gate.evaluate(&values);
+            values.push(value.clone());
+            circuit.add_input(value);
+            circuit.add_gate(gate.clone());
+            after_gate(index + 1)?;
+        }
+        for wire in &self.public_outputs {
+            circuit.expose_output(*wire);
+        }
+        Ok(circuit)
+    }
+
+    /// Assigns named inputs and evaluates every gate.
+    ///
+    /// # Parameters
+    /// - `inputs`: The named inputs, each in the section matching its visibility.
+    ///
+    /// # Returns
+    /// - `Result<Circuit>`: The circuit with every wire assigned, or an error if an input
+    ///   is missing, unknown or given with the wrong visibility.
+    pub fn assign_inputs(&self, inputs: &Inputs) -> Result<Circuit> {
+        self.assign(&self.input_values(inputs)?)
+    }
+
+    /// Orders named inputs as the input wires are declared.
+    ///
+    /// # Parameters
+    /// - `inputs`: The named inputs, each in the section matching its visibility.
+    ///
+    /// # Returns
+    /// - `Result<Vec<FieldElement>>`: The values of the input wires, or an error if an input
+    ///   is missing, unknown or given with the wrong visibility.
+    pub(crate) fn input_values(&self, inputs: &Inputs) -> Result<Vec<FieldElement>> {
+        let mut values = Vec::with_capacity(self.num_inputs);
+        for (index, name) in self.wire_names[..self.num_inputs].iter().enumerate() {
+            let public = self.public_inputs.contains(&index);
+            let (section, other) = if public { (&inputs.public, &inputs.private) } else { (&inputs.private, &inputs.public) };
+            match section.get(name) {
+                Some(value) => values.push(value.clone()),
+                None if other.contains_key(name) => return Err(Error::Input(format!(
+                    "Input `{}` is {}, but was given as {}",
+                    name,
+                    if public { "public" } else { "private" },
+                    if public { "private" } else { "public" },
+                ))),
+                None => return Err(Error::Input(format!("Input `{}` is missing", name))),
+            }
+        }
+
+        let declared = &self.wire_names[..self.num_inputs];
+        if let Some(name) = inputs.public.keys().chain(inputs.private.keys()).find(|name| !declared.contains(name)) {
+            return Err(Error::Input(format!("Unknown input `{}`", name)));
+        }
+        Ok(values)
+    }
+
+    /// Computes the id of the circuit's constraint system, which does not depend on the inputs.
+    ///
+    /// # Returns
+    /// - `CircuitId`: The id shared by every proof of the circuit.
+    pub fn circuit_id(&self) -> CircuitId {
+        let zeros = vec![FieldElement::new(BigInt::zero()); self.num_inputs];
+        self.assign(&zeros).expect("Assigning one value per input cannot fail").to_r1cs().circuit_id()
+    }
+
+    /// Verifies a proof, rebuilding the circuit from the inputs carried by its witness.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to verify.
+    ///
+    /// # Returns
+    /// - `Result<bool>`: `true` if the proof is valid, otherwise `false`, including for a proof without
+    ///   its witness, or `Error::VersionMismatch` if the proof was generated by a release of the circuit
+    ///   this one does not accept.
+    pub fn verify(&self, proof: &Proof) -> Result<bool> {
+        Ok(self.verify_report(proof)?.is_valid())
+    }
+
+    /// Verifies a proof like `verify`, reporting every check.
+    ///
+    /// The built-in proof system can only check the constraints on the
+    /// witness, so a proof without one, such as a decoded proof, is reported
+    /// as unverifiable: only its circuit version and id, its values and the
+    /// number of its public inputs and outputs are checked, and it is never
+    /// valid. `Verifier::check` also compares the public inputs with the
+    /// claimed ones.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to verify.
+    ///
+    /// # Returns
+    /// - `Result<VerificationReport>`: The outcome of each check, or `Error::VersionMismatch` if the
+    ///   proof was generated by a release of the circuit this one does not accept.
+    pub fn verify_report(&self, proof: &Proof) -> Result<VerificationReport> {
+        proof.version.check_compatible(&self.version)?;
+        if proof.witness.is_empty() {
+            let start = Instant::now();
+            let mut report = VerificationReport::public(self.version, proof);
+            report.circuit_id = Some(self.circuit_id());
+            report.public_inputs_valid = proof.public_inputs.len() == self.public_inputs.len();
+            report.outputs_valid = proof.outputs.len() == self.public_outputs.len();
+            report.duration = start.elapsed();
+            return Ok(report);
+        }
+        if proof.witness.len() < self.num_inputs {
+            // Without every input the circuit cannot be rebuilt, let alone checked
+            return Ok(VerificationReport::new(self.wire_names.len() + 1, self.version, proof));
+        }
+        let inputs: Vec<FieldElement> = proof.witness[..self.num_inputs].iter()
+            .map(|value| FieldElement::new(value.clone()))
+            .collect();
+        Ok(self.assign(&inputs)?.verify_report(proof))
+    }
+
+    /// Serializes the compiled circuit to bytes.
+    ///
+    /// # Returns
+    /// - `Result<Vec<u8>>`: The encoded circuit.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Deserializes a compiled circuit from bytes.
+    ///
+    /// # Parameters
+    /// - `bytes`: The encoded circuit.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The decoded circuit.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
+
+/// Defines a new wire, rejecting names that are already in use.
+fn define(wires: &mut HashMap<String, usize>, wire_names: &mut Vec<String>, name: &str) -> Result<usize> {
+    if wires.contains_key(name) {
+        return Err(Error::Compile(format!("Wire `{}` is defined more than once", name)));
+    }
+    let index = wire_names.len();
+    wires.insert(name.to_string(), index);
+    wire_names.push(name.to_string());
+    Ok(index)
+}
+
+/// Looks up a wire that must already be defined.
+fn lookup(wires: &HashMap<String, usize>, name: &str) -> Result<usize> {
+    wires.get(name).copied().ok_or_else(|| Error::Compile(format!("Wire `{}` is used before it is defined", name)))
+}
+
+/// Parses a constant or coefficient of the description.
+fn parse_constant(value: &str) -> Result<FieldElement> {
+    value.parse().map_err(|_| Error::Compile(format!("Invalid constant `{}`", value)))
+}
@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+use num_bigint::BigInt;
+use serde::{Deserialize, Serialize};
+use crate::circuit::Artifacts;
+use crate::error::{Error, Result};
+use crate::field::{FieldElement, ModulusPolicy};
+
+/// The name of the project configuration file read by the CLI.
+pub const CONFIG_FILE: &str = "zk.toml";
+
+/// The BN254 scalar field prime used by the Groth16 backend.
+const BN254_PRIME: &str = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+/// Represents the prime field circuits are defined over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Field {
+    /// The default field of `FieldElement::new`, modulo `1_000_000_007`.
+    Default,
+    /// The BN254 scalar field.
+    Bn254,
+}
+
+impl Field {
+    /// Gets the modulus of the field.
+    ///
+    /// # Returns
+    /// - `BigInt`: The prime modulus.
+    pub fn modulus(&self) -> BigInt {
+        match self {
+            Field::Default => FieldElement::default_modulus(),
+            Field::Bn254 => BN254_PRIME.parse().expect("Invalid BN254 prime"),
+        }
+    }
+
+    /// Gets the policy files in this field are loaded with: the 30-bit default
+    /// field is a toy field, so only it needs the opt-in.
+    ///
+    /// # Returns
+    /// - `ModulusPolicy`: The policy accepting the modulus of the field.
+    pub fn policy(&self) -> ModulusPolicy {
+        match self {
+            Field::Default => ModulusPolicy::toy(),
+            Field::Bn254 => ModulusPolicy::default(),
+        }
+    }
+}
+
+/// Represents the hash function used by Merkle trees and hashing gadgets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashFunction {
+    /// The Poseidon permutation.
+    Poseidon,
+    /// The Poseidon2 permutation.
+    Poseidon2,
+}
+
+/// Represents the proof system used to prove and verify circuits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// The built-in transparent proof system.
+    Builtin,
+    /// Groth16 over BN254, from the `ark-backend` feature.
+    Groth16,
+}
+
+/// Represents the directories artifacts are written to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ArtifactDirs {
+    /// The directory of compiled circuits, R1CS files and keys.
+    pub build: PathBuf,
+    /// The directory of proofs.
+    pub proofs: PathBuf,
+}
+
+impl Default for ArtifactDirs {
+    fn default() -> Self {
+        ArtifactDirs { build: PathBuf::from("."), proofs: PathBuf::from(".") }
+    }
+}
+
+/// Represents a project configuration, as read from `zk.toml`:
+///
+/// ```toml
+/// field = "bn254"
+/// hash = "poseidon2"
+/// backend = "groth16"
+/// threads = 8
+/// memory_limit_mb = 4096
+///
+/// [artifacts]
+/// build = "build"
+/// proofs = "proofs"
+/// ```
+///
+/// Every entry is optional and defaults to the values of `Config::default`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The field circuits are defined over.
+    pub field: Field,
+    /// The hash function of Merkle trees and hashing gadgets.
+    pub hash: HashFunction,
+    /// The proof system.
+    pub backend: Backend,
+    /// The number of proving threads with the `parallel` feature, or one per core if unset.
+    pub threads: Option<usize>,
+    /// The ceiling on heap memory in MiB with the `memory` feature, or no ceiling if unset.
+    pub memory_limit_mb: Option<usize>,
+    /// The artifact directories.
+    pub artifacts: ArtifactDirs,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            field: Field::Default,
+            hash: HashFunction::Poseidon,
+            backend: Backend::Builtin,
+            threads: None,
+            memory_limit_mb: None,
+            artifacts: ArtifactDirs::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Parses a configuration from TOML and checks that its choices are compatible.
+    ///
+    /// # Parameters
+    /// - `toml`: The contents of a `zk.toml` file.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The configuration, or an error if it is malformed or inconsistent.
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let config: Config = toml::from_str(toml).map_err(|err| Error::Input(format!("Invalid configuration: {}", err)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reads a configuration file.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the file.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The configuration, or an error if the file cannot be read or parsed.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Reads a configuration file if it exists, falling back to the defaults otherwise.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the file, usually `zk.toml`.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The configuration, or an error if an existing file is invalid.
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if path.exists() {
+            Self::from_file(path)
+        } else {
+            Ok(Config::default())
+        }
+    }
+
+    /// Checks that the field and backend are compatible and the thread count and memory limit are positive.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the combination is not supported.
+    pub fn validate(&self) -> Result<()> {
+        if self.threads == Some(0) {
+            return Err(Error::Input("The number of threads must be positive".to_string()));
+        }
+        if self.memory_limit_mb == Some(0) {
+            return Err(Error::Input("The memory limit must be positive".to_string()));
+        }
+        match (self.backend, self.field) {
+            (Backend::Builtin, Field::Default) | (Backend::Groth16, Field::Bn254) => Ok(()),
+            (Backend::Builtin, field) => Err(Error::Input(format!("The built-in backend only supports the default field, not {:?}", field))),
+            (Backend::Groth16, field) => Err(Error::Input(format!("The Groth16 backend only supports the BN254 field, not {:?}", field))),
+        }
+    }
+
+    /// Gets the artifact paths of a named circuit: the R1CS in the build
+    /// directory and the proof in the proofs directory.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the circuit.
+    ///
+    /// # Returns
+    /// - `Artifacts`: The artifact paths.
+    pub fn artifacts(&self, name: &str) -> Artifacts {
+        Artifacts {
+            r1cs: Artifacts::new(&self.artifacts.build, name).r1cs,
+            proof: Artifacts::new(&self.artifacts.proofs, name).proof,
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use alloc::vec::Vec;
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::field::FieldElement;
+
+/// Allocates variables and enforces constraints on them: the interface gadgets
+/// and statements build circuits through, so they work with any frontend.
+///
+/// `Circuit` implements it, and a third-party frontend or backend only needs
+/// the allocation methods, `value`, `enforce_mul` and `enforce_linear` to
+/// reuse every gadget of the crate. Allocating computes the witness value at
+/// the same time, so implementations keep the value of every variable.
+pub trait ConstraintSystem {
+    /// Allocates a private variable.
+    ///
+    /// # Parameters
+    /// - `value`: The value of the variable.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the variable.
+    fn alloc_private(&mut self, value: FieldElement) -> usize;
+
+    /// Allocates a public input variable, whose value the verifier knows.
+    ///
+    /// # Parameters
+    /// - `value`: The value of the variable.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the variable.
+    fn alloc_public(&mut self, value: FieldElement) -> usize;
+
+    /// Gets the value assigned to a variable.
+    ///
+    /// # Parameters
+    /// - `variable`: The index of the variable.
+    ///
+    /// # Returns
+    /// - `&FieldElement`: The value of the variable.
+    fn value(&self, variable: usize) -> &FieldElement;
+
+    /// Enforces `a * b = output`.
+    ///
+    /// # Parameters
+    /// - `a`: The index of the first factor.
+    /// - `b`: The index of the second factor.
+    /// - `output`: The index of the product.
+    fn enforce_mul(&mut self, a: usize, b: usize, output: usize);
+
+    /// Enforces that a weighted sum of variables plus a constant equals `output`.
+    ///
+    /// # Parameters
+    /// - `terms`: The `(variable, coefficient)` pairs of the sum.
+    /// - `constant`: The constant term.
+    /// - `output`: The index of the result.
+    fn enforce_linear(&mut self, terms: &[(usize, FieldElement)], constant: FieldElement, output: usize);
+
+    /// Allocates a private variable holding `a * b` and constrains it.
+    ///
+    /// # Parameters
+    /// - `a`: The index of the first factor.
+    /// - `b`: The index of the second factor.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the product variable.
+    fn alloc_mul(&mut self, a: usize, b: usize) -> usize {
+        let value = self.value(a).mul(self.value(b));
+        let output = self.alloc_private(value);
+        self.enforce_mul(a, b, output);
+        output
+    }
+
+    /// Allocates a private variable holding a weighted sum of variables plus a
+    /// constant and constrains it.
+    ///
+    /// # Parameters
+    /// - `terms`: The `(variable, coefficient)` pairs of the sum.
+    /// - `constant`: The constant term.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the result variable.
+    fn alloc_linear(&mut self, terms: &[(usize, FieldElement)], constant: FieldElement) -> usize {
+        let value = terms.iter().fold(constant.clone(), |acc, (variable, coeff)| acc.add(&coeff.mul(self.value(*variable))));
+        let output = self.alloc_private(value);
+        self.enforce_linear(terms, constant, output);
+        output
+    }
+
+    /// Constrains two variables to hold the same value.
+    ///
+    /// # Parameters
+    /// - `a`: The index of the first variable.
+    /// - `b`: The index of the second variable.
+    fn enforce_equal(&mut self, a: usize, b: usize) {
+        self.enforce_linear(&[(a, FieldElement::new(BigInt::one()))], FieldElement::new(BigInt::zero()), b);
+    }
+
+    /// Allocates private advice: a value the prover computes from other
+    /// variables while generating the witness, without any constraint.
+    ///
+    /// Hints supply what a circuit checks more cheaply than it computes, such
+    /// as an inverse or a quotient. Nothing ties the advice to its inputs, so
+    /// the caller must constrain it.
+    ///
+    /// # Parameters
+    /// - `inputs`: The variables the hint reads.
+    /// - `hint`: Computes the advice from the values of the inputs.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the advice variable.
+    fn alloc_hint<F: FnOnce(&[FieldElement]) -> FieldElement>(&mut self, inputs: &[usize], hint: F) -> usize {
+        self.alloc_hints(inputs, |values| Vec::from([hint(values)]))[0]
+    }
+
+    /// Allocates several private advice values computed together, such as a
+    /// quotient and a remainder, without any constraint.
+    ///
+    /// # Parameters
+    /// - `inputs`: The variables the hint reads.
+    /// - `hint`: Computes the advice from the values of the inputs.
+    ///
+    /// # Returns
+    /// - `Vec<usize>`: The indices of the advice variables, in the order the hint returned them.
+    fn alloc_hints<F: FnOnce(&[FieldElement]) -> Vec<FieldElement>>(&mut self, inputs: &[usize], hint: F) -> Vec<usize> {
+        let values: Vec<FieldElement> = inputs.iter().map(|variable| self.value(*variable).clone()).collect();
+        hint(&values).into_iter().map(|value| self.alloc_private(value)).collect()
+    }
+}
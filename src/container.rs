@@ -0,0 +1,257 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::path::Path;
+use num_bigint::{BigInt, Sign};
+use sha2::{Digest, Sha256};
+use crate::error::{Error, Result};
+use crate::field::is_probable_prime;
+
+/// The version of the container format written by `Container::new`.
+///
+/// The header layout is the same in every version; the version records the
+/// layout of the payload, so loaders upgrade older payloads in memory:
+/// - `1`: The first release of the container.
+/// - `2`: R1CS and proofs gained their public outputs.
+/// - `3`: Proofs gained the id of their constraint system.
+/// - `4`: R1CS and proofs gained the version of their circuit.
+/// - `5`: Hashes gained a domain tag; proofs committed under earlier
+///   versions still load but no longer verify.
+/// - `6`: R1CS and proofs gained their public inputs, and proofs no longer
+///   carry the private witness.
+pub const FORMAT_VERSION: u32 = 6;
+/// The oldest container format version that can still be decoded.
+pub const MIN_FORMAT_VERSION: u32 = 1;
+/// The magic bytes of proof files.
+pub const PROOF_MAGIC: &[u8; 4] = b"ZKPF";
+/// The magic bytes of R1CS files.
+pub const R1CS_MAGIC: &[u8; 4] = b"ZKRC";
+/// The magic bytes of witness files.
+pub const WITNESS_MAGIC: &[u8; 4] = b"ZKWT";
+/// The magic bytes of proving key files.
+pub const PROVING_KEY_MAGIC: &[u8; 4] = b"ZKPK";
+/// The magic bytes of verifying key files.
+pub const VERIFYING_KEY_MAGIC: &[u8; 4] = b"ZKVK";
+
+/// The size of the SHA-256 checksum in bytes.
+const CHECKSUM_LEN: usize = 32;
+/// The magic bytes starting every zstd frame.
+#[cfg(feature = "std")]
+pub const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Represents the compression applied when writing a container to a file.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// The container is written as is.
+    None,
+    /// The container is compressed with zstd at the given level.
+    #[cfg(feature = "zstd")]
+    Zstd(i32),
+}
+
+/// Represents a versioned binary container around a serialized payload.
+///
+/// The encoding is the magic bytes, the format version (`u32`), the length of
+/// the field modulus (`u32`) followed by its big-endian bytes, the payload
+/// length (`u64`), the SHA-256 checksum of the payload and finally the
+/// payload itself. All integers are little-endian.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Container {
+    /// The magic bytes identifying the kind of payload.
+    pub magic: [u8; 4],
+    /// The format version the payload is laid out in.
+    pub version: u32,
+    /// The modulus of the field the payload is defined over.
+    pub modulus: BigInt,
+    /// The serialized payload.
+    pub payload: Vec<u8>,
+}
+
+impl Container {
+    /// Creates a container for a payload laid out in the current format version.
+    ///
+    /// # Parameters
+    /// - `magic`: The magic bytes identifying the kind of payload.
+    /// - `modulus`: The modulus of the field the payload is defined over.
+    /// - `payload`: The serialized payload.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Container` struct.
+    pub fn new(magic: &[u8; 4], modulus: BigInt, payload: Vec<u8>) -> Self {
+        Container { magic: *magic, version: FORMAT_VERSION, modulus, payload }
+    }
+
+    /// Encodes the container with its format version.
+    ///
+    /// # Returns
+    /// - `Vec<u8>`: The encoded container.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (_, modulus) = self.modulus.to_bytes_be();
+        let mut bytes = Vec::with_capacity(4 + 4 + 4 + modulus.len() + 8 + CHECKSUM_LEN + self.payload.len());
+        bytes.extend_from_slice(&self.magic);
+        bytes.extend_from_slice(&self.version.to_le_bytes());
+        bytes.extend_from_slice(&(modulus.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&modulus);
+        bytes.extend_from_slice(&(self.payload.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&Sha256::digest(&self.payload));
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+
+    /// Decodes a container, checking its magic bytes, version, length and checksum.
+    ///
+    /// Any version from `MIN_FORMAT_VERSION` to `FORMAT_VERSION` is accepted
+    /// and recorded, so the caller can upgrade the payload.
+    ///
+    /// # Parameters
+    /// - `magic`: The expected magic bytes.
+    /// - `bytes`: The encoded container.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The decoded container, an `UnsupportedVersion` error for a version
+    ///   outside the supported range, or a serialization error describing the first other
+    ///   check that failed, including a modulus that is not prime.
+    pub fn from_bytes(magic: &[u8; 4], bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader { bytes };
+        if reader.take(4)? != magic {
+            return Err(Error::Serialization(format!("Not a {} file", String::from_utf8_lossy(magic))));
+        }
+        let version = reader.u32()?;
+        if !(MIN_FORMAT_VERSION..=FORMAT_VERSION).contains(&version) {
+            return Err(Error::UnsupportedVersion { found: version, min: MIN_FORMAT_VERSION, max: FORMAT_VERSION });
+        }
+
+        let modulus_len = reader.u32()? as usize;
+        let modulus = BigInt::from_bytes_be(Sign::Plus, reader.take(modulus_len)?);
+        if !is_probable_prime(&modulus) {
+            return Err(Error::Serialization(format!("The field modulus {} is not prime", modulus)));
+        }
+        let payload_len = reader.u64()?;
+        let checksum = reader.take(CHECKSUM_LEN)?;
+        if reader.bytes.len() as u64 != payload_len {
+            return Err(Error::Serialization(format!(
+                "Payload is {} bytes, expected {}",
+                reader.bytes.len(), payload_len
+            )));
+        }
+        if Sha256::digest(reader.bytes).as_slice() != checksum {
+            return Err(Error::Serialization("Payload checksum mismatch".to_string()));
+        }
+
+        Ok(Container { magic: *magic, version, modulus, payload: reader.bytes.to_vec() })
+    }
+
+    /// Checks that the payload is defined over the expected field.
+    ///
+    /// # Parameters
+    /// - `modulus`: The expected field modulus.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the moduli differ.
+    pub fn expect_modulus(&self, modulus: &BigInt) -> Result<()> {
+        if self.modulus != *modulus {
+            return Err(Error::Serialization(format!(
+                "Field modulus {} does not match the expected {}",
+                self.modulus, modulus
+            )));
+        }
+        Ok(())
+    }
+
+    /// Writes the container to a file.
+    ///
+    /// # Parameters
+    /// - `filename`: The path of the file to write.
+    /// - `compression`: The compression applied to the whole container.
+    ///
+    /// # Returns
+    /// - `Result<()>`: The result of the file operation.
+    #[cfg(feature = "std")]
+    pub fn write_to_file(&self, filename: impl AsRef<Path>, compression: Compression) -> Result<()> {
+        let bytes = match compression {
+            Compression::None => self.to_bytes(),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd(level) => zstd::encode_all(self.to_bytes().as_slice(), level)?,
+        };
+        std::fs::write(filename, bytes)?;
+        Ok(())
+    }
+
+    /// Reads a container from a file, decompressing it first if it is a zstd frame.
+    ///
+    /// # Parameters
+    /// - `magic`: The expected magic bytes.
+    /// - `filename`: The path of the file to read.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The decoded container, or an error if the file cannot be
+    ///   read, is compressed without the `zstd` feature, or is not a valid container.
+    #[cfg(feature = "std")]
+    pub fn read_from_file(magic: &[u8; 4], filename: impl AsRef<Path>) -> Result<Self> {
+        let bytes = std::fs::read(&filename)?;
+        if !bytes.starts_with(&ZSTD_MAGIC) {
+            return Container::from_bytes(magic, &bytes);
+        }
+
+        #[cfg(feature = "zstd")]
+        return Container::from_bytes(magic, &zstd::decode_all(bytes.as_slice())?);
+        #[cfg(not(feature = "zstd"))]
+        Err(Error::Serialization(format!("{} is zstd-compressed, which requires the zstd feature", filename.as_ref().display())))
+    }
+}
+
+/// Reads the header fields of a container in order.
+struct Reader<'a> {
+    /// The bytes left to read.
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Takes the next `len` bytes, failing on truncated input.
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(Error::Serialization("Truncated container header".to_string()));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    /// Reads a little-endian `u32`.
+    fn u32(&mut self) -> Result<u32> {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    /// Reads a little-endian `u64`.
+    fn u64(&mut self) -> Result<u64> {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use super::*;
+
+    #[test]
+    fn prime_moduli_round_trip() {
+        let container = Container::new(PROOF_MAGIC, BigInt::from(1_000_000_007), vec![1, 2, 3]);
+        let decoded = Container::from_bytes(PROOF_MAGIC, &container.to_bytes()).unwrap();
+        assert_eq!(decoded.modulus, container.modulus);
+        assert_eq!(decoded.payload, container.payload);
+    }
+
+    #[test]
+    fn zero_and_composite_moduli_are_rejected() {
+        for modulus in [0, 1, 1_000_000_008].iter() {
+            let bytes = Container::new(PROOF_MAGIC, BigInt::from(*modulus), vec![1, 2, 3]).to_bytes();
+            assert!(Container::from_bytes(PROOF_MAGIC, &bytes).is_err(), "{} accepted", modulus);
+        }
+    }
+}
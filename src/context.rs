@@ -0,0 +1,86 @@
+use alloc::collections::BTreeMap;
+use num_bigint::BigInt;
+use crate::domain::Domain;
+use crate::field::FieldElement;
+use crate::poseidon::Poseidon;
+use crate::proof::Proof;
+use crate::r1cs::R1CS;
+
+/// Caches the precomputation shared by every proof over the same field, so
+/// repeated proofs for a circuit do not rebuild it on each call: the Poseidon
+/// parameters of the witness commitment and the evaluation domains, with their
+/// roots of unity and barycentric weights, by size.
+#[derive(Clone, Debug)]
+pub struct ProverContext {
+    /// The Poseidon parameters used to commit to witnesses.
+    poseidon: Poseidon,
+    /// The evaluation domains built so far, by size.
+    domains: BTreeMap<usize, Domain>,
+}
+
+impl Default for ProverContext {
+    fn default() -> Self {
+        ProverContext::new()
+    }
+}
+
+impl ProverContext {
+    /// Creates a context for the crate's default field.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `ProverContext` struct.
+    pub fn new() -> Self {
+        ProverContext { poseidon: Poseidon::new(), domains: BTreeMap::new() }
+    }
+
+    /// Gets the Poseidon parameters used to commit to witnesses.
+    ///
+    /// # Returns
+    /// - `&Poseidon`: The Poseidon parameters.
+    pub fn poseidon(&self) -> &Poseidon {
+        &self.poseidon
+    }
+
+    /// Gets the evaluation domain of a given size, building it on first use.
+    ///
+    /// The domain is made of the `size`-th roots of unity when the field has
+    /// them, and of the points `1, 2, ..., size` otherwise.
+    ///
+    /// # Parameters
+    /// - `size`: The number of points, at least 1.
+    ///
+    /// # Returns
+    /// - `&Domain`: The cached domain.
+    pub fn domain(&mut self, size: usize) -> &Domain {
+        let modulus = &self.poseidon.modulus;
+        self.domains.entry(size).or_insert_with(|| {
+            Domain::roots_of_unity(size, modulus).unwrap_or_else(|| {
+                Domain::new((1..=size).map(|x| FieldElement::new_with_modulus(BigInt::from(x), modulus.clone())).collect())
+            })
+        })
+    }
+
+    /// Generates a proof from an R1CS and a witness, reusing the cached parameters.
+    ///
+    /// # Parameters
+    /// - `r1cs`: The R1CS constraints.
+    /// - `witness`: The witness values.
+    ///
+    /// # Returns
+    /// - `Proof`: The generated proof.
+    pub fn generate_proof(&self, r1cs: &R1CS, witness: &[FieldElement]) -> Proof {
+        Proof::generate_proof_with(self, r1cs, witness)
+    }
+
+    /// Verifies a proof against an R1CS, reusing the cached parameters.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to verify.
+    /// - `r1cs`: The R1CS constraints.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the proof is valid, otherwise `false`.
+    pub fn verify_proof(&self, proof: &Proof, r1cs: &R1CS) -> bool {
+        Proof::verify_proof_with(self, proof, r1cs)
+    }
+}
@@ -0,0 +1,132 @@
+use alloc::vec::Vec;
+use num_bigint::{BigInt, Sign};
+pub use subtle::{Choice, ConstantTimeEq};
+
+/// Compares two integers in time depending only on `width`, as long as both
+/// take at most `width` bytes.
+///
+/// Comparing commitments with `==` stops at the first differing limb, so a
+/// verifier serving requests would leak through its response time how close
+/// a forged value came.
+///
+/// # Parameters
+/// - `a`: The first integer.
+/// - `b`: The second integer.
+/// - `width`: The number of bytes both integers are padded to, usually that of the modulus.
+///
+/// # Returns
+/// - `Choice`: `1` if the integers are equal, otherwise `0`.
+pub fn bigint_eq(a: &BigInt, b: &BigInt, width: usize) -> Choice {
+    let (a_sign, a_bytes) = padded(a, width);
+    let (b_sign, b_bytes) = padded(b, width);
+    a_sign.ct_eq(&b_sign) & a_bytes.ct_eq(&b_bytes)
+}
+
+/// Compares two lists of integers element by element without stopping at
+/// the first difference. Only the lengths, which are public, are compared
+/// in variable time.
+///
+/// # Parameters
+/// - `a`: The first list.
+/// - `b`: The second list.
+/// - `width`: The number of bytes every integer is padded to.
+///
+/// # Returns
+/// - `Choice`: `1` if the lists have the same length and elements, otherwise `0`.
+pub fn bigints_eq(a: &[BigInt], b: &[BigInt], width: usize) -> Choice {
+    if a.len() != b.len() {
+        return Choice::from(0);
+    }
+    a.iter().zip(b).fold(Choice::from(1), |equal, (a, b)| equal & bigint_eq(a, b, width))
+}
+
+/// Converts the outcome of a check into a `Choice`, to combine it with the
+/// others without branching.
+///
+/// # Parameters
+/// - `value`: The outcome.
+///
+/// # Returns
+/// - `Choice`: `1` if the outcome is `true`, otherwise `0`.
+pub fn choice(value: bool) -> Choice {
+    Choice::from(value as u8)
+}
+
+/// Gets the number of bytes of the values of a field.
+///
+/// # Parameters
+/// - `modulus`: The modulus of the field.
+///
+/// # Returns
+/// - `usize`: The byte length of the modulus.
+pub fn field_width(modulus: &BigInt) -> usize {
+    (modulus.bits() as usize).div_ceil(8)
+}
+
+/// Encodes an integer as its sign and its little-endian magnitude padded to
+/// `width` bytes, or longer if it does not fit.
+fn padded(value: &BigInt, width: usize) -> (u8, Vec<u8>) {
+    let (sign, mut bytes) = value.to_bytes_le();
+    if bytes.len() < width {
+        bytes.resize(width, 0);
+    }
+    ((sign == Sign::Minus) as u8, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use crate::field::FieldElement;
+
+    #[test]
+    fn equal_integers_compare_equal() {
+        let value = BigInt::from(0x1234_5678u32);
+        assert!(bool::from(bigint_eq(&value, &value.clone(), 32)));
+        assert!(bool::from(bigint_eq(&BigInt::from(0), &BigInt::from(0), 32)));
+        assert!(bool::from(bigint_eq(&-value.clone(), &-value, 32)));
+    }
+
+    #[test]
+    fn unequal_integers_compare_unequal() {
+        assert!(!bool::from(bigint_eq(&BigInt::from(1), &BigInt::from(2), 32)));
+        assert!(!bool::from(bigint_eq(&BigInt::from(5), &BigInt::from(-5), 32)));
+        assert!(!bool::from(bigint_eq(&BigInt::from(0x100), &BigInt::from(0x1), 32)));
+    }
+
+    #[test]
+    fn integers_of_different_byte_lengths_compare_by_value() {
+        let short = BigInt::from(0xff);
+        let long = BigInt::from(0xff_0000_00ffu64);
+        assert!(!bool::from(bigint_eq(&short, &long, 32)));
+        // Values wider than `width` are not truncated
+        assert!(!bool::from(bigint_eq(&short, &long, 1)));
+        assert!(bool::from(bigint_eq(&long, &long.clone(), 1)));
+    }
+
+    #[test]
+    fn lists_compare_element_by_element() {
+        let values = vec![BigInt::from(1), BigInt::from(2), BigInt::from(3)];
+        assert!(bool::from(bigints_eq(&values, &values.clone(), 32)));
+        let mut changed = values.clone();
+        changed[2] = BigInt::from(4);
+        assert!(!bool::from(bigints_eq(&values, &changed, 32)));
+        assert!(!bool::from(bigints_eq(&values, &values[..2], 32)));
+        assert!(bool::from(bigints_eq(&[], &[], 32)));
+    }
+
+    #[test]
+    fn padding_extends_short_values_only() {
+        assert_eq!(padded(&BigInt::from(0x0102), 4), (0, vec![0x02, 0x01, 0, 0]));
+        assert_eq!(padded(&BigInt::from(-1), 2), (1, vec![1, 0]));
+        assert_eq!(padded(&BigInt::from(0x01_0203u32), 2), (0, vec![0x03, 0x02, 0x01]));
+        assert_eq!(padded(&BigInt::from(0), 3), (0, vec![0, 0, 0]));
+    }
+
+    #[test]
+    fn field_width_rounds_up_to_whole_bytes() {
+        assert_eq!(field_width(&BigInt::from(255)), 1);
+        assert_eq!(field_width(&BigInt::from(256)), 2);
+        assert_eq!(field_width(&FieldElement::default_modulus()), 4);
+    }
+}
@@ -0,0 +1,305 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Write;
+use core::ops::Range;
+use num_bigint::BigInt;
+use num_traits::One;
+use crate::fingerprint::NormalConstraint;
+use crate::r1cs::R1CS;
+
+/// Represents a constraint present in only one of two systems, or rewritten between them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConstraintChange {
+    /// A constraint of the other system with no counterpart in this one.
+    Added {
+        /// The index of the constraint in the other system.
+        index: usize,
+        /// The rendered constraint.
+        constraint: String,
+    },
+    /// A constraint of this system with no counterpart in the other one.
+    Removed {
+        /// The index of the constraint in this system.
+        index: usize,
+        /// The rendered constraint.
+        constraint: String,
+    },
+    /// A constraint rewritten in place of another one.
+    Changed {
+        /// The index of the constraint in this system.
+        before: usize,
+        /// The index of the constraint in the other system.
+        after: usize,
+        /// The rendered constraint of this system.
+        old: String,
+        /// The rendered constraint of the other system.
+        new: String,
+    },
+}
+
+impl fmt::Display for ConstraintChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintChange::Added { index, constraint } => write!(f, "+ #{} {}", index, constraint),
+            ConstraintChange::Removed { index, constraint } => write!(f, "- #{} {}", index, constraint),
+            ConstraintChange::Changed { before, after, old, new } => {
+                write!(f, "~ #{} {} -> #{} {}", before, old, after, new)
+            },
+        }
+    }
+}
+
+/// Describes how one constraint system differs from another, as computed by `R1CS::diff`.
+///
+/// Constraints are compared in normal form, so reordered terms, merged
+/// duplicate terms and zero coefficients are not differences. Variable values
+/// are left out, so two witnesses of the same circuit have an empty diff.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct R1csDiff {
+    /// Whether the systems are over different fields.
+    pub modulus_changed: bool,
+    /// The variables of the other system past the last variable of this one.
+    pub added_wires: Range<usize>,
+    /// The variables of this system past the last variable of the other one.
+    pub removed_wires: Range<usize>,
+    /// Whether the systems take different public inputs.
+    pub public_inputs_changed: bool,
+    /// Whether the systems expose different outputs.
+    pub outputs_changed: bool,
+    /// The added, removed and changed constraints, in order.
+    pub constraints: Vec<ConstraintChange>,
+}
+
+impl R1csDiff {
+    /// Checks whether the systems are constraint-equivalent.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the diff found no difference.
+    pub fn is_empty(&self) -> bool {
+        !self.modulus_changed
+            && self.added_wires.is_empty()
+            && self.removed_wires.is_empty()
+            && !self.public_inputs_changed
+            && !self.outputs_changed
+            && self.constraints.is_empty()
+    }
+}
+
+impl fmt::Display for R1csDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "No differences");
+        }
+        let mut lines = Vec::new();
+        if self.modulus_changed {
+            lines.push(String::from("Modulus changed"));
+        }
+        if !self.added_wires.is_empty() {
+            lines.push(format!("Wires {}..{} added", self.added_wires.start, self.added_wires.end));
+        }
+        if !self.removed_wires.is_empty() {
+            lines.push(format!("Wires {}..{} removed", self.removed_wires.start, self.removed_wires.end));
+        }
+        if self.public_inputs_changed {
+            lines.push(String::from("Public inputs changed"));
+        }
+        if self.outputs_changed {
+            lines.push(String::from("Outputs changed"));
+        }
+        lines.extend(self.constraints.iter().map(|change| format!("{}", change)));
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl R1CS {
+    /// Compares the constraint system with another one, typically the same
+    /// circuit before and after refactoring a gadget.
+    ///
+    /// Constraints are matched along their longest common subsequence, so an
+    /// inserted constraint is reported once instead of shifting every later one;
+    /// a run of removed constraints followed by added ones is reported as changed.
+    ///
+    /// # Parameters
+    /// - `other`: The constraint system to compare with.
+    ///
+    /// # Returns
+    /// - `R1csDiff`: The differences, empty if the systems are constraint-equivalent.
+    pub fn diff(&self, other: &R1CS) -> R1csDiff {
+        let (modulus, other_modulus) = (self.modulus(), other.modulus());
+        let before: Vec<NormalConstraint> = self.constraints.iter()
+            .map(|constraint| NormalConstraint::new(constraint, &modulus))
+            .collect();
+        let after: Vec<NormalConstraint> = other.constraints.iter()
+            .map(|constraint| NormalConstraint::new(constraint, &other_modulus))
+            .collect();
+
+        let mut constraints = Vec::new();
+        let (mut removed, mut added) = (Vec::new(), Vec::new());
+        for step in align(&before, &after) {
+            match step {
+                Step::Keep => flush(&mut constraints, &mut removed, &mut added, &before, &after),
+                Step::Remove(index) => removed.push(index),
+                Step::Add(index) => added.push(index),
+            }
+        }
+        flush(&mut constraints, &mut removed, &mut added, &before, &after);
+
+        let (count, other_count) = (self.variables.len(), other.variables.len());
+        R1csDiff {
+            modulus_changed: modulus != other_modulus,
+            added_wires: count.min(other_count)..other_count,
+            removed_wires: other_count.min(count)..count,
+            public_inputs_changed: self.public_inputs != other.public_inputs,
+            outputs_changed: self.outputs != other.outputs,
+            constraints,
+        }
+    }
+
+    /// Renders the constraint system in normal form, one line per constraint,
+    /// for comparing against a snapshot checked into a test.
+    ///
+    /// # Returns
+    /// - `String`: The modulus, the number of variables, the public inputs, the outputs and the constraints.
+    pub fn snapshot(&self) -> String {
+        let modulus = self.modulus();
+        let mut snapshot = format!("modulus {}\nvariables {}\npublic inputs {:?}\noutputs {:?}\n", modulus, self.variables.len(), self.public_inputs, self.outputs);
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            let _ = writeln!(snapshot, "#{} {}", index, render(&NormalConstraint::new(constraint, &modulus)));
+        }
+        snapshot
+    }
+}
+
+/// Represents one step of the alignment of two constraint lists.
+enum Step {
+    /// Both lists continue with the same constraint.
+    Keep,
+    /// The constraint at this index of the first list has no counterpart.
+    Remove(usize),
+    /// The constraint at this index of the second list has no counterpart.
+    Add(usize),
+}
+
+/// Aligns two constraint lists along their longest common subsequence, after
+/// skipping their common prefix and suffix to keep the table small.
+fn align(before: &[NormalConstraint], after: &[NormalConstraint]) -> Vec<Step> {
+    let prefix = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+    let suffix = before[prefix..].iter().rev().zip(after[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+    let (a, b) = (&before[prefix..before.len() - suffix], &after[prefix..after.len() - suffix]);
+
+    // lengths[i][j] is the length of the longest common subsequence of a[i..] and b[j..]
+    let mut lengths = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if a[i] == b[j] { lengths[i + 1][j + 1] + 1 } else { lengths[i + 1][j].max(lengths[i][j + 1]) };
+        }
+    }
+
+    let mut steps: Vec<Step> = (0..prefix).map(|_| Step::Keep).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            steps.push(Step::Keep);
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            steps.push(Step::Remove(prefix + i));
+            i += 1;
+        } else {
+            steps.push(Step::Add(prefix + j));
+            j += 1;
+        }
+    }
+    steps.extend((0..suffix).map(|_| Step::Keep));
+    steps
+}
+
+/// Records a run of removed and added constraints, pairing them up as changed ones.
+fn flush(
+    changes: &mut Vec<ConstraintChange>,
+    removed: &mut Vec<usize>,
+    added: &mut Vec<usize>,
+    before: &[NormalConstraint],
+    after: &[NormalConstraint],
+) {
+    let paired = removed.len().min(added.len());
+    for (&old, &new) in removed.iter().zip(added.iter()) {
+        changes.push(ConstraintChange::Changed { before: old, after: new, old: render(&before[old]), new: render(&after[new]) });
+    }
+    for &index in &removed[paired..] {
+        changes.push(ConstraintChange::Removed { index, constraint: render(&before[index]) });
+    }
+    for &index in &added[paired..] {
+        changes.push(ConstraintChange::Added { index, constraint: render(&after[index]) });
+    }
+    removed.clear();
+    added.clear();
+}
+
+/// Renders a normalized constraint as `A = C` for additions and `A * B = C` otherwise.
+fn render(constraint: &NormalConstraint) -> String {
+    let sides: Vec<String> = constraint.sides.iter().map(render_side).collect();
+    match (constraint.addition, sides.as_slice()) {
+        (true, [a, c]) => format!("add ({}) = ({})", a, c),
+        (false, [a, b, c]) => format!("mul ({}) * ({}) = ({})", a, b, c),
+        _ => unreachable!("Normalized constraints have two sides for additions and three otherwise"),
+    }
+}
+
+/// Renders a linear combination as `3·w0 + w2`, or `0` when it has no terms.
+fn render_side(terms: &BTreeMap<usize, BigInt>) -> String {
+    if terms.is_empty() {
+        return String::from("0");
+    }
+    terms.iter()
+        .map(|(index, coeff)| if coeff.is_one() { format!("w{}", index) } else { format!("{}·w{}", coeff, index) })
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::circuit::{Circuit, Gate};
+    use crate::field::FieldElement;
+
+    /// Builds a circuit summing two inputs with the given gate, then squaring the sum.
+    fn sum_then_square(sum: impl Fn(usize, usize, usize) -> Gate) -> Circuit {
+        let mut circuit = Circuit::new();
+        let a = circuit.add_input(FieldElement::new(BigInt::from(3)));
+        let b = circuit.add_input(FieldElement::new(BigInt::from(4)));
+        let out = circuit.add_input(FieldElement::new(BigInt::from(7)));
+        circuit.add_gate(sum(a, b, out));
+        circuit.alloc_mul(out, out);
+        circuit
+    }
+
+    #[test]
+    fn equivalent_gates_have_an_empty_diff() {
+        let added = sum_then_square(Gate::Add);
+        let linear = sum_then_square(|a, b, out| {
+            let one = FieldElement::new(BigInt::one());
+            Gate::Linear(vec![(b, one.clone()), (a, one)], FieldElement::new(BigInt::from(0)), out)
+        });
+        let diff = added.to_r1cs().diff(&linear.to_r1cs());
+        assert!(diff.is_empty(), "{}", diff);
+        assert_eq!(added.to_r1cs().snapshot(), linear.to_r1cs().snapshot());
+    }
+
+    #[test]
+    fn rewritten_and_inserted_constraints_are_reported() {
+        let original = sum_then_square(Gate::Add);
+        let mut refactored = sum_then_square(Gate::Mul);
+        refactored.enforce_equal(0, 0);
+        let diff = original.to_r1cs().diff(&refactored.to_r1cs());
+
+        assert!(matches!(diff.constraints[0], ConstraintChange::Changed { before: 0, after: 0, .. }));
+        assert!(matches!(diff.constraints[1], ConstraintChange::Added { index: 2, .. }));
+        assert_eq!(diff.constraints.len(), 2);
+        assert!(diff.added_wires.is_empty() && diff.removed_wires.is_empty());
+    }
+}
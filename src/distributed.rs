@@ -0,0 +1,162 @@
+use std::thread;
+use num_bigint::BigInt;
+use crate::domain::{self, Domain};
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+
+/// Runs batches of number-theoretic transforms on behalf of a `Coordinator`.
+pub trait Worker: Send + Sync {
+    /// Computes the number-theoretic transform of every chunk.
+    ///
+    /// # Parameters
+    /// - `chunks`: The chunks to transform, all of the same power-of-two length.
+    /// - `root`: A primitive root of unity whose order is the length of the chunks.
+    ///
+    /// # Returns
+    /// - `Result<Vec<Vec<FieldElement>>>`: The transformed chunks, in order, or an error if the worker failed.
+    fn transform(&self, chunks: &[Vec<FieldElement>], root: &FieldElement) -> Result<Vec<Vec<FieldElement>>>;
+}
+
+/// Runs transforms in the current process.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LocalWorker;
+
+impl Worker for LocalWorker {
+    fn transform(&self, chunks: &[Vec<FieldElement>], root: &FieldElement) -> Result<Vec<Vec<FieldElement>>> {
+        Ok(chunks.iter().map(|chunk| domain::ntt(chunk, root)).collect())
+    }
+}
+
+/// Shards large transforms across workers, such as other processes reached
+/// through `grpc::RemoteWorker`, and assembles their partial results.
+///
+/// A transform of size `n = rows * columns` is computed with the four-step
+/// algorithm: `columns` transforms of size `rows`, a twiddle multiplication,
+/// then `rows` transforms of size `columns`. Each step splits its chunks evenly
+/// between the workers, which run concurrently.
+pub struct Coordinator {
+    /// The workers the chunks are sent to.
+    workers: Vec<Box<dyn Worker>>,
+}
+
+impl Coordinator {
+    /// Creates a coordinator over a set of workers.
+    ///
+    /// # Parameters
+    /// - `workers`: The workers, at least one.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Coordinator` struct.
+    pub fn new(workers: Vec<Box<dyn Worker>>) -> Self {
+        Coordinator { workers }
+    }
+
+    /// Evaluates a polynomial at every point of a domain, like `Domain::fft`.
+    ///
+    /// # Parameters
+    /// - `domain`: A domain of roots of unity whose size is a power of two.
+    /// - `coefficients`: The coefficients of the polynomial from the constant term up, at most one per point.
+    ///
+    /// # Returns
+    /// - `Result<Vec<FieldElement>>`: The value of the polynomial at each point, or an error if the
+    ///   domain is not supported or a worker failed.
+    pub fn fft(&self, domain: &Domain, coefficients: &[FieldElement]) -> Result<Vec<FieldElement>> {
+        let root = transform_root(domain)?;
+        if coefficients.len() > domain.size() {
+            return Err(Error::Input(format!("Expected at most {} coefficients, got {}", domain.size(), coefficients.len())));
+        }
+        let mut padded = coefficients.to_vec();
+        padded.resize(domain.size(), root.with_value(BigInt::from(0)));
+        self.transform(&padded, root)
+    }
+
+    /// Recovers the coefficients of the polynomial taking `values` on a domain, like `Domain::ifft`.
+    ///
+    /// # Parameters
+    /// - `domain`: A domain of roots of unity whose size is a power of two.
+    /// - `values`: The value of the polynomial at each point of the domain.
+    ///
+    /// # Returns
+    /// - `Result<Vec<FieldElement>>`: The coefficients from the constant term up, or an error if the
+    ///   domain is not supported or a worker failed.
+    pub fn ifft(&self, domain: &Domain, values: &[FieldElement]) -> Result<Vec<FieldElement>> {
+        let root = transform_root(domain)?;
+        if values.len() != domain.size() {
+            return Err(Error::Input(format!("Expected {} values, got {}", domain.size(), values.len())));
+        }
+        let size_inv = root.with_value(BigInt::from(domain.size())).inv();
+        let transformed = self.transform(values, &root.inv())?;
+        Ok(transformed.iter().map(|value| value.mul(&size_inv)).collect())
+    }
+
+    /// Computes the number-theoretic transform of `values` with the four-step algorithm.
+    fn transform(&self, values: &[FieldElement], root: &FieldElement) -> Result<Vec<FieldElement>> {
+        let size = values.len();
+        let rows = 1 << (size.trailing_zeros() / 2);
+        let columns = size / rows;
+
+        // Viewing the input as a rows x columns matrix in row-major order, transform each column
+        let column_chunks: Vec<Vec<FieldElement>> = (0..columns)
+            .map(|column| (0..rows).map(|row| values[row * columns + column].clone()).collect())
+            .collect();
+        let mut transformed = self.dispatch(column_chunks, &root.pow(&BigInt::from(columns)))?;
+
+        // Multiply entry `row` of column `column` by root^(row * column)
+        for (column, chunk) in transformed.iter_mut().enumerate() {
+            let step = root.pow(&BigInt::from(column));
+            let mut twiddle = root.with_value(BigInt::from(1));
+            for value in chunk.iter_mut() {
+                *value = value.mul(&twiddle);
+                twiddle = twiddle.mul(&step);
+            }
+        }
+
+        // Transform each row; entry `column` of row `row` is the evaluation at root^(row + rows * column)
+        let row_chunks: Vec<Vec<FieldElement>> = (0..rows)
+            .map(|row| transformed.iter().map(|chunk| chunk[row].clone()).collect())
+            .collect();
+        let rows_transformed = self.dispatch(row_chunks, &root.pow(&BigInt::from(rows)))?;
+        let mut result = vec![root.with_value(BigInt::from(0)); size];
+        for (row, chunk) in rows_transformed.into_iter().enumerate() {
+            for (column, value) in chunk.into_iter().enumerate() {
+                result[row + rows * column] = value;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Splits chunks evenly between the workers, transforms them concurrently and
+    /// returns the results in the original order.
+    fn dispatch(&self, chunks: Vec<Vec<FieldElement>>, root: &FieldElement) -> Result<Vec<Vec<FieldElement>>> {
+        if self.workers.is_empty() {
+            return Err(Error::Input("A coordinator needs at least one worker".to_string()));
+        }
+        let length = chunks[0].len();
+        let per_worker = chunks.len().div_ceil(self.workers.len());
+        let results: Vec<Result<Vec<Vec<FieldElement>>>> = thread::scope(|scope| {
+            let handles: Vec<_> = self.workers.iter().zip(chunks.chunks(per_worker))
+                .map(|(worker, batch)| scope.spawn(move || worker.transform(batch, root)))
+                .collect();
+            handles.into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|_| Err(Error::External("A worker panicked".to_string()))))
+                .collect()
+        });
+
+        let mut transformed = Vec::with_capacity(chunks.len());
+        for result in results {
+            transformed.extend(result?);
+        }
+        if transformed.len() != chunks.len() || transformed.iter().any(|chunk| chunk.len() != length) {
+            return Err(Error::External("A worker returned chunks of the wrong shape".to_string()));
+        }
+        Ok(transformed)
+    }
+}
+
+/// Gets the generator of a domain that distributed transforms support.
+fn transform_root(domain: &Domain) -> Result<&FieldElement> {
+    match &domain.generator {
+        Some(root) if domain.size().is_power_of_two() => Ok(root),
+        _ => Err(Error::Input("Distributed transforms need a power-of-two domain of roots of unity".to_string())),
+    }
+}
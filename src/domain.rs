@@ -0,0 +1,259 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::field::FieldElement;
+use crate::qap::Polynomial;
+
+/// The number of candidate generators tried when searching for a root of unity.
+const MAX_GENERATOR: u32 = 256;
+
+/// Represents an evaluation domain: a set of distinct points together with
+/// their barycentric weights `w_i = 1 / prod_{j != i} (x_i - x_j)`.
+///
+/// Computing the weights is the expensive part of Lagrange interpolation, so a
+/// domain is built once and reused for every polynomial over the same points.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Domain {
+    /// The points of the domain.
+    pub points: Vec<FieldElement>,
+    /// The barycentric weight of each point.
+    pub weights: Vec<FieldElement>,
+    /// The coefficients of the vanishing polynomial `prod_i (x - x_i)`, from the constant term up.
+    pub vanishing: Vec<FieldElement>,
+    /// The generator `w` of the points when the domain is made of roots of unity.
+    pub generator: Option<FieldElement>,
+}
+
+impl Domain {
+    /// Creates a domain over arbitrary points.
+    ///
+    /// # Parameters
+    /// - `points`: The distinct points of the domain, all in the same field.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Domain` struct.
+    ///
+    /// # Panics
+    /// - If `points` is empty or two points are equal.
+    pub fn new(points: Vec<FieldElement>) -> Self {
+        let weights = points.iter().enumerate()
+            .map(|(i, x_i)| {
+                points.iter().enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .fold(x_i.with_value(BigInt::one()), |acc, (_, x_j)| acc.mul(&x_i.sub(x_j)))
+                    .inv()
+            })
+            .collect();
+        let vanishing = vanishing_polynomial(&points);
+        Domain { points, weights, vanishing, generator: None }
+    }
+
+    /// Creates the domain of the `size`-th roots of unity `1, w, w^2, ...`, whose
+    /// weights `w^i / size` need no inversion but one.
+    ///
+    /// # Parameters
+    /// - `size`: The number of points, which must divide `modulus - 1`.
+    /// - `modulus`: The prime modulus of the field.
+    ///
+    /// # Returns
+    /// - `Option<Self>`: The domain, or `None` if the field has no root of unity of that order.
+    pub fn roots_of_unity(size: usize, modulus: &BigInt) -> Option<Self> {
+        let order = modulus - BigInt::one();
+        if size == 0 || !(&order % size).is_zero() {
+            return None;
+        }
+        let exponent = &order / size;
+        let factors = prime_factors(size);
+        // w = g^((p - 1) / size) has order exactly `size` unless w^(size / q) = 1 for a prime q
+        let root = (2..MAX_GENERATOR)
+            .map(|g| FieldElement::new_with_modulus(BigInt::from(g), modulus.clone()).pow(&exponent))
+            .find(|root| factors.iter().all(|q| !root.pow(&BigInt::from(size / q)).get_value().is_one()))?;
+
+        let mut points = Vec::with_capacity(size);
+        let mut point = root.with_value(BigInt::one());
+        for _ in 0..size {
+            points.push(point.clone());
+            point = point.mul(&root);
+        }
+        let size_inv = root.with_value(BigInt::from(size)).inv();
+        let weights = points.iter().map(|point| point.mul(&size_inv)).collect();
+        // The vanishing polynomial of the roots of unity is x^size - 1
+        let mut vanishing = vec![root.with_value(BigInt::zero()); size + 1];
+        vanishing[0] = root.with_value(-BigInt::one());
+        vanishing[size] = root.with_value(BigInt::one());
+        Some(Domain { points, weights, vanishing, generator: Some(root) })
+    }
+
+    /// Gets the number of points of the domain.
+    ///
+    /// # Returns
+    /// - `usize`: The number of points.
+    pub fn size(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Evaluates the polynomial taking `values` on the domain at a point, using
+    /// the barycentric formula `L(x) = sum_i values[i] * w_i * prod_{j != i} (x - x_j)`.
+    ///
+    /// # Parameters
+    /// - `values`: The value of the polynomial at each point of the domain.
+    /// - `x`: The point to evaluate at.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The value of the interpolating polynomial at `x`.
+    pub fn evaluate(&self, values: &[FieldElement], x: &FieldElement) -> FieldElement {
+        assert_eq!(values.len(), self.size(), "Expected one value per point of the domain");
+        if let Some(i) = self.points.iter().position(|point| point == x) {
+            return values[i].clone();
+        }
+        let vanishing = self.points.iter().fold(x.with_value(BigInt::one()), |acc, point| acc.mul(&x.sub(point)));
+        let sum = self.points.iter().zip(&self.weights).zip(values)
+            .fold(x.with_value(BigInt::zero()), |acc, ((point, weight), value)| {
+                acc.add(&weight.mul(value).mul(&x.sub(point).inv()))
+            });
+        vanishing.mul(&sum)
+    }
+
+    /// Interpolates the polynomial taking `values` on the domain, dividing the
+    /// cached vanishing polynomial by `x - x_i` for each point instead of
+    /// inverting `x_i - x_j` for every pair.
+    ///
+    /// # Parameters
+    /// - `values`: The value of the polynomial at each point of the domain.
+    ///
+    /// # Returns
+    /// - `Polynomial`: The interpolating polynomial, with coefficients keyed by degree.
+    pub fn interpolate(&self, values: &[FieldElement]) -> Polynomial {
+        assert_eq!(values.len(), self.size(), "Expected one value per point of the domain");
+        let zero = self.vanishing[0].with_value(BigInt::zero());
+        let mut coefficients = vec![zero.clone(); self.size()];
+        for ((point, weight), value) in self.points.iter().zip(&self.weights).zip(values) {
+            let scale = weight.mul(value);
+            // Synthetic division of the vanishing polynomial by (x - point), from the leading term down
+            let mut carry = zero.clone();
+            for degree in (0..self.size()).rev() {
+                carry = self.vanishing[degree + 1].add(&carry.mul(point));
+                coefficients[degree] = coefficients[degree].add(&carry.mul(&scale));
+            }
+        }
+        Polynomial::from_coefficients(coefficients.into_iter().enumerate().collect::<BTreeMap<_, _>>())
+    }
+
+    /// Evaluates a polynomial at every point of the domain.
+    ///
+    /// Domains of roots of unity whose size is a power of two use the
+    /// number-theoretic transform, on the registered accelerator with the
+    /// `accel` feature; other domains evaluate each point in turn.
+    ///
+    /// # Parameters
+    /// - `coefficients`: The coefficients of the polynomial from the constant term up, at most one per point.
+    ///
+    /// # Returns
+    /// - `Vec<FieldElement>`: The value of the polynomial at each point of the domain.
+    pub fn fft(&self, coefficients: &[FieldElement]) -> Vec<FieldElement> {
+        assert!(coefficients.len() <= self.size(), "Expected at most one coefficient per point of the domain");
+        let zero = self.points[0].with_value(BigInt::zero());
+        let mut padded = coefficients.to_vec();
+        padded.resize(self.size(), zero.clone());
+        match &self.generator {
+            Some(root) if self.size().is_power_of_two() => ntt(&padded, root),
+            _ => self.points.iter()
+                .map(|point| padded.iter().rev().fold(zero.clone(), |acc, coeff| acc.mul(point).add(coeff)))
+                .collect(),
+        }
+    }
+
+    /// Recovers the coefficients of the polynomial taking `values` on the domain.
+    ///
+    /// Domains of roots of unity whose size is a power of two use the inverse
+    /// number-theoretic transform; other domains fall back to `interpolate`.
+    ///
+    /// # Parameters
+    /// - `values`: The value of the polynomial at each point of the domain.
+    ///
+    /// # Returns
+    /// - `Vec<FieldElement>`: The coefficients of the polynomial from the constant term up, one per point.
+    pub fn ifft(&self, values: &[FieldElement]) -> Vec<FieldElement> {
+        assert_eq!(values.len(), self.size(), "Expected one value per point of the domain");
+        match &self.generator {
+            Some(root) if self.size().is_power_of_two() => {
+                // Evaluating at the inverse roots and dividing by the size inverts the transform
+                let size_inv = root.with_value(BigInt::from(self.size())).inv();
+                ntt(values, &root.inv()).iter().map(|value| value.mul(&size_inv)).collect()
+            },
+            _ => self.interpolate(values).coefficients().values().cloned().collect(),
+        }
+    }
+}
+
+/// Computes the number-theoretic transform of `values` for a root of unity of
+/// order `values.len()`, a power of two, trying the registered accelerator first.
+pub(crate) fn ntt(values: &[FieldElement], root: &FieldElement) -> Vec<FieldElement> {
+    #[cfg(feature = "accel")]
+    if let Some(evaluations) = crate::accel::accelerator().and_then(|accelerator| accelerator.ntt(values, root)) {
+        return evaluations;
+    }
+    scalar_ntt(values, root)
+}
+
+/// Computes the number-theoretic transform with the iterative radix-2 Cooley-Tukey algorithm.
+fn scalar_ntt(values: &[FieldElement], root: &FieldElement) -> Vec<FieldElement> {
+    let size = values.len();
+    let bits = size.trailing_zeros();
+    // Bit-reverse the inputs so every butterfly pass writes in place
+    let mut result: Vec<FieldElement> = (0..size)
+        .map(|i| values[if bits == 0 { 0 } else { i.reverse_bits() >> (usize::BITS - bits) }].clone())
+        .collect();
+    let mut half = 1;
+    while half < size {
+        // A root of unity of order 2 * half
+        let step = root.pow(&BigInt::from(size / (2 * half)));
+        for start in (0..size).step_by(2 * half) {
+            let mut twiddle = root.with_value(BigInt::one());
+            for i in start..start + half {
+                let odd = result[i + half].mul(&twiddle);
+                result[i + half] = result[i].sub(&odd);
+                result[i] = result[i].add(&odd);
+                twiddle = twiddle.mul(&step);
+            }
+        }
+        half *= 2;
+    }
+    result
+}
+
+/// Multiplies out `prod_i (x - x_i)`, returning its coefficients from the constant term up.
+fn vanishing_polynomial(points: &[FieldElement]) -> Vec<FieldElement> {
+    let one = points[0].with_value(BigInt::one());
+    let mut coefficients = vec![one];
+    for point in points {
+        let mut next = vec![point.with_value(BigInt::zero()); coefficients.len() + 1];
+        for (degree, coeff) in coefficients.iter().enumerate() {
+            next[degree + 1] = next[degree + 1].add(coeff);
+            next[degree] = next[degree].sub(&coeff.mul(point));
+        }
+        coefficients = next;
+    }
+    coefficients
+}
+
+/// Gets the distinct prime factors of a number by trial division.
+fn prime_factors(mut n: usize) -> Vec<usize> {
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            factors.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+        }
+        p += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
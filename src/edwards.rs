@@ -0,0 +1,223 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::field::FieldElement;
+
+/// Represents a point of a twisted Edwards curve in affine coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Point {
+    /// The x-coordinate.
+    pub x: FieldElement,
+    /// The y-coordinate.
+    pub y: FieldElement,
+}
+
+/// Represents a twisted Edwards curve `a x^2 + y^2 = 1 + d x^2 y^2` with a
+/// generator of a prime-order subgroup.
+///
+/// With `a` a square and `d` a non-square, the addition law is complete: the
+/// same formula adds any two points, doublings and the identity `(0, 1)`
+/// included, which keeps the circuit free of special cases.
+#[derive(Clone, Debug)]
+pub struct EdwardsCurve {
+    /// The coefficient of `x^2`.
+    pub a: FieldElement,
+    /// The coefficient of `x^2 y^2`.
+    pub d: FieldElement,
+    /// The generator of the prime-order subgroup.
+    pub generator: Point,
+    /// The order of the subgroup.
+    pub order: BigInt,
+}
+
+impl Default for EdwardsCurve {
+    /// Creates the Edwards curve `x^2 + y^2 = 1 + 148 x^2 y^2` over the default
+    /// field, of order `4 * 250009553`. Its generator is four times the point
+    /// with the smallest y-coordinate. The subgroup is far too small for real
+    /// use: like `Pedersen::default`, it is for learning and testing circuits.
+    fn default() -> Self {
+        let element = |value: u64| FieldElement::new(BigInt::from(value));
+        EdwardsCurve {
+            a: element(1),
+            d: element(148),
+            generator: Point { x: element(756_219_424), y: element(720_720_570) },
+            order: BigInt::from(250_009_553u64),
+        }
+    }
+}
+
+impl EdwardsCurve {
+    /// Creates Baby Jubjub, the twisted Edwards curve over the BN254 scalar
+    /// field from EIP-2494, with the generator of its prime-order subgroup.
+    ///
+    /// # Returns
+    /// - `Self`: The curve.
+    pub fn baby_jubjub() -> Self {
+        let modulus = decimal("21888242871839275222246405745257275088548364400416034343698204186575808495617");
+        let element = |value: BigInt| FieldElement::new_with_modulus(value, modulus.clone());
+        EdwardsCurve {
+            a: element(BigInt::from(168_700)),
+            d: element(BigInt::from(168_696)),
+            generator: Point {
+                x: element(decimal("5299619240641551281634865583518297030282874472190772894086521144482721001553")),
+                y: element(decimal("16950150798460657717958625567821834550301663161624707787222815936182638968203")),
+            },
+            order: decimal("2736030358979909402780800718157159386076813972158567259200215660948447373041"),
+        }
+    }
+
+    /// Gets the modulus of the field the curve is defined over.
+    ///
+    /// # Returns
+    /// - `&BigInt`: The modulus.
+    pub fn modulus(&self) -> &BigInt {
+        self.a.get_modulus()
+    }
+
+    /// Creates a field element of the base field.
+    ///
+    /// # Parameters
+    /// - `value`: The value of the element.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The element, reduced modulo the base field.
+    pub fn element(&self, value: BigInt) -> FieldElement {
+        FieldElement::new_with_modulus(value, self.modulus().clone())
+    }
+
+    /// Gets the neutral element `(0, 1)`.
+    ///
+    /// # Returns
+    /// - `Point`: The identity.
+    pub fn identity(&self) -> Point {
+        Point { x: self.element(BigInt::zero()), y: self.element(BigInt::one()) }
+    }
+
+    /// Checks whether a point lies on the curve.
+    ///
+    /// # Parameters
+    /// - `point`: The point.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the point satisfies the curve equation in the base field.
+    pub fn is_on_curve(&self, point: &Point) -> bool {
+        if point.x.get_modulus() != self.modulus() || point.y.get_modulus() != self.modulus() {
+            return false;
+        }
+        let (xx, yy) = (point.x.mul(&point.x), point.y.mul(&point.y));
+        self.a.mul(&xx).add(&yy) == self.element(BigInt::one()).add(&self.d.mul(&xx).mul(&yy))
+    }
+
+    /// Checks whether a point lies in the prime-order subgroup, which
+    /// excludes the points of small order that the cofactor adds.
+    ///
+    /// # Parameters
+    /// - `point`: The point.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the point is on the curve and `order * point` is the identity.
+    pub fn is_in_subgroup(&self, point: &Point) -> bool {
+        self.is_on_curve(point) && self.mul(&self.order, point) == self.identity()
+    }
+
+    /// Adds two points with the complete addition law.
+    ///
+    /// # Parameters
+    /// - `p`: The first point.
+    /// - `q`: The second point.
+    ///
+    /// # Returns
+    /// - `Point`: The sum.
+    pub fn add(&self, p: &Point, q: &Point) -> Point {
+        let one = self.element(BigInt::one());
+        let t = self.d.mul(&p.x).mul(&q.x).mul(&p.y).mul(&q.y);
+        let x = p.x.mul(&q.y).add(&p.y.mul(&q.x)).mul(&one.add(&t).inv());
+        let y = p.y.mul(&q.y).sub(&self.a.mul(&p.x).mul(&q.x)).mul(&one.sub(&t).inv());
+        Point { x, y }
+    }
+
+    /// Multiplies a point by a scalar with double-and-add.
+    ///
+    /// # Parameters
+    /// - `scalar`: The non-negative scalar.
+    /// - `point`: The point.
+    ///
+    /// # Returns
+    /// - `Point`: The multiple `scalar * point`.
+    pub fn mul(&self, scalar: &BigInt, point: &Point) -> Point {
+        let mut result = self.identity();
+        for position in (0..scalar.bits()).rev() {
+            result = self.add(&result, &result);
+            if scalar.bit(position) {
+                result = self.add(&result, point);
+            }
+        }
+        result
+    }
+}
+
+/// Parses a decimal constant.
+fn decimal(digits: &str) -> BigInt {
+    BigInt::parse_bytes(digits.as_bytes(), 10).expect("Curve constants are valid decimals")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Negates a point, `-(x, y) = (-x, y)`.
+    fn negate(point: &Point) -> Point {
+        Point { x: point.x.negate(), y: point.y.clone() }
+    }
+
+    /// Checks the group law on the multiples of the generator of a curve.
+    fn check_group_law(curve: &EdwardsCurve) {
+        let generator = &curve.generator;
+        let identity = curve.identity();
+        assert!(curve.is_on_curve(&identity));
+        assert!(curve.is_in_subgroup(generator));
+        assert_eq!(curve.add(generator, &identity), *generator);
+        assert_eq!(curve.add(&identity, generator), *generator);
+        assert_eq!(curve.add(generator, &negate(generator)), identity);
+
+        let p = curve.mul(&BigInt::from(12_345u64), generator);
+        let q = curve.mul(&BigInt::from(67_890u64), generator);
+        for sum in [curve.add(&p, &q), curve.add(&p, &p), curve.add(&identity, &identity)].iter() {
+            assert!(curve.is_on_curve(sum));
+        }
+        assert_eq!(curve.add(&p, &q), curve.add(&q, &p));
+        assert_eq!(curve.add(&p, &q), curve.mul(&BigInt::from(80_235u64), generator));
+        assert_eq!(curve.mul(&curve.order, generator), identity);
+        assert_eq!(curve.mul(&(&curve.order - 1u32), generator), negate(generator));
+    }
+
+    #[test]
+    fn the_default_curve_satisfies_the_group_law() {
+        check_group_law(&EdwardsCurve::default());
+    }
+
+    #[test]
+    fn baby_jubjub_satisfies_the_group_law() {
+        check_group_law(&EdwardsCurve::baby_jubjub());
+    }
+
+    #[test]
+    fn points_off_the_curve_are_detected() {
+        let curve = EdwardsCurve::default();
+        let generator = &curve.generator;
+        let shifted = Point { x: generator.x.clone(), y: generator.y.add(&curve.element(BigInt::one())) };
+        assert!(!curve.is_on_curve(&shifted));
+        assert!(!curve.is_in_subgroup(&shifted));
+        assert!(!EdwardsCurve::baby_jubjub().is_on_curve(generator));
+    }
+
+    #[test]
+    fn points_of_small_order_are_outside_the_subgroup() {
+        let curve = EdwardsCurve::default();
+        // (0, -1) has order 2 on every twisted Edwards curve
+        let torsion = Point { x: curve.element(BigInt::zero()), y: curve.element(BigInt::from(-1)) };
+        assert!(curve.is_on_curve(&torsion));
+        assert_eq!(curve.add(&torsion, &torsion), curve.identity());
+        assert!(!curve.is_in_subgroup(&torsion));
+        assert!(!curve.is_in_subgroup(&curve.add(&curve.generator, &torsion)));
+    }
+}
@@ -0,0 +1,92 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+use crate::fingerprint::CircuitVersion;
+#[cfg(feature = "std")]
+use std::io;
+
+/// Represents the errors returned by the crate's fallible APIs.
+#[derive(Debug)]
+pub enum Error {
+    /// An I/O operation failed.
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    /// Data could not be serialized or deserialized.
+    Serialization(String),
+    /// A circuit description could not be compiled.
+    Compile(String),
+    /// Circuit inputs were missing or malformed.
+    Input(String),
+    /// An external tool or proving backend, such as circom or arkworks, failed.
+    External(String),
+    /// A long-running operation was cancelled by the caller.
+    Cancelled,
+    /// A file was written in a format version this release cannot read.
+    UnsupportedVersion {
+        /// The version recorded in the file.
+        found: u32,
+        /// The oldest version that can be read.
+        min: u32,
+        /// The newest version that can be read.
+        max: u32,
+    },
+    /// An artifact was made by a release of its circuit that the verifier does not accept.
+    VersionMismatch {
+        /// The version of the verifier, from its key or constraint system.
+        expected: CircuitVersion,
+        /// The version recorded in the artifact.
+        found: CircuitVersion,
+    },
+}
+
+/// A specialized `Result` type for the crate's fallible APIs.
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            Error::Io(err) => write!(f, "I/O error: {}", err),
+            Error::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+            Error::Compile(msg) => write!(f, "Compile error: {}", msg),
+            Error::Input(msg) => write!(f, "Input error: {}", msg),
+            Error::External(msg) => write!(f, "External tool error: {}", msg),
+            Error::Cancelled => write!(f, "Operation cancelled"),
+            Error::UnsupportedVersion { found, min, max } => {
+                write!(f, "Unsupported format version {}, expected {} to {}", found, min, max)
+            }
+            Error::VersionMismatch { expected, found } => {
+                write!(f, "Circuit version {} is not compatible with version {}", found, expected)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<bincode::Error> for Error {
+    fn from(err: bincode::Error) -> Self {
+        Error::Serialization(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Serialization(err.to_string())
+    }
+}
@@ -0,0 +1,159 @@
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::Proof as Groth16Proof;
+use num_bigint::{BigInt, Sign};
+use sha3::{Digest, Keccak256};
+use crate::ark::{modulus, to_ark};
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+
+/// The size of an ABI word in bytes.
+const WORD: usize = 32;
+/// The number of words taken by a proof: `a` (2), `b` (2x2) and `c` (2).
+const PROOF_WORDS: usize = 8;
+
+/// Encodes Groth16 proofs for the standard Solidity verifier contracts.
+///
+/// The calldata targets `verifyProof(uint256[2],uint256[2][2],uint256[2],uint256[N])`,
+/// as generated by snarkjs and most Groth16 verifier templates, with the G2
+/// coordinates in the `[c1, c0]` order the precompiles expect.
+pub trait EthCalldata: Sized {
+    /// Encodes the proof and its public inputs as calldata, including the function selector.
+    ///
+    /// # Parameters
+    /// - `public_inputs`: The public inputs, in the BN254 scalar field.
+    ///
+    /// # Returns
+    /// - `Result<Vec<u8>>`: The ABI-encoded calldata.
+    fn to_eth_calldata(&self, public_inputs: &[FieldElement]) -> Result<Vec<u8>>;
+
+    /// Decodes calldata back into a proof and its public inputs.
+    ///
+    /// # Parameters
+    /// - `calldata`: The ABI-encoded calldata, including the function selector.
+    ///
+    /// # Returns
+    /// - `Result<(Self, Vec<FieldElement>)>`: The proof and its public inputs, or an error
+    ///   if the selector, length, coordinates or points are invalid.
+    fn from_eth_calldata(calldata: &[u8]) -> Result<(Self, Vec<FieldElement>)>;
+}
+
+impl EthCalldata for Groth16Proof<Bn254> {
+    fn to_eth_calldata(&self, public_inputs: &[FieldElement]) -> Result<Vec<u8>> {
+        let mut calldata = verify_proof_selector(public_inputs.len()).to_vec();
+        encode_g1(&mut calldata, &self.a);
+        encode_g2(&mut calldata, &self.b);
+        encode_g1(&mut calldata, &self.c);
+        for input in public_inputs {
+            let input: Fr = to_ark(input)?;
+            calldata.extend(word(&input));
+        }
+        Ok(calldata)
+    }
+
+    fn from_eth_calldata(calldata: &[u8]) -> Result<(Self, Vec<FieldElement>)> {
+        let body = calldata.get(4..).unwrap_or_default();
+        if body.len() % WORD != 0 || body.len() < PROOF_WORDS * WORD {
+            return Err(Error::Serialization("Calldata is not a whole number of ABI words".to_string()));
+        }
+        let num_inputs = body.len() / WORD - PROOF_WORDS;
+        if calldata[..4] != verify_proof_selector(num_inputs) {
+            return Err(Error::Serialization("Calldata does not call verifyProof".to_string()));
+        }
+
+        let words: Vec<&[u8]> = body.chunks(WORD).collect();
+        let proof = Groth16Proof {
+            a: decode_g1(&words[0..2])?,
+            b: decode_g2(&words[2..6])?,
+            c: decode_g1(&words[6..8])?,
+        };
+        let public_inputs = words[PROOF_WORDS..].iter()
+            .map(|input| Ok(FieldElement::new_with_modulus(canonical::<Fr>(input)?, modulus::<Fr>())))
+            .collect::<Result<Vec<_>>>()?;
+        Ok((proof, public_inputs))
+    }
+}
+
+/// Computes the selector of `verifyProof` for a number of public inputs.
+///
+/// # Parameters
+/// - `num_inputs`: The number of public inputs.
+///
+/// # Returns
+/// - `[u8; 4]`: The first four bytes of the Keccak-256 hash of the signature.
+pub fn verify_proof_selector(num_inputs: usize) -> [u8; 4] {
+    let signature = format!("verifyProof(uint256[2],uint256[2][2],uint256[2],uint256[{}])", num_inputs);
+    let hash = Keccak256::digest(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Encodes a field element as a big-endian ABI word.
+fn word<F: PrimeField>(value: &F) -> [u8; WORD] {
+    let bytes = value.into_bigint().to_bytes_be();
+    let mut word = [0u8; WORD];
+    word[WORD - bytes.len()..].copy_from_slice(&bytes);
+    word
+}
+
+/// Encodes a G1 point as `[x, y]`, with the identity as `[0, 0]`.
+fn encode_g1(calldata: &mut Vec<u8>, point: &G1Affine) {
+    if point.infinity {
+        calldata.extend([0u8; 2 * WORD]);
+    } else {
+        calldata.extend(word(&point.x));
+        calldata.extend(word(&point.y));
+    }
+}
+
+/// Encodes a G2 point as `[[x.c1, x.c0], [y.c1, y.c0]]`, with the identity as zeros.
+fn encode_g2(calldata: &mut Vec<u8>, point: &G2Affine) {
+    if point.infinity {
+        calldata.extend([0u8; 4 * WORD]);
+    } else {
+        for coordinate in [&point.x, &point.y] {
+            calldata.extend(word(&coordinate.c1));
+            calldata.extend(word(&coordinate.c0));
+        }
+    }
+}
+
+/// Decodes a G1 point and checks that it lies in the prime-order subgroup.
+fn decode_g1(words: &[&[u8]]) -> Result<G1Affine> {
+    let (x, y) = (base_field(words[0])?, base_field(words[1])?);
+    if x == Fq::from(0u8) && y == Fq::from(0u8) {
+        return Ok(G1Affine::identity());
+    }
+    let point = G1Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::Serialization("Calldata holds an invalid G1 point".to_string()));
+    }
+    Ok(point)
+}
+
+/// Decodes a G2 point and checks that it lies in the prime-order subgroup.
+fn decode_g2(words: &[&[u8]]) -> Result<G2Affine> {
+    let x = Fq2::new(base_field(words[1])?, base_field(words[0])?);
+    let y = Fq2::new(base_field(words[3])?, base_field(words[2])?);
+    if x == Fq2::from(0u8) && y == Fq2::from(0u8) {
+        return Ok(G2Affine::identity());
+    }
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::Serialization("Calldata holds an invalid G2 point".to_string()));
+    }
+    Ok(point)
+}
+
+/// Decodes a coordinate of the BN254 base field.
+fn base_field(word: &[u8]) -> Result<Fq> {
+    Ok(Fq::from_le_bytes_mod_order(&canonical::<Fq>(word)?.to_bytes_le().1))
+}
+
+/// Reads a big-endian word, rejecting values outside the field `F`.
+fn canonical<F: PrimeField>(word: &[u8]) -> Result<BigInt> {
+    let value = BigInt::from_bytes_be(Sign::Plus, word);
+    if value >= modulus::<F>() {
+        return Err(Error::Serialization("Calldata holds a non-canonical field element".to_string()));
+    }
+    Ok(value)
+}
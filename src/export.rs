@@ -0,0 +1,317 @@
+use core::fmt::Write;
+use core::str::FromStr;
+use ark_bn254::{Bn254, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::VerifyingKey;
+use ark_serialize::CanonicalSerialize;
+use num_bigint::{BigInt, Sign};
+use crate::error::{Error, Result};
+
+/// Represents the kind of standalone verifier `export_verifier` emits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifierTarget {
+    /// A Solidity contract exposing `verifyProof`, checked with the BN254 precompiles.
+    Solidity,
+    /// A `no_std` Rust crate embedding the verifying key.
+    Rust,
+    /// A Rust crate exporting the verifier to JavaScript through wasm-bindgen.
+    Wasm,
+}
+
+impl FromStr for VerifierTarget {
+    type Err = Error;
+
+    /// Parses a target from its lowercase name.
+    ///
+    /// # Parameters
+    /// - `s`: `solidity`, `rust` or `wasm`.
+    ///
+    /// # Returns
+    /// - `Result<VerifierTarget, Error>`: The parsed target.
+    fn from_str(s: &str) -> Result<VerifierTarget> {
+        match s {
+            "solidity" => Ok(VerifierTarget::Solidity),
+            "rust" => Ok(VerifierTarget::Rust),
+            "wasm" => Ok(VerifierTarget::Wasm),
+            _ => Err(Error::Input(format!("Unknown verifier target {}, expected solidity, rust or wasm", s))),
+        }
+    }
+}
+
+/// Represents a file of an exported verifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifierFile {
+    /// The path of the file, relative to the output directory.
+    pub path: String,
+    /// The contents of the file.
+    pub contents: String,
+}
+
+/// Generates a standalone verifier for a Groth16 verifying key over BN254.
+///
+/// The Solidity contract takes the calldata of `eth::EthCalldata`. The Rust
+/// and wasm crates take the proof in arkworks' compressed serialization and
+/// every public input as 32 little-endian bytes.
+///
+/// # Parameters
+/// - `verifying_key`: The verifying key from `ark::setup`.
+/// - `target`: The kind of verifier to emit.
+///
+/// # Returns
+/// - `Result<Vec<VerifierFile>>`: The files of the verifier, or an error if the key cannot be
+///   serialized or, for Solidity, has no public inputs, which the ABI cannot express.
+pub fn export_verifier(verifying_key: &VerifyingKey<Bn254>, target: VerifierTarget) -> Result<Vec<VerifierFile>> {
+    match target {
+        VerifierTarget::Solidity => Ok(vec![file("Groth16Verifier.sol", solidity(verifying_key)?)]),
+        VerifierTarget::Rust => Ok(vec![
+            file("Cargo.toml", RUST_MANIFEST.to_string()),
+            file("src/lib.rs", RUST_LIB.replace("{{VERIFYING_KEY}}", &key_bytes(verifying_key)?)),
+        ]),
+        VerifierTarget::Wasm => Ok(vec![
+            file("Cargo.toml", WASM_MANIFEST.to_string()),
+            file("src/lib.rs", WASM_LIB.replace("{{VERIFYING_KEY}}", &key_bytes(verifying_key)?)),
+        ]),
+    }
+}
+
+/// Creates a verifier file.
+fn file(path: &str, contents: String) -> VerifierFile {
+    VerifierFile { path: path.to_string(), contents }
+}
+
+/// Renders the Solidity contract, unrolling the linear combination of the public inputs.
+fn solidity(verifying_key: &VerifyingKey<Bn254>) -> Result<String> {
+    let num_inputs = verifying_key.gamma_abc_g1.len() - 1;
+    if num_inputs == 0 {
+        return Err(Error::Input("A Solidity verifier needs at least one public input".to_string()));
+    }
+    let mut constants = String::new();
+    g1_constants(&mut constants, "ALPHA", &verifying_key.alpha_g1);
+    for (name, point) in [("BETA", &verifying_key.beta_g2), ("GAMMA", &verifying_key.gamma_g2), ("DELTA", &verifying_key.delta_g2)] {
+        g2_constants(&mut constants, name, point);
+    }
+    for (i, point) in verifying_key.gamma_abc_g1.iter().enumerate() {
+        g1_constants(&mut constants, &format!("IC{}", i), point);
+    }
+    let mut accumulate = String::new();
+    for i in 0..num_inputs {
+        let _ = writeln!(accumulate, "        require(input[{}] < R, \"Public input {} is not in the scalar field\");", i, i);
+        let _ = writeln!(accumulate, "        x = ecAdd(x, ecMul(IC{}_X, IC{}_Y, input[{}]));", i + 1, i + 1, i);
+    }
+    Ok(SOLIDITY
+        .replace("{{CONSTANTS}}", constants.trim_end())
+        .replace("{{NUM_INPUTS}}", &num_inputs.to_string())
+        .replace("{{ACCUMULATE}}", accumulate.trim_end()))
+}
+
+/// Declares the coordinates of a G1 point as `<NAME>_X` and `<NAME>_Y`.
+fn g1_constants(out: &mut String, name: &str, point: &G1Affine) {
+    let _ = writeln!(out, "    uint256 constant {}_X = {};", name, decimal(&point.x));
+    let _ = writeln!(out, "    uint256 constant {}_Y = {};", name, decimal(&point.y));
+}
+
+/// Declares the coordinates of a G2 point in the `[c1, c0]` order of the pairing precompile.
+fn g2_constants(out: &mut String, name: &str, point: &G2Affine) {
+    let _ = writeln!(out, "    uint256 constant {}_X1 = {};", name, decimal(&point.x.c1));
+    let _ = writeln!(out, "    uint256 constant {}_X0 = {};", name, decimal(&point.x.c0));
+    let _ = writeln!(out, "    uint256 constant {}_Y1 = {};", name, decimal(&point.y.c1));
+    let _ = writeln!(out, "    uint256 constant {}_Y0 = {};", name, decimal(&point.y.c0));
+}
+
+/// Formats a field element in decimal.
+fn decimal<F: PrimeField>(value: &F) -> String {
+    BigInt::from_bytes_le(Sign::Plus, &value.into_bigint().to_bytes_le()).to_string()
+}
+
+/// Formats the compressed verifying key as the body of a Rust byte array.
+fn key_bytes(verifying_key: &VerifyingKey<Bn254>) -> Result<String> {
+    let mut bytes = Vec::new();
+    verifying_key.serialize_compressed(&mut bytes).map_err(|err| Error::Serialization(err.to_string()))?;
+    let lines: Vec<String> = bytes.chunks(16)
+        .map(|chunk| {
+            let line: Vec<String> = chunk.iter().map(|byte| format!("0x{:02x}", byte)).collect();
+            format!("    {},", line.join(", "))
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// The Solidity verifier, with the key constants, the number of public inputs
+/// and the accumulation of the inputs left as placeholders.
+const SOLIDITY: &str = r#"// SPDX-License-Identifier: MIT
+// Generated by `zk export-verifier`.
+pragma solidity ^0.8.0;
+
+/// @title Groth16 verifier over BN254
+/// @notice Checks proofs encoded by `EthCalldata::to_eth_calldata` against one verifying key.
+contract Groth16Verifier {
+    // The order of the scalar field
+    uint256 constant R = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+    // The order of the base field
+    uint256 constant Q = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+
+{{CONSTANTS}}
+
+    /// @notice Verifies a proof.
+    /// @return True if the proof is valid for the public inputs.
+    function verifyProof(
+        uint256[2] calldata a,
+        uint256[2][2] calldata b,
+        uint256[2] calldata c,
+        uint256[{{NUM_INPUTS}}] calldata input
+    ) external view returns (bool) {
+        uint256[2] memory x = [IC0_X, IC0_Y];
+{{ACCUMULATE}}
+
+        // e(-A, B) * e(alpha, beta) * e(x, gamma) * e(C, delta) == 1
+        uint256[24] memory p;
+        p[0] = a[0];
+        p[1] = (Q - (a[1] % Q)) % Q;
+        p[2] = b[0][0];
+        p[3] = b[0][1];
+        p[4] = b[1][0];
+        p[5] = b[1][1];
+        p[6] = ALPHA_X;
+        p[7] = ALPHA_Y;
+        p[8] = BETA_X1;
+        p[9] = BETA_X0;
+        p[10] = BETA_Y1;
+        p[11] = BETA_Y0;
+        p[12] = x[0];
+        p[13] = x[1];
+        p[14] = GAMMA_X1;
+        p[15] = GAMMA_X0;
+        p[16] = GAMMA_Y1;
+        p[17] = GAMMA_Y0;
+        p[18] = c[0];
+        p[19] = c[1];
+        p[20] = DELTA_X1;
+        p[21] = DELTA_X0;
+        p[22] = DELTA_Y1;
+        p[23] = DELTA_Y0;
+
+        uint256[1] memory out;
+        bool ok;
+        assembly {
+            ok := staticcall(gas(), 8, p, 0x300, out, 0x20)
+        }
+        return ok && out[0] == 1;
+    }
+
+    /// Adds two G1 points with the ecAdd precompile.
+    function ecAdd(uint256[2] memory p1, uint256[2] memory p2) internal view returns (uint256[2] memory r) {
+        uint256[4] memory data = [p1[0], p1[1], p2[0], p2[1]];
+        bool ok;
+        assembly {
+            ok := staticcall(gas(), 6, data, 0x80, r, 0x40)
+        }
+        require(ok, "ecAdd failed");
+    }
+
+    /// Multiplies a G1 point by a scalar with the ecMul precompile.
+    function ecMul(uint256 px, uint256 py, uint256 s) internal view returns (uint256[2] memory r) {
+        uint256[3] memory data = [px, py, s];
+        bool ok;
+        assembly {
+            ok := staticcall(gas(), 7, data, 0x60, r, 0x40)
+        }
+        require(ok, "ecMul failed");
+    }
+}
+"#;
+
+/// The manifest of the `no_std` Rust verifier crate.
+const RUST_MANIFEST: &str = r#"[package]
+name = "groth16-verifier"
+version = "0.1.0"
+edition = "2021"
+description = "Standalone Groth16 verifier generated by `zk export-verifier`"
+
+[dependencies]
+ark-bn254 = { version = "0.4", default-features = false, features = ["curve"] }
+ark-groth16 = { version = "0.4", default-features = false }
+ark-serialize = { version = "0.4", default-features = false }
+"#;
+
+/// The shared body of the Rust and wasm verifier crates, with the verifying
+/// key left as a placeholder.
+macro_rules! verifier_body {
+    () => {
+        r#"use ark_bn254::{Bn254, Fr};
+use ark_groth16::{prepare_verifying_key, Groth16, Proof, VerifyingKey};
+use ark_serialize::CanonicalDeserialize;
+
+/// The verifying key, in arkworks' compressed serialization.
+const VERIFYING_KEY: &[u8] = &[
+{{VERIFYING_KEY}}
+];
+
+/// Verifies a Groth16 proof over BN254.
+///
+/// `proof` is the proof in arkworks' compressed serialization, and every
+/// public input is a scalar in 32 little-endian bytes.
+pub fn verify(proof: &[u8], public_inputs: &[[u8; 32]]) -> bool {
+    let Ok(key) = VerifyingKey::<Bn254>::deserialize_compressed(VERIFYING_KEY) else {
+        return false;
+    };
+    let Ok(proof) = Proof::<Bn254>::deserialize_compressed(proof) else {
+        return false;
+    };
+    let mut inputs = Vec::with_capacity(public_inputs.len());
+    for input in public_inputs {
+        let Ok(input) = Fr::deserialize_compressed(&input[..]) else {
+            return false;
+        };
+        inputs.push(input);
+    }
+    if inputs.len() + 1 != key.gamma_abc_g1.len() {
+        return false;
+    }
+    Groth16::<Bn254>::verify_proof(&prepare_verifying_key(&key), &proof, &inputs).unwrap_or(false)
+}
+"#
+    };
+}
+
+/// The library of the `no_std` Rust verifier crate.
+const RUST_LIB: &str = concat!(
+    "//! Standalone Groth16 verifier generated by `zk export-verifier`.\n#![no_std]\n\nextern crate alloc;\n\nuse alloc::vec::Vec;\n",
+    verifier_body!(),
+);
+
+/// The manifest of the wasm verifier crate.
+const WASM_MANIFEST: &str = r#"[package]
+name = "groth16-verifier"
+version = "0.1.0"
+edition = "2021"
+description = "Standalone Groth16 verifier for WebAssembly generated by `zk export-verifier`"
+
+[lib]
+crate-type = ["cdylib", "rlib"]
+
+[dependencies]
+ark-bn254 = { version = "0.4", default-features = false, features = ["curve"] }
+ark-groth16 = { version = "0.4", default-features = false }
+ark-serialize = { version = "0.4", default-features = false }
+wasm-bindgen = "0.2"
+"#;
+
+/// The library of the wasm verifier crate, exporting `verify` to JavaScript.
+const WASM_LIB: &str = concat!(
+    "//! Standalone Groth16 verifier for WebAssembly generated by `zk export-verifier`.\n\nuse wasm_bindgen::prelude::wasm_bindgen;\n",
+    verifier_body!(),
+    r#"
+/// Verifies a proof from JavaScript, with the public inputs concatenated as
+/// 32 little-endian bytes each.
+#[wasm_bindgen(js_name = verifyProof)]
+pub fn verify_proof(proof: &[u8], public_inputs: &[u8]) -> bool {
+    if public_inputs.len() % 32 != 0 {
+        return false;
+    }
+    let inputs: Vec<[u8; 32]> = public_inputs.chunks(32)
+        .map(|chunk| chunk.try_into().expect("Chunks are 32 bytes"))
+        .collect();
+    verify(proof, &inputs)
+}
+"#,
+);
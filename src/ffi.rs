@@ -0,0 +1,185 @@
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::panic;
+use std::ptr;
+use std::slice;
+use crate::api;
+use crate::error::Error;
+
+/// The call succeeded.
+pub const ZK_OK: i32 = 0;
+/// A required pointer argument was null.
+pub const ZK_ERR_NULL: i32 = -1;
+/// A string argument was not valid UTF-8.
+pub const ZK_ERR_UTF8: i32 = -2;
+/// Data could not be serialized or deserialized.
+pub const ZK_ERR_SERIALIZATION: i32 = -3;
+/// The circuit description could not be compiled.
+pub const ZK_ERR_COMPILE: i32 = -4;
+/// The circuit inputs were missing or malformed.
+pub const ZK_ERR_INPUT: i32 = -5;
+/// An I/O operation failed.
+pub const ZK_ERR_IO: i32 = -6;
+/// The prover panicked.
+pub const ZK_ERR_PANIC: i32 = -7;
+/// An external tool failed.
+pub const ZK_ERR_EXTERNAL: i32 = -8;
+/// The operation was cancelled.
+pub const ZK_ERR_CANCELLED: i32 = -9;
+/// The proof was made by an incompatible version of the circuit.
+pub const ZK_ERR_VERSION: i32 = -10;
+
+/// Represents a byte buffer owned by the library and handed to the caller.
+///
+/// Buffers returned by `zk_compile` and `zk_prove` must be released with
+/// `zk_buffer_free`.
+#[repr(C)]
+pub struct ZkBuffer {
+    /// The start of the buffer, or null when empty.
+    pub data: *mut u8,
+    /// The length of the buffer in bytes.
+    pub len: usize,
+}
+
+impl ZkBuffer {
+    /// Hands a vector over to the caller.
+    ///
+    /// # Parameters
+    /// - `bytes`: The bytes to hand over.
+    ///
+    /// # Returns
+    /// - `Self`: The buffer owning the bytes.
+    fn from_vec(bytes: Vec<u8>) -> Self {
+        let mut bytes = bytes.into_boxed_slice();
+        let buffer = ZkBuffer { data: bytes.as_mut_ptr(), len: bytes.len() };
+        std::mem::forget(bytes);
+        buffer
+    }
+}
+
+/// Compiles a circuit from its JSON description.
+///
+/// # Parameters
+/// - `circuit_json`: The NUL-terminated circuit description.
+/// - `out`: Receives the compiled circuit on success.
+///
+/// # Returns
+/// - `i32`: `ZK_OK` on success, otherwise a negative error code.
+///
+/// # Safety
+/// `circuit_json` must be a valid NUL-terminated string and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn zk_compile(circuit_json: *const c_char, out: *mut ZkBuffer) -> i32 {
+    if circuit_json.is_null() || out.is_null() {
+        return ZK_ERR_NULL;
+    }
+    let circuit_json = match CStr::from_ptr(circuit_json).to_str() {
+        Ok(json) => json,
+        Err(_) => return ZK_ERR_UTF8,
+    };
+    write_result(guard(|| api::compile_circuit(circuit_json)), out)
+}
+
+/// Proves a compiled circuit with the given inputs.
+///
+/// # Parameters
+/// - `compiled`: The compiled circuit.
+/// - `compiled_len`: The length of the compiled circuit in bytes.
+/// - `inputs_json`: The NUL-terminated JSON array of input values, or object of named inputs.
+/// - `out`: Receives the proof on success.
+///
+/// # Returns
+/// - `i32`: `ZK_OK` on success, otherwise a negative error code.
+///
+/// # Safety
+/// `compiled` must be valid for reads of `compiled_len` bytes, `inputs_json` must be a
+/// valid NUL-terminated string and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn zk_prove(compiled: *const u8, compiled_len: usize, inputs_json: *const c_char, out: *mut ZkBuffer) -> i32 {
+    if compiled.is_null() || inputs_json.is_null() || out.is_null() {
+        return ZK_ERR_NULL;
+    }
+    let compiled = slice::from_raw_parts(compiled, compiled_len);
+    let inputs_json = match CStr::from_ptr(inputs_json).to_str() {
+        Ok(json) => json,
+        Err(_) => return ZK_ERR_UTF8,
+    };
+    write_result(guard(|| api::prove(compiled, inputs_json)), out)
+}
+
+/// Verifies a proof against a compiled circuit.
+///
+/// # Parameters
+/// - `compiled`: The compiled circuit.
+/// - `compiled_len`: The length of the compiled circuit in bytes.
+/// - `proof`: The proof.
+/// - `proof_len`: The length of the proof in bytes.
+///
+/// # Returns
+/// - `i32`: `1` if the proof is valid, `0` if it is not or carries no witness to verify, otherwise a
+///   negative error code.
+///
+/// # Safety
+/// `compiled` and `proof` must be valid for reads of `compiled_len` and `proof_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zk_verify(compiled: *const u8, compiled_len: usize, proof: *const u8, proof_len: usize) -> i32 {
+    if compiled.is_null() || proof.is_null() {
+        return ZK_ERR_NULL;
+    }
+    let compiled = slice::from_raw_parts(compiled, compiled_len);
+    let proof = slice::from_raw_parts(proof, proof_len);
+    match guard(|| api::verify(compiled, proof)) {
+        Ok(valid) => valid as i32,
+        Err(code) => code,
+    }
+}
+
+/// Releases a buffer returned by the library.
+///
+/// # Parameters
+/// - `buffer`: The buffer to release; empty buffers are ignored.
+///
+/// # Safety
+/// `buffer` must come from `zk_compile` or `zk_prove` and must not be released twice.
+#[no_mangle]
+pub unsafe extern "C" fn zk_buffer_free(buffer: ZkBuffer) {
+    if !buffer.data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buffer.data, buffer.len)));
+    }
+}
+
+/// Runs an API call, turning errors and panics into error codes so they never cross the FFI boundary.
+fn guard<T>(call: impl FnOnce() -> Result<T, Error>) -> Result<T, i32> {
+    match panic::catch_unwind(panic::AssertUnwindSafe(call)) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(err)) => Err(error_code(&err)),
+        Err(_) => Err(ZK_ERR_PANIC),
+    }
+}
+
+/// Maps a crate error to its error code.
+fn error_code(err: &Error) -> i32 {
+    match err {
+        Error::Io(_) => ZK_ERR_IO,
+        Error::Serialization(_) | Error::UnsupportedVersion { .. } => ZK_ERR_SERIALIZATION,
+        Error::Compile(_) => ZK_ERR_COMPILE,
+        Error::Input(_) => ZK_ERR_INPUT,
+        Error::External(_) => ZK_ERR_EXTERNAL,
+        Error::Cancelled => ZK_ERR_CANCELLED,
+        Error::VersionMismatch { .. } => ZK_ERR_VERSION,
+    }
+}
+
+/// Writes a successful result to the output buffer, or returns the error code.
+///
+/// # Safety
+/// `out` must be valid for writes.
+unsafe fn write_result(result: Result<Vec<u8>, i32>, out: *mut ZkBuffer) -> i32 {
+    match result {
+        Ok(bytes) => {
+            out.write(ZkBuffer::from_vec(bytes));
+            ZK_OK
+        },
+        Err(code) => code,
+    }
+}
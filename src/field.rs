@@ -1,8 +1,14 @@
-use std::ops::AddAssign;
-use num_bigint::BigInt;
-use num_traits::{One, Zero};
-use std::ops::{Add, Mul};
+use core::ops::AddAssign;
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+use core::ops::{Add, Mul};
+use core::str::FromStr;
+use alloc::format;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::ct::{self, Choice, ConstantTimeEq};
+use crate::error::Error;
 
 /// Represents an element in a finite field.
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
@@ -22,9 +28,39 @@ impl FieldElement {
     /// # Returns
     /// - `Self`: A new instance of the `FieldElement` struct.
     pub fn new(value: BigInt) -> Self {
-        let default_modulus = BigInt::from(1_000_000_007);
-        let normalized_value = value.clone() % &default_modulus;
-        FieldElement { value: normalized_value, modulus: default_modulus }
+        FieldElement::new_with_modulus(value, FieldElement::default_modulus())
+    }
+
+    /// Gets the modulus used by `new`.
+    ///
+    /// # Returns
+    /// - `BigInt`: The default modulus.
+    pub fn default_modulus() -> BigInt {
+        BigInt::from(1_000_000_007)
+    }
+
+    /// Creates a new field element with a given modulus.
+    ///
+    /// # Parameters
+    /// - `value`: The value of the field element.
+    /// - `modulus`: The modulus of the field.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `FieldElement` struct.
+    pub fn new_with_modulus(value: BigInt, modulus: BigInt) -> Self {
+        let normalized_value = value.mod_floor(&modulus); // Keep the representative in [0, modulus)
+        FieldElement { value: normalized_value, modulus }
+    }
+
+    /// Creates a field element in the same field as this one.
+    ///
+    /// # Parameters
+    /// - `value`: The value of the new field element.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The new field element.
+    pub(crate) fn with_value(&self, value: BigInt) -> FieldElement {
+        FieldElement::new_with_modulus(value, self.modulus.clone())
     }
 
     /// Gets the underlying value of the field element.
@@ -52,7 +88,7 @@ impl FieldElement {
     /// - `FieldElement`: The result of the addition.
     pub fn add(&self, other: &FieldElement) -> FieldElement {
         assert_eq!(self.modulus, other.modulus);
-        FieldElement::new(self.value.clone() + other.value.clone())
+        self.with_value(&self.value + &other.value)
     }
 
     /// Subtracts one field element from another.
@@ -64,7 +100,7 @@ impl FieldElement {
     /// - `FieldElement`: The result of the subtraction.
     pub fn sub(&self, other: &FieldElement) -> FieldElement {
         assert_eq!(self.modulus, other.modulus);
-        FieldElement::new(self.value.clone() - other.value.clone())
+        self.with_value(&self.value - &other.value)
     }
 
     /// Multiplies two field elements.
@@ -76,24 +112,62 @@ impl FieldElement {
     /// - `FieldElement`: The result of the multiplication.
     pub fn mul(&self, other: &FieldElement) -> FieldElement {
         assert_eq!(self.modulus, other.modulus);
-        FieldElement::new(&self.value * &other.value % &self.modulus)
+        self.with_value(&self.value * &other.value)
     }
 
     /// Computes the multiplicative inverse of the field element.
     ///
+    /// Odd moduli that fit in a `u64`, such as the default modulus, use the
+    /// binary extended GCD on machine words, which only shifts and subtracts.
+    /// Other moduli use the iterative extended Euclidean algorithm, which
+    /// outperforms the binary variant on `BigInt`s.
+    ///
     /// # Returns
     /// - `FieldElement`: The multiplicative inverse.
     ///
     /// # Panics
     /// - If the inverse does not exist.
     pub fn inv(&self) -> FieldElement {
-        let (gcd, x, _) = self.extended_gcd(&self.value, &self.modulus);
-        if gcd != BigInt::one() {
+        let inverse = match (self.value.to_u64(), self.modulus.to_u64()) {
+            (Some(value), Some(modulus)) if modulus % 2 == 1 => binary_inverse(value, modulus).map(BigInt::from),
+            _ => {
+                let (gcd, x, _) = extended_gcd(&self.value, &self.modulus);
+                if gcd.is_one() { Some(x) } else { None }
+            },
+        };
+        match inverse {
+            // Normalize the inverse to be positive
+            Some(x) => self.with_value(x),
+            None => panic!("Inverse does not exist"),
+        }
+    }
+
+    /// Computes the multiplicative inverse by Fermat's little theorem, as `self^(p - 2)`.
+    ///
+    /// This performs the same sequence of operations for every nonzero element,
+    /// but is only correct when the modulus is prime.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The multiplicative inverse.
+    ///
+    /// # Panics
+    /// - If the element is zero.
+    pub fn inv_prime(&self) -> FieldElement {
+        if self.value.is_zero() {
             panic!("Inverse does not exist");
         }
-        // Normalize the inverse to be positive
-        let normalized_inverse = x % &self.modulus;
-        FieldElement::new(normalized_inverse)
+        self.pow(&(&self.modulus - BigInt::from(2)))
+    }
+
+    /// Raises the field element to a power.
+    ///
+    /// # Parameters
+    /// - `exponent`: The exponent to raise the element to.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The result of the exponentiation.
+    pub fn pow(&self, exponent: &BigInt) -> FieldElement {
+        self.with_value(self.value.modpow(exponent, &self.modulus))
     }
 
     /// Negates the field element.
@@ -101,28 +175,268 @@ impl FieldElement {
     /// # Returns
     /// - `FieldElement`: The negated field element.
     pub fn negate(&self) -> FieldElement {
-        FieldElement::new(&self.modulus - &self.value)
+        self.with_value(&self.modulus - &self.value)
+    }
+}
+
+/// Sums field elements and products of them without reducing modulo the
+/// modulus after every term: the sum grows as an unbounded integer and is
+/// reduced once by `finish`.
+///
+/// Reducing a sum of `n` products costs one division instead of `n`, while
+/// the integer only grows by `log2(n)` bits beyond the size of a product.
+#[derive(Clone, Debug)]
+pub struct Accumulator {
+    /// The unreduced sum.
+    sum: BigInt,
+    /// The modulus of the field.
+    modulus: BigInt,
+}
+
+impl Accumulator {
+    /// Creates an empty accumulator over a field.
+    ///
+    /// # Parameters
+    /// - `modulus`: The modulus of the field.
+    ///
+    /// # Returns
+    /// - `Self`: An accumulator holding zero.
+    pub fn new(modulus: BigInt) -> Self {
+        Accumulator { sum: BigInt::zero(), modulus }
+    }
+
+    /// Adds a field element.
+    ///
+    /// # Parameters
+    /// - `value`: The field element to add.
+    ///
+    /// # Panics
+    /// - If the element belongs to another field.
+    pub fn add(&mut self, value: &FieldElement) {
+        assert_eq!(self.modulus, value.modulus, "Moduli must match for addition");
+        self.sum += &value.value;
+    }
+
+    /// Adds the product of two field elements.
+    ///
+    /// # Parameters
+    /// - `left`: The first factor.
+    /// - `right`: The second factor.
+    ///
+    /// # Panics
+    /// - If either factor belongs to another field.
+    pub fn add_product(&mut self, left: &FieldElement, right: &FieldElement) {
+        assert_eq!(self.modulus, left.modulus, "Moduli must match for multiplication");
+        assert_eq!(self.modulus, right.modulus, "Moduli must match for multiplication");
+        self.sum += &left.value * &right.value;
+    }
+
+    /// Adds a field element scaled by an integer, such as the coefficient of a
+    /// constraint term, which may be negative or unreduced.
+    ///
+    /// # Parameters
+    /// - `value`: The field element.
+    /// - `scalar`: The integer to scale it by.
+    ///
+    /// # Panics
+    /// - If the element belongs to another field.
+    pub fn add_scaled(&mut self, value: &FieldElement, scalar: &BigInt) {
+        assert_eq!(self.modulus, value.modulus, "Moduli must match for multiplication");
+        self.sum += &value.value * scalar;
+    }
+
+    /// Reduces the sum modulo the modulus.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The sum, as an element of the field.
+    pub fn finish(self) -> FieldElement {
+        FieldElement::new_with_modulus(self.sum, self.modulus)
+    }
+}
+
+/// The fewest bits of a modulus `ModulusPolicy::default` accepts without the
+/// toy field opt-in. Below it, random challenges collide too often for sound
+/// proofs.
+pub const MIN_MODULUS_BITS: u64 = 128;
+
+/// The primes dividing out small factors and serving as the fixed
+/// Miller-Rabin bases, which alone decide primality below `3.3 * 10^24`.
+const SMALL_PRIMES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// The number of Miller-Rabin bases derived from the candidate on top of
+/// `SMALL_PRIMES`, bounding the error on larger candidates by `4^-32`.
+const DERIVED_BASES: u32 = 32;
+
+/// Represents the requirements on a modulus supplied by a user, such as the
+/// prime of a circom file, before proofs are built over its field.
+///
+/// A composite modulus is always rejected: its "field" has zero divisors, so
+/// inverses fail and soundness arguments break. Small primes such as the
+/// 30-bit default modulus are fine for tests and demonstrations, but only
+/// with `allow_toy_fields` set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModulusPolicy {
+    /// The fewest bits of an accepted modulus.
+    pub min_bits: u64,
+    /// Whether moduli below `min_bits` are accepted, for testing.
+    pub allow_toy_fields: bool,
+}
+
+impl Default for ModulusPolicy {
+    fn default() -> Self {
+        ModulusPolicy { min_bits: MIN_MODULUS_BITS, allow_toy_fields: false }
+    }
+}
+
+impl ModulusPolicy {
+    /// Creates a policy accepting any prime modulus, for tests and demonstrations.
+    ///
+    /// # Returns
+    /// - `Self`: The policy, with `allow_toy_fields` set.
+    pub fn toy() -> Self {
+        ModulusPolicy { allow_toy_fields: true, ..ModulusPolicy::default() }
     }
 
-    /// Computes the extended GCD of two numbers.
+    /// Checks a modulus against the policy.
     ///
     /// # Parameters
-    /// - `a`: The first number.
-    /// - `b`: The second number.
+    /// - `modulus`: The modulus to check.
     ///
     /// # Returns
-    /// - `(BigInt, BigInt, BigInt)`: The GCD and the coefficients of Bézout's identity.
-    fn extended_gcd(&self, a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
-        if *b == BigInt::zero() {
-            return (a.clone(), BigInt::one(), BigInt::zero());
+    /// - `Result<(), Error>`: An `Input` error if the modulus is not prime, or is smaller than
+    ///   `min_bits` bits without the toy field opt-in.
+    pub fn check(&self, modulus: &BigInt) -> Result<(), Error> {
+        if !is_probable_prime(modulus) {
+            return Err(Error::Input(format!("The modulus {} is not prime", modulus)));
+        }
+        if modulus.bits() < self.min_bits && !self.allow_toy_fields {
+            return Err(Error::Input(format!(
+                "The modulus {} has {} bits, fewer than the {} required; allow toy fields to use it for testing",
+                modulus, modulus.bits(), self.min_bits
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Tests a number for primality with the Miller-Rabin test.
+///
+/// Trial division by `SMALL_PRIMES` comes first, then one round per small
+/// prime, which is exact below `3.3 * 10^24`, then `DERIVED_BASES` rounds
+/// with bases hashed from the candidate with SHA-256, so the answer is
+/// deterministic and no fixed set of bases can be targeted by a crafted
+/// composite.
+///
+/// # Parameters
+/// - `candidate`: The number to test.
+///
+/// # Returns
+/// - `bool`: `true` if the number is prime with overwhelming probability, otherwise `false`.
+pub fn is_probable_prime(candidate: &BigInt) -> bool {
+    if *candidate < BigInt::from(2) {
+        return false;
+    }
+    for prime in SMALL_PRIMES {
+        let prime = BigInt::from(prime);
+        if *candidate == prime {
+            return true;
+        }
+        if (candidate % &prime).is_zero() {
+            return false;
+        }
+    }
+
+    let minus_one = candidate - BigInt::one();
+    let shift = minus_one.trailing_zeros().expect("An odd candidate above 37 has a nonzero predecessor");
+    let odd = &minus_one >> shift;
+    let is_witness = |base: &BigInt| {
+        let mut x = base.modpow(&odd, candidate);
+        if x.is_one() || x == minus_one {
+            return false;
+        }
+        for _ in 1..shift {
+            x = &x * &x % candidate;
+            if x == minus_one {
+                return false;
+            }
+        }
+        true
+    };
+
+    let bytes = candidate.to_signed_bytes_le();
+    let derived = (0..DERIVED_BASES).map(|round| {
+        let digest = Sha256::new().chain_update(round.to_le_bytes()).chain_update(&bytes).finalize();
+        BigInt::from_bytes_le(Sign::Plus, &digest) % (candidate - BigInt::from(3)) + 2 // A base in [2, candidate - 2]
+    });
+    !SMALL_PRIMES.iter().map(|prime| BigInt::from(*prime)).chain(derived).any(|base| is_witness(&base))
+}
+
+/// Computes the inverse of `value` modulo an odd `modulus` with the binary extended GCD.
+///
+/// # Parameters
+/// - `value`: The number to invert, in `[0, modulus)`.
+/// - `modulus`: The odd modulus.
+///
+/// # Returns
+/// - `Option<u64>`: The inverse, in `[0, modulus)`, or `None` if `value` and `modulus` are not coprime.
+fn binary_inverse(value: u64, modulus: u64) -> Option<u64> {
+    let modulus = u128::from(modulus);
+    // Invariants: x1 * value = u and x2 * value = v (mod modulus), with x1 and x2 in [0, modulus)
+    let (mut u, mut v) = (u128::from(value), modulus);
+    let (mut x1, mut x2) = (1, 0);
+    while u != 1 && v != 1 {
+        if u == 0 {
+            return None; // u and v met at a common divisor greater than one
         }
-        let (gcd, x1, y1) = self.extended_gcd(b, &(a % b));
-        let x = y1.clone();
-        let y = x1 - (a / b) * &y1;
-        (gcd, x, y)
+        shift_out_twos(&mut u, &mut x1, modulus);
+        shift_out_twos(&mut v, &mut x2, modulus);
+        if u >= v {
+            u -= v;
+            x1 = if x1 >= x2 { x1 - x2 } else { x1 + modulus - x2 };
+        } else {
+            v -= u;
+            x2 = if x2 >= x1 { x2 - x1 } else { x2 + modulus - x1 };
+        }
+    }
+    // The result is below the modulus, which came from a u64
+    Some((if u == 1 { x1 } else { x2 }) as u64)
+}
+
+/// Divides `u` by its largest power of two, dividing `x` by the same power modulo an odd modulus.
+fn shift_out_twos(u: &mut u128, x: &mut u128, modulus: u128) {
+    let twos = u.trailing_zeros();
+    *u >>= twos;
+    for _ in 0..twos {
+        if *x % 2 == 1 {
+            *x += modulus; // Make x even without changing it modulo the modulus
+        }
+        *x >>= 1;
     }
 }
 
+/// Computes the extended GCD of two numbers iteratively.
+///
+/// # Parameters
+/// - `a`: The first number.
+/// - `b`: The second number.
+///
+/// # Returns
+/// - `(BigInt, BigInt, BigInt)`: The GCD and the coefficients of Bézout's identity.
+fn extended_gcd(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
+    let (mut old_r, mut r) = (a.clone(), b.clone());
+    let (mut old_x, mut x) = (BigInt::one(), BigInt::zero());
+    let (mut old_y, mut y) = (BigInt::zero(), BigInt::one());
+    while !r.is_zero() {
+        let (quotient, remainder) = old_r.div_rem(&r);
+        old_r = core::mem::replace(&mut r, remainder);
+        let next_x = &old_x - &quotient * &x;
+        old_x = core::mem::replace(&mut x, next_x);
+        let next_y = &old_y - &quotient * &y;
+        old_y = core::mem::replace(&mut y, next_y);
+    }
+    (old_r, old_x, old_y)
+}
+
 // Implement AddAssign for FieldElement
 impl AddAssign for FieldElement {
     /// Adds another field element to this one, in place.
@@ -131,7 +445,7 @@ impl AddAssign for FieldElement {
     /// - `other`: The other field element to add.
     fn add_assign(&mut self, other: FieldElement) {
         assert_eq!(self.modulus, other.modulus, "Moduli must match for addition");
-        self.value = (self.value.clone() + other.value) % &self.modulus; // Perform addition and normalize
+        self.value = (&self.value + other.value).mod_floor(&self.modulus); // Perform addition and normalize
     }
 }
 
@@ -166,7 +480,7 @@ impl Add for &FieldElement {
     /// - `FieldElement`: The result of the addition.
     fn add(self, other: &FieldElement) -> FieldElement {
         assert_eq!(self.modulus, other.modulus, "Moduli must match for addition");
-        FieldElement::new((self.value.clone() + other.value.clone()) % &self.modulus)
+        self.with_value(&self.value + &other.value)
     }
 }
 
@@ -182,7 +496,7 @@ impl Mul<&BigInt> for FieldElement {
     /// # Returns
     /// - `FieldElement`: The result of the multiplication.
     fn mul(self, rhs: &BigInt) -> FieldElement {
-        FieldElement::new((self.value.clone() * rhs) % &self.modulus) // Perform multiplication and normalize
+        self.with_value(&self.value * rhs) // Perform multiplication and normalize
     }
 }
 
@@ -200,4 +514,179 @@ impl Mul<BigInt> for FieldElement {
     fn mul(self, rhs: BigInt) -> FieldElement {
         self * &rhs // Delegate to the implementation that takes a reference
     }
-}
\ No newline at end of file
+}
+
+// Implementing ConstantTimeEq for FieldElement
+impl ConstantTimeEq for FieldElement {
+    /// Compares two field elements in time independent of their values;
+    /// only the moduli, which are public, are compared in variable time.
+    ///
+    /// # Parameters
+    /// - `other`: The other field element.
+    ///
+    /// # Returns
+    /// - `Choice`: `1` if the elements are equal, otherwise `0`.
+    fn ct_eq(&self, other: &FieldElement) -> Choice {
+        if self.modulus != other.modulus {
+            return Choice::from(0);
+        }
+        ct::bigint_eq(&self.value, &other.value, ct::field_width(&self.modulus))
+    }
+}
+
+// Implementing FromStr for FieldElement
+impl FromStr for FieldElement {
+    type Err = Error;
+
+    /// Parses a field element from a decimal string, or a hexadecimal one prefixed with `0x`.
+    ///
+    /// # Parameters
+    /// - `s`: The string to parse, optionally prefixed with `-`.
+    ///
+    /// # Returns
+    /// - `Result<FieldElement, Error>`: The parsed field element, reduced modulo the default modulus.
+    fn from_str(s: &str) -> Result<FieldElement, Error> {
+        let trimmed = s.trim();
+        let (negative, digits) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed),
+        };
+        let parsed = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+            Some(hex) => BigInt::parse_bytes(hex.as_bytes(), 16),
+            None => BigInt::parse_bytes(digits.as_bytes(), 10),
+        };
+        let value = parsed.ok_or_else(|| Error::Input(format!("Invalid field element: {}", s)))?;
+        Ok(FieldElement::new(if negative { -value } else { value }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The BN254 scalar field prime.
+    fn bn254() -> BigInt {
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617".parse().unwrap()
+    }
+
+    #[test]
+    fn policies_accept_large_primes() {
+        assert!(ModulusPolicy::default().check(&bn254()).is_ok());
+        assert!(ModulusPolicy::toy().check(&bn254()).is_ok());
+        assert!(ModulusPolicy::toy().check(&FieldElement::default_modulus()).is_ok());
+    }
+
+    #[test]
+    fn policies_reject_zero_and_composites() {
+        let composite = bn254() * BigInt::from(3);
+        for modulus in [BigInt::zero(), BigInt::one(), BigInt::from(1_000_000_008), composite].iter() {
+            assert!(ModulusPolicy::toy().check(modulus).is_err(), "{} accepted", modulus);
+            assert!(ModulusPolicy::default().check(modulus).is_err(), "{} accepted", modulus);
+        }
+    }
+
+    /// Checks the inverses of zero, one, `p - 1` and random elements under a modulus.
+    fn check_inverses(modulus: &BigInt) {
+        use num_bigint::RandBigInt;
+        let element = |value: BigInt| FieldElement::new_with_modulus(value, modulus.clone());
+        let one = element(BigInt::one());
+        assert_eq!(one.inv(), one);
+        let minus_one = element(modulus - BigInt::one());
+        assert_eq!(minus_one.inv(), minus_one);
+        let mut rng = crate::rng::seeded(7);
+        for _ in 0..16 {
+            let value = element(rng.gen_bigint_range(&BigInt::one(), modulus));
+            assert_eq!(FieldElement::mul(&value, &value.inv()), one, "{} has no inverse", value.get_value());
+            assert_eq!(value.inv(), value.inv_prime());
+        }
+    }
+
+    #[test]
+    fn inverses_use_machine_words_below_64_bits() {
+        check_inverses(&FieldElement::default_modulus());
+        // Near `u64::MAX`, adding the modulus during the halving needs the `u128` words
+        check_inverses(&BigInt::from(18_446_744_073_709_551_557u64));
+        assert_eq!(binary_inverse(2, 18_446_744_073_709_551_557), Some(9_223_372_036_854_775_779));
+    }
+
+    #[test]
+    fn inverses_wider_than_64_bits_use_the_euclidean_algorithm() {
+        check_inverses(&bn254());
+        check_inverses(&((BigInt::one() << 127) - BigInt::one()));
+    }
+
+    #[test]
+    fn zero_has_no_inverse() {
+        // Both algorithms stop on zero instead of looping
+        assert_eq!(binary_inverse(0, 1_000_000_007), None);
+        assert_eq!(binary_inverse(0, 18_446_744_073_709_551_557), None);
+        assert_eq!(binary_inverse(6, 9), None);
+        assert_eq!(extended_gcd(&BigInt::zero(), &bn254()).0, bn254());
+    }
+
+    #[test]
+    #[should_panic(expected = "Inverse does not exist")]
+    fn inverting_zero_panics() {
+        FieldElement::new(BigInt::zero()).inv();
+    }
+
+    #[test]
+    fn empty_accumulators_finish_at_zero() {
+        let modulus = FieldElement::default_modulus();
+        let sum = Accumulator::new(modulus.clone()).finish();
+        assert_eq!(sum, FieldElement::new(BigInt::zero()));
+        assert_eq!(sum.get_modulus(), &modulus);
+    }
+
+    #[test]
+    fn accumulators_match_reducing_after_every_term() {
+        use num_bigint::RandBigInt;
+        for modulus in [FieldElement::default_modulus(), bn254()].iter() {
+            let element = |value: BigInt| FieldElement::new_with_modulus(value, modulus.clone());
+            // Values next to the modulus make every term wrap around it
+            let mut values: Vec<FieldElement> = (1..=3).map(|offset| element(modulus - BigInt::from(offset))).collect();
+            let mut rng = crate::rng::seeded(3);
+            values.extend((0..32).map(|_| element(rng.gen_bigint_range(&BigInt::zero(), modulus))));
+
+            let (mut sum, mut products, mut scaled) = (Accumulator::new(modulus.clone()), Accumulator::new(modulus.clone()), Accumulator::new(modulus.clone()));
+            let (mut naive_sum, mut naive_products, mut naive_scaled) = (element(BigInt::zero()), element(BigInt::zero()), element(BigInt::zero()));
+            for (index, pair) in values.windows(2).enumerate() {
+                // Scalars may be negative or larger than the modulus
+                let scalar = if index % 2 == 0 { -BigInt::from(index + 1) } else { modulus * BigInt::from(3) + BigInt::from(index) };
+                sum.add(&pair[0]);
+                products.add_product(&pair[0], &pair[1]);
+                scaled.add_scaled(&pair[0], &scalar);
+                naive_sum = FieldElement::add(&naive_sum, &pair[0]);
+                naive_products = FieldElement::add(&naive_products, &FieldElement::mul(&pair[0], &pair[1]));
+                naive_scaled = FieldElement::add(&naive_scaled, &FieldElement::mul(&pair[0], &element(scalar)));
+            }
+            assert_eq!(sum.finish(), naive_sum);
+            assert_eq!(products.finish(), naive_products);
+            assert_eq!(scaled.finish(), naive_scaled);
+        }
+    }
+
+    #[test]
+    fn accumulated_sums_reduce_into_the_field() {
+        // Each (p - 1)^2 is 1, but the unreduced sum is far above the modulus
+        let modulus = FieldElement::default_modulus();
+        let minus_one = FieldElement::new(&modulus - BigInt::one());
+        let mut accumulator = Accumulator::new(modulus.clone());
+        for _ in 0..10 {
+            accumulator.add_product(&minus_one, &minus_one);
+        }
+        assert_eq!(accumulator.clone().finish(), FieldElement::new(BigInt::from(10)));
+
+        // A negative sum reduces to its representative in [0, p)
+        let mut negative = Accumulator::new(modulus.clone());
+        negative.add_scaled(&FieldElement::new(BigInt::one()), &BigInt::from(-5));
+        assert_eq!(negative.finish().get_value(), &modulus - BigInt::from(5));
+    }
+
+    #[test]
+    fn small_primes_need_the_toy_field_opt_in() {
+        assert!(ModulusPolicy::default().check(&FieldElement::default_modulus()).is_err());
+        assert!(ModulusPolicy::default().check(&BigInt::from(65_537)).is_err());
+        assert!(ModulusPolicy::toy().check(&BigInt::from(65_537)).is_ok());
+    }
+}
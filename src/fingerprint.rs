@@ -0,0 +1,246 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::ct::{Choice, ConstantTimeEq};
+use crate::error::Error;
+use crate::r1cs::{Constraint, Operation, Variable, R1CS};
+
+/// Identifies a constraint system by a SHA-256 hash of its normalized form.
+///
+/// Two systems have the same id exactly when they have the same modulus, the
+/// same number of variables, the same outputs and the same constraints up to
+/// the order of terms, merged duplicate terms and zero coefficients. Variable
+/// values are left out, so every witness of a circuit shares its id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CircuitId(pub [u8; 32]);
+
+impl fmt::Display for CircuitId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+impl ConstantTimeEq for CircuitId {
+    fn ct_eq(&self, other: &CircuitId) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl FromStr for CircuitId {
+    type Err = Error;
+
+    /// Parses a circuit id from the 64 hexadecimal digits `Display` writes.
+    ///
+    /// # Parameters
+    /// - `s`: The string to parse.
+    ///
+    /// # Returns
+    /// - `Result<CircuitId, Error>`: The parsed id.
+    fn from_str(s: &str) -> Result<CircuitId, Error> {
+        let invalid = || Error::Input(alloc::format!("Invalid circuit id: {}", s));
+        if s.len() != 64 || !s.is_ascii() {
+            return Err(invalid());
+        }
+        let mut id = [0u8; 32];
+        for (byte, digits) in id.iter_mut().zip(s.as_bytes().chunks(2)) {
+            let digits = core::str::from_utf8(digits).map_err(|_| invalid())?;
+            *byte = u8::from_str_radix(digits, 16).map_err(|_| invalid())?;
+        }
+        Ok(CircuitId(id))
+    }
+}
+
+/// Identifies a release of a circuit with a semantic version set by its
+/// author, carried by its R1CS, keys and proofs.
+///
+/// Unlike the `CircuitId`, which changes with any edit to the constraints,
+/// the version records the author's intent, so verifiers can tell a proof
+/// from a compatible release apart from one of a breaking release. Circuits
+/// without a version are `0.0.0`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CircuitVersion {
+    /// The major version, bumped by changes that break existing proofs.
+    pub major: u32,
+    /// The minor version, bumped by compatible changes.
+    pub minor: u32,
+    /// The patch version, bumped by fixes.
+    pub patch: u32,
+}
+
+impl CircuitVersion {
+    /// Creates a version.
+    ///
+    /// # Parameters
+    /// - `major`: The major version.
+    /// - `minor`: The minor version.
+    /// - `patch`: The patch version.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `CircuitVersion` struct.
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        CircuitVersion { major, minor, patch }
+    }
+
+    /// Checks whether artifacts of this version can be verified by a verifier
+    /// at another version.
+    ///
+    /// Versions follow semver's caret rule: the verifier accepts every
+    /// release from this one up to its own, as long as the leftmost non-zero
+    /// component is the same, so `1.4.0` accepts `1.2.3` but neither `2.0.0`
+    /// nor `1.5.0`, and `0.3.1` accepts `0.3.0` but not `0.2.0`.
+    ///
+    /// # Parameters
+    /// - `verifier`: The version of the verifying key or constraint system.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the verifier accepts this version, otherwise `false`.
+    pub fn is_compatible_with(&self, verifier: &CircuitVersion) -> bool {
+        let same_release = if verifier.major > 0 {
+            self.major == verifier.major
+        } else if verifier.minor > 0 {
+            self.major == 0 && self.minor == verifier.minor
+        } else {
+            self == verifier
+        };
+        same_release && self <= verifier
+    }
+
+    /// Checks that artifacts of this version can be verified by a verifier at
+    /// another version, as `is_compatible_with` does.
+    ///
+    /// # Parameters
+    /// - `verifier`: The version of the verifying key or constraint system.
+    ///
+    /// # Returns
+    /// - `Result<(), Error>`: `Ok` if the versions are compatible, otherwise `Error::VersionMismatch`.
+    pub fn check_compatible(&self, verifier: &CircuitVersion) -> Result<(), Error> {
+        if self.is_compatible_with(verifier) {
+            Ok(())
+        } else {
+            Err(Error::VersionMismatch { expected: *verifier, found: *self })
+        }
+    }
+}
+
+impl fmt::Display for CircuitVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for CircuitVersion {
+    type Err = Error;
+
+    /// Parses a version written as `major.minor.patch`.
+    ///
+    /// # Parameters
+    /// - `s`: The string to parse.
+    ///
+    /// # Returns
+    /// - `Result<CircuitVersion, Error>`: The parsed version.
+    fn from_str(s: &str) -> Result<CircuitVersion, Error> {
+        let invalid = || Error::Input(alloc::format!("Invalid circuit version `{}`, expected major.minor.patch", s));
+        let parts = s.split('.')
+            .map(|part| if part.is_empty() || !part.bytes().all(|byte| byte.is_ascii_digit()) { Err(invalid()) } else { part.parse().map_err(|_| invalid()) })
+            .collect::<Result<Vec<u32>, Error>>()?;
+        match parts[..] {
+            [major, minor, patch] => Ok(CircuitVersion { major, minor, patch }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl R1CS {
+    /// Computes the id of the constraint system, so artifacts built for another
+    /// circuit are detected instead of silently verifying the wrong thing.
+    ///
+    /// Addition constraints are hashed as their folded `A = left + right`, and
+    /// multiplication and hash constraints, which share their semantics, alike.
+    ///
+    /// # Returns
+    /// - `CircuitId`: The id of the constraint system.
+    pub fn circuit_id(&self) -> CircuitId {
+        let modulus = self.modulus();
+        let mut hasher = Sha256::new();
+        hasher.update(b"zk-r1cs");
+        update_integer(&mut hasher, &modulus);
+        update_length(&mut hasher, self.variables.len());
+        for wires in [&self.public_inputs, &self.outputs] {
+            update_length(&mut hasher, wires.len());
+            for wire in wires {
+                update_length(&mut hasher, *wire);
+            }
+        }
+        update_length(&mut hasher, self.constraints.len());
+        for constraint in &self.constraints {
+            let normal = NormalConstraint::new(constraint, &modulus);
+            hasher.update([if normal.addition { 0u8 } else { 1u8 }]);
+            for side in &normal.sides {
+                update_length(&mut hasher, side.len());
+                for (index, coeff) in side {
+                    update_length(&mut hasher, *index);
+                    update_integer(&mut hasher, coeff);
+                }
+            }
+        }
+        CircuitId(hasher.finalize().into())
+    }
+}
+
+/// Represents a constraint in normal form, so equivalent constraints compare equal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct NormalConstraint {
+    /// Whether this is an addition constraint, whose left and right sides are folded into one.
+    pub(crate) addition: bool,
+    /// The sides of the constraint, `A` and `C` for additions and `A`, `B` and `C` otherwise,
+    /// with terms sorted by variable, duplicates merged, coefficients reduced and zero terms dropped.
+    pub(crate) sides: Vec<BTreeMap<usize, BigInt>>,
+}
+
+impl NormalConstraint {
+    /// Normalizes a constraint over the field of the given modulus.
+    pub(crate) fn new(constraint: &Constraint, modulus: &BigInt) -> Self {
+        let normalize = |terms: &mut dyn Iterator<Item = &(Variable, BigInt)>| {
+            let mut normalized: BTreeMap<usize, BigInt> = BTreeMap::new();
+            for (variable, coeff) in terms {
+                let sum = normalized.entry(variable.index).or_insert_with(BigInt::zero);
+                *sum = (&*sum + coeff).mod_floor(modulus);
+            }
+            normalized.retain(|_, coeff| !coeff.is_zero());
+            normalized
+        };
+        // Multiplication and hash constraints share their semantics
+        let (addition, sides) = match constraint.operation {
+            Operation::Add => (true, vec![
+                normalize(&mut constraint.left.iter().chain(&constraint.right)),
+                normalize(&mut constraint.output.iter()),
+            ]),
+            Operation::Mul | Operation::Hash => (false, vec![
+                normalize(&mut constraint.left.iter()),
+                normalize(&mut constraint.right.iter()),
+                normalize(&mut constraint.output.iter()),
+            ]),
+        };
+        NormalConstraint { addition, sides }
+    }
+}
+
+/// Hashes a length or index as 8 little-endian bytes.
+pub(crate) fn update_length(hasher: &mut Sha256, length: usize) {
+    hasher.update((length as u64).to_le_bytes());
+}
+
+/// Hashes a non-negative integer as its length-prefixed big-endian bytes.
+pub(crate) fn update_integer(hasher: &mut Sha256, value: &BigInt) {
+    let (_, bytes) = value.to_bytes_be();
+    let bytes = if value.sign() == Sign::NoSign { Vec::new() } else { bytes };
+    update_length(hasher, bytes.len());
+    hasher.update(&bytes);
+}
@@ -0,0 +1,356 @@
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use serde::{Deserialize, Serialize};
+use crate::ct::{self, ConstantTimeEq};
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::fingerprint::CircuitId;
+use crate::poseidon::{DomainTag, Poseidon};
+use crate::r1cs::{evaluate, Operation, R1CS};
+
+/// Represents a relaxed R1CS instance together with its witness, satisfied when
+/// `(A z) ∘ (B z) = u · (C z) + E` holds constraint by constraint.
+///
+/// A fresh witness is the relaxed instance with `u = 1` and `E = 0`. Unlike
+/// plain R1CS, relaxed instances are closed under random linear combinations,
+/// which is what lets a folding scheme merge them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RelaxedInstance {
+    /// The assignment `z` of every variable.
+    pub witness: Vec<FieldElement>,
+    /// The scalar `u`, one for a fresh instance.
+    pub scalar: FieldElement,
+    /// The error vector `E`, one entry per constraint, zero for a fresh instance.
+    pub error: Vec<FieldElement>,
+}
+
+/// Records the public data of one folding step, from which a verifier
+/// recomputes its challenge and the folded scalar and error.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FoldStep {
+    /// The Poseidon commitment to the witness folded in at this step.
+    pub commitment: BigInt,
+    /// The cross term `T`, one entry per constraint.
+    pub cross_term: Vec<BigInt>,
+}
+
+/// Represents the outcome of folding a sequence of step witnesses of one
+/// constraint system, checked with a single relaxed R1CS check.
+///
+/// The verifier recomputes the challenges from the commitments and cross
+/// terms, then the folded scalar and error, so it only trusts the folded
+/// witness. Poseidon commitments are not homomorphic, so the folded witness is
+/// not bound to the committed step witnesses the way Nova binds them with
+/// Pedersen commitments: the proof demonstrates the folding algebra, not a
+/// succinct argument.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FoldingProof {
+    /// The id of the constraint system every step satisfies.
+    pub circuit_id: CircuitId,
+    /// The Poseidon commitment to the witness of the first step.
+    pub commitment: BigInt,
+    /// The steps folded into the first one, in order.
+    pub steps: Vec<FoldStep>,
+    /// The folded witness `z`.
+    pub witness: Vec<BigInt>,
+}
+
+/// Folds the witnesses of repeated steps of a constraint system into one
+/// relaxed instance, Nova-style, so an iterative computation is checked once
+/// at the end instead of proving every step from scratch.
+pub struct Folder<'a> {
+    /// The constraint system of a step.
+    r1cs: &'a R1CS,
+    /// The Poseidon parameters for commitments and challenges, over the field of the R1CS.
+    poseidon: Poseidon,
+    /// The running instance, which every folded step has been merged into.
+    instance: RelaxedInstance,
+    /// The last challenge, or the hash of the first commitment before any step is folded.
+    transcript: FieldElement,
+    /// The commitment to the witness of the first step.
+    commitment: BigInt,
+    /// The steps folded so far.
+    steps: Vec<FoldStep>,
+}
+
+impl<'a> Folder<'a> {
+    /// Starts folding from the witness of the first step.
+    ///
+    /// # Parameters
+    /// - `r1cs`: The constraint system of a step.
+    /// - `witness`: The witness of the first step, one value per variable.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The folder, or an error if the witness does not assign every variable of the R1CS.
+    pub fn new(r1cs: &'a R1CS, witness: &[FieldElement]) -> Result<Self> {
+        let modulus = r1cs.modulus();
+        let poseidon = Poseidon::generate(&modulus, 3)?;
+        check_length(r1cs, witness)?;
+        let instance = RelaxedInstance {
+            witness: witness.to_vec(),
+            scalar: FieldElement::new_with_modulus(BigInt::one(), modulus.clone()),
+            error: vec![FieldElement::new_with_modulus(BigInt::zero(), modulus); r1cs.constraints.len()],
+        };
+        let commitment = poseidon.hash(DomainTag::COMMITMENT, witness);
+        let transcript = poseidon.hash(DomainTag::CHALLENGE, core::slice::from_ref(&commitment));
+        Ok(Folder { r1cs, poseidon, instance, transcript, commitment: commitment.get_value(), steps: Vec::new() })
+    }
+
+    /// Folds the witness of the next step into the running instance.
+    ///
+    /// With the running instance `(z1, u1, E1)` and the fresh `(z2, 1, 0)`, the
+    /// cross term is `T = Az1 ∘ Bz2 + Az2 ∘ Bz1 - u1 · Cz2 - Cz1`, and the folded
+    /// instance is `(z1 + r z2, u1 + r, E1 + r T)` for a challenge `r` derived
+    /// from the transcript, the commitment to `z2` and `T`.
+    ///
+    /// # Parameters
+    /// - `witness`: The witness of the step, one value per variable.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the witness does not assign every variable of the R1CS.
+    pub fn fold(&mut self, witness: &[FieldElement]) -> Result<()> {
+        check_length(self.r1cs, witness)?;
+        let one = self.poseidon.element(BigInt::one());
+        let running = sides(self.r1cs, &self.instance.witness, &self.instance.scalar)?;
+        let incoming = sides(self.r1cs, witness, &one)?;
+        let cross_term: Vec<FieldElement> = running.iter().zip(&incoming)
+            .map(|((a1, b1, c1), (a2, b2, c2))| {
+                a1.mul(b2).add(&a2.mul(b1)).sub(&self.instance.scalar.mul(c2)).sub(c1)
+            })
+            .collect();
+
+        let commitment = self.poseidon.hash(DomainTag::COMMITMENT, witness);
+        let challenge = challenge(&self.poseidon, &self.transcript, &commitment, &cross_term);
+        self.instance.witness = self.instance.witness.iter().zip(witness)
+            .map(|(running, incoming)| running.add(&challenge.mul(incoming)))
+            .collect();
+        self.instance.scalar = self.instance.scalar.add(&challenge);
+        self.instance.error = fold_error(&self.instance.error, &cross_term, &challenge);
+        self.transcript = challenge;
+        self.steps.push(FoldStep {
+            commitment: commitment.get_value(),
+            cross_term: cross_term.iter().map(FieldElement::get_value).collect(),
+        });
+        Ok(())
+    }
+
+    /// Gets the running instance.
+    ///
+    /// # Returns
+    /// - `&RelaxedInstance`: The instance every step so far has been folded into.
+    pub fn instance(&self) -> &RelaxedInstance {
+        &self.instance
+    }
+
+    /// Gets the number of steps folded so far, including the first one.
+    ///
+    /// # Returns
+    /// - `usize`: The number of steps.
+    pub fn len(&self) -> usize {
+        self.steps.len() + 1
+    }
+
+    /// Checks whether only the first step has been folded.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if no step has been folded into the first one.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Finishes folding.
+    ///
+    /// # Returns
+    /// - `FoldingProof`: The commitments and cross terms of every step, with the folded witness.
+    pub fn finish(self) -> FoldingProof {
+        FoldingProof {
+            circuit_id: self.r1cs.circuit_id(),
+            commitment: self.commitment,
+            steps: self.steps,
+            witness: self.instance.witness.iter().map(FieldElement::get_value).collect(),
+        }
+    }
+}
+
+impl FoldingProof {
+    /// Verifies the folding of every step against the constraint system of a step.
+    ///
+    /// # Parameters
+    /// - `r1cs`: The constraint system of a step.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the proof was made for this R1CS and the folded witness
+    ///   satisfies the relaxed instance recomputed from the steps, otherwise `false`.
+    pub fn verify(&self, r1cs: &R1CS) -> bool {
+        let bound = self.circuit_id.ct_eq(&r1cs.circuit_id()); // Folded for this constraint system
+        let modulus = r1cs.modulus();
+        let poseidon = match Poseidon::generate(&modulus, 3) {
+            Ok(poseidon) => poseidon,
+            Err(_) => return false,
+        };
+        let mut scalar = poseidon.element(BigInt::one());
+        let mut error = vec![poseidon.element(BigInt::zero()); r1cs.constraints.len()];
+        let mut transcript = poseidon.hash(DomainTag::CHALLENGE, &[poseidon.element(self.commitment.clone())]);
+        for step in &self.steps {
+            if step.cross_term.len() != error.len() {
+                return false;
+            }
+            let cross_term: Vec<FieldElement> = step.cross_term.iter().map(|value| poseidon.element(value.clone())).collect();
+            let challenge = challenge(&poseidon, &transcript, &poseidon.element(step.commitment.clone()), &cross_term);
+            scalar = scalar.add(&challenge);
+            error = fold_error(&error, &cross_term, &challenge);
+            transcript = challenge;
+        }
+        let witness = self.witness.iter().map(|value| poseidon.element(value.clone())).collect();
+        bool::from(bound & ct::choice(r1cs.is_satisfied_relaxed(&RelaxedInstance { witness, scalar, error })))
+    }
+}
+
+impl R1CS {
+    /// Checks a relaxed instance, `(A z) ∘ (B z) = u · (C z) + E`, against every constraint.
+    ///
+    /// Addition constraints take `A = left + right` and `B = u`, the relaxed
+    /// counterpart of the constant one, so they stay linear in `z`.
+    ///
+    /// # Parameters
+    /// - `instance`: The relaxed instance.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if every constraint holds, otherwise `false`, including when
+    ///   the witness or error vector has the wrong length.
+    pub fn is_satisfied_relaxed(&self, instance: &RelaxedInstance) -> bool {
+        if instance.witness.len() != self.variables.len() || instance.error.len() != self.constraints.len() {
+            return false;
+        }
+        match sides(self, &instance.witness, &instance.scalar) {
+            Ok(sides) => sides.iter().zip(&instance.error)
+                .fold(ct::choice(true), |holds, ((a, b, c), error)| holds & a.mul(b).ct_eq(&instance.scalar.mul(c).add(error)))
+                .into(),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Fails unless a witness assigns every variable of the R1CS.
+fn check_length(r1cs: &R1CS, witness: &[FieldElement]) -> Result<()> {
+    if witness.len() != r1cs.variables.len() {
+        return Err(Error::Input(format!("Expected a step witness of {} values, got {}", r1cs.variables.len(), witness.len())));
+    }
+    Ok(())
+}
+
+/// Evaluates the `A`, `B` and `C` sides of every constraint on a witness with scalar `u`.
+fn sides(r1cs: &R1CS, witness: &[FieldElement], scalar: &FieldElement) -> Result<Vec<(FieldElement, FieldElement, FieldElement)>> {
+    let modulus = scalar.get_modulus();
+    r1cs.constraints.iter().enumerate()
+        .map(|(index, constraint)| {
+            let left = evaluate(index, &constraint.left, witness, modulus)?;
+            let right = evaluate(index, &constraint.right, witness, modulus)?;
+            let output = evaluate(index, &constraint.output, witness, modulus)?;
+            Ok(match constraint.operation {
+                Operation::Add => (left.add(&right), scalar.clone(), output),
+                Operation::Mul | Operation::Hash => (left, right, output),
+            })
+        })
+        .collect()
+}
+
+/// Derives the challenge of a step from the transcript, the commitment to the
+/// incoming witness and the cross term.
+fn challenge(poseidon: &Poseidon, transcript: &FieldElement, commitment: &FieldElement, cross_term: &[FieldElement]) -> FieldElement {
+    poseidon.hash(DomainTag::CHALLENGE, &[transcript.clone(), commitment.clone(), poseidon.hash(DomainTag::COMMITMENT, cross_term)])
+}
+
+/// Folds the error vector of a fresh instance, which is zero, into a running one as `E1 + r T`.
+fn fold_error(error: &[FieldElement], cross_term: &[FieldElement], challenge: &FieldElement) -> Vec<FieldElement> {
+    error.iter().zip(cross_term).map(|(error, cross)| error.add(&challenge.mul(cross))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an element of the default field.
+    fn element(value: i64) -> FieldElement {
+        FieldElement::new(BigInt::from(value))
+    }
+
+    /// Builds the step `y = x^2`, `z = y * x` over the variables `x`, `y` and `z`.
+    fn cube() -> R1CS {
+        let mut r1cs = R1CS::new();
+        for value in [2, 4, 8].iter() {
+            r1cs.add_variable(element(*value));
+        }
+        r1cs.add_constraints(&[
+            (vec![(0, element(1))], vec![(0, element(1))], vec![(1, element(1))]),
+            (vec![(1, element(1))], vec![(0, element(1))], vec![(2, element(1))]),
+        ]);
+        r1cs
+    }
+
+    /// Creates the witness `(x, x^2, x^3)`, with the cube shifted by `error`.
+    fn step(x: i64, error: i64) -> Vec<FieldElement> {
+        vec![element(x), element(x * x), element(x * x * x + error)]
+    }
+
+    /// Folds the given step witnesses into a proof.
+    fn fold(r1cs: &R1CS, steps: &[Vec<FieldElement>]) -> FoldingProof {
+        let mut folder = Folder::new(r1cs, &steps[0]).unwrap();
+        for witness in &steps[1..] {
+            folder.fold(witness).unwrap();
+        }
+        assert_eq!(folder.len(), steps.len());
+        folder.finish()
+    }
+
+    #[test]
+    fn folded_valid_steps_verify() {
+        let r1cs = cube();
+        let mut folder = Folder::new(&r1cs, &step(2, 0)).unwrap();
+        folder.fold(&step(3, 0)).unwrap();
+        assert!(r1cs.is_satisfied_relaxed(folder.instance()));
+        assert!(!folder.instance().error.iter().all(|error| error.get_value().is_zero()));
+
+        let proof = fold(&r1cs, &[step(2, 0), step(3, 0), step(5, 0), step(7, 0)]);
+        assert_eq!(proof.steps.len(), 3);
+        assert!(proof.verify(&r1cs));
+    }
+
+    #[test]
+    fn folding_an_invalid_step_fails_verification() {
+        let r1cs = cube();
+        for position in 0..3 {
+            let mut steps = vec![step(2, 0), step(3, 0), step(5, 0)];
+            steps[position] = step(3, 1);
+            assert!(!fold(&r1cs, &steps).verify(&r1cs), "An invalid step {} verified", position);
+        }
+    }
+
+    #[test]
+    fn tampered_folding_proofs_are_rejected() {
+        let r1cs = cube();
+        let honest = fold(&r1cs, &[step(2, 0), step(3, 0)]);
+
+        let mut witness = honest.clone();
+        witness.witness[0] += 1;
+        let mut cross_term = honest.clone();
+        cross_term.steps[0].cross_term[0] += 1;
+        let mut dropped = honest.clone();
+        dropped.steps.clear();
+        for tampered in [witness, cross_term, dropped].iter() {
+            assert!(!tampered.verify(&r1cs));
+        }
+    }
+
+    #[test]
+    fn witnesses_of_the_wrong_length_are_refused() {
+        let r1cs = cube();
+        assert!(Folder::new(&r1cs, &step(2, 0)[..2]).is_err());
+        let mut folder = Folder::new(&r1cs, &step(2, 0)).unwrap();
+        assert!(folder.fold(&[element(3)]).is_err());
+        assert!(folder.is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets::{Gadget, Wire};
+use crate::gadgets::boolean;
+
+/// Allocates a one-hot selector for a witness-provided index: one boolean
+/// wire per position, exactly one of them set, at the position the index
+/// wire holds.
+///
+/// The gates enforce that the selector bits sum to one and that their
+/// weighted sum `sum_j j * s_j` equals the index, so the circuit is only
+/// satisfied if the index is below `length`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `index`: The index of the wire holding the position.
+/// - `length`: The number of positions.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the selector bit wires.
+pub fn selector<CS: ConstraintSystem>(circuit: &mut CS, index: usize, length: usize) -> Vec<usize> {
+    let one = FieldElement::new(BigInt::one());
+    let zero = FieldElement::new(BigInt::zero());
+    let position = circuit.value(index).get_value();
+    let bits: Vec<usize> = (0..length).map(|j| boolean::alloc_bit(circuit, position == BigInt::from(j))).collect();
+
+    let count = circuit.alloc_linear(&[], one.clone());
+    let terms: Vec<(usize, FieldElement)> = bits.iter().map(|bit| (*bit, one.clone())).collect();
+    circuit.enforce_linear(&terms, zero.clone(), count);
+    let terms: Vec<(usize, FieldElement)> = bits.iter().enumerate().map(|(j, bit)| (*bit, FieldElement::new(BigInt::from(j)))).collect();
+    circuit.enforce_linear(&terms, zero, index);
+    bits
+}
+
+/// Reads `array[index]` for an index only known to the witness, by a linear
+/// scan costing one multiplication per element.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `array`: The indices of the element wires.
+/// - `index`: The index of the wire holding the position.
+///
+/// # Returns
+/// - `usize`: The index of the wire holding the selected element.
+pub fn get<CS: ConstraintSystem>(circuit: &mut CS, array: &[usize], index: usize) -> usize {
+    let one = FieldElement::new(BigInt::one());
+    let bits = selector(circuit, index, array.len());
+    let terms: Vec<(usize, FieldElement)> = bits.iter().zip(array)
+        .map(|(bit, element)| (circuit.alloc_mul(*bit, *element), one.clone()))
+        .collect();
+    circuit.alloc_linear(&terms, FieldElement::new(BigInt::zero()))
+}
+
+/// Writes `array[index] = value` for an index only known to the witness,
+/// returning the updated array.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `array`: The indices of the element wires.
+/// - `index`: The index of the wire holding the position.
+/// - `value`: The index of the wire holding the new element.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the updated element wires.
+pub fn set<CS: ConstraintSystem>(circuit: &mut CS, array: &[usize], index: usize, value: usize) -> Vec<usize> {
+    let one = FieldElement::new(BigInt::one());
+    let zero = FieldElement::new(BigInt::zero());
+    let bits = selector(circuit, index, array.len());
+    bits.iter().zip(array)
+        .map(|(bit, element)| {
+            // element + s * (value - element)
+            let change = circuit.alloc_linear(&[(value, one.clone()), (*element, one.negate())], zero.clone());
+            let applied = circuit.alloc_mul(*bit, change);
+            circuit.alloc_linear(&[(*element, one.clone()), (applied, one.clone())], zero.clone())
+        })
+        .collect()
+}
+
+/// `get` as a `Gadget`: the element wires followed by the position wire in,
+/// the selected element out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Get;
+
+impl Gadget for Get {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let [array @ .., index] = inputs else { panic!("Get takes the elements and a position") };
+        vec![get(cs, array, *index)]
+    }
+}
+
+/// `set` as a `Gadget`: the element wires followed by the position and the
+/// new element in, the updated elements out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Set;
+
+impl Gadget for Set {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let [array @ .., index, value] = inputs else { panic!("Set takes the elements, a position and a value") };
+        set(cs, array, *index, *value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::Circuit;
+    use crate::gadgets::is_satisfied;
+
+    /// Allocates the elements `10, 20, 30, 40` and a position wire.
+    fn array(circuit: &mut Circuit, position: i64) -> (Vec<usize>, usize) {
+        let elements = (1..=4).map(|value| circuit.add_input(FieldElement::new(BigInt::from(10 * value)))).collect();
+        (elements, circuit.add_input(FieldElement::new(BigInt::from(position))))
+    }
+
+    #[test]
+    fn private_positions_read_and_write_their_element() {
+        let mut circuit = Circuit::new();
+        let (elements, index) = array(&mut circuit, 2);
+        let element = get(&mut circuit, &elements, index);
+        assert_eq!(circuit.value(element).get_value(), BigInt::from(30));
+
+        let value = circuit.add_input(FieldElement::new(BigInt::from(99)));
+        let updated: Vec<BigInt> = set(&mut circuit, &elements, index, value).iter().map(|wire| circuit.value(*wire).get_value()).collect();
+        assert_eq!(updated, [10, 20, 99, 40].map(BigInt::from));
+        assert!(is_satisfied(&circuit));
+    }
+
+    #[test]
+    fn positions_out_of_range_are_unsatisfiable() {
+        // No selector bit is set, so they cannot sum to one
+        let mut circuit = Circuit::new();
+        let (elements, index) = array(&mut circuit, 4);
+        get(&mut circuit, &elements, index);
+        assert!(!is_satisfied(&circuit));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::blake2s::{self as native, BLOCK_BYTES, IV, MIX, SIGMA};
+use crate::constraint_system::ConstraintSystem;
+use crate::gadgets::{boolean, Gadget, Wire};
+use crate::gadgets::uint::UInt32;
+
+/// Runs the Blake2s compression function on one block of word wires.
+///
+/// Words stay decomposed into bits, so the gadget works below any modulus.
+/// Rotations only reorder wires; additions cost two multiplications per bit
+/// and exclusive ors one, about 41,000 multiplications per block.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `state`: The chaining value, eight words.
+/// - `block`: The message block, sixteen little-endian words.
+/// - `counter`: The number of message bytes hashed so far, including this block.
+/// - `last`: Whether this is the final block.
+///
+/// # Returns
+/// - `Vec<UInt32>`: The new chaining value.
+///
+/// # Panics
+/// - If the state is not eight words or the block is not sixteen words.
+pub fn compress<CS: ConstraintSystem>(circuit: &mut CS, state: &[UInt32], block: &[UInt32], counter: u64, last: bool) -> Vec<UInt32> {
+    assert_eq!(state.len(), 8, "The state must have eight words");
+    assert_eq!(block.len(), 16, "The block must have sixteen words");
+
+    // The lower half of the working vector only depends on public parameters
+    let mut lower = IV;
+    lower[4] ^= counter as u32;
+    lower[5] ^= (counter >> 32) as u32;
+    if last {
+        lower[6] = !lower[6];
+    }
+    let mut v: Vec<UInt32> = state.to_vec();
+    v.extend(lower.iter().map(|word| UInt32::constant(circuit, *word)));
+
+    for sigma in &SIGMA {
+        for (call, [a, b, c, d]) in MIX.iter().enumerate() {
+            mix(circuit, &mut v, [*a, *b, *c, *d], &block[sigma[2 * call]], &block[sigma[2 * call + 1]]);
+        }
+    }
+
+    (0..8).map(|i| {
+        let mixed = v[i].xor(circuit, &v[i + 8]);
+        state[i].xor(circuit, &mixed)
+    }).collect()
+}
+
+/// Hashes a byte string of bit wires into the unkeyed 32-byte Blake2s digest,
+/// matching `blake2s::hash`.
+///
+/// The length of the message is fixed by the circuit. The wires must be
+/// boolean, for example from `boolean::alloc_bytes`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `input`: The indices of the message bits, least significant bit of every byte first.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the 256 digest bits, least significant bit of every byte first.
+///
+/// # Panics
+/// - If the number of bits is not a multiple of eight.
+pub fn hash<CS: ConstraintSystem>(circuit: &mut CS, input: &[usize]) -> Vec<usize> {
+    assert_eq!(input.len() % 8, 0, "The message must be whole bytes");
+    let length = input.len() / 8;
+    let blocks = length.div_ceil(BLOCK_BYTES).max(1);
+    let zero = boolean::constant(circuit, false);
+
+    let mut state: Vec<UInt32> = native::initial_state().iter().map(|word| UInt32::constant(circuit, *word)).collect();
+    for index in 0..blocks {
+        // Little-endian words read the bits of their bytes in order, so a word is 32 consecutive bits
+        let mut bits: Vec<usize> = input.iter().copied().skip(index * BLOCK_BYTES * 8).take(BLOCK_BYTES * 8).collect();
+        bits.resize(BLOCK_BYTES * 8, zero);
+        let block: Vec<UInt32> = bits.chunks(UInt32::BITS).map(|word| UInt32::from_bits(word.to_vec())).collect();
+        let counter = ((index + 1) * BLOCK_BYTES).min(length) as u64;
+        state = compress(circuit, &state, &block, counter, index + 1 == blocks);
+    }
+    state.into_iter().flat_map(|word| word.bits).collect()
+}
+
+/// Applies the `G` function to four words of the working vector, mixing in two message words.
+fn mix<CS: ConstraintSystem>(circuit: &mut CS, v: &mut [UInt32], [a, b, c, d]: [usize; 4], x: &UInt32, y: &UInt32) {
+    v[a] = v[a].add(circuit, &v[b]).add(circuit, x);
+    v[d] = v[d].xor(circuit, &v[a]).rotate_right(16);
+    v[c] = v[c].add(circuit, &v[d]);
+    v[b] = v[b].xor(circuit, &v[c]).rotate_right(12);
+    v[a] = v[a].add(circuit, &v[b]).add(circuit, y);
+    v[d] = v[d].xor(circuit, &v[a]).rotate_right(8);
+    v[c] = v[c].add(circuit, &v[d]);
+    v[b] = v[b].xor(circuit, &v[c]).rotate_right(7);
+}
+
+/// `hash` as a `Gadget`: the message bit wires in, the 256 digest bit wires out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Blake2s;
+
+impl Gadget for Blake2s {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        hash(cs, inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::Circuit;
+    use crate::field::FieldElement;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn digests_match_the_native_hash_and_satisfy_the_constraints() {
+        // The RFC 7693 vector, which the native hash is checked against
+        let mut circuit = Circuit::new();
+        let bits = boolean::alloc_bytes(&mut circuit, b"abc");
+        let digest = hash(&mut circuit, &bits);
+        assert_eq!(boolean::read_bytes(&circuit, &digest), native::hash(b"abc"));
+
+        let r1cs = circuit.to_r1cs();
+        let mut witness = r1cs.generate_witness();
+        assert!(r1cs.check_witness(&witness).unwrap().is_empty());
+
+        // A digest bit flipped after the fact breaks the last exclusive or
+        let flipped = BigInt::from(1) - witness[digest[0]].get_value();
+        witness[digest[0]] = FieldElement::new(flipped);
+        assert!(!r1cs.check_witness(&witness).unwrap().is_empty());
+    }
+}
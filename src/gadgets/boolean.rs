@@ -0,0 +1,229 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets::{Gadget, Wire};
+
+/// Allocates a private boolean wire.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `value`: The value of the bit.
+///
+/// # Returns
+/// - `usize`: The index of the bit wire.
+pub fn alloc_bit<CS: ConstraintSystem>(circuit: &mut CS, value: bool) -> usize {
+    let bit = circuit.alloc_private(from_bool(value));
+    circuit.enforce_mul(bit, bit, bit); // bit * bit = bit forces a boolean
+    bit
+}
+
+/// Allocates a wire fixed to a constant bit.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `value`: The value of the bit.
+///
+/// # Returns
+/// - `usize`: The index of the bit wire.
+pub fn constant<CS: ConstraintSystem>(circuit: &mut CS, value: bool) -> usize {
+    circuit.alloc_linear(&[], from_bool(value))
+}
+
+/// Allocates the private bits of a byte string, least significant bit of
+/// every byte first.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `bytes`: The bytes.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the bit wires, eight per byte.
+pub fn alloc_bytes<CS: ConstraintSystem>(circuit: &mut CS, bytes: &[u8]) -> Vec<usize> {
+    bytes.iter()
+        .flat_map(|byte| (0..8).map(move |position| (byte >> position) & 1 == 1))
+        .map(|bit| alloc_bit(circuit, bit))
+        .collect()
+}
+
+/// Reads the bytes held by bit wires, least significant bit of every byte first.
+///
+/// # Parameters
+/// - `circuit`: The circuit holding the wires.
+/// - `bits`: The indices of the bit wires, eight per byte.
+///
+/// # Returns
+/// - `Vec<u8>`: The bytes.
+///
+/// # Panics
+/// - If the number of bits is not a multiple of eight.
+pub fn read_bytes<CS: ConstraintSystem>(circuit: &CS, bits: &[usize]) -> Vec<u8> {
+    assert_eq!(bits.len() % 8, 0, "Bits must come in whole bytes");
+    bits.chunks(8)
+        .map(|byte| byte.iter().enumerate().fold(0u8, |acc, (position, bit)| acc | (u8::from(value(circuit, *bit)) << position)))
+        .collect()
+}
+
+/// Reads the value of a bit wire.
+///
+/// # Parameters
+/// - `circuit`: The circuit holding the wire.
+/// - `bit`: The index of the bit wire.
+///
+/// # Returns
+/// - `bool`: `true` if the wire holds one.
+pub fn value<CS: ConstraintSystem>(circuit: &CS, bit: usize) -> bool {
+    circuit.value(bit).get_value().is_one()
+}
+
+/// Allocates the negation `1 - a` of a bit.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `a`: The index of the bit wire.
+///
+/// # Returns
+/// - `usize`: The index of the result wire.
+pub fn not<CS: ConstraintSystem>(circuit: &mut CS, a: usize) -> usize {
+    let one = FieldElement::new(BigInt::one());
+    circuit.alloc_linear(&[(a, one.negate())], one)
+}
+
+/// Allocates the conjunction `a * b` of two bits.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `a`: The index of the first bit wire.
+/// - `b`: The index of the second bit wire.
+///
+/// # Returns
+/// - `usize`: The index of the result wire.
+pub fn and<CS: ConstraintSystem>(circuit: &mut CS, a: usize, b: usize) -> usize {
+    circuit.alloc_mul(a, b)
+}
+
+/// Allocates the exclusive or `a + b - 2ab` of two bits, for one multiplication.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `a`: The index of the first bit wire.
+/// - `b`: The index of the second bit wire.
+///
+/// # Returns
+/// - `usize`: The index of the result wire.
+pub fn xor<CS: ConstraintSystem>(circuit: &mut CS, a: usize, b: usize) -> usize {
+    let product = circuit.alloc_mul(a, b);
+    xor_with_product(circuit, a, b, product)
+}
+
+/// Allocates `condition ? a : b` as `b + condition * (a - b)`, for one multiplication.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `condition`: The index of the selecting bit wire.
+/// - `a`: The index of the wire chosen when the bit is one.
+/// - `b`: The index of the wire chosen when the bit is zero.
+///
+/// # Returns
+/// - `usize`: The index of the result wire.
+pub fn select<CS: ConstraintSystem>(circuit: &mut CS, condition: usize, a: usize, b: usize) -> usize {
+    let one = FieldElement::new(BigInt::one());
+    let zero = FieldElement::new(BigInt::zero());
+    let difference = circuit.alloc_linear(&[(a, one.clone()), (b, one.negate())], zero.clone());
+    let chosen = circuit.alloc_mul(condition, difference);
+    circuit.alloc_linear(&[(b, one.clone()), (chosen, one)], zero)
+}
+
+/// Adds three bits, for two multiplications.
+///
+/// The carry `ab + (a xor b)c` needs no extra gate, since at most one of its
+/// two products can be one.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `a`: The index of the first bit wire.
+/// - `b`: The index of the second bit wire.
+/// - `carry`: The index of the incoming carry bit wire.
+///
+/// # Returns
+/// - `(usize, usize)`: The indices of the sum bit and outgoing carry bit wires.
+pub fn full_adder<CS: ConstraintSystem>(circuit: &mut CS, a: usize, b: usize, carry: usize) -> (usize, usize) {
+    let one = FieldElement::new(BigInt::one());
+    let both = circuit.alloc_mul(a, b);
+    let half = xor_with_product(circuit, a, b, both);
+    let propagated = circuit.alloc_mul(half, carry);
+    let sum = xor_with_product(circuit, half, carry, propagated);
+    let carry = circuit.alloc_linear(&[(both, one.clone()), (propagated, one)], FieldElement::new(BigInt::zero()));
+    (sum, carry)
+}
+
+/// Allocates `a + b - 2 * product`, the exclusive or of two bits whose
+/// product is already on a wire.
+fn xor_with_product<CS: ConstraintSystem>(circuit: &mut CS, a: usize, b: usize, product: usize) -> usize {
+    let one = FieldElement::new(BigInt::one());
+    let minus_two = FieldElement::new(BigInt::from(-2));
+    circuit.alloc_linear(&[(a, one.clone()), (b, one), (product, minus_two)], FieldElement::new(BigInt::zero()))
+}
+
+/// Maps a bit to a field element.
+fn from_bool(value: bool) -> FieldElement {
+    FieldElement::new(if value { BigInt::one() } else { BigInt::zero() })
+}
+
+/// `not` as a `Gadget`: one bit wire in, its negation out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Not;
+
+impl Gadget for Not {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let &[a] = inputs else { panic!("Not takes one input, got {}", inputs.len()) };
+        vec![not(cs, a)]
+    }
+}
+
+/// `and` as a `Gadget`: two bit wires in, their conjunction out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct And;
+
+impl Gadget for And {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let &[a, b] = inputs else { panic!("And takes two inputs, got {}", inputs.len()) };
+        vec![and(cs, a, b)]
+    }
+}
+
+/// `xor` as a `Gadget`: two bit wires in, their exclusive or out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Xor;
+
+impl Gadget for Xor {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let &[a, b] = inputs else { panic!("Xor takes two inputs, got {}", inputs.len()) };
+        vec![xor(cs, a, b)]
+    }
+}
+
+/// `select` as a `Gadget`: the condition bit, then the wires chosen when it
+/// is one and zero, in; the chosen wire out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Select;
+
+impl Gadget for Select {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let &[condition, a, b] = inputs else { panic!("Select takes three inputs, got {}", inputs.len()) };
+        vec![select(cs, condition, a, b)]
+    }
+}
+
+/// `full_adder` as a `Gadget`: two bits and a carry in, the sum bit and the
+/// outgoing carry out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FullAdder;
+
+impl Gadget for FullAdder {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let &[a, b, carry] = inputs else { panic!("FullAdder takes three inputs, got {}", inputs.len()) };
+        let (sum, carry) = full_adder(cs, a, b, carry);
+        vec![sum, carry]
+    }
+}
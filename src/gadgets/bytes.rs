@@ -0,0 +1,174 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets::{boolean, range};
+
+/// The block size of SHA-256 in bytes.
+pub const SHA256_BLOCK_BYTES: usize = 64;
+/// The rate of Keccak-256 in bytes.
+pub const KECCAK256_RATE_BYTES: usize = 136;
+/// The domain separation byte of the original Keccak padding, as used by Ethereum.
+pub const KECCAK_DOMAIN: u8 = 0x01;
+/// The domain separation byte of the standardized SHA-3 padding.
+pub const SHA3_DOMAIN: u8 = 0x06;
+
+/// Gets the number of bytes packed into one field element: the most whose
+/// values all stay below the modulus.
+///
+/// # Returns
+/// - `usize`: The number of bytes per element.
+pub fn bytes_per_element() -> usize {
+    let modulus = FieldElement::default_modulus();
+    (1..).take_while(|bytes| BigInt::one() << (8 * bytes) < modulus).last().unwrap_or(0)
+}
+
+/// Packs a byte string of bit wires into field elements, `bytes_per_element`
+/// bytes each, little-endian; the last element holds the remaining bytes.
+///
+/// Packing is linear, so it only costs one constraint per element, and a
+/// message of `n` bytes always packs into `ceil(n / bytes_per_element)`
+/// elements, so the length is fixed by the circuit.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `bits`: The indices of the bit wires, least significant bit of every byte first.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the element wires.
+///
+/// # Panics
+/// - If the number of bits is not a multiple of eight.
+pub fn pack<CS: ConstraintSystem>(circuit: &mut CS, bits: &[usize]) -> Vec<usize> {
+    assert_eq!(bits.len() % 8, 0, "Bits must come in whole bytes");
+    bits.chunks(8 * bytes_per_element())
+        .map(|chunk| {
+            let terms: Vec<(usize, FieldElement)> = chunk.iter().enumerate()
+                .map(|(position, bit)| (*bit, FieldElement::new(BigInt::one() << position)))
+                .collect();
+            circuit.alloc_linear(&terms, FieldElement::new(BigInt::zero()))
+        })
+        .collect()
+}
+
+/// Unpacks field elements into a byte string of bit wires, the inverse of
+/// `pack` for a message of a given length.
+///
+/// Every element is decomposed into exactly the bytes it holds, so the
+/// circuit is only satisfied if no element holds more.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `elements`: The indices of the element wires.
+/// - `length`: The length of the message in bytes.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the bit wires, least significant bit of every byte first.
+///
+/// # Panics
+/// - If the number of elements does not match the length.
+pub fn unpack<CS: ConstraintSystem>(circuit: &mut CS, elements: &[usize], length: usize) -> Vec<usize> {
+    let per_element = bytes_per_element();
+    assert_eq!(elements.len(), length.div_ceil(per_element), "{} bytes do not pack into {} elements", length, elements.len());
+    elements.iter().enumerate()
+        .flat_map(|(index, element)| {
+            let bytes = per_element.min(length - index * per_element);
+            range::to_bits(circuit, *element, 8 * bytes)
+        })
+        .collect()
+}
+
+/// Pads a message for SHA-256: a one bit, zeros, then the length of the
+/// message in bits as a 64-bit big-endian integer, up to a whole number of
+/// 64-byte blocks.
+///
+/// The length is fixed by the circuit, so the padding is constant wires.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `message`: The indices of the message bits, least significant bit of every byte first.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the padded message bits.
+///
+/// # Panics
+/// - If the number of bits is not a multiple of eight.
+pub fn pad_sha256<CS: ConstraintSystem>(circuit: &mut CS, message: &[usize]) -> Vec<usize> {
+    assert_eq!(message.len() % 8, 0, "The message must be whole bytes");
+    let length = message.len() / 8;
+    let mut padding = vec![0x80];
+    padding.resize((SHA256_BLOCK_BYTES + 55 - length % SHA256_BLOCK_BYTES) % SHA256_BLOCK_BYTES + 1, 0);
+    padding.extend_from_slice(&(8 * length as u64).to_be_bytes());
+    append_constant(circuit, message, &padding)
+}
+
+/// Pads a message for a Keccak sponge with the multi-rate padding: the
+/// domain separation byte, zeros, and a final one bit, up to a whole number of
+/// blocks of the rate.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `message`: The indices of the message bits, least significant bit of every byte first.
+/// - `rate`: The rate of the sponge in bytes, such as `KECCAK256_RATE_BYTES`.
+/// - `domain`: The domain separation byte, `KECCAK_DOMAIN` or `SHA3_DOMAIN`.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the padded message bits.
+///
+/// # Panics
+/// - If the number of bits is not a multiple of eight or the rate is zero.
+pub fn pad_keccak<CS: ConstraintSystem>(circuit: &mut CS, message: &[usize], rate: usize, domain: u8) -> Vec<usize> {
+    assert_eq!(message.len() % 8, 0, "The message must be whole bytes");
+    assert!(rate > 0, "The rate must be positive");
+    let mut padding = vec![0; rate - (message.len() / 8) % rate];
+    padding[0] = domain;
+    *padding.last_mut().expect("Padding is at least one byte") |= 0x80;
+    append_constant(circuit, message, &padding)
+}
+
+/// Appends constant bytes to a byte string of bit wires.
+fn append_constant<CS: ConstraintSystem>(circuit: &mut CS, message: &[usize], bytes: &[u8]) -> Vec<usize> {
+    let mut bits = message.to_vec();
+    bits.extend(bytes.iter()
+        .flat_map(|byte| (0..8).map(move |position| (byte >> position) & 1 == 1))
+        .map(|bit| boolean::constant(circuit, bit)));
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::Circuit;
+    use crate::gadgets::is_satisfied;
+
+    #[test]
+    fn bytes_pack_unpack_and_pad() {
+        let message = b"hello world";
+        let mut circuit = Circuit::new();
+        let bits = boolean::alloc_bytes(&mut circuit, message);
+        let elements = pack(&mut circuit, &bits);
+        assert_eq!(elements.len(), message.len().div_ceil(bytes_per_element()));
+        let unpacked = unpack(&mut circuit, &elements, message.len());
+        assert_eq!(boolean::read_bytes(&circuit, &unpacked), message);
+
+        let sha256 = pad_sha256(&mut circuit, &bits);
+        let padded = boolean::read_bytes(&circuit, &sha256);
+        assert_eq!(padded.len(), SHA256_BLOCK_BYTES);
+        assert_eq!(padded[message.len()], 0x80);
+        assert_eq!(padded[SHA256_BLOCK_BYTES - 8..], (8 * message.len() as u64).to_be_bytes());
+
+        let keccak = pad_keccak(&mut circuit, &bits, KECCAK256_RATE_BYTES, KECCAK_DOMAIN);
+        let padded = boolean::read_bytes(&circuit, &keccak);
+        assert_eq!(padded.len(), KECCAK256_RATE_BYTES);
+        assert_eq!((padded[message.len()], padded[KECCAK256_RATE_BYTES - 1]), (KECCAK_DOMAIN, 0x80));
+        assert!(is_satisfied(&circuit));
+    }
+
+    #[test]
+    fn elements_holding_more_bytes_are_unsatisfiable() {
+        let mut circuit = Circuit::new();
+        let element = circuit.add_input(FieldElement::new(BigInt::from(0x1ff)));
+        unpack(&mut circuit, &[element], 1);
+        assert!(!is_satisfied(&circuit));
+    }
+}
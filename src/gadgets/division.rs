@@ -0,0 +1,64 @@
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets::{range, Gadget, Wire};
+
+/// Divides two wires holding values below `2^bits`, allocating the quotient
+/// and remainder of the integer division.
+///
+/// The prover supplies `q` and `r`, and the gates enforce `a = q * b + r`,
+/// `q` and `r` below `2^bits` and `r < b`, which only the true quotient and
+/// remainder satisfy. A zero divisor leaves the circuit unsatisfied.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `a`: The index of the dividend wire.
+/// - `b`: The index of the divisor wire.
+/// - `bits`: The number of bits of both values.
+///
+/// # Returns
+/// - `(usize, usize)`: The indices of the quotient and remainder wires.
+///
+/// # Panics
+/// - If `2^(2 * bits + 1)` is not below the modulus, since `q * b + r` could then wrap around.
+pub fn div_rem<CS: ConstraintSystem>(circuit: &mut CS, a: usize, b: usize, bits: usize) -> (usize, usize) {
+    assert!(BigInt::one() << (2 * bits + 1) < FieldElement::default_modulus(), "{} bits are too many to divide", bits);
+    let one = FieldElement::new(BigInt::one());
+    let advice = circuit.alloc_hints(&[a, b], |values| {
+        let (dividend, divisor) = (values[0].get_value(), values[1].get_value());
+        let (quotient, remainder) = match divisor.is_zero() {
+            true => (BigInt::zero(), dividend),
+            false => dividend.div_rem(&divisor),
+        };
+        vec![FieldElement::new(quotient), FieldElement::new(remainder)]
+    });
+    let (q, r) = (advice[0], advice[1]);
+    range::enforce_range(circuit, q, bits);
+    range::enforce_range(circuit, r, bits);
+
+    let product = circuit.alloc_mul(q, b);
+    circuit.enforce_linear(&[(product, one.clone()), (r, one.clone())], FieldElement::new(BigInt::zero()), a);
+
+    // r < b is r + 1 <= b
+    let successor = circuit.alloc_linear(&[(r, one.clone())], one);
+    range::enforce_less_or_equal(circuit, successor, b, bits);
+    (q, r)
+}
+
+/// `div_rem` as a `Gadget`: the dividend and divisor wires in, the quotient
+/// and remainder wires out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DivRem {
+    /// The number of bits of both values.
+    pub bits: usize,
+}
+
+impl Gadget for DivRem {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let &[a, b] = inputs else { panic!("DivRem takes two inputs, got {}", inputs.len()) };
+        let (q, r) = div_rem(cs, a, b, self.bits);
+        vec![q, r]
+    }
+}
@@ -0,0 +1,172 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::constraint_system::ConstraintSystem;
+use crate::edwards::{EdwardsCurve, Point};
+use crate::field::FieldElement;
+use crate::gadgets::boolean;
+
+/// Represents a curve point allocated in a circuit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PointWires {
+    /// The index of the wire holding the x-coordinate.
+    pub x: usize,
+    /// The index of the wire holding the y-coordinate.
+    pub y: usize,
+}
+
+/// Allocates a point as private wires, without checking it is on the curve.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `point`: The point.
+///
+/// # Returns
+/// - `PointWires`: The coordinate wires.
+pub fn alloc_point<CS: ConstraintSystem>(circuit: &mut CS, point: &Point) -> PointWires {
+    PointWires { x: circuit.alloc_private(point.x.clone()), y: circuit.alloc_private(point.y.clone()) }
+}
+
+/// Allocates wires fixed to a constant point.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `point`: The point.
+///
+/// # Returns
+/// - `PointWires`: The coordinate wires.
+pub fn constant_point<CS: ConstraintSystem>(circuit: &mut CS, point: &Point) -> PointWires {
+    PointWires { x: circuit.alloc_linear(&[], point.x.clone()), y: circuit.alloc_linear(&[], point.y.clone()) }
+}
+
+/// Constrains a point to lie on the curve, `a x^2 + y^2 = 1 + d x^2 y^2`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `curve`: The curve, defined over the field of the circuit.
+/// - `point`: The point.
+///
+/// # Panics
+/// - If the curve is not defined over the field of the circuit.
+pub fn enforce_on_curve<CS: ConstraintSystem>(circuit: &mut CS, curve: &EdwardsCurve, point: &PointWires) {
+    check_field(circuit, curve, point);
+    let one = FieldElement::new(BigInt::one());
+    let xx = circuit.alloc_mul(point.x, point.x);
+    let yy = circuit.alloc_mul(point.y, point.y);
+    let xxyy = circuit.alloc_mul(xx, yy);
+    let left = circuit.alloc_linear(&[(xx, curve.a.clone()), (yy, one.clone())], FieldElement::new(BigInt::zero()));
+    circuit.enforce_linear(&[(xxyy, curve.d.clone())], one, left);
+}
+
+/// Adds two points with the complete addition law, for six multiplications
+/// and two divisions checked as multiplications.
+///
+/// With `beta = x1 y2`, `gamma = y1 x2`, `delta = (y1 - a x1)(x2 + y2)` and
+/// `tau = beta gamma`, the sum satisfies `x3 (1 + d tau) = beta + gamma` and
+/// `y3 (1 - d tau) = delta + a beta - gamma`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `curve`: The curve, defined over the field of the circuit.
+/// - `p`: The first point.
+/// - `q`: The second point.
+///
+/// # Returns
+/// - `PointWires`: The sum.
+///
+/// # Panics
+/// - If the curve is not defined over the field of the circuit.
+pub fn add<CS: ConstraintSystem>(circuit: &mut CS, curve: &EdwardsCurve, p: &PointWires, q: &PointWires) -> PointWires {
+    check_field(circuit, curve, p);
+    let one = FieldElement::new(BigInt::one());
+    let zero = FieldElement::new(BigInt::zero());
+    let sum = circuit.alloc_hints(&[p.x, p.y, q.x, q.y], |values| {
+        let sum = curve.add(&Point { x: values[0].clone(), y: values[1].clone() }, &Point { x: values[2].clone(), y: values[3].clone() });
+        vec![sum.x, sum.y]
+    });
+
+    let beta = circuit.alloc_mul(p.x, q.y);
+    let gamma = circuit.alloc_mul(p.y, q.x);
+    let tau = circuit.alloc_mul(beta, gamma);
+    let u = circuit.alloc_linear(&[(p.y, one.clone()), (p.x, curve.a.negate())], zero.clone());
+    let v = circuit.alloc_linear(&[(q.x, one.clone()), (q.y, one.clone())], zero.clone());
+    let delta = circuit.alloc_mul(u, v);
+
+    let x = sum[0];
+    let denominator = circuit.alloc_linear(&[(tau, curve.d.clone())], one.clone());
+    let numerator = circuit.alloc_linear(&[(beta, one.clone()), (gamma, one.clone())], zero.clone());
+    circuit.enforce_mul(x, denominator, numerator);
+
+    let y = sum[1];
+    let denominator = circuit.alloc_linear(&[(tau, curve.d.negate())], one.clone());
+    let numerator = circuit.alloc_linear(&[(delta, one.clone()), (beta, curve.a.clone()), (gamma, one.negate())], zero);
+    circuit.enforce_mul(y, denominator, numerator);
+    PointWires { x, y }
+}
+
+/// Multiplies a point by a scalar given as bits, with double-and-add.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `curve`: The curve, defined over the field of the circuit.
+/// - `bits`: The indices of the boolean wires of the scalar, least significant first.
+/// - `point`: The point.
+///
+/// # Returns
+/// - `PointWires`: The multiple.
+///
+/// # Panics
+/// - If the curve is not defined over the field of the circuit.
+pub fn scalar_mul<CS: ConstraintSystem>(circuit: &mut CS, curve: &EdwardsCurve, bits: &[usize], point: &PointWires) -> PointWires {
+    let mut result = constant_point(circuit, &curve.identity());
+    let mut base = *point;
+    for (position, bit) in bits.iter().enumerate() {
+        let sum = add(circuit, curve, &result, &base);
+        result = PointWires { x: boolean::select(circuit, *bit, sum.x, result.x), y: boolean::select(circuit, *bit, sum.y, result.y) };
+        if position + 1 < bits.len() {
+            base = add(circuit, curve, &base, &base);
+        }
+    }
+    result
+}
+
+/// Decomposes a wire into bits, least significant first, constraining every
+/// bit to be boolean and their weighted sum to equal the wire.
+///
+/// Unlike `range::to_bits`, the number of bits may reach the size of the
+/// field, as scalars hashed into it do. The sum may then wrap around, so a
+/// small value has a second decomposition, `value + modulus`; callers that
+/// need a unique scalar must bound it separately.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `wire`: The index of the wire.
+/// - `bits`: The number of bits.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the bit wires.
+pub fn scalar_bits<CS: ConstraintSystem>(circuit: &mut CS, wire: usize, bits: usize) -> Vec<usize> {
+    let value = circuit.value(wire).get_value();
+    let bit_wires: Vec<usize> = (0..bits).map(|position| boolean::alloc_bit(circuit, value.bit(position as u64))).collect();
+    let terms: Vec<(usize, FieldElement)> = bit_wires.iter().enumerate()
+        .map(|(position, bit)| (*bit, FieldElement::new(BigInt::one() << position)))
+        .collect();
+    circuit.enforce_linear(&terms, FieldElement::new(BigInt::zero()), wire);
+    bit_wires
+}
+
+/// Reads the point held by coordinate wires.
+///
+/// # Parameters
+/// - `circuit`: The circuit holding the wires.
+/// - `point`: The coordinate wires.
+///
+/// # Returns
+/// - `Point`: The point.
+pub fn value<CS: ConstraintSystem>(circuit: &CS, point: &PointWires) -> Point {
+    Point { x: circuit.value(point.x).clone(), y: circuit.value(point.y).clone() }
+}
+
+/// Fails unless the curve is defined over the field of the circuit.
+fn check_field<CS: ConstraintSystem>(circuit: &CS, curve: &EdwardsCurve, point: &PointWires) {
+    assert_eq!(circuit.value(point.x).get_modulus(), curve.modulus(), "The curve must be defined over the field of the circuit");
+}
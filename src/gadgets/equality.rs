@@ -0,0 +1,103 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets::{Gadget, Wire};
+
+/// Allocates a boolean wire holding 1 if a wire is zero and 0 otherwise.
+///
+/// The prover supplies `out` and `inv`, the inverse of the value or zero, and
+/// the gates enforce `x * inv = 1 - out` and `x * out = 0`: a nonzero `x`
+/// forces `out = 0`, and a zero `x` forces `out = 1`, whatever `inv` is.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `wire`: The index of the wire to test.
+///
+/// # Returns
+/// - `usize`: The index of the result wire.
+pub fn is_zero<CS: ConstraintSystem>(circuit: &mut CS, wire: usize) -> usize {
+    let one = FieldElement::new(BigInt::one());
+    let zero = FieldElement::new(BigInt::zero());
+    let inverse = circuit.alloc_hint(&[wire], inverse_or_zero);
+    let out = circuit.alloc_hint(&[wire], |values| FieldElement::new(BigInt::from(u8::from(values[0].get_value().is_zero()))));
+    let complement = circuit.alloc_linear(&[(out, one.negate())], one);
+    circuit.enforce_mul(wire, inverse, complement);
+    let expected = circuit.alloc_linear(&[], zero);
+    circuit.enforce_mul(wire, out, expected);
+    out
+}
+
+/// Allocates a boolean wire holding 1 if two wires are equal and 0 otherwise.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `a`: The index of the first wire.
+/// - `b`: The index of the second wire.
+///
+/// # Returns
+/// - `usize`: The index of the result wire.
+pub fn is_equal<CS: ConstraintSystem>(circuit: &mut CS, a: usize, b: usize) -> usize {
+    let one = FieldElement::new(BigInt::one());
+    let difference = circuit.alloc_linear(&[(a, one.clone()), (b, one.negate())], FieldElement::new(BigInt::zero()));
+    is_zero(circuit, difference)
+}
+
+/// Constrains two wires to hold different values, by proving that their
+/// difference has an inverse.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `a`: The index of the first wire.
+/// - `b`: The index of the second wire.
+pub fn enforce_not_equal<CS: ConstraintSystem>(circuit: &mut CS, a: usize, b: usize) {
+    let one = FieldElement::new(BigInt::one());
+    let zero = FieldElement::new(BigInt::zero());
+    let difference = circuit.alloc_linear(&[(a, one.clone()), (b, one.negate())], zero);
+    let inverse = circuit.alloc_hint(&[difference], inverse_or_zero);
+    let product = circuit.alloc_mul(difference, inverse);
+    let expected = circuit.alloc_linear(&[], one);
+    circuit.enforce_equal(product, expected);
+}
+
+/// Hints the inverse of a value, or zero for zero.
+fn inverse_or_zero(values: &[FieldElement]) -> FieldElement {
+    match values[0].get_value().is_zero() {
+        true => values[0].clone(),
+        false => values[0].inv(),
+    }
+}
+
+/// `is_zero` as a `Gadget`: one input wire, one boolean output wire.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IsZero;
+
+impl Gadget for IsZero {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let &[wire] = inputs else { panic!("IsZero takes one input, got {}", inputs.len()) };
+        vec![is_zero(cs, wire)]
+    }
+}
+
+/// `is_equal` as a `Gadget`: two input wires, one boolean output wire.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IsEqual;
+
+impl Gadget for IsEqual {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let &[a, b] = inputs else { panic!("IsEqual takes two inputs, got {}", inputs.len()) };
+        vec![is_equal(cs, a, b)]
+    }
+}
+
+/// `enforce_not_equal` as a `Gadget`: two input wires and no output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NotEqual;
+
+impl Gadget for NotEqual {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let &[a, b] = inputs else { panic!("NotEqual takes two inputs, got {}", inputs.len()) };
+        enforce_not_equal(cs, a, b);
+        Vec::new()
+    }
+}
@@ -0,0 +1,203 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets::range;
+use crate::gadgets::signed::SignedInt;
+
+/// Represents a fixed-point number in a circuit: a signed integer holding the
+/// number scaled by `2^frac_bits`, so scaling is tracked by the type instead
+/// of by hand.
+///
+/// Operations combine numbers of the same format only. Results are
+/// range-checked like `SignedInt`, so an overflow makes the circuit
+/// unsatisfiable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedPoint {
+    /// The scaled value, whose width is the total number of bits.
+    pub integer: SignedInt,
+    /// The number of fractional bits.
+    pub frac_bits: usize,
+}
+
+impl FixedPoint {
+    /// Allocates a private fixed-point number, rounded to the nearest representable value.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `value`: The value; the circuit is only satisfied if it fits in the format.
+    /// - `bits`: The total width, sign included.
+    /// - `frac_bits`: The number of fractional bits, below `bits`.
+    ///
+    /// # Returns
+    /// - `Self`: The number.
+    ///
+    /// # Panics
+    /// - If the format is invalid or products of two numbers would not fit below the modulus.
+    pub fn alloc<CS: ConstraintSystem>(circuit: &mut CS, value: f64, bits: usize, frac_bits: usize) -> Self {
+        check_format(bits, frac_bits);
+        FixedPoint { integer: SignedInt::alloc(circuit, scale(value, frac_bits), bits), frac_bits }
+    }
+
+    /// Allocates a fixed-point number fixed to a constant, rounded to the nearest representable value.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `value`: The value, which must fit in the format.
+    /// - `bits`: The total width, sign included.
+    /// - `frac_bits`: The number of fractional bits, below `bits`.
+    ///
+    /// # Returns
+    /// - `Self`: The number.
+    ///
+    /// # Panics
+    /// - If the format is invalid or the value does not fit.
+    pub fn constant<CS: ConstraintSystem>(circuit: &mut CS, value: f64, bits: usize, frac_bits: usize) -> Self {
+        check_format(bits, frac_bits);
+        FixedPoint { integer: SignedInt::constant(circuit, scale(value, frac_bits), bits), frac_bits }
+    }
+
+    /// Reads the value held by the number.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit holding the wire.
+    ///
+    /// # Returns
+    /// - `f64`: The value.
+    pub fn value<CS: ConstraintSystem>(&self, circuit: &CS) -> f64 {
+        self.integer.value(circuit) as f64 / (1u64 << self.frac_bits) as f64
+    }
+
+    /// Adds two numbers of the same format, which is exact.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `other`: The other number.
+    ///
+    /// # Returns
+    /// - `Self`: The sum.
+    ///
+    /// # Panics
+    /// - If the formats differ.
+    pub fn add<CS: ConstraintSystem>(&self, circuit: &mut CS, other: &Self) -> Self {
+        self.check_same_format(other);
+        FixedPoint { integer: self.integer.add(circuit, &other.integer), frac_bits: self.frac_bits }
+    }
+
+    /// Subtracts a number of the same format, which is exact.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `other`: The number to subtract.
+    ///
+    /// # Returns
+    /// - `Self`: The difference.
+    ///
+    /// # Panics
+    /// - If the formats differ.
+    pub fn sub<CS: ConstraintSystem>(&self, circuit: &mut CS, other: &Self) -> Self {
+        self.check_same_format(other);
+        FixedPoint { integer: self.integer.sub(circuit, &other.integer), frac_bits: self.frac_bits }
+    }
+
+    /// Multiplies two numbers of the same format.
+    ///
+    /// The product of the scaled values carries `2 * frac_bits` fractional
+    /// bits. The prover supplies the truncated quotient `q` and the remainder
+    /// `r`, and the gates enforce `product = q * 2^frac_bits + r` with
+    /// `0 <= r < 2^frac_bits`, so the result rounds towards negative infinity.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `other`: The other number.
+    ///
+    /// # Returns
+    /// - `Self`: The truncated product.
+    ///
+    /// # Panics
+    /// - If the formats differ.
+    pub fn mul<CS: ConstraintSystem>(&self, circuit: &mut CS, other: &Self) -> Self {
+        self.check_same_format(other);
+        let product = circuit.alloc_mul(self.integer.wire, other.integer.wire);
+        let exact = self.integer.value(circuit) * other.integer.value(circuit);
+        let scale = 1i64 << self.frac_bits;
+
+        let quotient = SignedInt::alloc(circuit, exact.div_euclid(scale), self.integer.bits);
+        let remainder = circuit.alloc_private(FieldElement::new(BigInt::from(exact.rem_euclid(scale))));
+        range::enforce_range(circuit, remainder, self.frac_bits);
+        let terms = [(quotient.wire, FieldElement::new(BigInt::from(scale))), (remainder, FieldElement::new(BigInt::one()))];
+        circuit.enforce_linear(&terms, FieldElement::new(BigInt::zero()), product);
+        FixedPoint { integer: quotient, frac_bits: self.frac_bits }
+    }
+
+    /// Allocates a boolean wire holding 1 if this number is below another.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `other`: The other number, of the same format.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the result wire.
+    ///
+    /// # Panics
+    /// - If the formats differ.
+    pub fn less_than<CS: ConstraintSystem>(&self, circuit: &mut CS, other: &Self) -> usize {
+        self.check_same_format(other);
+        self.integer.less_than(circuit, &other.integer)
+    }
+
+    /// Fails unless two numbers share a format.
+    fn check_same_format(&self, other: &Self) {
+        assert!(self.integer.bits == other.integer.bits && self.frac_bits == other.frac_bits,
+            "Cannot combine fixed-point numbers of {}.{} and {}.{} bits",
+            self.integer.bits, self.frac_bits, other.integer.bits, other.frac_bits);
+    }
+}
+
+/// Fails unless a format is usable: fractional bits below the width, and the
+/// product of two numbers below half the modulus so its sign survives.
+fn check_format(bits: usize, frac_bits: usize) {
+    assert!(frac_bits < bits, "{} fractional bits leave no room in {} bits", frac_bits, bits);
+    assert!(BigInt::one() << (2 * bits - 1) < FieldElement::default_modulus(), "Products of {}-bit fixed-point numbers do not fit below the modulus", bits);
+}
+
+/// Scales a value by `2^frac_bits`, rounding to the nearest integer.
+fn scale(value: f64, frac_bits: usize) -> i64 {
+    (value * (1u64 << frac_bits) as f64).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::Circuit;
+    use crate::gadgets::{boolean, is_satisfied};
+
+    #[test]
+    fn numbers_follow_fixed_point_arithmetic() {
+        let mut circuit = Circuit::new();
+        let (a, b) = (FixedPoint::alloc(&mut circuit, 1.5, 12, 4), FixedPoint::alloc(&mut circuit, -2.25, 12, 4));
+        assert_eq!(a.add(&mut circuit, &b).value(&circuit), -0.75);
+        assert_eq!(a.sub(&mut circuit, &b).value(&circuit), 3.75);
+        assert_eq!(a.mul(&mut circuit, &b).value(&circuit), -3.375);
+        // 0.3 rounds to 5/16, and its square truncates towards negative infinity
+        let c = FixedPoint::alloc(&mut circuit, 0.3, 12, 4);
+        assert_eq!(c.mul(&mut circuit, &c).value(&circuit), 0.0625);
+        let below = b.less_than(&mut circuit, &a);
+        assert!(boolean::value(&circuit, below));
+        assert!(is_satisfied(&circuit));
+    }
+
+    #[test]
+    fn overflows_are_unsatisfiable() {
+        // 12 bits with 4 fractional ones hold [-128, 128)
+        let mut circuit = Circuit::new();
+        let a = FixedPoint::alloc(&mut circuit, 100.0, 12, 4);
+        a.add(&mut circuit, &a);
+        assert!(!is_satisfied(&circuit));
+
+        let mut circuit = Circuit::new();
+        let a = FixedPoint::alloc(&mut circuit, 12.0, 12, 4);
+        a.mul(&mut circuit, &a);
+        assert!(!is_satisfied(&circuit));
+    }
+}
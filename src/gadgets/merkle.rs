@@ -0,0 +1,236 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets::{Gadget, Wire};
+use crate::hash::{CircuitHash, DomainTag};
+use crate::merkle::StateTransitionProof;
+
+/// Represents a Merkle path allocated in a circuit.
+pub struct PathWires {
+    /// The indices of the sibling wires, from the leaf level upwards.
+    pub siblings: Vec<usize>,
+    /// The indices of the direction bits, 1 when the current node is the right child.
+    pub bits: Vec<usize>,
+}
+
+/// Represents the wires exposed by the state-transition gadget.
+pub struct TransitionWires {
+    /// The index of the wire holding the old root.
+    pub old_root: usize,
+    /// The index of the wire holding the new root.
+    pub new_root: usize,
+    /// The index of the wire holding the leaf index encoded by the direction bits.
+    pub index: usize,
+}
+
+/// Allocates a Merkle path as private wires.
+///
+/// Each level allocates the sibling and a direction bit, so the position of the
+/// leaf is part of the witness rather than the circuit shape.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `path`: The Merkle path as returned by `MerkleTree::merkle_path`.
+///
+/// # Returns
+/// - `PathWires`: The allocated sibling and direction wires.
+pub fn alloc_path<CS: ConstraintSystem>(circuit: &mut CS, path: &[(FieldElement, bool)]) -> PathWires {
+    let mut wires = PathWires { siblings: Vec::new(), bits: Vec::new() };
+    for (sibling_hash, is_left) in path {
+        wires.siblings.push(circuit.alloc_private(sibling_hash.clone()));
+
+        let bit = circuit.alloc_private(FieldElement::new(BigInt::from(!*is_left as u8)));
+        circuit.enforce_mul(bit, bit, bit); // bit * bit = bit forces a boolean
+        wires.bits.push(bit);
+    }
+    wires
+}
+
+/// Recomputes a Merkle root from a leaf wire and an allocated path, hashing
+/// the leaf and the nodes as `MerkleHasher` does.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `hasher`: The hash used by the tree, with the same parameters as the native one.
+/// - `leaf`: The index of the leaf wire.
+/// - `path`: The allocated path.
+///
+/// # Returns
+/// - `usize`: The index of the computed root wire.
+pub fn root_from_path<CS: ConstraintSystem, G: CircuitHash>(circuit: &mut CS, hasher: &G, leaf: usize, path: &PathWires) -> usize {
+    let one = FieldElement::new(BigInt::one());
+    let minus_one = one.negate();
+    let zero = FieldElement::new(BigInt::zero());
+    let mut current = hasher.hash_gadget(circuit, DomainTag::MERKLE_LEAF, &[leaf]);
+
+    for (&sibling, &bit) in path.siblings.iter().zip(&path.bits) {
+        // left = current + bit * (sibling - current), right = current + sibling - left
+        let diff = circuit.alloc_linear(&[(sibling, one.clone()), (current, minus_one.clone())], zero.clone());
+        let offset = circuit.alloc_mul(bit, diff);
+        let left = circuit.alloc_linear(&[(current, one.clone()), (offset, one.clone())], zero.clone());
+        let right = circuit.alloc_linear(&[(current, one.clone()), (sibling, one.clone()), (left, minus_one.clone())], zero.clone());
+
+        current = hasher.hash_two_gadget(circuit, DomainTag::MERKLE_NODE, left, right);
+    }
+
+    current
+}
+
+/// Recomputes a Merkle root from a leaf wire and its authentication path.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `hasher`: The hash used by the tree, with the same parameters as the native one.
+/// - `leaf`: The index of the leaf wire.
+/// - `path`: The Merkle path as returned by `MerkleTree::merkle_path`.
+///
+/// # Returns
+/// - `usize`: The index of the computed root wire.
+pub fn merkle_root<CS: ConstraintSystem, G: CircuitHash>(circuit: &mut CS, hasher: &G, leaf: usize, path: &[(FieldElement, bool)]) -> usize {
+    let path = alloc_path(circuit, path);
+    root_from_path(circuit, hasher, leaf, &path)
+}
+
+/// Constrains a leaf wire to be a member of the Merkle tree with the given root.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `hasher`: The hash used by the tree, with the same parameters as the native one.
+/// - `leaf`: The index of the leaf wire.
+/// - `path`: The Merkle path as returned by `MerkleTree::merkle_path`.
+/// - `root`: The expected root.
+///
+/// # Returns
+/// - `usize`: The index of the wire holding the expected root.
+pub fn enforce_membership<CS: ConstraintSystem, G: CircuitHash>(circuit: &mut CS, hasher: &G, leaf: usize, path: &[(FieldElement, bool)], root: &FieldElement) -> usize {
+    let computed = merkle_root(circuit, hasher, leaf, path);
+    let expected = circuit.alloc_linear(&[], root.clone());
+    circuit.enforce_equal(computed, expected);
+    expected
+}
+
+/// Constrains a state transition of a Merkle tree.
+///
+/// A single set of sibling and direction wires is shared by the old and new
+/// root computations, so the circuit only accepts updates that change the leaf
+/// at one position and nothing else.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `hasher`: The hash used by the tree, with the same parameters as the native one.
+/// - `old_leaf`: The index of the wire holding the old leaf value.
+/// - `new_leaf`: The index of the wire holding the new leaf value.
+/// - `proof`: The state-transition proof supplying the path and expected roots.
+///
+/// # Returns
+/// - `TransitionWires`: The wires holding the old root, the new root and the leaf
+///   index, which equals `proof.index` for trees built with `new_with_depth`.
+pub fn enforce_transition<CS: ConstraintSystem, G: CircuitHash>(circuit: &mut CS, hasher: &G, old_leaf: usize, new_leaf: usize, proof: &StateTransitionProof<FieldElement>) -> TransitionWires {
+    assert!(proof.old_path == proof.new_path, "Old and new paths must share their siblings");
+    let path = alloc_path(circuit, &proof.old_path);
+
+    let computed_old = root_from_path(circuit, hasher, old_leaf, &path);
+    let old_root = circuit.alloc_linear(&[], proof.old_root.clone());
+    circuit.enforce_equal(computed_old, old_root);
+
+    let computed_new = root_from_path(circuit, hasher, new_leaf, &path);
+    let new_root = circuit.alloc_linear(&[], proof.new_root.clone());
+    circuit.enforce_equal(computed_new, new_root);
+
+    // Pack the direction bits, least significant level first, into the leaf index
+    let terms: Vec<(usize, FieldElement)> = path.bits.iter().enumerate()
+        .map(|(level, bit)| (*bit, FieldElement::new(BigInt::one() << level)))
+        .collect();
+    let index = circuit.alloc_linear(&terms, FieldElement::new(BigInt::zero()));
+
+    TransitionWires { old_root, new_root, index }
+}
+
+/// `root_from_path` as a `Gadget`: the leaf wire followed by one sibling and
+/// direction bit per level in, the root out.
+///
+/// The direction bits must already be constrained to be boolean, as
+/// `alloc_path` does.
+#[derive(Clone, Debug)]
+pub struct MerkleRoot<G: CircuitHash> {
+    /// The hash used by the tree, with the same parameters as the native one.
+    pub hasher: G,
+}
+
+impl<G: CircuitHash> Gadget for MerkleRoot<G> {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let [leaf, levels @ ..] = inputs else { panic!("MerkleRoot takes a leaf") };
+        assert_eq!(levels.len() % 2, 0, "Every level takes a sibling and a direction bit");
+        let path = PathWires {
+            siblings: levels.iter().step_by(2).copied().collect(),
+            bits: levels.iter().skip(1).step_by(2).copied().collect(),
+        };
+        vec![root_from_path(cs, &self.hasher, *leaf, &path)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::Circuit;
+    use crate::merkle::{MerkleHasher, PoseidonMerkleTree};
+    use crate::poseidon::Poseidon;
+
+    fn tree() -> PoseidonMerkleTree {
+        PoseidonMerkleTree::new((1..=5).map(|value| FieldElement::new(BigInt::from(value))).collect())
+    }
+
+    /// Proves membership of `leaf` at `index` against `root` and checks the witness.
+    fn membership_holds(tree: &PoseidonMerkleTree, index: usize, leaf: FieldElement, root: &FieldElement) -> bool {
+        let mut circuit = Circuit::new();
+        let leaf = circuit.add_input(leaf);
+        enforce_membership(&mut circuit, tree.hasher(), leaf, &tree.merkle_path(index), root);
+        let r1cs = circuit.to_r1cs();
+        r1cs.verify_witness(&r1cs.generate_witness())
+    }
+
+    #[test]
+    fn gadget_roots_match_the_native_tree() {
+        let tree = tree();
+        for index in 0..tree.leaves.len() {
+            let mut circuit = Circuit::new();
+            let leaf = circuit.add_input(tree.leaves[index].clone());
+            let root = merkle_root(&mut circuit, &Poseidon::new(), leaf, &tree.merkle_path(index));
+            assert_eq!(circuit.get_input(root), Some(&tree.root));
+        }
+    }
+
+    #[test]
+    fn members_satisfy_the_membership_constraints() {
+        let tree = tree();
+        assert!((0..tree.leaves.len()).all(|index| membership_holds(&tree, index, tree.leaves[index].clone(), &tree.root)));
+    }
+
+    #[test]
+    fn other_leaves_and_roots_are_rejected() {
+        let tree = tree();
+        assert!(!membership_holds(&tree, 1, tree.leaves[2].clone(), &tree.root));
+        assert!(!membership_holds(&tree, 1, tree.leaves[1].clone(), &tree.leaves[0]));
+    }
+
+    #[test]
+    fn nodes_cannot_pass_for_leaves() {
+        // The parent of the first two leaves with the rest of their path still fails as a leaf
+        let tree = tree();
+        let path = tree.merkle_path(0);
+        let hasher = tree.hasher();
+        let parent = hasher.hash_nodes(&hasher.hash_leaf(&tree.leaves[0]), &path[0].0);
+        let root = path[1..].iter().fold(parent.clone(), |node, (sibling, is_left)| match is_left {
+            true => hasher.hash_nodes(&node, sibling),
+            false => hasher.hash_nodes(sibling, &node),
+        });
+        assert_eq!(root, tree.root);
+
+        let mut circuit = Circuit::new();
+        let leaf = circuit.add_input(parent);
+        enforce_membership(&mut circuit, tree.hasher(), leaf, &path[1..], &tree.root);
+        let r1cs = circuit.to_r1cs();
+        assert!(!r1cs.verify_witness(&r1cs.generate_witness()));
+    }
+}
@@ -0,0 +1,59 @@
+//! Circuit gadgets: reusable building blocks that allocate wires and gates on a
+//! [`Circuit`](crate::circuit::Circuit) while computing the matching witness values.
+//!
+//! Every module exposes its gadgets as functions, and the wire-level ones also
+//! implement [`Gadget`], so they compose with gadgets from other crates
+//! through one interface.
+
+use crate::constraint_system::ConstraintSystem;
+
+pub mod array;
+pub mod blake2s;
+pub mod boolean;
+pub mod bytes;
+pub mod division;
+pub mod edwards;
+pub mod equality;
+pub mod fixed;
+pub mod merkle;
+pub mod pedersen;
+pub mod permutation;
+pub mod poseidon;
+pub mod poseidon2;
+pub mod range;
+pub mod schnorr;
+pub mod signature;
+pub mod signed;
+pub mod uint;
+
+/// The index of a wire in a constraint system.
+pub type Wire = usize;
+
+/// A reusable circuit component: it reads input wires, adds its gates to any
+/// constraint system and returns its output wires.
+///
+/// Parameters fixed when the circuit is built, such as a bit width or a hash
+/// function, live in the implementing type, and wires flow through
+/// `synthesize`. Third-party crates implement it to publish gadgets that mix
+/// with the built-in ones.
+pub trait Gadget {
+    /// Adds the gates of the gadget and computes their witness values.
+    ///
+    /// # Parameters
+    /// - `cs`: The constraint system to add the gates to.
+    /// - `inputs`: The input wires, in the order the gadget documents.
+    ///
+    /// # Returns
+    /// - `Vec<Wire>`: The output wires, empty for gadgets that only enforce constraints.
+    ///
+    /// # Panics
+    /// - If the number of inputs does not match the gadget.
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire>;
+}
+
+/// Checks every constraint of a circuit against the witness its gadgets computed.
+#[cfg(test)]
+pub(crate) fn is_satisfied(circuit: &crate::circuit::Circuit) -> bool {
+    let r1cs = circuit.to_r1cs();
+    r1cs.check_witness(&r1cs.generate_witness()).expect("The witness is in the field of the circuit").is_empty()
+}
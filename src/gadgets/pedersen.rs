@@ -0,0 +1,54 @@
+use num_bigint::BigInt;
+use num_traits::One;
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets::{range, Gadget, Wire};
+use crate::pedersen::Pedersen;
+
+/// Commits to a value wire with a blinding wire, matching `Pedersen::commit`.
+///
+/// Both exponents are decomposed into `bits` bits, which also range-checks
+/// them, and each bit selects whether the matching power of the generator
+/// enters the product: `g^v = prod_i (1 + v_i * (g^(2^i) - 1))`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `pedersen`: The commitment parameters, shared with the native implementation.
+/// - `value`: The index of the value wire.
+/// - `blinding`: The index of the blinding wire.
+/// - `bits`: The number of bits of both exponents.
+///
+/// # Returns
+/// - `usize`: The index of the commitment wire.
+///
+/// # Panics
+/// - If `2^bits` is not below the modulus.
+pub fn commit<CS: ConstraintSystem>(circuit: &mut CS, pedersen: &Pedersen, value: usize, blinding: usize, bits: usize) -> usize {
+    let one = FieldElement::new(BigInt::one());
+    let mut commitment = circuit.alloc_linear(&[], one.clone());
+    for (exponent, generator) in [(value, &pedersen.g), (blinding, &pedersen.h)] {
+        let mut power = generator.clone();
+        for bit in range::to_bits(circuit, exponent, bits) {
+            let factor = circuit.alloc_linear(&[(bit, power.sub(&one))], one.clone());
+            commitment = circuit.alloc_mul(commitment, factor);
+            power = power.mul(&power);
+        }
+    }
+    commitment
+}
+
+/// `commit` as a `Gadget`: the value and blinding wires in, the commitment out.
+#[derive(Clone, Debug)]
+pub struct Commit {
+    /// The commitment parameters, shared with the native implementation.
+    pub pedersen: Pedersen,
+    /// The number of bits of both exponents.
+    pub bits: usize,
+}
+
+impl Gadget for Commit {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let &[value, blinding] = inputs else { panic!("Commit takes two inputs, got {}", inputs.len()) };
+        vec![commit(cs, &self.pedersen, value, blinding, self.bits)]
+    }
+}
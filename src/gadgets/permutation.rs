@@ -0,0 +1,132 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets::{range, Gadget, Wire};
+use crate::hash::{CircuitHash, DomainTag};
+
+/// The domain tag of the challenge of the grand product.
+const CHALLENGE_TAG: DomainTag = DomainTag::new("permutation challenge");
+
+/// Constrains one list of wires to be a permutation of another, as
+/// multisets, with a randomized grand product.
+///
+/// The challenge `gamma` hashes both lists, so the prover cannot choose it,
+/// and the gates enforce `prod_i (x_i - gamma) = prod_i (y_i - gamma)`. Two
+/// different multisets give two different polynomials of degree `n`, which
+/// agree on at most `n` points, so a cheating prover succeeds with
+/// probability about `n / modulus`: negligible over a large field, but only
+/// a sanity check over the default one.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `hasher`: The hash function deriving the challenge.
+/// - `inputs`: The indices of the first list of wires.
+/// - `outputs`: The indices of the second list of wires.
+///
+/// # Panics
+/// - If the lists have different lengths.
+pub fn enforce_permutation<CS: ConstraintSystem, H: CircuitHash>(circuit: &mut CS, hasher: &H, inputs: &[usize], outputs: &[usize]) {
+    assert_eq!(inputs.len(), outputs.len(), "A permutation needs lists of the same length");
+    let transcript: Vec<usize> = inputs.iter().chain(outputs).copied().collect();
+    let gamma = hasher.hash_gadget(circuit, CHALLENGE_TAG, &transcript);
+    let left = grand_product(circuit, inputs, gamma);
+    let right = grand_product(circuit, outputs, gamma);
+    circuit.enforce_equal(left, right);
+}
+
+/// Sorts a list of wires holding values below `2^bits`, allocating the
+/// sorted list and constraining it to be non-decreasing and a permutation of
+/// the input.
+///
+/// Following the sort with `range::enforce_increasing` on the output instead
+/// rejects duplicates, for deduplication and set statements.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `hasher`: The hash function deriving the challenge of the permutation check.
+/// - `inputs`: The indices of the wires to sort.
+/// - `bits`: The number of bits of every value.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the sorted wires, smallest first.
+///
+/// # Panics
+/// - If `2^(bits + 1)` is not below the modulus.
+pub fn sort<CS: ConstraintSystem, H: CircuitHash>(circuit: &mut CS, hasher: &H, inputs: &[usize], bits: usize) -> Vec<usize> {
+    let outputs = circuit.alloc_hints(inputs, |values| {
+        let mut values = values.to_vec();
+        values.sort_by_key(|value| value.get_value());
+        values
+    });
+    for pair in outputs.windows(2) {
+        range::enforce_less_or_equal(circuit, pair[0], pair[1], bits);
+    }
+    enforce_permutation(circuit, hasher, inputs, &outputs);
+    outputs
+}
+
+/// Allocates `prod_i (wires_i - gamma)`.
+fn grand_product<CS: ConstraintSystem>(circuit: &mut CS, wires: &[usize], gamma: usize) -> usize {
+    let one = FieldElement::new(BigInt::one());
+    let mut product = circuit.alloc_linear(&[], one.clone());
+    for wire in wires {
+        let factor = circuit.alloc_linear(&[(*wire, one.clone()), (gamma, one.negate())], FieldElement::new(BigInt::zero()));
+        product = circuit.alloc_mul(product, factor);
+    }
+    product
+}
+
+/// `sort` as a `Gadget`: the wires to sort in, the sorted wires out.
+#[derive(Clone, Debug)]
+pub struct Sort<H: CircuitHash> {
+    /// The hash function deriving the challenge of the permutation check.
+    pub hasher: H,
+    /// The number of bits of every value.
+    pub bits: usize,
+}
+
+impl<H: CircuitHash> Gadget for Sort<H> {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        sort(cs, &self.hasher, inputs, self.bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::Circuit;
+    use crate::gadgets::is_satisfied;
+    use crate::poseidon::Poseidon;
+
+    fn inputs(circuit: &mut Circuit, values: &[i64]) -> Vec<usize> {
+        values.iter().map(|value| circuit.add_input(FieldElement::new(BigInt::from(*value)))).collect()
+    }
+
+    #[test]
+    fn permutations_and_sorted_lists_are_satisfiable() {
+        let mut circuit = Circuit::new();
+        let (first, second) = (inputs(&mut circuit, &[1, 2, 3, 3]), inputs(&mut circuit, &[3, 1, 3, 2]));
+        enforce_permutation(&mut circuit, &Poseidon::new(), &first, &second);
+
+        let unsorted = inputs(&mut circuit, &[5, 3, 9, 3]);
+        let sorted: Vec<BigInt> = sort(&mut circuit, &Poseidon::new(), &unsorted, 4).iter().map(|wire| circuit.value(*wire).get_value()).collect();
+        assert_eq!(sorted, [3, 3, 5, 9].map(BigInt::from));
+        assert!(is_satisfied(&circuit));
+    }
+
+    #[test]
+    fn other_multisets_are_unsatisfiable() {
+        // The same elements with other multiplicities are not a permutation
+        let mut circuit = Circuit::new();
+        let (first, second) = (inputs(&mut circuit, &[1, 2, 3, 3]), inputs(&mut circuit, &[1, 2, 2, 3]));
+        enforce_permutation(&mut circuit, &Poseidon::new(), &first, &second);
+        assert!(!is_satisfied(&circuit));
+
+        // Values too far apart for the comparisons cannot be sorted
+        let mut circuit = Circuit::new();
+        let unsorted = inputs(&mut circuit, &[40, 3]);
+        sort(&mut circuit, &Poseidon::new(), &unsorted, 4);
+        assert!(!is_satisfied(&circuit));
+    }
+}
@@ -0,0 +1,218 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets::{Gadget, Wire};
+use crate::poseidon::{DomainTag, Poseidon};
+
+/// Applies the Poseidon permutation to a state of wires.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `poseidon`: The Poseidon parameters, shared with the native implementation.
+/// - `state`: The indices of the state wires, of length `width`.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the permuted state wires.
+pub fn permute<CS: ConstraintSystem>(circuit: &mut CS, poseidon: &Poseidon, state: &[usize]) -> Vec<usize> {
+    assert_eq!(state.len(), poseidon.width, "State length must match the permutation width");
+    let one = FieldElement::new(BigInt::one());
+    let mut state = state.to_vec();
+
+    for round in 0..poseidon.full_rounds + poseidon.partial_rounds {
+        let full = poseidon.is_full_round(round);
+
+        // Add round constants, then apply the S-box where required
+        for (position, wire) in state.iter_mut().enumerate() {
+            let constant = poseidon.round_constants[round][position].clone();
+            *wire = circuit.alloc_linear(&[(*wire, one.clone())], constant);
+            if full || position == 0 {
+                *wire = sbox(circuit, *wire, poseidon.alpha);
+            }
+        }
+
+        // Mix the state with the MDS matrix
+        state = poseidon.mds.iter().map(|row| {
+            let terms: Vec<(usize, FieldElement)> = state.iter().cloned().zip(row.iter().cloned()).collect();
+            circuit.alloc_linear(&terms, FieldElement::new(BigInt::zero()))
+        }).collect();
+    }
+
+    state
+}
+
+/// Hashes a sequence of wires into one, matching `Poseidon::hash`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `poseidon`: The Poseidon parameters, shared with the native implementation.
+/// - `tag`: The purpose of the hash.
+/// - `inputs`: The indices of the input wires.
+///
+/// # Returns
+/// - `usize`: The index of the hash wire.
+pub fn hash<CS: ConstraintSystem>(circuit: &mut CS, poseidon: &Poseidon, tag: DomainTag, inputs: &[usize]) -> usize {
+    let one = FieldElement::new(BigInt::one());
+    let zero = FieldElement::new(BigInt::zero());
+    let rate = poseidon.width - 1;
+
+    // The capacity element starts at the tagged length, the rate at zero
+    let mut state: Vec<usize> = (0..poseidon.width).map(|position| {
+        let value = if position == 0 { tag.capacity(&poseidon.modulus, inputs.len()) } else { BigInt::zero() };
+        circuit.alloc_linear(&[], FieldElement::new(value))
+    }).collect();
+
+    for (chunk_index, chunk) in inputs.chunks(rate).enumerate() {
+        if chunk_index > 0 {
+            state = permute(circuit, poseidon, &state);
+        }
+        for (position, input) in chunk.iter().enumerate() {
+            state[1 + position] = circuit.alloc_linear(&[(state[1 + position], one.clone()), (*input, one.clone())], zero.clone());
+        }
+    }
+
+    permute(circuit, poseidon, &state)[1]
+}
+
+/// Hashes two wires into one, matching `Poseidon::hash_two`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `poseidon`: The Poseidon parameters, shared with the native implementation.
+/// - `tag`: The purpose of the hash.
+/// - `left`: The index of the left input wire.
+/// - `right`: The index of the right input wire.
+///
+/// # Returns
+/// - `usize`: The index of the hash wire.
+pub fn hash_two<CS: ConstraintSystem>(circuit: &mut CS, poseidon: &Poseidon, tag: DomainTag, left: usize, right: usize) -> usize {
+    hash(circuit, poseidon, tag, &[left, right])
+}
+
+/// Represents a Poseidon sponge over wires, matching `PoseidonSponge`, for
+/// Fiat-Shamir transcripts verified inside a circuit.
+///
+/// It duplexes like the native sponge: absorbing after squeezing, or into a
+/// full rate, permutes first, so a circuit absorbing the same messages in the
+/// same order squeezes the same challenges as the native prover.
+#[derive(Clone, Debug)]
+pub struct Sponge {
+    /// The permutation used by the sponge.
+    poseidon: Poseidon,
+    /// The indices of the state wires, the capacity element first.
+    state: Vec<usize>,
+    /// The next rate position to absorb into or squeeze from.
+    position: usize,
+    /// Whether the last operation was a squeeze.
+    squeezing: bool,
+}
+
+impl Sponge {
+    /// Creates a sponge with a zero capacity element, matching `PoseidonSponge::new`.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `poseidon`: The Poseidon parameters, shared with the native implementation.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Sponge` struct.
+    pub fn new<CS: ConstraintSystem>(circuit: &mut CS, poseidon: Poseidon) -> Self {
+        let capacity = circuit.alloc_linear(&[], FieldElement::new(BigInt::zero()));
+        Sponge::with_capacity(circuit, poseidon, capacity)
+    }
+
+    /// Creates a sponge with a given initial capacity wire, matching `PoseidonSponge::with_capacity`.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `poseidon`: The Poseidon parameters, shared with the native implementation.
+    /// - `capacity`: The index of the wire holding the initial capacity element.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Sponge` struct.
+    pub fn with_capacity<CS: ConstraintSystem>(circuit: &mut CS, poseidon: Poseidon, capacity: usize) -> Self {
+        let mut state = vec![capacity];
+        state.extend((1..poseidon.width).map(|_| circuit.alloc_linear(&[], FieldElement::new(BigInt::zero()))));
+        Sponge { poseidon, state, position: 0, squeezing: false }
+    }
+
+    /// Absorbs a wire into the sponge.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `element`: The index of the wire to absorb.
+    pub fn absorb<CS: ConstraintSystem>(&mut self, circuit: &mut CS, element: usize) {
+        if self.squeezing || self.position == self.rate() {
+            self.state = permute(circuit, &self.poseidon, &self.state);
+            self.position = 0;
+            self.squeezing = false;
+        }
+        let one = FieldElement::new(BigInt::one());
+        let slot = 1 + self.position;
+        self.state[slot] = circuit.alloc_linear(&[(self.state[slot], one.clone()), (element, one)], FieldElement::new(BigInt::zero()));
+        self.position += 1;
+    }
+
+    /// Squeezes a challenge wire out of the sponge.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the squeezed wire.
+    pub fn squeeze<CS: ConstraintSystem>(&mut self, circuit: &mut CS) -> usize {
+        if !self.squeezing || self.position == self.rate() {
+            self.state = permute(circuit, &self.poseidon, &self.state);
+            self.position = 0;
+            self.squeezing = true;
+        }
+        let output = self.state[1 + self.position];
+        self.position += 1;
+        output
+    }
+
+    /// Gets the number of rate elements of the sponge.
+    fn rate(&self) -> usize {
+        self.poseidon.width - 1
+    }
+}
+
+/// Raises a wire to the S-box exponent by square-and-multiply.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `input`: The index of the input wire.
+/// - `alpha`: The S-box exponent.
+///
+/// # Returns
+/// - `usize`: The index of the output wire.
+pub(crate) fn sbox<CS: ConstraintSystem>(circuit: &mut CS, input: usize, alpha: u64) -> usize {
+    let mut result: Option<usize> = None;
+    for bit in (0..64 - alpha.leading_zeros()).rev() {
+        if let Some(current) = result {
+            result = Some(circuit.alloc_mul(current, current));
+        }
+        if (alpha >> bit) & 1 == 1 {
+            result = Some(match result {
+                Some(current) => circuit.alloc_mul(current, input),
+                None => input,
+            });
+        }
+    }
+    result.expect("S-box exponent must be positive")
+}
+
+/// `hash` as a `Gadget`: the input wires in, their hash under `tag` out.
+#[derive(Clone, Debug)]
+pub struct Hash {
+    /// The Poseidon parameters, shared with the native implementation.
+    pub poseidon: Poseidon,
+    /// The purpose of the hash.
+    pub tag: DomainTag,
+}
+
+impl Gadget for Hash {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        vec![hash(cs, &self.poseidon, self.tag, inputs)]
+    }
+}
@@ -0,0 +1,146 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets::{Gadget, Wire};
+use crate::gadgets::poseidon::sbox;
+use crate::poseidon::DomainTag;
+use crate::poseidon2::Poseidon2;
+
+/// Applies the Poseidon2 permutation to a state of wires.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `poseidon2`: The Poseidon2 parameters, shared with the native implementation.
+/// - `state`: The indices of the state wires, of length `width`.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the permuted state wires.
+pub fn permute<CS: ConstraintSystem>(circuit: &mut CS, poseidon2: &Poseidon2, state: &[usize]) -> Vec<usize> {
+    assert_eq!(state.len(), poseidon2.width, "State length must match the permutation width");
+    let one = FieldElement::new(BigInt::one());
+    let half = poseidon2.external_rounds / 2;
+    let external_diagonal = vec![one.clone(); poseidon2.width];
+
+    let mut state = mix(circuit, state, &external_diagonal);
+
+    for round in 0..half {
+        state = external_round(circuit, poseidon2, &state, round);
+    }
+
+    for round in 0..poseidon2.internal_rounds {
+        let constant = poseidon2.internal_constants[round].clone();
+        state[0] = circuit.alloc_linear(&[(state[0], one.clone())], constant);
+        state[0] = sbox(circuit, state[0], poseidon2.alpha);
+        state = mix(circuit, &state, &poseidon2.internal_diagonal);
+    }
+
+    for round in half..poseidon2.external_rounds {
+        state = external_round(circuit, poseidon2, &state, round);
+    }
+
+    state
+}
+
+/// Hashes a sequence of wires into one, matching `Poseidon2::hash`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `poseidon2`: The Poseidon2 parameters, shared with the native implementation.
+/// - `tag`: The purpose of the hash.
+/// - `inputs`: The indices of the input wires.
+///
+/// # Returns
+/// - `usize`: The index of the hash wire.
+pub fn hash<CS: ConstraintSystem>(circuit: &mut CS, poseidon2: &Poseidon2, tag: DomainTag, inputs: &[usize]) -> usize {
+    let one = FieldElement::new(BigInt::one());
+    let zero = FieldElement::new(BigInt::zero());
+    let rate = poseidon2.width - 1;
+
+    // The capacity element starts at the tagged length, the rate at zero
+    let mut state: Vec<usize> = (0..poseidon2.width).map(|position| {
+        let value = if position == 0 { tag.capacity(&poseidon2.modulus, inputs.len()) } else { BigInt::zero() };
+        circuit.alloc_linear(&[], FieldElement::new(value))
+    }).collect();
+
+    for (chunk_index, chunk) in inputs.chunks(rate).enumerate() {
+        if chunk_index > 0 {
+            state = permute(circuit, poseidon2, &state);
+        }
+        for (position, input) in chunk.iter().enumerate() {
+            state[1 + position] = circuit.alloc_linear(&[(state[1 + position], one.clone()), (*input, one.clone())], zero.clone());
+        }
+    }
+
+    permute(circuit, poseidon2, &state)[1]
+}
+
+/// Hashes two wires into one, matching `Poseidon2::hash_two`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `poseidon2`: The Poseidon2 parameters, shared with the native implementation.
+/// - `tag`: The purpose of the hash.
+/// - `left`: The index of the left input wire.
+/// - `right`: The index of the right input wire.
+///
+/// # Returns
+/// - `usize`: The index of the hash wire.
+pub fn hash_two<CS: ConstraintSystem>(circuit: &mut CS, poseidon2: &Poseidon2, tag: DomainTag, left: usize, right: usize) -> usize {
+    hash(circuit, poseidon2, tag, &[left, right])
+}
+
+/// Applies one external round: constants and S-box on every wire, then the external matrix.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `poseidon2`: The Poseidon2 parameters.
+/// - `state`: The indices of the state wires.
+/// - `round`: The index of the external round.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the updated state wires.
+fn external_round<CS: ConstraintSystem>(circuit: &mut CS, poseidon2: &Poseidon2, state: &[usize], round: usize) -> Vec<usize> {
+    let one = FieldElement::new(BigInt::one());
+    let boxed: Vec<usize> = state.iter().enumerate().map(|(position, wire)| {
+        let constant = poseidon2.external_constants[round][position].clone();
+        let shifted = circuit.alloc_linear(&[(*wire, one.clone())], constant);
+        sbox(circuit, shifted, poseidon2.alpha)
+    }).collect();
+    mix(circuit, &boxed, &vec![one; poseidon2.width])
+}
+
+/// Multiplies a state of wires by `J + diag(d)`, one linear gate per output wire.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `state`: The indices of the state wires.
+/// - `diagonal`: The diagonal `d` added to the all-ones matrix `J`.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the mixed state wires.
+fn mix<CS: ConstraintSystem>(circuit: &mut CS, state: &[usize], diagonal: &[FieldElement]) -> Vec<usize> {
+    let one = FieldElement::new(BigInt::one());
+    (0..state.len()).map(|i| {
+        let terms: Vec<(usize, FieldElement)> = state.iter().enumerate().map(|(j, wire)| {
+            let coeff = if i == j { one.add(&diagonal[i]) } else { one.clone() };
+            (*wire, coeff)
+        }).collect();
+        circuit.alloc_linear(&terms, FieldElement::new(BigInt::zero()))
+    }).collect()
+}
+
+/// `hash` as a `Gadget`: the input wires in, their hash under `tag` out.
+#[derive(Clone, Debug)]
+pub struct Hash {
+    /// The Poseidon2 parameters, shared with the native implementation.
+    pub poseidon2: Poseidon2,
+    /// The purpose of the hash.
+    pub tag: DomainTag,
+}
+
+impl Gadget for Hash {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        vec![hash(cs, &self.poseidon2, self.tag, inputs)]
+    }
+}
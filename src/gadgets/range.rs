@@ -0,0 +1,138 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets::{Gadget, Wire};
+
+/// Decomposes a wire into bits, least significant first, constraining every bit
+/// to be boolean and their weighted sum to equal the wire.
+///
+/// The circuit is only satisfied if the value of the wire is below `2^bits`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `wire`: The index of the wire to decompose.
+/// - `bits`: The number of bits.
+///
+/// # Returns
+/// - `Vec<usize>`: The indices of the bit wires.
+///
+/// # Panics
+/// - If `2^bits` is not below the modulus, since the sum of the bits could then wrap around.
+pub fn to_bits<CS: ConstraintSystem>(circuit: &mut CS, wire: usize, bits: usize) -> Vec<usize> {
+    assert!(BigInt::one() << bits < FieldElement::default_modulus(), "{} bits do not fit below the modulus", bits);
+    let value = circuit.value(wire).get_value();
+    let bit_wires: Vec<usize> = (0..bits)
+        .map(|position| {
+            let bit = circuit.alloc_private(FieldElement::new((&value >> position) & BigInt::one()));
+            circuit.enforce_mul(bit, bit, bit); // bit * bit = bit forces a boolean
+            bit
+        })
+        .collect();
+
+    let terms: Vec<(usize, FieldElement)> = bit_wires.iter().enumerate()
+        .map(|(position, bit)| (*bit, FieldElement::new(BigInt::one() << position)))
+        .collect();
+    let sum = circuit.alloc_linear(&terms, FieldElement::new(BigInt::zero()));
+    circuit.enforce_equal(sum, wire);
+    bit_wires
+}
+
+/// Constrains a wire to hold a value below `2^bits`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `wire`: The index of the wire.
+/// - `bits`: The number of bits.
+///
+/// # Panics
+/// - If `2^bits` is not below the modulus.
+pub fn enforce_range<CS: ConstraintSystem>(circuit: &mut CS, wire: usize, bits: usize) {
+    to_bits(circuit, wire, bits);
+}
+
+/// Constrains `a <= b` for two wires holding values below `2^bits`, by
+/// range-checking the difference `b - a`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `a`: The index of the smaller wire.
+/// - `b`: The index of the larger wire.
+/// - `bits`: The number of bits of both values.
+///
+/// # Panics
+/// - If `2^(bits + 1)` is not below the modulus, since `b - a` could then wrap around into range.
+pub fn enforce_less_or_equal<CS: ConstraintSystem>(circuit: &mut CS, a: usize, b: usize, bits: usize) {
+    assert!(BigInt::one() << (bits + 1) < FieldElement::default_modulus(), "{} bits are too many to compare", bits);
+    let one = FieldElement::new(BigInt::one());
+    let difference = circuit.alloc_linear(&[(b, one.clone()), (a, one.negate())], FieldElement::new(BigInt::zero()));
+    enforce_range(circuit, difference, bits);
+}
+
+/// Constrains a list of wires holding values below `2^bits` to be strictly
+/// increasing, so a sorted list has no duplicates.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `wires`: The indices of the wires, in list order.
+/// - `bits`: The number of bits of every value.
+///
+/// # Panics
+/// - If `2^(bits + 1)` is not below the modulus.
+pub fn enforce_increasing<CS: ConstraintSystem>(circuit: &mut CS, wires: &[usize], bits: usize) {
+    let one = FieldElement::new(BigInt::one());
+    for pair in wires.windows(2) {
+        // a < b is a + 1 <= b
+        let successor = circuit.alloc_linear(&[(pair[0], one.clone())], one.clone());
+        enforce_less_or_equal(circuit, successor, pair[1], bits);
+    }
+}
+
+/// Constrains a wire to hold one of a small table of values, by enforcing
+/// `prod_i (wire - values[i]) = 0`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `wire`: The index of the wire.
+/// - `values`: The allowed values.
+pub fn enforce_one_of<CS: ConstraintSystem>(circuit: &mut CS, wire: usize, values: &[FieldElement]) {
+    let one = FieldElement::new(BigInt::one());
+    let mut product = circuit.alloc_linear(&[], one.clone());
+    for value in values {
+        let difference = circuit.alloc_linear(&[(wire, one.clone())], value.negate());
+        product = circuit.alloc_mul(product, difference);
+    }
+    let expected = circuit.alloc_linear(&[], FieldElement::new(BigInt::zero()));
+    circuit.enforce_equal(product, expected);
+}
+
+/// `to_bits` as a `Gadget`: one input wire, `bits` bit wires out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ToBits {
+    /// The number of bits.
+    pub bits: usize,
+}
+
+impl Gadget for ToBits {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        let &[wire] = inputs else { panic!("ToBits takes one input, got {}", inputs.len()) };
+        to_bits(cs, wire, self.bits)
+    }
+}
+
+/// `enforce_range` as a `Gadget`: constrains every input wire below
+/// `2^bits`, with no output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RangeCheck {
+    /// The number of bits.
+    pub bits: usize,
+}
+
+impl Gadget for RangeCheck {
+    fn synthesize<CS: ConstraintSystem>(&self, cs: &mut CS, inputs: &[Wire]) -> Vec<Wire> {
+        for wire in inputs {
+            enforce_range(cs, *wire, self.bits);
+        }
+        Vec::new()
+    }
+}
@@ -0,0 +1,106 @@
+use num_bigint::BigInt;
+use crate::constraint_system::ConstraintSystem;
+use crate::edwards::EdwardsCurve;
+use crate::field::FieldElement;
+use crate::gadgets::edwards::{self, PointWires};
+use crate::hash::CircuitHash;
+use crate::schnorr::{self, SchnorrSignature};
+
+/// Represents a Schnorr signature allocated in a circuit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignatureWires {
+    /// The nonce commitment `R`.
+    pub r: PointWires,
+    /// The index of the wire holding the response `s`.
+    pub s: usize,
+}
+
+/// Allocates a signature as private wires.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `signature`: The signature.
+///
+/// # Returns
+/// - `SignatureWires`: The signature wires.
+pub fn alloc_signature<CS: ConstraintSystem>(circuit: &mut CS, signature: &SchnorrSignature) -> SignatureWires {
+    let r = edwards::alloc_point(circuit, &signature.r);
+    let s = circuit.alloc_private(FieldElement::new_with_modulus(signature.s.clone(), signature.r.x.get_modulus().clone()));
+    SignatureWires { r, s }
+}
+
+/// Constrains a signature to be valid for a public key and a message,
+/// matching `schnorr::verify`: both points are on the curve and
+/// `s * G = R + e * A` with `e = H(R.x, R.y, A.x, A.y, message)`.
+///
+/// The response is decomposed into as many bits as the subgroup order and
+/// the challenge into as many as the modulus, so verification costs two
+/// scalar multiplications of two point additions per bit. The public key is
+/// only constrained to the curve: checking it lies in the prime-order
+/// subgroup, as `schnorr::verify` does, is left to whoever publishes it.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `curve`: The curve of the scheme, defined over the field of the circuit.
+/// - `hasher`: The hash function of the challenge, with the same parameters as the native one.
+/// - `public_key`: The public key of the signer.
+/// - `message`: The index of the message wire.
+/// - `signature`: The signature.
+///
+/// # Panics
+/// - If the curve is not defined over the field of the circuit.
+pub fn enforce_signature<CS: ConstraintSystem, H: CircuitHash>(circuit: &mut CS, curve: &EdwardsCurve, hasher: &H, public_key: &PointWires, message: usize, signature: &SignatureWires) {
+    edwards::enforce_on_curve(circuit, curve, public_key);
+    edwards::enforce_on_curve(circuit, curve, &signature.r);
+
+    let e = hasher.hash_gadget(circuit, schnorr::CHALLENGE_TAG, &[signature.r.x, signature.r.y, public_key.x, public_key.y, message]);
+    let e_bits = edwards::scalar_bits(circuit, e, bits(curve.modulus()));
+    let s_bits = edwards::scalar_bits(circuit, signature.s, bits(&curve.order));
+
+    let generator = edwards::constant_point(circuit, &curve.generator);
+    let left = edwards::scalar_mul(circuit, curve, &s_bits, &generator);
+    let shift = edwards::scalar_mul(circuit, curve, &e_bits, public_key);
+    let right = edwards::add(circuit, curve, &signature.r, &shift);
+    circuit.enforce_equal(left.x, right.x);
+    circuit.enforce_equal(left.y, right.y);
+}
+
+/// Gets the number of bits of a positive integer.
+fn bits(value: &BigInt) -> usize {
+    value.bits() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::Circuit;
+    use crate::gadgets::is_satisfied;
+    use crate::poseidon::Poseidon;
+    use crate::rng;
+    use crate::schnorr::SchnorrKey;
+
+    /// Builds a circuit checking a signature of `signed` against the message `claimed`.
+    fn circuit(signed: u64, claimed: u64) -> Circuit {
+        let curve = EdwardsCurve::default();
+        let mut rng = rng::seeded(0);
+        let key = SchnorrKey::random_with_rng(&curve, &mut rng);
+        let signature = key.sign_with_rng(&curve, &Poseidon::new(), &curve.element(BigInt::from(signed)), &mut rng);
+
+        let mut circuit = Circuit::new();
+        let public_key = edwards::alloc_point(&mut circuit, &key.public_key(&curve));
+        let message = circuit.alloc_private(curve.element(BigInt::from(claimed)));
+        let signature = alloc_signature(&mut circuit, &signature);
+        enforce_signature(&mut circuit, &curve, &Poseidon::new(), &public_key, message, &signature);
+        circuit
+    }
+
+    #[test]
+    fn valid_signatures_satisfy_the_constraints() {
+        assert!(is_satisfied(&circuit(42, 42)));
+    }
+
+    #[test]
+    fn signatures_of_another_message_violate_the_constraints() {
+        assert!(!is_satisfied(&circuit(42, 43)));
+    }
+}
@@ -0,0 +1,24 @@
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::hash::CircuitHash;
+use crate::signature::{PUBLIC_KEY_TAG, SIGNATURE_TAG};
+
+/// Constrains a secret key wire to sign a message wire, matching `SigningKey`:
+/// the key hashes to the public key and the tag is `H(secret, message)`.
+///
+/// # Parameters
+/// - `circuit`: The circuit to add the gates to.
+/// - `hasher`: The hash function of the scheme, with the same parameters as the native one.
+/// - `secret`: The index of the secret key wire.
+/// - `message`: The index of the message wire.
+/// - `public_key`: The public key of the signer.
+/// - `tag`: The public tag of the signature.
+pub fn enforce_signature<CS: ConstraintSystem, G: CircuitHash>(circuit: &mut CS, hasher: &G, secret: usize, message: usize, public_key: &FieldElement, tag: &FieldElement) {
+    let computed_key = hasher.hash_gadget(circuit, PUBLIC_KEY_TAG, &[secret]);
+    let expected_key = circuit.alloc_linear(&[], public_key.clone());
+    circuit.enforce_equal(computed_key, expected_key);
+
+    let computed_tag = hasher.hash_two_gadget(circuit, SIGNATURE_TAG, secret, message);
+    let expected_tag = circuit.alloc_linear(&[], tag.clone());
+    circuit.enforce_equal(computed_tag, expected_tag);
+}
@@ -0,0 +1,235 @@
+use num_bigint::BigInt;
+use num_traits::{One, ToPrimitive, Zero};
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets::{boolean, range};
+
+/// Represents a signed integer in a circuit: a single wire holding the value
+/// modulo the field, negative values wrapping to the top of the field, and
+/// constrained to the two's-complement range `[-2^(bits - 1), 2^(bits - 1))`.
+///
+/// Every operation range-checks its result, so an overflow makes the circuit
+/// unsatisfiable instead of silently wrapping around the modulus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignedInt {
+    /// The index of the wire holding the value.
+    pub wire: usize,
+    /// The width of the two's-complement range.
+    pub bits: usize,
+}
+
+impl SignedInt {
+    /// Allocates a private signed integer and range-checks it.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `value`: The value; the circuit is only satisfied if it fits in `bits`.
+    /// - `bits`: The width of the two's-complement range.
+    ///
+    /// # Returns
+    /// - `Self`: The integer.
+    ///
+    /// # Panics
+    /// - If `bits` is zero or `2^(bits + 1)` is not below the modulus, since comparisons could then wrap around.
+    pub fn alloc<CS: ConstraintSystem>(circuit: &mut CS, value: i64, bits: usize) -> Self {
+        let wire = circuit.alloc_private(FieldElement::new(BigInt::from(value)));
+        SignedInt::from_wire(circuit, wire, bits)
+    }
+
+    /// Allocates a signed integer fixed to a constant.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `value`: The value, which must fit in `bits`.
+    /// - `bits`: The width of the two's-complement range.
+    ///
+    /// # Returns
+    /// - `Self`: The integer.
+    ///
+    /// # Panics
+    /// - If `bits` is zero, `2^(bits + 1)` is not below the modulus or the value does not fit.
+    pub fn constant<CS: ConstraintSystem>(circuit: &mut CS, value: i64, bits: usize) -> Self {
+        check_width(bits);
+        let bound = BigInt::one() << (bits - 1);
+        assert!(-&bound <= BigInt::from(value) && BigInt::from(value) < bound, "{} does not fit in {} signed bits", value, bits);
+        let wire = circuit.alloc_linear(&[], FieldElement::new(BigInt::from(value)));
+        SignedInt { wire, bits }
+    }
+
+    /// Interprets a wire as a signed integer and range-checks it.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `wire`: The index of the wire.
+    /// - `bits`: The width of the two's-complement range.
+    ///
+    /// # Returns
+    /// - `Self`: The integer.
+    ///
+    /// # Panics
+    /// - If `bits` is zero or `2^(bits + 1)` is not below the modulus.
+    pub fn from_wire<CS: ConstraintSystem>(circuit: &mut CS, wire: usize, bits: usize) -> Self {
+        check_width(bits);
+        let integer = SignedInt { wire, bits };
+        integer.to_bits(circuit);
+        integer
+    }
+
+    /// Reads the value held by the integer.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit holding the wire.
+    ///
+    /// # Returns
+    /// - `i64`: The value, negative if the wire holds the top half of the field.
+    pub fn value<CS: ConstraintSystem>(&self, circuit: &CS) -> i64 {
+        let value = circuit.value(self.wire).get_value();
+        let modulus = FieldElement::default_modulus();
+        let signed = if value > &modulus / 2 { value - modulus } else { value };
+        signed.to_i64().expect("Signed integers fit in 64 bits below the modulus")
+    }
+
+    /// Adds two integers of the same width.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `other`: The other integer.
+    ///
+    /// # Returns
+    /// - `Self`: The sum; the circuit is only satisfied if it does not overflow.
+    ///
+    /// # Panics
+    /// - If the widths differ.
+    pub fn add<CS: ConstraintSystem>(&self, circuit: &mut CS, other: &Self) -> Self {
+        self.combine(circuit, other, BigInt::one())
+    }
+
+    /// Subtracts an integer of the same width.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `other`: The integer to subtract.
+    ///
+    /// # Returns
+    /// - `Self`: The difference; the circuit is only satisfied if it does not overflow.
+    ///
+    /// # Panics
+    /// - If the widths differ.
+    pub fn sub<CS: ConstraintSystem>(&self, circuit: &mut CS, other: &Self) -> Self {
+        self.combine(circuit, other, -BigInt::one())
+    }
+
+    /// Negates an integer.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    ///
+    /// # Returns
+    /// - `Self`: The negation; the circuit is only satisfied if the integer is not the minimum.
+    pub fn neg<CS: ConstraintSystem>(&self, circuit: &mut CS) -> Self {
+        let wire = circuit.alloc_linear(&[(self.wire, FieldElement::new(-BigInt::one()))], FieldElement::new(BigInt::zero()));
+        SignedInt::from_wire(circuit, wire, self.bits)
+    }
+
+    /// Decomposes an integer into its two's-complement bits, by decomposing
+    /// `value + 2^(bits - 1)`, which is non-negative, and flipping its top bit.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    ///
+    /// # Returns
+    /// - `Vec<usize>`: The indices of the bit wires, least significant first, the last one being the sign.
+    pub fn to_bits<CS: ConstraintSystem>(&self, circuit: &mut CS) -> Vec<usize> {
+        let offset = circuit.alloc_linear(&[(self.wire, FieldElement::new(BigInt::one()))], FieldElement::new(BigInt::one() << (self.bits - 1)));
+        let mut bits = range::to_bits(circuit, offset, self.bits);
+        let top = bits.pop().expect("Signed integers have at least one bit");
+        bits.push(boolean::not(circuit, top));
+        bits
+    }
+
+    /// Allocates a boolean wire holding 1 if the integer is negative.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the sign bit wire.
+    pub fn is_negative<CS: ConstraintSystem>(&self, circuit: &mut CS) -> usize {
+        self.to_bits(circuit)[self.bits - 1]
+    }
+
+    /// Allocates a boolean wire holding 1 if this integer is below another,
+    /// from the sign of their difference, which needs one more bit.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `other`: The other integer, of the same width.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the result wire.
+    ///
+    /// # Panics
+    /// - If the widths differ.
+    pub fn less_than<CS: ConstraintSystem>(&self, circuit: &mut CS, other: &Self) -> usize {
+        assert_eq!(self.bits, other.bits, "Cannot compare integers of {} and {} bits", self.bits, other.bits);
+        let one = FieldElement::new(BigInt::one());
+        let difference = circuit.alloc_linear(&[(self.wire, one.clone()), (other.wire, one.negate())], FieldElement::new(BigInt::zero()));
+        SignedInt { wire: difference, bits: self.bits + 1 }.is_negative(circuit)
+    }
+
+    /// Allocates `self + sign * other` and range-checks it.
+    fn combine<CS: ConstraintSystem>(&self, circuit: &mut CS, other: &Self, sign: BigInt) -> Self {
+        assert_eq!(self.bits, other.bits, "Cannot combine integers of {} and {} bits", self.bits, other.bits);
+        let terms = [(self.wire, FieldElement::new(BigInt::one())), (other.wire, FieldElement::new(sign))];
+        let wire = circuit.alloc_linear(&terms, FieldElement::new(BigInt::zero()));
+        SignedInt::from_wire(circuit, wire, self.bits)
+    }
+}
+
+/// Fails unless a width is usable: positive, with room below the modulus for
+/// the extra bit of a comparison.
+fn check_width(bits: usize) {
+    assert!(bits > 0, "Signed integers need at least one bit");
+    assert!(BigInt::one() << (bits + 1) < FieldElement::default_modulus(), "{} signed bits do not fit below the modulus", bits);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::Circuit;
+    use crate::gadgets::is_satisfied;
+
+    #[test]
+    fn integers_follow_signed_arithmetic() {
+        let mut circuit = Circuit::new();
+        let (a, b) = (SignedInt::alloc(&mut circuit, -100, 8), SignedInt::alloc(&mut circuit, 27, 8));
+        assert_eq!(a.add(&mut circuit, &b).value(&circuit), -73);
+        assert_eq!(b.sub(&mut circuit, &a).value(&circuit), 127);
+        assert_eq!(a.neg(&mut circuit).value(&circuit), 100);
+        let signs = [a.is_negative(&mut circuit), b.is_negative(&mut circuit)];
+        assert_eq!(signs.map(|sign| boolean::value(&circuit, sign)), [true, false]);
+        let comparisons = [a.less_than(&mut circuit, &b), b.less_than(&mut circuit, &a)];
+        assert_eq!(comparisons.map(|below| boolean::value(&circuit, below)), [true, false]);
+        // Two's complement of -100 in 8 bits is 0b1001_1100
+        let bits: Vec<bool> = a.to_bits(&mut circuit).iter().map(|bit| boolean::value(&circuit, *bit)).collect();
+        assert_eq!(bits, [false, false, true, true, true, false, false, true]);
+        assert!(is_satisfied(&circuit));
+    }
+
+    #[test]
+    fn overflows_are_unsatisfiable() {
+        let mut circuit = Circuit::new();
+        let (a, b) = (SignedInt::alloc(&mut circuit, 100, 8), SignedInt::alloc(&mut circuit, 28, 8));
+        a.add(&mut circuit, &b);
+        assert!(!is_satisfied(&circuit));
+
+        let mut circuit = Circuit::new();
+        SignedInt::alloc(&mut circuit, -129, 8);
+        assert!(!is_satisfied(&circuit));
+
+        // The minimum has no negation in the same width
+        let mut circuit = Circuit::new();
+        SignedInt::alloc(&mut circuit, -128, 8).neg(&mut circuit);
+        assert!(!is_satisfied(&circuit));
+    }
+}
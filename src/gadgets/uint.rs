@@ -0,0 +1,321 @@
+use crate::constraint_system::ConstraintSystem;
+use crate::gadgets::{boolean, range};
+
+/// Defines an unsigned integer type over boolean wires with the wrapping
+/// semantics of the matching native type.
+macro_rules! uint {
+    ($name:ident, $native:ty, $bits:expr) => {
+        #[doc = concat!("Represents a `", stringify!($native), "` in a circuit as ", stringify!($bits), " boolean wires, least significant bit first.")]
+        ///
+        /// Words are never packed into a single field element, so they work
+        /// below any modulus. Rotations and shifts only reorder wires, bitwise
+        /// operations cost one multiplication per bit and additions two.
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub struct $name {
+            /// The indices of the bit wires, least significant first.
+            pub bits: Vec<usize>,
+        }
+
+        impl $name {
+            /// The number of bits of a word.
+            pub const BITS: usize = $bits;
+
+            /// Allocates a private word.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the gates to.
+            /// - `value`: The value of the word.
+            ///
+            /// # Returns
+            /// - `Self`: The word.
+            pub fn alloc<CS: ConstraintSystem>(circuit: &mut CS, value: $native) -> Self {
+                $name { bits: (0..Self::BITS).map(|position| boolean::alloc_bit(circuit, (value >> position) & 1 == 1)).collect() }
+            }
+
+            /// Allocates a word fixed to a constant.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the gates to.
+            /// - `value`: The value of the word.
+            ///
+            /// # Returns
+            /// - `Self`: The word.
+            pub fn constant<CS: ConstraintSystem>(circuit: &mut CS, value: $native) -> Self {
+                $name { bits: (0..Self::BITS).map(|position| boolean::constant(circuit, (value >> position) & 1 == 1)).collect() }
+            }
+
+            /// Wraps boolean wires into a word without adding gates.
+            ///
+            /// # Parameters
+            /// - `bits`: The indices of the bit wires, least significant first, already constrained to be boolean.
+            ///
+            /// # Returns
+            /// - `Self`: The word.
+            ///
+            /// # Panics
+            /// - If the number of bits does not match the width of the word.
+            pub fn from_bits(bits: Vec<usize>) -> Self {
+                assert_eq!(bits.len(), $bits, "A word needs {} bits", $bits);
+                $name { bits }
+            }
+
+            /// Reads the value held by the word.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit holding the wires.
+            ///
+            /// # Returns
+            #[doc = concat!("- `", stringify!($native), "`: The value of the word.")]
+            pub fn value<CS: ConstraintSystem>(&self, circuit: &CS) -> $native {
+                self.bits.iter().enumerate()
+                    .fold(0, |acc, (position, bit)| acc | (<$native>::from(boolean::value(circuit, *bit)) << position))
+            }
+
+            /// Adds two words, wrapping around on overflow.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the gates to.
+            /// - `other`: The other word.
+            ///
+            /// # Returns
+            /// - `Self`: The sum modulo `2^BITS`.
+            pub fn add<CS: ConstraintSystem>(&self, circuit: &mut CS, other: &Self) -> Self {
+                self.add_with_carry(circuit, other).0
+            }
+
+            /// Adds two words with a ripple-carry adder.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the gates to.
+            /// - `other`: The other word.
+            ///
+            /// # Returns
+            /// - `(Self, usize)`: The sum modulo `2^BITS` and the index of the outgoing carry bit wire.
+            pub fn add_with_carry<CS: ConstraintSystem>(&self, circuit: &mut CS, other: &Self) -> (Self, usize) {
+                let mut bits = Vec::with_capacity(Self::BITS);
+                let mut carry = boolean::and(circuit, self.bits[0], other.bits[0]);
+                bits.push(boolean::xor(circuit, self.bits[0], other.bits[0]));
+                for position in 1..Self::BITS {
+                    let (bit, next) = boolean::full_adder(circuit, self.bits[position], other.bits[position], carry);
+                    bits.push(bit);
+                    carry = next;
+                }
+                ($name { bits }, carry)
+            }
+
+            /// Computes the bitwise exclusive or of two words.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the gates to.
+            /// - `other`: The other word.
+            ///
+            /// # Returns
+            /// - `Self`: The result word.
+            pub fn xor<CS: ConstraintSystem>(&self, circuit: &mut CS, other: &Self) -> Self {
+                $name { bits: self.bits.iter().zip(&other.bits).map(|(a, b)| boolean::xor(circuit, *a, *b)).collect() }
+            }
+
+            /// Computes the bitwise and of two words.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the gates to.
+            /// - `other`: The other word.
+            ///
+            /// # Returns
+            /// - `Self`: The result word.
+            pub fn and<CS: ConstraintSystem>(&self, circuit: &mut CS, other: &Self) -> Self {
+                $name { bits: self.bits.iter().zip(&other.bits).map(|(a, b)| boolean::and(circuit, *a, *b)).collect() }
+            }
+
+            /// Computes the bitwise negation of a word.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the gates to.
+            ///
+            /// # Returns
+            /// - `Self`: The result word.
+            pub fn not<CS: ConstraintSystem>(&self, circuit: &mut CS) -> Self {
+                $name { bits: self.bits.iter().map(|bit| boolean::not(circuit, *bit)).collect() }
+            }
+
+            /// Rotates a word right, which only reorders its bits.
+            ///
+            /// # Parameters
+            /// - `shift`: The number of positions, taken modulo `BITS`.
+            ///
+            /// # Returns
+            /// - `Self`: The rotated word.
+            pub fn rotate_right(&self, shift: usize) -> Self {
+                $name { bits: (0..Self::BITS).map(|position| self.bits[(position + shift) % Self::BITS]).collect() }
+            }
+
+            /// Rotates a word left, which only reorders its bits.
+            ///
+            /// # Parameters
+            /// - `shift`: The number of positions, taken modulo `BITS`.
+            ///
+            /// # Returns
+            /// - `Self`: The rotated word.
+            pub fn rotate_left(&self, shift: usize) -> Self {
+                self.rotate_right(Self::BITS - shift % Self::BITS)
+            }
+
+            /// Shifts a word right, filling the top bits with zeros.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the zero wire to.
+            /// - `shift`: The number of positions; `BITS` or more clears the word.
+            ///
+            /// # Returns
+            /// - `Self`: The shifted word.
+            pub fn shift_right<CS: ConstraintSystem>(&self, circuit: &mut CS, shift: usize) -> Self {
+                let zero = boolean::constant(circuit, false);
+                $name { bits: (0..Self::BITS).map(|position| self.bits.get(position + shift).copied().unwrap_or(zero)).collect() }
+            }
+
+            /// Shifts a word left, filling the bottom bits with zeros.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the zero wire to.
+            /// - `shift`: The number of positions; `BITS` or more clears the word.
+            ///
+            /// # Returns
+            /// - `Self`: The shifted word.
+            pub fn shift_left<CS: ConstraintSystem>(&self, circuit: &mut CS, shift: usize) -> Self {
+                let zero = boolean::constant(circuit, false);
+                $name { bits: (0..Self::BITS).map(|position| position.checked_sub(shift).map_or(zero, |source| self.bits[source])).collect() }
+            }
+
+            /// Rotates a word right by an amount only known to the witness.
+            ///
+            /// The amount is decomposed into `log2(BITS)` bits, each selecting
+            /// whether a rotation by its power of two applies, for one
+            /// multiplication per bit of the word and of the amount.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the gates to.
+            /// - `amount`: The index of the wire holding the amount; the circuit is only satisfied if it is below `BITS`.
+            ///
+            /// # Returns
+            /// - `Self`: The rotated word.
+            pub fn rotate_right_variable<CS: ConstraintSystem>(&self, circuit: &mut CS, amount: usize) -> Self {
+                self.barrel(circuit, amount, |word, _, shift| word.rotate_right(shift))
+            }
+
+            /// Rotates a word left by an amount only known to the witness.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the gates to.
+            /// - `amount`: The index of the wire holding the amount; the circuit is only satisfied if it is below `BITS`.
+            ///
+            /// # Returns
+            /// - `Self`: The rotated word.
+            pub fn rotate_left_variable<CS: ConstraintSystem>(&self, circuit: &mut CS, amount: usize) -> Self {
+                self.barrel(circuit, amount, |word, _, shift| word.rotate_left(shift))
+            }
+
+            /// Shifts a word right by an amount only known to the witness, filling the top bits with zeros.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the gates to.
+            /// - `amount`: The index of the wire holding the amount; the circuit is only satisfied if it is below `BITS`.
+            ///
+            /// # Returns
+            /// - `Self`: The shifted word.
+            pub fn shift_right_variable<CS: ConstraintSystem>(&self, circuit: &mut CS, amount: usize) -> Self {
+                self.barrel(circuit, amount, |word, circuit, shift| word.shift_right(circuit, shift))
+            }
+
+            /// Shifts a word left by an amount only known to the witness, filling the bottom bits with zeros.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the gates to.
+            /// - `amount`: The index of the wire holding the amount; the circuit is only satisfied if it is below `BITS`.
+            ///
+            /// # Returns
+            /// - `Self`: The shifted word.
+            pub fn shift_left_variable<CS: ConstraintSystem>(&self, circuit: &mut CS, amount: usize) -> Self {
+                self.barrel(circuit, amount, |word, circuit, shift| word.shift_left(circuit, shift))
+            }
+
+            /// Constrains two words to hold the same value.
+            ///
+            /// # Parameters
+            /// - `circuit`: The circuit to add the gates to.
+            /// - `other`: The other word.
+            pub fn enforce_equal<CS: ConstraintSystem>(&self, circuit: &mut CS, other: &Self) {
+                for (a, b) in self.bits.iter().zip(&other.bits) {
+                    circuit.enforce_equal(*a, *b);
+                }
+            }
+
+            /// Applies an operation by a witness amount as a barrel shifter: one
+            /// stage per bit of the amount, each selecting the operation by its
+            /// power of two or the unchanged word.
+            fn barrel<CS: ConstraintSystem>(&self, circuit: &mut CS, amount: usize, operation: impl Fn(&Self, &mut CS, usize) -> Self) -> Self {
+                let stages = Self::BITS.trailing_zeros() as usize;
+                let mut word = self.clone();
+                for (stage, bit) in range::to_bits(circuit, amount, stages).into_iter().enumerate() {
+                    let moved = operation(&word, circuit, 1 << stage);
+                    word = $name { bits: moved.bits.iter().zip(&word.bits).map(|(moved, kept)| boolean::select(circuit, bit, *moved, *kept)).collect() };
+                }
+                word
+            }
+        }
+    };
+}
+
+uint!(UInt32, u32, 32);
+uint!(UInt64, u64, 64);
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use super::*;
+    use crate::circuit::Circuit;
+    use crate::field::FieldElement;
+    use crate::gadgets::is_satisfied;
+
+    #[test]
+    fn words_follow_native_wrapping_arithmetic() {
+        let (a, b) = (0xffff_fff0u32, 0x8000_0123u32);
+        let mut circuit = Circuit::new();
+        let (x, y) = (UInt32::alloc(&mut circuit, a), UInt32::alloc(&mut circuit, b));
+        let (sum, carry) = x.add_with_carry(&mut circuit, &y);
+        assert_eq!(sum.value(&circuit), a.wrapping_add(b));
+        assert!(boolean::value(&circuit, carry));
+        assert_eq!(x.xor(&mut circuit, &y).value(&circuit), a ^ b);
+        assert_eq!(x.and(&mut circuit, &y).value(&circuit), a & b);
+        assert_eq!(x.not(&mut circuit).value(&circuit), !a);
+        assert_eq!(x.rotate_right(7).value(&circuit), a.rotate_right(7));
+        assert_eq!(y.shift_left(&mut circuit, 5).value(&circuit), b << 5);
+
+        let amount = circuit.add_input(FieldElement::new(BigInt::from(13)));
+        assert_eq!(x.rotate_left_variable(&mut circuit, amount).value(&circuit), a.rotate_left(13));
+        assert_eq!(y.shift_right_variable(&mut circuit, amount).value(&circuit), b >> 13);
+
+        let (c, d) = (UInt64::alloc(&mut circuit, u64::MAX), UInt64::alloc(&mut circuit, 2));
+        assert_eq!(c.add(&mut circuit, &d).value(&circuit), 1);
+
+        let expected = UInt32::constant(&mut circuit, a.wrapping_add(b));
+        sum.enforce_equal(&mut circuit, &expected);
+        assert!(is_satisfied(&circuit));
+    }
+
+    #[test]
+    fn wrong_results_and_amounts_out_of_range_are_unsatisfiable() {
+        let mut circuit = Circuit::new();
+        let (x, y) = (UInt32::alloc(&mut circuit, 3), UInt32::alloc(&mut circuit, 5));
+        let sum = x.add(&mut circuit, &y);
+        let wrong = UInt32::constant(&mut circuit, 9);
+        sum.enforce_equal(&mut circuit, &wrong);
+        assert!(!is_satisfied(&circuit));
+
+        // The barrel shifter only decomposes amounts below the width
+        let mut circuit = Circuit::new();
+        let x = UInt32::alloc(&mut circuit, 1);
+        let amount = circuit.add_input(FieldElement::new(BigInt::from(32)));
+        x.rotate_right_variable(&mut circuit, amount);
+        assert!(!is_satisfied(&circuit));
+    }
+}
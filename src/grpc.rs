@@ -0,0 +1,193 @@
+use std::net::SocketAddr;
+use num_bigint::BigInt;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Response, Status, Streaming};
+use crate::api;
+use crate::compiler::CompiledCircuit;
+use crate::distributed::{LocalWorker, Worker};
+use crate::error::{self, Error};
+use crate::field::FieldElement;
+use crate::protobuf::{integer_from_bytes, integer_to_bytes};
+
+pub use crate::protobuf::proto;
+
+use proto::prove_request::Payload;
+use proto::prover_server::{Prover, ProverServer};
+use proto::proving_worker_server::{ProvingWorker, ProvingWorkerServer};
+
+/// The path of the `ProvingWorker.Transform` method.
+const TRANSFORM_PATH: &str = "/zk.v1.ProvingWorker/Transform";
+
+/// Represents the gRPC prover service, which keeps no state between calls.
+#[derive(Clone, Debug, Default)]
+pub struct ProverService;
+
+#[tonic::async_trait]
+impl Prover for ProverService {
+    async fn compile_circuit(&self, request: Request<proto::CompileCircuitRequest>) -> Result<Response<proto::CompileCircuitResponse>, Status> {
+        let compiled = api::compile_circuit(&request.into_inner().circuit_json).map_err(to_status)?;
+        Ok(Response::new(proto::CompileCircuitResponse { compiled }))
+    }
+
+    async fn prove(&self, request: Request<Streaming<proto::ProveRequest>>) -> Result<Response<proto::ProveResponse>, Status> {
+        let mut stream = request.into_inner();
+        let compiled = match stream.message().await?.and_then(|message| message.payload) {
+            Some(Payload::Compiled(compiled)) => compiled,
+            _ => return Err(Status::invalid_argument("The first message must carry the compiled circuit")),
+        };
+
+        // Collect the input chunks as they arrive
+        let mut inputs: Vec<FieldElement> = Vec::new();
+        while let Some(message) = stream.message().await? {
+            match message.payload {
+                Some(Payload::Inputs(chunk)) => {
+                    for value in &chunk.values {
+                        inputs.push(value.parse().map_err(to_status)?);
+                    }
+                },
+                _ => return Err(Status::invalid_argument("Only input chunks may follow the compiled circuit")),
+            }
+        }
+
+        // Proving is CPU-bound, so keep it off the async workers
+        let proof = tokio::task::spawn_blocking(move || api::prove_with_inputs(&compiled, &inputs))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .map_err(to_status)?;
+        Ok(Response::new(proto::ProveResponse { proof }))
+    }
+
+    async fn verify(&self, request: Request<proto::VerifyRequest>) -> Result<Response<proto::VerifyResponse>, Status> {
+        let request = request.into_inner();
+        let valid = tokio::task::spawn_blocking(move || api::verify(&request.compiled, &request.proof))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .map_err(to_status)?;
+        Ok(Response::new(proto::VerifyResponse { valid }))
+    }
+
+    async fn get_circuit_info(&self, request: Request<proto::GetCircuitInfoRequest>) -> Result<Response<proto::CircuitInfo>, Status> {
+        let compiled = CompiledCircuit::from_bytes(&request.into_inner().compiled).map_err(to_status)?;
+        Ok(Response::new(proto::CircuitInfo {
+            input_names: compiled.wire_names[..compiled.num_inputs].to_vec(),
+            num_wires: compiled.wire_names.len() as u32,
+            num_gates: compiled.gates.len() as u32,
+        }))
+    }
+}
+
+/// Represents the gRPC proving worker service, which runs the transforms a
+/// `distributed::Coordinator` sends to a `RemoteWorker`.
+#[derive(Clone, Debug, Default)]
+pub struct WorkerService;
+
+#[tonic::async_trait]
+impl ProvingWorker for WorkerService {
+    async fn transform(&self, request: Request<proto::TransformRequest>) -> Result<Response<proto::TransformResponse>, Status> {
+        let request = request.into_inner();
+        let modulus = integer_from_bytes(&request.modulus);
+        if modulus < BigInt::from(2) {
+            return Err(Status::invalid_argument("The modulus must be at least 2"));
+        }
+        let root = FieldElement::new_with_modulus(integer_from_bytes(&request.root), modulus.clone());
+        let chunks: Vec<Vec<FieldElement>> = request.chunks.iter()
+            .map(|chunk| chunk.values.iter()
+                .map(|value| FieldElement::new_with_modulus(integer_from_bytes(value), modulus.clone()))
+                .collect())
+            .collect();
+        let length = chunks.first().map_or(1, Vec::len);
+        if !length.is_power_of_two() || chunks.iter().any(|chunk| chunk.len() != length) {
+            return Err(Status::invalid_argument("Chunks must all have the same power-of-two length"));
+        }
+
+        let transformed = tokio::task::spawn_blocking(move || LocalWorker.transform(&chunks, &root))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .map_err(to_status)?;
+        Ok(Response::new(proto::TransformResponse { chunks: transformed.iter().map(|chunk| to_chunk(chunk)).collect() }))
+    }
+}
+
+/// Represents a worker reached over gRPC, serving `WorkerService`.
+///
+/// Calls block the current thread, so a `distributed::Coordinator` can run
+/// several remote workers concurrently from its own threads.
+#[derive(Debug)]
+pub struct RemoteWorker {
+    /// The connection to the worker.
+    channel: Channel,
+    /// The runtime driving the connection.
+    runtime: tokio::runtime::Runtime,
+}
+
+impl RemoteWorker {
+    /// Connects to a worker.
+    ///
+    /// # Parameters
+    /// - `endpoint`: The URI of the worker, such as `http://10.0.0.2:50051`.
+    ///
+    /// # Returns
+    /// - `error::Result<Self>`: The worker, or an error if the URI is invalid or the worker is unreachable.
+    pub fn connect(endpoint: &str) -> error::Result<Self> {
+        let endpoint = Endpoint::from_shared(endpoint.to_string()).map_err(|err| Error::Input(err.to_string()))?;
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        let channel = runtime.block_on(endpoint.connect()).map_err(|err| Error::External(err.to_string()))?;
+        Ok(RemoteWorker { channel, runtime })
+    }
+}
+
+impl Worker for RemoteWorker {
+    fn transform(&self, chunks: &[Vec<FieldElement>], root: &FieldElement) -> error::Result<Vec<Vec<FieldElement>>> {
+        let modulus = root.get_modulus();
+        let request = proto::TransformRequest {
+            modulus: integer_to_bytes(modulus),
+            root: integer_to_bytes(&root.get_value()),
+            chunks: chunks.iter().map(|chunk| to_chunk(chunk)).collect(),
+        };
+        // The generated client needs the 2021 prelude, so the unary call is made by hand
+        let response: Response<proto::TransformResponse> = self.runtime.block_on(async {
+            let mut client = tonic::client::Grpc::new(self.channel.clone());
+            client.ready().await.map_err(|err| Status::unavailable(err.to_string()))?;
+            client.unary(Request::new(request), PathAndQuery::from_static(TRANSFORM_PATH), tonic::codec::ProstCodec::default()).await
+        }).map_err(|status| Error::External(status.message().to_string()))?;
+
+        Ok(response.into_inner().chunks.iter()
+            .map(|chunk| chunk.values.iter()
+                .map(|value| FieldElement::new_with_modulus(integer_from_bytes(value), modulus.clone()))
+                .collect())
+            .collect())
+    }
+}
+
+/// Serves the prover and the proving worker over gRPC until the server fails.
+///
+/// # Parameters
+/// - `addr`: The address to listen on.
+///
+/// # Returns
+/// - `Result<(), tonic::transport::Error>`: The error that stopped the server.
+pub async fn serve(addr: SocketAddr) -> Result<(), tonic::transport::Error> {
+    tonic::transport::Server::builder()
+        .add_service(ProverServer::new(ProverService))
+        .add_service(ProvingWorkerServer::new(WorkerService))
+        .serve(addr)
+        .await
+}
+
+/// Encodes field elements as a protobuf chunk.
+fn to_chunk(values: &[FieldElement]) -> proto::Chunk {
+    proto::Chunk { values: values.iter().map(|value| integer_to_bytes(&value.get_value())).collect() }
+}
+
+/// Maps a crate error to a gRPC status.
+fn to_status(err: Error) -> Status {
+    match err {
+        Error::Serialization(_) | Error::Compile(_) | Error::Input(_) | Error::UnsupportedVersion { .. } => {
+            Status::invalid_argument(err.to_string())
+        }
+        Error::Io(_) | Error::External(_) => Status::internal(err.to_string()),
+        Error::Cancelled => Status::cancelled(err.to_string()),
+        Error::VersionMismatch { .. } => Status::failed_precondition(err.to_string()),
+    }
+}
@@ -0,0 +1,119 @@
+use crate::constraint_system::ConstraintSystem;
+use crate::field::FieldElement;
+use crate::gadgets;
+use crate::poseidon::Poseidon;
+pub use crate::poseidon::DomainTag;
+use crate::poseidon2::Poseidon2;
+
+/// A hash over field elements with a native implementation and a matching
+/// circuit gadget.
+///
+/// Both sides are implemented from the same parameters and padding, and every
+/// `CircuitHash` is a `MerkleHasher`, so a root computed by a `MerkleTree`
+/// always verifies in the Merkle gadget instantiated with the same hash.
+///
+/// Every call takes a `DomainTag` naming its purpose, so a hash computed for
+/// one protocol step never verifies as another.
+pub trait CircuitHash {
+    /// Hashes a sequence of field elements natively.
+    ///
+    /// # Parameters
+    /// - `tag`: The purpose of the hash.
+    /// - `inputs`: The elements to hash.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The hash of the inputs.
+    fn hash(&self, tag: DomainTag, inputs: &[FieldElement]) -> FieldElement;
+
+    /// Hashes a sequence of wires inside a circuit.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `tag`: The purpose of the hash.
+    /// - `inputs`: The indices of the input wires.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the hash wire.
+    fn hash_gadget<CS: ConstraintSystem>(&self, circuit: &mut CS, tag: DomainTag, inputs: &[usize]) -> usize;
+
+    /// Hashes two field elements natively.
+    ///
+    /// # Parameters
+    /// - `tag`: The purpose of the hash.
+    /// - `left`: The left input.
+    /// - `right`: The right input.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The hash of the two inputs.
+    fn hash_two(&self, tag: DomainTag, left: &FieldElement, right: &FieldElement) -> FieldElement {
+        self.hash(tag, &[left.clone(), right.clone()])
+    }
+
+    /// Hashes two wires inside a circuit.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit to add the gates to.
+    /// - `tag`: The purpose of the hash.
+    /// - `left`: The index of the left input wire.
+    /// - `right`: The index of the right input wire.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the hash wire.
+    fn hash_two_gadget<CS: ConstraintSystem>(&self, circuit: &mut CS, tag: DomainTag, left: usize, right: usize) -> usize {
+        self.hash_gadget(circuit, tag, &[left, right])
+    }
+}
+
+impl CircuitHash for Poseidon {
+    fn hash(&self, tag: DomainTag, inputs: &[FieldElement]) -> FieldElement {
+        Poseidon::hash(self, tag, inputs)
+    }
+
+    fn hash_gadget<CS: ConstraintSystem>(&self, circuit: &mut CS, tag: DomainTag, inputs: &[usize]) -> usize {
+        gadgets::poseidon::hash(circuit, self, tag, inputs)
+    }
+}
+
+impl CircuitHash for Poseidon2 {
+    fn hash(&self, tag: DomainTag, inputs: &[FieldElement]) -> FieldElement {
+        Poseidon2::hash(self, tag, inputs)
+    }
+
+    fn hash_gadget<CS: ConstraintSystem>(&self, circuit: &mut CS, tag: DomainTag, inputs: &[usize]) -> usize {
+        gadgets::poseidon2::hash(circuit, self, tag, inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+    use crate::circuit::Circuit;
+
+    /// Hashes `[3, 5]` under `tag` natively and in a circuit, checking the circuit's witness.
+    fn native_and_gadget<H: CircuitHash>(hasher: &H, tag: DomainTag) -> (FieldElement, FieldElement) {
+        let inputs = [FieldElement::new(BigInt::from(3)), FieldElement::new(BigInt::from(5))];
+        let mut circuit = Circuit::new();
+        let wires: Vec<usize> = inputs.iter().map(|input| circuit.add_input(input.clone())).collect();
+        let output = hasher.hash_gadget(&mut circuit, tag, &wires);
+        let r1cs = circuit.to_r1cs();
+        assert!(r1cs.verify_witness(&r1cs.generate_witness()));
+        (hasher.hash(tag, &inputs), circuit.get_input(output).unwrap().clone())
+    }
+
+    #[test]
+    fn gadgets_match_the_native_hashes_under_every_tag() {
+        for tag in [DomainTag::MERKLE_LEAF, DomainTag::MERKLE_NODE, DomainTag::COMMITMENT, DomainTag::new("custom")] {
+            let (native, gadget) = native_and_gadget(&Poseidon::new(), tag);
+            assert_eq!(native, gadget, "Poseidon under {}", tag.name());
+            let (native, gadget) = native_and_gadget(&Poseidon2::new(), tag);
+            assert_eq!(native, gadget, "Poseidon2 under {}", tag.name());
+        }
+    }
+
+    #[test]
+    fn gadgets_separate_their_tags() {
+        assert_ne!(native_and_gadget(&Poseidon::new(), DomainTag::MERKLE_LEAF).1, native_and_gadget(&Poseidon::new(), DomainTag::MERKLE_NODE).1);
+        assert_ne!(native_and_gadget(&Poseidon2::new(), DomainTag::MERKLE_LEAF).1, native_and_gadget(&Poseidon2::new(), DomainTag::MERKLE_NODE).1);
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use num_bigint::BigInt;
+use serde::Deserialize;
+use serde_json::Value;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+
+/// Represents named circuit inputs, as read from an `inputs.json` file.
+///
+/// Values are JSON numbers or strings holding decimal or `0x`-prefixed
+/// hexadecimal integers:
+///
+/// ```json
+/// {
+///   "public": { "x": 3 },
+///   "private": { "y": "0x2a" }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Inputs {
+    /// The values of the public inputs, by name.
+    pub public: BTreeMap<String, FieldElement>,
+    /// The values of the private inputs, by name.
+    pub private: BTreeMap<String, FieldElement>,
+}
+
+/// Mirrors the JSON layout of an inputs file before the values are parsed.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawInputs {
+    #[serde(default)]
+    public: BTreeMap<String, Value>,
+    #[serde(default)]
+    private: BTreeMap<String, Value>,
+}
+
+impl Inputs {
+    /// Parses named inputs from JSON.
+    ///
+    /// # Parameters
+    /// - `json`: An object with optional `public` and `private` maps from names to values.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The inputs, or an error if a value is malformed or a name
+    ///   appears in both maps.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let raw: RawInputs = serde_json::from_str(json)?;
+        let parse = |values: BTreeMap<String, Value>| values.into_iter()
+            .map(|(name, value)| Ok((name, parse_value(&value)?)))
+            .collect::<Result<BTreeMap<_, _>>>();
+
+        let inputs = Inputs { public: parse(raw.public)?, private: parse(raw.private)? };
+        if let Some(name) = inputs.public.keys().find(|name| inputs.private.contains_key(*name)) {
+            return Err(Error::Input(format!("Input `{}` is both public and private", name)));
+        }
+        Ok(inputs)
+    }
+
+    /// Reads named inputs from a JSON file.
+    ///
+    /// # Parameters
+    /// - `filename`: The path of the file, usually `inputs.json`.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The inputs, or an error if the file cannot be read or parsed.
+    pub fn from_file(filename: impl AsRef<Path>) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(filename)?)
+    }
+}
+
+/// Parses a single input value from a JSON number or numeric string.
+///
+/// # Parameters
+/// - `value`: The JSON value, either an integer or a decimal or `0x` hexadecimal string.
+///
+/// # Returns
+/// - `Result<FieldElement>`: The parsed value.
+pub fn parse_value(value: &Value) -> Result<FieldElement> {
+    match value {
+        Value::Number(n) => n.as_i64()
+            .map(|n| FieldElement::new(BigInt::from(n)))
+            .or_else(|| n.as_u64().map(|n| FieldElement::new(BigInt::from(n))))
+            .ok_or_else(|| Error::Input(format!("Input {} is not an integer", n))),
+        Value::String(s) => s.parse(),
+        other => Err(Error::Input(format!("Unsupported input {}", other))),
+    }
+}
@@ -0,0 +1,256 @@
+use ark_bn254::{Bn254, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::Pairing;
+use ark_ec::{CurveGroup, Group, VariableBaseMSM};
+use ark_ff::{Field, One, UniformRand, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseUVPolynomial, Polynomial};
+use crate::error::{Error, Result};
+use crate::progress::CancellationToken;
+use crate::rng::{CryptoRng, OsRng, RngCore};
+
+/// The number of powers computed between two checks of a `CancellationToken`.
+pub const CANCEL_CHUNK: usize = 1 << 10;
+
+/// Represents the structured reference string of the KZG polynomial commitment
+/// over BN254: the powers `[τ^i]₁` and `[τ^i]₂` of a secret `τ`.
+///
+/// `setup` samples `τ` locally and drops it, which is enough for tests and
+/// experiments; production deployments take the powers from a ceremony, such
+/// as one run with `ceremony::CeremonyTranscript`.
+#[derive(Clone, Debug)]
+pub struct KzgParams {
+    /// The powers `[τ^i]₁`, one more than the largest committable degree.
+    pub powers_g1: Vec<G1Affine>,
+    /// The powers `[τ^i]₂`, one more than the largest number of points opened together.
+    pub powers_g2: Vec<G2Affine>,
+}
+
+impl KzgParams {
+    /// Generates parameters from a secret sampled from `OsRng`.
+    ///
+    /// # Parameters
+    /// - `max_degree`: The largest degree of a committed polynomial.
+    /// - `max_points`: The largest number of points opened in one proof.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `KzgParams` struct.
+    pub fn setup(max_degree: usize, max_points: usize) -> Self {
+        KzgParams::setup_with_rng(max_degree, max_points, &mut OsRng)
+    }
+
+    /// Generates parameters from a secret sampled from a given generator.
+    ///
+    /// # Parameters
+    /// - `max_degree`: The largest degree of a committed polynomial.
+    /// - `max_points`: The largest number of points opened in one proof.
+    /// - `rng`: The source of the secret, which anyone replaying it can recover.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `KzgParams` struct.
+    pub fn setup_with_rng<R: RngCore + CryptoRng>(max_degree: usize, max_points: usize, rng: &mut R) -> Self {
+        KzgParams::setup_with_cancel(max_degree, max_points, rng, &CancellationToken::new())
+            .expect("A fresh token is never cancelled")
+    }
+
+    /// Generates parameters from a secret sampled from a given generator,
+    /// stopping early once the token is cancelled.
+    ///
+    /// The token is checked before each chunk of `CANCEL_CHUNK` powers, so a
+    /// setup for large degrees can be aborted from another thread.
+    ///
+    /// # Parameters
+    /// - `max_degree`: The largest degree of a committed polynomial.
+    /// - `max_points`: The largest number of points opened in one proof.
+    /// - `rng`: The source of the secret, which anyone replaying it can recover.
+    /// - `cancel`: The token checked between chunks.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The parameters, or `Error::Cancelled` if the token was cancelled.
+    pub fn setup_with_cancel<R: RngCore + CryptoRng>(max_degree: usize, max_points: usize, rng: &mut R, cancel: &CancellationToken) -> Result<Self> {
+        let tau = Fr::rand(rng);
+        let powers_g1 = scaled_powers(G1Projective::generator(), tau, max_degree + 1, cancel)?;
+        let powers_g2 = scaled_powers(G2Projective::generator(), tau, max_points + 1, cancel)?;
+        Ok(KzgParams {
+            powers_g1: G1Projective::normalize_batch(&powers_g1),
+            powers_g2: G2Projective::normalize_batch(&powers_g2),
+        })
+    }
+
+    /// Commits to a polynomial as `[p(τ)]₁`.
+    ///
+    /// # Parameters
+    /// - `polynomial`: The polynomial to commit to.
+    ///
+    /// # Returns
+    /// - `Result<G1Affine>`: The commitment, or an error if the degree exceeds the parameters.
+    pub fn commit(&self, polynomial: &DensePolynomial<Fr>) -> Result<G1Affine> {
+        msm_g1(&self.powers_g1, &polynomial.coeffs)
+    }
+
+    /// Opens a polynomial at a set of points with a single proof, the
+    /// commitment `[q(τ)]₁` to the quotient `q = (p - I) / Z`, where `I`
+    /// interpolates `p` on the points and `Z` vanishes on them.
+    ///
+    /// # Parameters
+    /// - `polynomial`: The committed polynomial.
+    /// - `points`: The distinct points to open at.
+    ///
+    /// # Returns
+    /// - `Result<G1Affine>`: The proof, or an error if there are more points than the parameters support.
+    pub fn open(&self, polynomial: &DensePolynomial<Fr>, points: &[Fr]) -> Result<G1Affine> {
+        self.check_points(points)?;
+        let values: Vec<Fr> = points.iter().map(|point| polynomial.evaluate(point)).collect();
+        let quotient = &(polynomial - &interpolate(points, &values)) / &vanishing(points);
+        msm_g1(&self.powers_g1, &quotient.coeffs)
+    }
+
+    /// Verifies an opening by checking `e(C - [I(τ)]₁, [1]₂) = e(π, [Z(τ)]₂)`.
+    ///
+    /// # Parameters
+    /// - `commitment`: The commitment to the polynomial.
+    /// - `points`: The distinct points opened at.
+    /// - `values`: The claimed value at each point.
+    /// - `proof`: The proof from `open`.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the polynomial takes the claimed values, otherwise `false`.
+    pub fn verify(&self, commitment: &G1Affine, points: &[Fr], values: &[Fr], proof: &G1Affine) -> bool {
+        if points.len() != values.len() || self.check_points(points).is_err() {
+            return false;
+        }
+        let (Ok(interpolation), Ok(zero)) = (
+            msm_g1(&self.powers_g1, &interpolate(points, values).coeffs),
+            msm_g2(&self.powers_g2, &vanishing(points).coeffs),
+        ) else {
+            return false;
+        };
+        let lhs = Bn254::pairing(*commitment - interpolation, self.powers_g2[0]);
+        let rhs = Bn254::pairing(*proof, zero);
+        lhs == rhs
+    }
+
+    /// Fails unless the points are distinct and few enough for the parameters.
+    fn check_points(&self, points: &[Fr]) -> Result<()> {
+        if points.len() >= self.powers_g2.len() {
+            return Err(Error::Input(format!("Cannot open at {} points with parameters for {}", points.len(), self.powers_g2.len() - 1)));
+        }
+        if points.iter().enumerate().any(|(i, point)| points[..i].contains(point)) {
+            return Err(Error::Input("Opening points must be distinct".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Computes the Lagrange polynomial through the given points and values.
+fn interpolate(points: &[Fr], values: &[Fr]) -> DensePolynomial<Fr> {
+    let mut result = DensePolynomial::zero();
+    for (i, (point, value)) in points.iter().zip(values).enumerate() {
+        let mut basis = DensePolynomial::from_coefficients_vec(vec![*value]);
+        let mut denominator = Fr::one();
+        for other in points.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, other)| other) {
+            basis = &basis * &DensePolynomial::from_coefficients_vec(vec![-*other, Fr::one()]);
+            denominator *= *point - other;
+        }
+        let scale = denominator.inverse().expect("Opening points are distinct");
+        result = &result + &DensePolynomial::from_coefficients_vec(basis.coeffs.iter().map(|coeff| *coeff * scale).collect());
+    }
+    result
+}
+
+/// Computes the polynomial `prod_i (x - points[i])`.
+fn vanishing(points: &[Fr]) -> DensePolynomial<Fr> {
+    points.iter().fold(DensePolynomial::from_coefficients_vec(vec![Fr::one()]), |acc, point| {
+        &acc * &DensePolynomial::from_coefficients_vec(vec![-*point, Fr::one()])
+    })
+}
+
+/// Computes `sum_i coeffs[i] · bases[i]` in G1.
+fn msm_g1(bases: &[G1Affine], coeffs: &[Fr]) -> Result<G1Affine> {
+    if coeffs.len() > bases.len() {
+        return Err(Error::Input(format!("Cannot commit to degree {} with parameters for degree {}", coeffs.len() - 1, bases.len() - 1)));
+    }
+    Ok(G1Projective::msm_unchecked(&bases[..coeffs.len()], coeffs).into_affine())
+}
+
+/// Computes `sum_i coeffs[i] · bases[i]` in G2.
+fn msm_g2(bases: &[G2Affine], coeffs: &[Fr]) -> Result<G2Affine> {
+    if coeffs.len() > bases.len() {
+        return Err(Error::Input(format!("Cannot evaluate degree {} in G2 with parameters for degree {}", coeffs.len() - 1, bases.len() - 1)));
+    }
+    Ok(G2Projective::msm_unchecked(&bases[..coeffs.len()], coeffs).into_affine())
+}
+
+/// Computes `[τ^i]` for `i` below `count`, checking the token before each chunk of powers.
+fn scaled_powers<G: Group<ScalarField = Fr>>(base: G, tau: Fr, count: usize, cancel: &CancellationToken) -> Result<Vec<G>> {
+    let mut scaled = Vec::with_capacity(count);
+    let mut power = Fr::one();
+    while scaled.len() < count {
+        cancel.check()?;
+        for _ in 0..CANCEL_CHUNK.min(count - scaled.len()) {
+            scaled.push(base * power);
+            power *= tau;
+        }
+    }
+    Ok(scaled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+
+    /// Sets up parameters for polynomials of degree 4 opened at up to 2 points.
+    fn params() -> KzgParams {
+        KzgParams::setup_with_rng(4, 2, &mut rng::seeded(0))
+    }
+
+    /// Builds the polynomial `1 + 2x + 3x^2 + 4x^3 + 5x^4`.
+    fn polynomial() -> DensePolynomial<Fr> {
+        DensePolynomial::from_coefficients_vec((1..=5u64).map(Fr::from).collect())
+    }
+
+    #[test]
+    fn openings_verify_at_one_and_several_points() {
+        let params = params();
+        let polynomial = polynomial();
+        let commitment = params.commit(&polynomial).unwrap();
+        for points in [vec![Fr::from(7u64)], vec![Fr::from(3u64), Fr::from(11u64)]].iter() {
+            let values: Vec<Fr> = points.iter().map(|point| polynomial.evaluate(point)).collect();
+            let proof = params.open(&polynomial, points).unwrap();
+            assert!(params.verify(&commitment, points, &values, &proof));
+        }
+    }
+
+    #[test]
+    fn openings_with_a_wrong_value_are_rejected() {
+        let params = params();
+        let polynomial = polynomial();
+        let point = Fr::from(7u64);
+        let commitment = params.commit(&polynomial).unwrap();
+        let proof = params.open(&polynomial, &[point]).unwrap();
+        assert!(!params.verify(&commitment, &[point], &[polynomial.evaluate(&point) + Fr::one()], &proof));
+        assert!(!params.verify(&commitment, &[point], &[], &proof));
+    }
+
+    #[test]
+    fn openings_at_a_wrong_point_are_rejected() {
+        let params = params();
+        let polynomial = polynomial();
+        let (point, other) = (Fr::from(7u64), Fr::from(8u64));
+        let commitment = params.commit(&polynomial).unwrap();
+        let proof = params.open(&polynomial, &[point]).unwrap();
+        assert!(!params.verify(&commitment, &[other], &[polynomial.evaluate(&point)], &proof));
+        assert!(!params.verify(&commitment, &[other], &[polynomial.evaluate(&other)], &proof));
+    }
+
+    #[test]
+    fn openings_beyond_the_parameters_are_refused() {
+        let params = params();
+        let polynomial = polynomial();
+        let points: Vec<Fr> = (1..=3u64).map(Fr::from).collect();
+        assert!(params.open(&polynomial, &points).is_err());
+        assert!(params.open(&polynomial, &[Fr::one(), Fr::one()]).is_err());
+        let too_long = DensePolynomial::from_coefficients_vec((1..=6u64).map(Fr::from).collect());
+        assert!(params.commit(&too_long).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+//! A minimal toolkit for experimenting with zero-knowledge proofs in Rust.
+//!
+//! The crate exposes finite field arithmetic, circuits, R1CS/QAP
+//! representations, Merkle trees and a simple proof system.
+//!
+//! With the default `std` feature disabled, the crate builds in `no_std` +
+//! `alloc` environments and keeps field arithmetic, the Poseidon hashes,
+//! R1CS witness checking and proof verification; file I/O, circuits, gadgets
+//! and Merkle structures require `std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "accel")]
+pub mod accel;
+#[cfg(feature = "std")]
+pub mod acir;
+pub mod air;
+#[cfg(feature = "std")]
+pub mod api;
+pub mod batch;
+#[cfg(feature = "ark-backend")]
+pub mod ark;
+#[cfg(feature = "std")]
+pub mod bench;
+pub mod blake2s;
+#[cfg(feature = "ark-backend")]
+pub mod ceremony;
+#[cfg(feature = "std")]
+pub mod circom;
+#[cfg(feature = "std")]
+pub mod circuit;
+#[cfg(feature = "std")]
+pub mod compiler;
+#[cfg(feature = "std")]
+pub mod config;
+pub mod constraint_system;
+pub mod container;
+pub mod context;
+pub mod ct;
+pub mod diff;
+#[cfg(feature = "std")]
+pub mod distributed;
+pub mod domain;
+pub mod edwards;
+pub mod error;
+#[cfg(feature = "ark-backend")]
+pub mod eth;
+#[cfg(feature = "ark-backend")]
+pub mod export;
+#[cfg(feature = "std")]
+pub mod ffi;
+pub mod field;
+pub mod fingerprint;
+pub mod folding;
+#[cfg(feature = "std")]
+pub mod gadgets;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "std")]
+pub mod hash;
+#[cfg(feature = "std")]
+pub mod inputs;
+#[cfg(feature = "ark-backend")]
+pub mod kzg;
+#[cfg(feature = "memory")]
+pub mod memory;
+#[cfg(feature = "std")]
+pub mod merkle;
+#[cfg(feature = "std")]
+pub mod merkle_map;
+#[cfg(feature = "std")]
+pub mod merkle_store;
+pub mod parallel;
+pub mod pedersen;
+pub mod plonkish;
+pub mod poseidon;
+pub mod poseidon2;
+pub mod proof;
+#[cfg(feature = "std")]
+pub mod progress;
+#[cfg(feature = "std")]
+pub mod prover;
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
+pub mod qap;
+pub mod r1cs;
+#[cfg(feature = "std")]
+pub mod rng;
+#[cfg(feature = "std")]
+pub mod schnorr;
+#[cfg(feature = "std")]
+pub mod signature;
+pub mod simplify;
+#[cfg(feature = "std")]
+pub mod statements;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod test_vectors;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+pub mod testing;
+mod trace;
+pub mod transcript;
+#[cfg(feature = "ark-backend")]
+pub mod vector_commitment;
+#[cfg(feature = "std")]
+pub mod verification;
+#[cfg(feature = "ark-backend")]
+pub mod verkle;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+pub mod witness;
+#[cfg(feature = "zkif")]
+pub mod zkif;
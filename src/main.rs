@@ -1,16 +1,39 @@
-mod circuit;
-mod r1cs;
-mod merkle;
-mod qap;
-mod field;
-mod proof;
-
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use clap::{Parser, Subcommand};
+use serde_json::{json, Map, Value};
 use num_bigint::ToBigInt;
-use circuit::Circuit;
-use crate::field::FieldElement;
+use zk_starter_kit::api;
+use zk_starter_kit::bench::{self, Workload};
+use zk_starter_kit::circuit::{self, Artifacts, Circuit};
+use zk_starter_kit::compiler::CompiledCircuit;
+use zk_starter_kit::container::{R1CS_MAGIC, ZSTD_MAGIC};
+use zk_starter_kit::error::{Error, Result};
+#[cfg(feature = "ark-backend")]
+use zk_starter_kit::{ark, export::{self, VerifierTarget}};
+use zk_starter_kit::field::FieldElement;
+use zk_starter_kit::gadgets;
+use zk_starter_kit::config::{Backend, Config, HashFunction, CONFIG_FILE};
+use zk_starter_kit::hash::CircuitHash;
+use zk_starter_kit::merkle::MerkleTree;
+#[cfg(feature = "memory")]
+use zk_starter_kit::memory::{self, TrackingAllocator};
+#[cfg(feature = "parallel")]
+use zk_starter_kit::parallel;
+use zk_starter_kit::poseidon::Poseidon;
+use zk_starter_kit::poseidon2::Poseidon2;
+use zk_starter_kit::r1cs::{self, R1CS};
+use zk_starter_kit::stats::CircuitStats;
+
+/// Counts heap usage for `--features memory` builds.
+#[cfg(feature = "memory")]
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
 
 /// A simple addition proof using the Circuit
-fn addition_proof() {
+fn addition_proof(artifacts: &Artifacts) -> Result<()> {
     let mut circuit = Circuit::new();
 
     let input1 = circuit.add_input(FieldElement::new(10.to_bigint().unwrap()));
@@ -27,71 +50,49 @@ fn addition_proof() {
 
     // Generate and verify the addition proof
     println!("Generating Addition Proof...");
-    circuit.generate_proof("addition_proof.bin");
-    let is_valid = circuit.verify_proof("addition_proof.bin");
+    circuit.generate_proof(artifacts)?;
+    let is_valid = circuit.verify_proof(artifacts)?;
     println!("Addition Proof is valid: {}", is_valid);
+    Ok(())
 }
 
-/// A Merkle Tree proof demonstrating the use of a Merkle path in a zk-circuit
-fn merkle_tree_proof() {
-    let transactions = vec![
-        10.to_bigint().unwrap(),
-        20.to_bigint().unwrap(),
-        50.to_bigint().unwrap(),
-        80.to_bigint().unwrap(),
-    ];
-
-    // Create the MerkleTree
-    let merkle_tree = merkle::MerkleTree::new(transactions.clone());
+/// A Merkle Tree proof demonstrating the use of a Merkle path in a zk-circuit, hashed with `H`
+fn merkle_tree_proof<H: CircuitHash + Default + Sync>(artifacts: &Artifacts) -> Result<()> {
+    let transactions: Vec<FieldElement> = vec![10, 20, 50, 80]
+        .into_iter()
+        .map(|value| FieldElement::new(value.to_bigint().unwrap()))
+        .collect();
+
+    // Create the MerkleTree over FieldElements, hashed with the same Poseidon as the gadget
+    let merkle_tree = MerkleTree::<H>::new(transactions.clone());
     let leaf_index = 2;
-    let leaf_value = transactions[leaf_index].clone();
     let merkle_path = merkle_tree.merkle_path(leaf_index);
 
-    let mut circuit = Circuit::new();  // Use modulus for Merkle proofs
-
-    let leaf_index_var = circuit.add_input(FieldElement::new(leaf_value));
-    let mut current_hash_index = leaf_index_var;
-
-    for (sibling_hash, is_left) in merkle_path {
-        let sibling_index_var = circuit.add_input(FieldElement::new(sibling_hash.clone()));
-
-        // Compute the new hash based on the sibling relationship
-        let new_hash_value = if is_left {
-            merkle_tree.apply_hash(
-                circuit.get_input(sibling_index_var).expect("Invalid input index"),
-                circuit.get_input(current_hash_index).expect("Invalid input index"),
-            )
-        } else {
-            merkle_tree.apply_hash(
-                circuit.get_input(current_hash_index).expect("Invalid input index"),
-                circuit.get_input(sibling_index_var).expect("Invalid input index"),
-            )
-        };
-
-        let new_hash_index = circuit.add_input(new_hash_value.clone());
-        circuit.set_output(new_hash_value.clone());
-
-        // Add a hash gate with correct sibling ordering for Merkle path
-        circuit.add_gate(if is_left {
-            circuit::Gate::Add(sibling_index_var, current_hash_index, new_hash_index)
-        } else {
-            circuit::Gate::Add(current_hash_index, sibling_index_var, new_hash_index)
-        });
-
-        current_hash_index = new_hash_index;
-    }
+    let mut circuit = Circuit::new();
+
+    // Recompute the root from the leaf and its path, and constrain it to the expected root
+    let leaf_var = circuit.add_input(transactions[leaf_index].clone());
+    let root_var = gadgets::merkle::enforce_membership(
+        &mut circuit,
+        merkle_tree.hasher(),
+        leaf_var,
+        &merkle_path,
+        &merkle_tree.root,
+    );
 
     // Set the final computed root in the circuit
-    circuit.set_output(FieldElement::new(merkle_tree.root.clone()));
+    let computed_root = circuit.get_input(root_var).expect("Invalid input index").clone();
+    circuit.set_output(computed_root);
 
-    println!("Expected Merkle root: {}", merkle_tree.root);
-    circuit.generate_proof("merkle_proof.bin");
-    let is_valid = circuit.verify_proof("merkle_proof.bin");
+    println!("Expected Merkle root: {}", merkle_tree.root.get_value());
+    circuit.generate_proof(artifacts)?;
+    let is_valid = circuit.verify_proof(artifacts)?;
     println!("Merkle Tree Proof is valid: {}", is_valid);
+    Ok(())
 }
 
 /// A function to demonstrate a multiplication proof using the Circuit and R1CS components
-fn multiplication_proof() {
+fn multiplication_proof(artifacts: &Artifacts) -> Result<()> {
     let mut circuit = Circuit::new();  // Using modulus for demonstration
 
     let input1 = circuit.add_input(FieldElement::new(3.to_bigint().unwrap())); // `a`
@@ -107,14 +108,519 @@ fn multiplication_proof() {
 
     // Generate and verify the multiplication proof
     println!("Generating Multiplication Proof...");
-    circuit.generate_proof("multiplication_proof.bin");
-    let is_valid = circuit.verify_proof("multiplication_proof.bin");
+    circuit.generate_proof(artifacts)?;
+    let is_valid = circuit.verify_proof(artifacts)?;
     println!("Multiplication Proof is valid: {}", is_valid);
+    Ok(())
 }
 
-fn main() {
-    // Run each proof demonstration
-    addition_proof();
-    multiplication_proof();
-    merkle_tree_proof(); // Include the merkle_tree_proof function
-}
\ No newline at end of file
+/// Compiles, proves and verifies circuits described in JSON.
+///
+/// Exit codes: 0 on success or a valid proof, 1 for an invalid proof and 2 on errors.
+#[derive(Parser)]
+#[command(name = "zk", version)]
+struct Cli {
+    /// The project configuration, used if the file exists.
+    #[arg(long, global = true, default_value = CONFIG_FILE)]
+    config: PathBuf,
+    /// Prints a single JSON document instead of human-readable text, for scripts and CI.
+    #[arg(long, global = true)]
+    json: bool,
+    /// The command to run.
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Represents the subcommands of the CLI.
+#[derive(Subcommand)]
+enum Command {
+    /// Compiles a JSON circuit description.
+    Compile {
+        /// The JSON circuit description.
+        circuit: PathBuf,
+        /// Where to write the compiled circuit, `circuit.bin` in the build directory by default.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Checks a compiled circuit and prints its shape; the built-in proof system needs no trusted setup.
+    Setup {
+        /// The compiled circuit, `circuit.bin` in the build directory by default.
+        circuit: Option<PathBuf>,
+    },
+    /// Proves a compiled circuit with the given inputs.
+    Prove {
+        /// A JSON file of named public and private inputs, or an array with one value per input.
+        #[arg(short, long)]
+        inputs: PathBuf,
+        /// The compiled circuit, `circuit.bin` in the build directory by default.
+        #[arg(short, long)]
+        circuit: Option<PathBuf>,
+        /// Where to write the proof, `proof.bin` in the proofs directory by default.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Verifies a proof against the inputs it was proven with, exiting with 0 if it is valid and 1 otherwise.
+    ///
+    /// The built-in proof system cannot check the constraints without the witness, so a proof
+    /// verified without `--inputs` is reported as unverifiable.
+    Verify {
+        /// The proof, `proof.bin` in the proofs directory by default.
+        proof: Option<PathBuf>,
+        /// The compiled circuit, `circuit.bin` in the build directory by default.
+        #[arg(short, long)]
+        circuit: Option<PathBuf>,
+        /// The inputs the proof was proven with, in the format of `zk prove`.
+        #[arg(short, long)]
+        inputs: Option<PathBuf>,
+    },
+    /// Evaluates a compiled circuit on the given inputs and checks its constraints, without proving.
+    Simulate {
+        /// A JSON file of named public and private inputs, or an array with one value per input.
+        #[arg(short, long)]
+        inputs: PathBuf,
+        /// The compiled circuit, `circuit.bin` in the build directory by default.
+        #[arg(short, long)]
+        circuit: Option<PathBuf>,
+    },
+    /// Checks a witness against an R1CS and prints every constraint it fails.
+    DebugWitness {
+        /// The R1CS file.
+        r1cs: PathBuf,
+        /// The witness file.
+        witness: PathBuf,
+    },
+    /// Prints the size of a compiled circuit or R1CS and estimates its proving cost.
+    Stats {
+        /// The compiled circuit or R1CS file, `circuit.bin` in the build directory by default.
+        file: Option<PathBuf>,
+    },
+    /// Times synthetic circuits through witness generation, constraint generation, proving and verification.
+    Bench {
+        /// The lengths of the multiplication chains to benchmark.
+        #[arg(long, value_delimiter = ',', default_value = "100,1000")]
+        mul: Vec<usize>,
+        /// The depths of the Merkle paths to benchmark.
+        #[arg(long, value_delimiter = ',', default_value = "4,8")]
+        depth: Vec<usize>,
+        /// The lengths of the Poseidon permutation chains to benchmark.
+        #[arg(long, value_delimiter = ',', default_value = "1,4")]
+        poseidon: Vec<usize>,
+    },
+    /// Writes a standalone verifier for a Groth16 verifying key saved by `ark::save_verifying_key`.
+    #[cfg(feature = "ark-backend")]
+    ExportVerifier {
+        /// The verifying key file.
+        key: PathBuf,
+        /// The kind of verifier: `solidity`, `rust` or `wasm`.
+        #[arg(short, long)]
+        target: VerifierTarget,
+        /// The directory to write the verifier to, `verifier` in the build directory by default.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Runs the addition, multiplication and Merkle tree demonstrations.
+    Demo,
+}
+
+/// The default name of compiled circuits in the build directory.
+const CIRCUIT_FILE: &str = "circuit.bin";
+/// The default name of proofs in the proofs directory.
+const PROOF_FILE: &str = "proof.bin";
+/// The default name of exported verifiers in the build directory.
+#[cfg(feature = "ark-backend")]
+const VERIFIER_DIR: &str = "verifier";
+
+/// Writes an artifact, creating its parent directory if needed.
+fn write_artifact(path: &Path, bytes: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Rejects configurations selecting a backend other than the built-in one, which is the only one the CLI drives.
+fn require_builtin(config: &Config) -> Result<()> {
+    if config.backend != Backend::Builtin {
+        return Err(Error::Input(format!("The zk tool only supports the built-in backend, but {:?} is configured", config.backend)));
+    }
+    Ok(())
+}
+
+/// Formats a byte count with a binary unit.
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Runs a phase, measuring its peak memory if the `memory` feature installed the tracking allocator.
+fn measure<T>(phase: impl FnOnce() -> T) -> (T, Option<usize>) {
+    #[cfg(feature = "memory")]
+    {
+        let (result, peak) = memory::measure(phase);
+        (result, Some(peak))
+    }
+    #[cfg(not(feature = "memory"))]
+    (phase(), None)
+}
+
+/// Adds the measured peak memory of each phase to a JSON document, as `peak_memory_bytes`.
+fn with_peak_memory(mut document: Value, phases: &[(&str, Option<usize>)]) -> Value {
+    let peaks: Map<String, Value> = phases.iter()
+        .filter_map(|(phase, peak)| peak.map(|peak| (phase.to_string(), json!(peak))))
+        .collect();
+    if !peaks.is_empty() {
+        document["peak_memory_bytes"] = Value::Object(peaks);
+    }
+    document
+}
+
+/// Prints the measured peak memory of each phase, if any was measured.
+fn print_peak_memory(phases: &[(&str, Option<usize>)]) {
+    let peaks: Vec<String> = phases.iter()
+        .filter_map(|(phase, peak)| peak.map(|peak| format!("{} {}", phase, format_bytes(peak))))
+        .collect();
+    if !peaks.is_empty() {
+        println!("Peak memory: {}", peaks.join(", "));
+    }
+}
+
+/// Prints a JSON document on a single line.
+fn print_json(value: Value) {
+    println!("{}", value);
+}
+
+/// Converts a duration to fractional milliseconds.
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Runs a subcommand.
+///
+/// # Parameters
+/// - `command`: The subcommand to run.
+/// - `config`: The project configuration.
+/// - `json`: Whether to print a JSON document instead of human-readable text.
+///
+/// # Returns
+/// - `Result<bool>`: Whether the command succeeded, which is `false` only for an invalid
+///   proof, an unsatisfied witness or a failed benchmark.
+fn run(command: Command, config: &Config, json: bool) -> Result<bool> {
+    #[cfg(feature = "parallel")]
+    if let Some(threads) = config.threads {
+        parallel::init_thread_pool(threads)?;
+    }
+    #[cfg(feature = "memory")]
+    memory::set_limit(config.memory_limit_mb.map(|mib| mib * 1024 * 1024));
+
+    match command {
+        Command::Compile { circuit, output } => {
+            let source = fs::read_to_string(circuit)?;
+            let (compiled, compile_peak) = measure(|| api::compile_circuit(&source));
+            let output = output.unwrap_or_else(|| config.artifacts.build.join(CIRCUIT_FILE));
+            write_artifact(&output, &compiled?)?;
+            let phases = [("compile", compile_peak)];
+            if json {
+                print_json(with_peak_memory(json!({ "circuit": output }), &phases));
+            } else {
+                println!("Compiled circuit written to {}", output.display());
+                print_peak_memory(&phases);
+            }
+        },
+        Command::Setup { circuit } => {
+            require_builtin(config)?;
+            let circuit = circuit.unwrap_or_else(|| config.artifacts.build.join(CIRCUIT_FILE));
+            let compiled = CompiledCircuit::from_bytes(&fs::read(circuit)?)?;
+            let inputs = &compiled.wire_names[..compiled.num_inputs];
+            let public: Vec<&str> = compiled.public_inputs.iter().map(|&index| compiled.wire_names[index].as_str()).collect();
+            if json {
+                print_json(json!({
+                    "inputs": inputs,
+                    "public_inputs": public,
+                    "wires": compiled.wire_names.len(),
+                    "gates": compiled.gates.len(),
+                }));
+            } else {
+                println!("Inputs: {}", inputs.join(", "));
+                println!("Public inputs: {}", public.join(", "));
+                println!("Wires: {}, gates: {}", compiled.wire_names.len(), compiled.gates.len());
+                println!("The built-in proof system is transparent, so no keys are needed");
+            }
+        },
+        Command::Prove { inputs, circuit, output } => {
+            require_builtin(config)?;
+            let circuit = circuit.unwrap_or_else(|| config.artifacts.build.join(CIRCUIT_FILE));
+            let output = output.unwrap_or_else(|| config.artifacts.proofs.join(PROOF_FILE));
+            let (compiled, inputs) = (fs::read(circuit)?, fs::read_to_string(inputs)?);
+            let start = Instant::now();
+            let (circuit, witness_peak) = measure(|| api::assign(&compiled, &inputs));
+            let circuit = circuit?;
+            let (proof, prove_peak) = measure(|| api::prove_circuit(&circuit));
+            let elapsed = start.elapsed();
+            write_artifact(&output, &proof?)?;
+            let phases = [("witness", witness_peak), ("prove", prove_peak)];
+            if json {
+                print_json(with_peak_memory(json!({ "proof": output, "prove_ms": millis(elapsed) }), &phases));
+            } else {
+                println!("Proof written to {}", output.display());
+                print_peak_memory(&phases);
+            }
+        },
+        Command::Verify { proof, circuit, inputs } => {
+            require_builtin(config)?;
+            let proof = proof.unwrap_or_else(|| config.artifacts.proofs.join(PROOF_FILE));
+            let circuit = circuit.unwrap_or_else(|| config.artifacts.build.join(CIRCUIT_FILE));
+            let (compiled, bytes) = (fs::read(circuit)?, fs::read(&proof)?);
+            let report = match inputs {
+                Some(inputs) => api::verify_report_with_inputs(&compiled, &bytes, &fs::read_to_string(inputs)?)?,
+                None => api::verify_report(&compiled, &bytes)?,
+            };
+            let valid = report.is_valid();
+            // Outputs are only worth showing once the proof is known to be valid
+            let outputs = if valid { api::public_outputs(&compiled, &bytes)? } else { Vec::new() };
+            if json {
+                let outputs: Map<String, Value> = outputs.iter()
+                    .map(|(name, value)| (name.clone(), json!(value.get_value().to_string())))
+                    .collect();
+                print_json(json!({
+                    "proof": proof,
+                    "valid": valid,
+                    "verify_ms": millis(report.duration),
+                    "failures": report.failures(),
+                    "outputs": outputs,
+                }));
+            } else {
+                println!("{}", report);
+                for (name, value) in &outputs {
+                    println!("  {} = {}", name, value.get_value());
+                }
+            }
+            return Ok(valid);
+        },
+        Command::Simulate { inputs, circuit } => {
+            let circuit = circuit.unwrap_or_else(|| config.artifacts.build.join(CIRCUIT_FILE));
+            let (bytes, inputs) = (fs::read(circuit)?, fs::read_to_string(inputs)?);
+            let compiled = CompiledCircuit::from_bytes(&bytes)?;
+            let assigned = api::assign(&bytes, &inputs)?;
+            let r1cs = assigned.to_r1cs();
+            let failures = r1cs.check_witness(&r1cs.generate_witness())?;
+            let role = |wire: usize| match (compiled.public_inputs.contains(&wire), compiled.public_outputs.contains(&wire)) {
+                (true, _) => "public input",
+                (_, true) => "public output",
+                _ if wire < compiled.num_inputs => "private input",
+                _ => "intermediate",
+            };
+            if json {
+                let wires: Vec<Value> = compiled.wire_names.iter().zip(assigned.wires()).enumerate()
+                    .map(|(wire, (name, value))| json!({
+                        "name": name,
+                        "role": role(wire),
+                        "value": value.get_value().to_string(),
+                    }))
+                    .collect();
+                let failures: Vec<Value> = failures.iter()
+                    .map(|failure| json!({ "index": failure.index, "label": failure.label }))
+                    .collect();
+                print_json(json!({
+                    "wires": wires,
+                    "satisfied": failures.is_empty(),
+                    "constraints": r1cs.constraints.len(),
+                    "failures": failures,
+                }));
+            } else {
+                for (wire, (name, value)) in compiled.wire_names.iter().zip(assigned.wires()).enumerate() {
+                    println!("{} = {} ({})", name, value.get_value(), role(wire));
+                }
+                for failure in &failures {
+                    println!("Constraint {} ({:?}) is not satisfied: {}", failure.index, failure.operation, failure.label);
+                }
+                match failures.len() {
+                    0 => println!("All {} constraints hold", r1cs.constraints.len()),
+                    count => println!("{} of {} constraints are not satisfied", count, r1cs.constraints.len()),
+                }
+            }
+            return Ok(failures.is_empty());
+        },
+        Command::DebugWitness { r1cs, witness } => {
+            let r1cs = R1CS::load_from_binary(r1cs, &config.field.policy())?;
+            let witness = r1cs::load_witness(witness, &config.field.policy())?;
+            let failures = r1cs.check_witness(&witness)?;
+            let mut reports = Vec::with_capacity(failures.len());
+            for failure in &failures {
+                let constraint = &r1cs.constraints[failure.index];
+                let mut indices: Vec<usize> = constraint.left.iter()
+                    .chain(&constraint.right)
+                    .chain(&constraint.output)
+                    .map(|(variable, _)| variable.index)
+                    .collect();
+                indices.sort_unstable();
+                indices.dedup();
+
+                if json {
+                    let variables: Map<String, Value> = indices.iter()
+                        .map(|&index| (format!("w{}", index), json!(witness[index].get_value().to_string())))
+                        .collect();
+                    reports.push(json!({
+                        "index": failure.index,
+                        "operation": format!("{:?}", failure.operation),
+                        "label": failure.label,
+                        "left": failure.left.get_value().to_string(),
+                        "right": failure.right.get_value().to_string(),
+                        "output": failure.output.get_value().to_string(),
+                        "expected": failure.expected.get_value().to_string(),
+                        "variables": variables,
+                    }));
+                } else {
+                    println!("Constraint {} ({:?}) is not satisfied: {}", failure.index, failure.operation, failure.label);
+                    println!(
+                        "  left = {}, right = {}, output = {}, expected output = {}",
+                        failure.left.get_value(), failure.right.get_value(), failure.output.get_value(), failure.expected.get_value()
+                    );
+                    let values: Vec<String> = indices.iter().map(|&index| format!("w{} = {}", index, witness[index].get_value())).collect();
+                    println!("  {}", values.join(", "));
+                }
+            }
+            if json {
+                print_json(json!({
+                    "satisfied": failures.is_empty(),
+                    "constraints": r1cs.constraints.len(),
+                    "failures": reports,
+                }));
+            } else {
+                println!("{} of {} constraints are not satisfied", failures.len(), r1cs.constraints.len());
+            }
+            return Ok(failures.is_empty());
+        },
+        Command::Stats { file } => {
+            let file = file.unwrap_or_else(|| config.artifacts.build.join(CIRCUIT_FILE));
+            let bytes = fs::read(&file)?;
+            let stats = if bytes.starts_with(R1CS_MAGIC) || bytes.starts_with(&ZSTD_MAGIC) {
+                CircuitStats::from_r1cs(&R1CS::load_from_binary(file, &config.field.policy())?)
+            } else {
+                CircuitStats::from_compiled(&CompiledCircuit::from_bytes(&bytes)?)
+            };
+            if json {
+                print_json(json!({
+                    "constraints": stats.constraints,
+                    "wires": stats.wires,
+                    "public_inputs": stats.public_inputs,
+                    "nonzero_entries": stats.nonzero_entries,
+                    "circuit_id": stats.circuit_id.to_string(),
+                    "estimated_proving_ms": millis(stats.estimated_proving_time()),
+                    "estimated_memory_bytes": stats.estimated_memory(),
+                }));
+                return Ok(true);
+            }
+            println!("Constraints: {}", stats.constraints);
+            println!("Wires: {}", stats.wires);
+            match stats.public_inputs {
+                Some(count) => println!("Public inputs: {}", count),
+                None => println!("Public inputs: not recorded"),
+            }
+            println!("Nonzero entries: {}", stats.nonzero_entries);
+            println!("Circuit ID: {}", stats.circuit_id);
+            println!("Estimated proving time: {:.2?}", stats.estimated_proving_time());
+            println!("Estimated memory: {}", format_bytes(stats.estimated_memory()));
+        },
+        Command::Bench { mul, depth, poseidon } => {
+            if !json {
+                println!(
+                    "{:<24} {:>11} {:>12} {:>12} {:>12} {:>12}",
+                    "workload", "constraints", "witness", "r1cs", "prove", "verify"
+                );
+            }
+            let workloads = mul.into_iter().map(Workload::MulChain)
+                .chain(depth.into_iter().map(Workload::MerklePath))
+                .chain(poseidon.into_iter().map(Workload::PoseidonChain));
+            let mut all_valid = true;
+            let mut reports = Vec::new();
+            for workload in workloads {
+                let report = bench::run(workload);
+                if json {
+                    reports.push(json!({
+                        "workload": report.workload.to_string(),
+                        "constraints": report.constraints,
+                        "witness_ms": millis(report.witness_generation),
+                        "r1cs_ms": millis(report.constraint_generation),
+                        "prove_ms": millis(report.proving),
+                        "verify_ms": millis(report.verification),
+                        "valid": report.valid,
+                    }));
+                } else {
+                    println!(
+                        "{:<24} {:>11} {:>12} {:>12} {:>12} {:>12}",
+                        report.workload.to_string(),
+                        report.constraints,
+                        format!("{:.2?}", report.witness_generation),
+                        format!("{:.2?}", report.constraint_generation),
+                        format!("{:.2?}", report.proving),
+                        format!("{:.2?}", report.verification),
+                    );
+                    if !report.valid {
+                        eprintln!("The proof of {} did not verify", report.workload);
+                    }
+                }
+                all_valid &= report.valid;
+            }
+            if json {
+                print_json(json!({ "results": reports }));
+            }
+            return Ok(all_valid);
+        },
+        #[cfg(feature = "ark-backend")]
+        Command::ExportVerifier { key, target, output } => {
+            let verifying_key = ark::load_verifying_key(key)?;
+            let output = output.unwrap_or_else(|| config.artifacts.build.join(VERIFIER_DIR));
+            let mut written = Vec::new();
+            for file in export::export_verifier(&verifying_key, target)? {
+                let path = output.join(&file.path);
+                write_artifact(&path, file.contents.as_bytes())?;
+                written.push(path);
+            }
+            if json {
+                print_json(json!({ "files": written }));
+            } else {
+                for path in &written {
+                    println!("Verifier file written to {}", path.display());
+                }
+            }
+        },
+        Command::Demo => {
+            if json {
+                return Err(Error::Input("`zk demo` does not support --json".to_string()));
+            }
+            for dir in [&config.artifacts.build, &config.artifacts.proofs] {
+                fs::create_dir_all(dir)?;
+            }
+            addition_proof(&config.artifacts("addition"))?;
+            multiplication_proof(&config.artifacts("multiplication"))?;
+            match config.hash {
+                HashFunction::Poseidon => merkle_tree_proof::<Poseidon>(&config.artifacts("merkle"))?,
+                HashFunction::Poseidon2 => merkle_tree_proof::<Poseidon2>(&config.artifacts("merkle"))?,
+            }
+        },
+    }
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let Cli { config, json, command } = Cli::parse();
+    match Config::load_or_default(&config).and_then(|config| run(command, &config, json)) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(err) => {
+            if json {
+                print_json(json!({ "error": err.to_string() }));
+            } else {
+                eprintln!("Error: {}", err);
+            }
+            ExitCode::from(2)
+        },
+    }
+}
@@ -0,0 +1,131 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The number of bytes currently allocated through the tracking allocator.
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+/// The highest value `CURRENT` reached since the last reset.
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+/// The ceiling on `CURRENT`, or `usize::MAX` for no ceiling.
+static LIMIT: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Wraps the system allocator to count the bytes in use, remember their peak
+/// and refuse allocations beyond a configurable ceiling.
+///
+/// Counting only happens once it is installed as the global allocator:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+/// ```
+///
+/// A refused allocation makes Rust report `memory allocation of N bytes failed`
+/// and abort, so a process hitting the ceiling stops with a clear message at a
+/// known size instead of growing until the operating system kills it.
+pub struct TrackingAllocator;
+
+/// Records `size` more bytes in use, or returns `false` if that would exceed the ceiling.
+fn reserve(size: usize) -> bool {
+    let limit = LIMIT.load(Ordering::Relaxed);
+    let previous = CURRENT.fetch_add(size, Ordering::Relaxed);
+    match previous.checked_add(size) {
+        Some(current) if current <= limit => {
+            PEAK.fetch_max(current, Ordering::Relaxed);
+            true
+        },
+        _ => {
+            CURRENT.fetch_sub(size, Ordering::Relaxed);
+            false
+        },
+    }
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if !reserve(layout.size()) {
+            return std::ptr::null_mut();
+        }
+        let ptr = System.alloc(layout);
+        if ptr.is_null() {
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if !reserve(layout.size()) {
+            return std::ptr::null_mut();
+        }
+        let ptr = System.alloc_zeroed(layout);
+        if ptr.is_null() {
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let growth = new_size.saturating_sub(layout.size());
+        if !reserve(growth) {
+            return std::ptr::null_mut();
+        }
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if new_ptr.is_null() {
+            CURRENT.fetch_sub(growth, Ordering::Relaxed);
+        } else {
+            CURRENT.fetch_sub(layout.size().saturating_sub(new_size), Ordering::Relaxed);
+        }
+        new_ptr
+    }
+}
+
+/// Sets the ceiling on the bytes in use, or removes it.
+///
+/// # Parameters
+/// - `limit`: The maximum number of bytes, or `None` for no ceiling.
+pub fn set_limit(limit: Option<usize>) {
+    LIMIT.store(limit.unwrap_or(usize::MAX), Ordering::Relaxed);
+}
+
+/// Gets the ceiling on the bytes in use.
+///
+/// # Returns
+/// - `Option<usize>`: The maximum number of bytes, or `None` if there is no ceiling.
+pub fn limit() -> Option<usize> {
+    Some(LIMIT.load(Ordering::Relaxed)).filter(|&limit| limit != usize::MAX)
+}
+
+/// Gets the number of bytes currently allocated.
+///
+/// # Returns
+/// - `usize`: The bytes in use, or 0 if `TrackingAllocator` is not the global allocator.
+pub fn current() -> usize {
+    CURRENT.load(Ordering::Relaxed)
+}
+
+/// Gets the highest number of bytes allocated at once since the last `measure`.
+///
+/// # Returns
+/// - `usize`: The peak bytes in use.
+pub fn peak() -> usize {
+    PEAK.load(Ordering::Relaxed)
+}
+
+/// Runs a phase and reports the most memory in use while it ran, including
+/// what was already allocated before it started.
+///
+/// Phases should not be nested, since each one resets the peak.
+///
+/// # Parameters
+/// - `phase`: The work to measure.
+///
+/// # Returns
+/// - `(T, usize)`: The result of the phase and its peak bytes in use.
+pub fn measure<T>(phase: impl FnOnce() -> T) -> (T, usize) {
+    PEAK.store(current(), Ordering::Relaxed);
+    let result = phase();
+    (result, peak())
+}
@@ -1,28 +1,297 @@
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io;
+use std::io::{Read, Write};
+use std::path::Path;
 use num_bigint::BigInt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use crate::error::Result;
 use crate::field::FieldElement;
+use crate::hash::{CircuitHash, DomainTag};
+use crate::poseidon::Poseidon;
+use crate::poseidon2::Poseidon2;
+use crate::progress::CancellationToken;
+use crate::parallel::{cfg_chunks, cfg_iter};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Magic bytes identifying a serialized Merkle Tree file.
+const MERKLE_MAGIC: &[u8; 4] = b"ZKMT";
+/// The current version of the serialized Merkle Tree format.
+const MERKLE_FORMAT_VERSION: u32 = 3;
+/// The number of nodes of a level hashed between two checks of a `CancellationToken`.
+pub const CANCEL_CHUNK: usize = 1 << 12;
+/// The tag of the hash of no inputs that identifies a `CircuitHash` and its parameters.
+const HASHER_ID_TAG: DomainTag = DomainTag::new("merkle hasher id");
+
+/// A two-to-one hash function used to build a `MerkleTree`.
+///
+/// The hasher fixes the type of the leaves and internal nodes, so trees can be
+/// built directly over `FieldElement`s without converting through `BigInt`.
+///
+/// Leaves are hashed on their own before being combined, with a different
+/// domain than internal nodes, so an internal node cannot be presented as a
+/// leaf with a shorter path.
+///
+/// Hashers and nodes are shared across threads when levels are hashed in
+/// parallel, hence the `Sync` and `Send` bounds.
+pub trait MerkleHasher: Sync {
+    /// The type of the leaves and internal nodes.
+    type Node: Clone + PartialEq + Send + Sync;
+
+    /// Hashes a leaf into the node at the bottom of the tree.
+    ///
+    /// # Parameters
+    /// - `leaf`: The leaf.
+    ///
+    /// # Returns
+    /// - `Self::Node`: The leaf node.
+    fn hash_leaf(&self, leaf: &Self::Node) -> Self::Node;
+
+    /// Hashes two child nodes into their parent.
+    ///
+    /// # Parameters
+    /// - `left`: The left child.
+    /// - `right`: The right child.
+    ///
+    /// # Returns
+    /// - `Self::Node`: The parent node.
+    fn hash_nodes(&self, left: &Self::Node, right: &Self::Node) -> Self::Node;
+
+    /// Identifies the hash function and its parameters, so a saved tree is
+    /// only loaded with the hasher that built it.
+    ///
+    /// # Returns
+    /// - `String`: The identifier, equal for two hashers exactly when they hash alike.
+    fn id(&self) -> String;
+}
+
+/// The placeholder additive hash over `BigInt` values.
+#[derive(Clone, Debug, Default)]
+pub struct AdditiveHasher;
+
+impl MerkleHasher for AdditiveHasher {
+    type Node = BigInt;
+
+    fn hash_leaf(&self, leaf: &BigInt) -> BigInt {
+        leaf.clone() // The placeholder hash keeps leaves as they are
+    }
+
+    fn hash_nodes(&self, left: &BigInt, right: &BigInt) -> BigInt {
+        MerkleTree::hash(left, right)
+    }
+
+    fn id(&self) -> String {
+        "additive".to_string()
+    }
+}
+
+impl<T: CircuitHash + Sync> MerkleHasher for T {
+    type Node = FieldElement;
+
+    fn hash_leaf(&self, leaf: &FieldElement) -> FieldElement {
+        self.hash(DomainTag::MERKLE_LEAF, std::slice::from_ref(leaf))
+    }
+
+    fn hash_nodes(&self, left: &FieldElement, right: &FieldElement) -> FieldElement {
+        self.hash_two(DomainTag::MERKLE_NODE, left, right)
+    }
+
+    fn id(&self) -> String {
+        // The hash of no inputs depends on every parameter, including the round constants
+        let digest = self.hash(HASHER_ID_TAG, &[]);
+        format!("{:x}:{:x}", digest.get_modulus(), digest.get_value())
+    }
+}
+
+/// Represents a proof that a tree moved from `old_root` to `new_root` by
+/// replacing the leaf at `index` from `old_leaf` to `new_leaf`.
+///
+/// The old path authenticates the old leaf under the old root and the new path
+/// authenticates the new leaf under the new root. Both paths must carry the
+/// same siblings, which shows that nothing but the leaf changed. The
+/// direction bits of the path must spell out `index` in a tree of
+/// `num_leaves` leaves, so the proof cannot claim a different position.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StateTransitionProof<N> {
+    /// The index of the replaced leaf.
+    pub index: usize,
+    /// The number of leaves in the tree, which fixes the levels where the leaf's ancestor is promoted.
+    pub num_leaves: usize,
+    /// The leaf value before the update.
+    pub old_leaf: N,
+    /// The leaf value after the update.
+    pub new_leaf: N,
+    /// The Merkle path of the leaf in the tree before the update.
+    pub old_path: Vec<(N, bool)>,
+    /// The Merkle path of the leaf in the tree after the update.
+    pub new_path: Vec<(N, bool)>,
+    /// The root before the update.
+    pub old_root: N,
+    /// The root after the update.
+    pub new_root: N,
+}
+
+/// Represents the result of `MerkleTree::update_leaves`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MerkleBatchUpdate<N> {
+    /// The root before the batch.
+    pub old_root: N,
+    /// The root after the batch.
+    pub new_root: N,
+    /// The transitions of the individual updates, in order.
+    pub transitions: Vec<StateTransitionProof<N>>,
+}
+
+impl<N: Clone + PartialEq> StateTransitionProof<N> {
+    /// Verifies the transition from the old root to the new root.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hasher used by the tree.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if both paths share their siblings, their direction bits
+    ///   match `index`, the old path proves the old leaf under the old root and
+    ///   the new path proves the new leaf under the new root, otherwise `false`.
+    pub fn verify<H: MerkleHasher<Node = N>>(&self, hasher: &H) -> bool {
+        self.old_path == self.new_path
+            && path_matches_index(self.index, self.num_leaves, &self.old_path)
+            && root_from_path(hasher, &self.old_leaf, &self.old_path) == self.old_root
+            && root_from_path(hasher, &self.new_leaf, &self.new_path) == self.new_root
+    }
+}
+
+impl<N: Clone + PartialEq> MerkleBatchUpdate<N> {
+    /// Verifies that the transitions chain from the old root to the new root.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hasher used by the tree.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if every transition is valid and they connect the two roots, otherwise `false`.
+    pub fn verify<H: MerkleHasher<Node = N>>(&self, hasher: &H) -> bool {
+        let mut root = &self.old_root;
+        for transition in &self.transitions {
+            if transition.old_root != *root || !transition.verify(hasher) {
+                return false;
+            }
+            root = &transition.new_root;
+        }
+        *root == self.new_root
+    }
+}
 
 /// Represents a Merkle Tree with a root and leaves.
-pub struct MerkleTree {
+///
+/// The tree is generic over its hasher; the default keeps the original
+/// `BigInt` leaves, while any `CircuitHash` (such as `PoseidonMerkleTree`)
+/// works over `FieldElement`s and verifies in the gadget in `gadgets::merkle`.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "H::Node: Serialize", deserialize = "H: Default, H::Node: DeserializeOwned"))]
+pub struct MerkleTree<H: MerkleHasher = AdditiveHasher> {
     /// The root of the Merkle Tree.
-    pub root: BigInt,
+    pub root: H::Node,
     /// The leaves of the Merkle Tree.
-    pub leaves: Vec<BigInt>,
+    pub leaves: Vec<H::Node>,
+    /// The levels of the tree, from the hashed leaves up to the root.
+    levels: Vec<Vec<H::Node>>,
+    /// The identifier of the hasher, checked against the hasher a saved tree is loaded with.
+    hasher_id: String,
+    /// The hasher used to combine nodes.
+    #[serde(skip)]
+    hasher: H,
 }
 
-impl MerkleTree {
+/// A Merkle Tree over `FieldElement` leaves hashed with Poseidon.
+pub type PoseidonMerkleTree = MerkleTree<Poseidon>;
+
+/// A Merkle Tree over `FieldElement` leaves hashed with Poseidon2.
+pub type Poseidon2MerkleTree = MerkleTree<Poseidon2>;
+
+impl<H: MerkleHasher + Default> MerkleTree<H> {
     /// Creates a new Merkle Tree from a list of leaves.
     ///
     /// # Parameters
-    /// - `leaves`: A vector of `BigInt` representing the leaves.
+    /// - `leaves`: A vector representing the leaves.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `MerkleTree` struct.
+    pub fn new(leaves: Vec<H::Node>) -> Self {
+        MerkleTree::with_hasher(H::default(), leaves)
+    }
+
+    /// Creates a Merkle Tree of fixed depth, padding the leaves with an empty value.
+    ///
+    /// Every level of the padded tree has an even number of nodes, so every
+    /// path has exactly `depth` entries as fixed-size Merkle circuits expect.
+    ///
+    /// # Parameters
+    /// - `depth`: The depth of the tree, allowing up to `2^depth` leaves.
+    /// - `leaves`: A vector representing the leaves.
+    /// - `empty_leaf`: The value used for unfilled leaf positions.
     ///
     /// # Returns
     /// - `Self`: A new instance of the `MerkleTree` struct.
-    pub fn new(leaves: Vec<BigInt>) -> Self {
-        let root = MerkleTree::compute_root(&leaves);
-        MerkleTree {
+    ///
+    /// # Panics
+    /// - If `2^depth` does not fit in a `usize`, or there are more than `2^depth` leaves.
+    pub fn new_with_depth(depth: usize, leaves: Vec<H::Node>, empty_leaf: H::Node) -> Self {
+        let capacity = depth_capacity(depth)
+            .unwrap_or_else(|| panic!("Depth {} is too large for a tree indexed by usize", depth));
+        assert!(leaves.len() <= capacity, "Too many leaves for a tree of depth {}", depth);
+        let mut padded = leaves;
+        padded.resize(capacity, empty_leaf);
+        MerkleTree::new(padded)
+    }
+}
+
+impl<H: MerkleHasher> MerkleTree<H> {
+    /// Creates a new Merkle Tree from a list of leaves using a specific hasher.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hasher used to combine nodes.
+    /// - `leaves`: A vector representing the leaves.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `MerkleTree` struct.
+    pub fn with_hasher(hasher: H, leaves: Vec<H::Node>) -> Self {
+        MerkleTree::with_hasher_and_cancel(hasher, leaves, &CancellationToken::new())
+            .expect("A fresh token is never cancelled")
+    }
+
+    /// Creates a new Merkle Tree from a list of leaves using a specific hasher,
+    /// stopping early once the token is cancelled.
+    ///
+    /// The token is checked before each chunk of `CANCEL_CHUNK` nodes of every
+    /// level, so building a tree over millions of leaves can be aborted from
+    /// another thread.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hasher used to combine nodes.
+    /// - `leaves`: A vector representing the leaves.
+    /// - `cancel`: The token checked between chunks.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The tree, or `Error::Cancelled` if the token was cancelled.
+    pub fn with_hasher_and_cancel(hasher: H, leaves: Vec<H::Node>, cancel: &CancellationToken) -> Result<Self> {
+        let levels = MerkleTree::compute_levels(&hasher, &leaves, cancel)?;
+        let root = levels[levels.len() - 1][0].clone();
+        Ok(MerkleTree {
             root,
             leaves,
-        }
+            levels,
+            hasher_id: hasher.id(),
+            hasher,
+        })
+    }
+
+    /// Gets the hasher used by the tree, to share with circuit gadgets.
+    ///
+    /// # Returns
+    /// - `&H`: A reference to the hasher.
+    pub fn hasher(&self) -> &H {
+        &self.hasher
     }
 
     /// Computes the Merkle path for a given leaf index.
@@ -31,62 +300,251 @@ impl MerkleTree {
     /// - `index`: The index of the leaf.
     ///
     /// # Returns
-    /// - `Vec<(BigInt, bool)>`: The Merkle path as a vector of tuples containing the sibling hash and a boolean indicating if the current node is a left sibling.
-    pub fn merkle_path(&self, index: usize) -> Vec<(BigInt, bool)> {
+    /// - `Vec<(H::Node, bool)>`: The Merkle path as a vector of tuples containing the sibling hash and a boolean indicating if the current node is a left sibling.
+    pub fn merkle_path(&self, index: usize) -> Vec<(H::Node, bool)> {
         let mut path = Vec::new();
         let mut current_index = index;
-        let mut nodes = self.leaves.clone();
-
-        while nodes.len() > 1 {
-            let next_level: Vec<BigInt> = nodes
-                .chunks(2)
-                .map(|chunk| {
-                    if chunk.len() == 2 {
-                        MerkleTree::hash(&chunk[0], &chunk[1])
-                    } else {
-                        chunk[0].clone() // Handle last single node in an odd-numbered level
-                    }
-                })
-                .collect();
 
-            let sibling_index = if current_index % 2 == 0 {
+        for nodes in &self.levels[..self.levels.len() - 1] {
+            let sibling_index = if current_index.is_multiple_of(2) {
                 current_index + 1
             } else {
                 current_index - 1
             };
 
             if sibling_index < nodes.len() {
-                path.push((nodes[sibling_index].clone(), current_index % 2 == 0));
+                path.push((nodes[sibling_index].clone(), current_index.is_multiple_of(2)));
             }
 
             current_index /= 2;
-            nodes = next_level;
         }
 
         path
     }
 
-    /// Computes the root of the Merkle Tree from the leaves.
+    /// Verifies a Merkle path for a leaf against the root of the tree.
+    ///
+    /// # Parameters
+    /// - `leaf`: The leaf value.
+    /// - `path`: The Merkle path as returned by `merkle_path`.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the path leads from the leaf to the root, otherwise `false`.
+    pub fn verify_path(&self, leaf: &H::Node, path: &[(H::Node, bool)]) -> bool {
+        root_from_path(&self.hasher, leaf, path) == self.root
+    }
+
+    /// Replaces several leaves, recomputing only the nodes on their paths.
+    ///
+    /// Updates are applied in order, each one producing a transition from the
+    /// root before it to the root after it, so the transitions chain from the
+    /// old root to the new root.
+    ///
+    /// # Parameters
+    /// - `updates`: The `(index, value)` pairs to apply.
+    ///
+    /// # Returns
+    /// - `MerkleBatchUpdate<H::Node>`: The old and new roots with one transition proof per update.
+    ///
+    /// # Panics
+    /// - If an index is out of range.
+    pub fn update_leaves(&mut self, updates: &[(usize, H::Node)]) -> MerkleBatchUpdate<H::Node> {
+        let old_root = self.root.clone();
+        let transitions = updates.iter()
+            .map(|(index, value)| self.update_leaf(*index, value.clone()))
+            .collect();
+        MerkleBatchUpdate {
+            old_root,
+            new_root: self.root.clone(),
+            transitions,
+        }
+    }
+
+    /// Replaces one leaf and recomputes the nodes on its path.
+    ///
+    /// # Parameters
+    /// - `index`: The index of the leaf.
+    /// - `value`: The new leaf value.
+    ///
+    /// # Returns
+    /// - `StateTransitionProof<H::Node>`: The transition from the previous root to the new one.
+    fn update_leaf(&mut self, index: usize, value: H::Node) -> StateTransitionProof<H::Node> {
+        assert!(index < self.leaves.len(), "Leaf index {} out of range", index);
+        let old_path = self.merkle_path(index);
+        let old_root = self.root.clone();
+        let old_leaf = std::mem::replace(&mut self.leaves[index], value.clone());
+        self.levels[0][index] = self.hasher.hash_leaf(&value);
+
+        let mut current_index = index;
+        for level in 0..self.levels.len() - 1 {
+            let nodes = &self.levels[level];
+            let sibling_index = current_index ^ 1;
+            let parent = if sibling_index >= nodes.len() {
+                nodes[current_index].clone() // Promoted last single node
+            } else if current_index.is_multiple_of(2) {
+                self.hasher.hash_nodes(&nodes[current_index], &nodes[sibling_index])
+            } else {
+                self.hasher.hash_nodes(&nodes[sibling_index], &nodes[current_index])
+            };
+            current_index /= 2;
+            self.levels[level + 1][current_index] = parent;
+        }
+        self.root = self.levels[self.levels.len() - 1][0].clone();
+
+        StateTransitionProof {
+            index,
+            num_leaves: self.leaves.len(),
+            old_leaf,
+            new_leaf: value,
+            old_path,
+            new_path: self.merkle_path(index),
+            old_root,
+            new_root: self.root.clone(),
+        }
+    }
+
+    /// Computes every level of the Merkle Tree from the leaves, stopping early
+    /// once the token is cancelled.
+    ///
+    /// The leaves are hashed into the first level, then each level is
+    /// allocated once at its final size and filled in chunks of `CANCEL_CHUNK`
+    /// nodes, checking the token before each chunk; with the `parallel`
+    /// feature, the pairs of a chunk are hashed across the thread pool.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hasher used to combine nodes.
+    /// - `leaves`: A slice representing the leaves.
+    /// - `cancel`: The token checked between chunks.
+    ///
+    /// # Returns
+    /// - `Result<Vec<Vec<H::Node>>>`: The levels of the tree, from the leaves up to the root,
+    ///   or `Error::Cancelled` if the token was cancelled.
+    fn compute_levels(hasher: &H, leaves: &[H::Node], cancel: &CancellationToken) -> Result<Vec<Vec<H::Node>>> {
+        let depth = leaves.len().next_power_of_two().trailing_zeros() as usize;
+        let mut levels = Vec::with_capacity(depth + 1);
+        let mut first_level = Vec::with_capacity(leaves.len());
+        for block in leaves.chunks(2 * CANCEL_CHUNK) {
+            cancel.check()?;
+            let mut hashed: Vec<H::Node> = cfg_iter!(block).map(|leaf| hasher.hash_leaf(leaf)).collect();
+            first_level.append(&mut hashed);
+        }
+        levels.push(first_level);
+        loop {
+            let level = &levels[levels.len() - 1];
+            if level.len() <= 1 {
+                break;
+            }
+            let mut next_level = Vec::with_capacity(level.len().div_ceil(2));
+            for block in level.chunks(2 * CANCEL_CHUNK) {
+                cancel.check()?;
+                let mut hashed: Vec<H::Node> = cfg_chunks!(block, 2).map(|chunk| {
+                    if chunk.len() == 2 {
+                        hasher.hash_nodes(&chunk[0], &chunk[1])
+                    } else {
+                        chunk[0].clone() // Handle last single node in an odd-numbered level
+                    }
+                }).collect();
+                next_level.append(&mut hashed);
+            }
+            levels.push(next_level);
+        }
+        Ok(levels)
+    }
+
+    /// Checks the cached levels against the leaves and the root: every node
+    /// must be the hash of its children on the level below, or its promoted
+    /// single child, so a corrupted node on any level is caught.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if every level follows from the one below and ends in the root, otherwise `false`.
+    fn levels_consistent(&self) -> bool {
+        let leaves_hashed = self.levels.first().is_some_and(|level| {
+            level.len() == self.leaves.len()
+                && cfg_iter!(level).zip(cfg_iter!(self.leaves)).all(|(node, leaf)| *node == self.hasher.hash_leaf(leaf))
+        });
+        let parents_hashed = self.levels.windows(2).all(|pair| {
+            let (children, parents) = (&pair[0], &pair[1]);
+            parents.len() == children.len().div_ceil(2)
+                && cfg_chunks!(children, 2).zip(cfg_iter!(parents)).all(|(chunk, parent)| match chunk {
+                    [left, right] => *parent == self.hasher.hash_nodes(left, right),
+                    single => *parent == single[0],
+                })
+        });
+        leaves_hashed
+            && parents_hashed
+            && self.levels.last().map(|level| level.as_slice()) == Some(core::slice::from_ref(&self.root))
+    }
+}
+
+impl<H> MerkleTree<H>
+where
+    H: MerkleHasher + Default,
+    H::Node: Serialize + DeserializeOwned,
+{
+    /// Saves the Merkle Tree, including its cached levels, to a binary file.
+    ///
+    /// The file starts with the `ZKMT` magic bytes and a little-endian format
+    /// version, followed by the bincode-encoded tree with the identifier of its hasher.
     ///
     /// # Parameters
-    /// - `leaves`: A reference to a vector of `BigInt` representing the leaves.
+    /// - `filename`: The path of the file to save the tree to.
     ///
     /// # Returns
-    /// - `BigInt`: The root of the Merkle Tree.
-    fn compute_root(leaves: &Vec<BigInt>) -> BigInt {
-        let mut nodes = leaves.clone();
-        while nodes.len() > 1 {
-            nodes = nodes.chunks(2).map(|chunk| {
-                if chunk.len() == 2 {
-                    MerkleTree::hash(&chunk[0], &chunk[1])
-                } else {
-                    chunk[0].clone()
-                }
-            }).collect();
+    /// - `io::Result<()>`: The result of the file operation.
+    pub fn save_to_binary(&self, filename: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = File::create(filename)?;
+        let encoded: Vec<u8> = bincode::serialize(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        file.write_all(MERKLE_MAGIC)?;
+        file.write_all(&MERKLE_FORMAT_VERSION.to_le_bytes())?;
+        file.write_all(&encoded)?;
+        Ok(())
+    }
+
+    /// Loads a Merkle Tree from a binary file written by `save_to_binary`.
+    ///
+    /// The tree must have been saved with a hasher of the same identifier as
+    /// `H::default()`. Every cached level is then checked against the one
+    /// below it, which costs one hash per node but no allocation beyond the
+    /// decoded tree.
+    ///
+    /// # Parameters
+    /// - `filename`: The path of the file to load the tree from.
+    ///
+    /// # Returns
+    /// - `io::Result<Self>`: The loaded tree, or an `InvalidData` error if the file
+    ///   has the wrong magic bytes, an unsupported version, another hasher, or inconsistent levels.
+    pub fn load_from_binary(filename: impl AsRef<Path>) -> io::Result<Self> {
+        let mut data = Vec::new();
+        File::open(filename)?.read_to_end(&mut data)?;
+
+        if data.len() < 8 || &data[..4] != MERKLE_MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Not a Merkle Tree file"));
+        }
+        let version = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+        if version != MERKLE_FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unsupported Merkle Tree format version {}", version),
+            ));
+        }
+
+        let tree: MerkleTree<H> = bincode::deserialize(&data[8..]).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        if tree.hasher_id != tree.hasher.id() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Merkle Tree was built with hasher {}, not {}", tree.hasher_id, tree.hasher.id()),
+            ));
         }
-        nodes[0].clone()
+
+        if !tree.levels_consistent() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Inconsistent Merkle Tree levels"));
+        }
+        Ok(tree)
     }
+}
 
+impl MerkleTree<AdditiveHasher> {
     /// A simple hashing mechanism combining two BigInt values.
     ///
     /// # Parameters
@@ -116,4 +574,347 @@ impl MerkleTree {
         let new_value = (a.get_value() + b.get_value()) % a.get_modulus(); // Simple addition as a placeholder for hashing
         FieldElement::new(new_value)
     }
-}
\ No newline at end of file
+}
+
+/// Recomputes a root from a leaf and its Merkle path.
+///
+/// # Parameters
+/// - `hasher`: The hasher used by the tree.
+/// - `leaf`: The leaf value.
+/// - `path`: The Merkle path as returned by `MerkleTree::merkle_path`.
+///
+/// # Returns
+/// - `H::Node`: The computed root.
+pub(crate) fn root_from_path<H: MerkleHasher>(hasher: &H, leaf: &H::Node, path: &[(H::Node, bool)]) -> H::Node {
+    path.iter().fold(hasher.hash_leaf(leaf), |current, (sibling, is_left)| {
+        if *is_left {
+            hasher.hash_nodes(&current, sibling)
+        } else {
+            hasher.hash_nodes(sibling, &current)
+        }
+    })
+}
+
+/// Checks that the direction bits of a path are those of the leaf at `index`
+/// in a tree of `num_leaves` leaves, laid out as `MerkleTree::merkle_path`
+/// lays them out.
+///
+/// A level where the ancestor of the leaf is a promoted last node has no
+/// sibling and no path entry, so the shape of the path follows from the size
+/// of the tree.
+///
+/// # Parameters
+/// - `index`: The index of the leaf.
+/// - `num_leaves`: The number of leaves in the tree.
+/// - `path`: The Merkle path.
+///
+/// # Returns
+/// - `bool`: `true` if the path has one entry per level with a sibling and each
+///   entry's direction matches the parity of the ancestor, otherwise `false`.
+fn path_matches_index<N>(index: usize, num_leaves: usize, path: &[(N, bool)]) -> bool {
+    if index >= num_leaves {
+        return false;
+    }
+    let mut directions = path.iter().map(|(_, is_left)| *is_left);
+    let (mut position, mut width) = (index, num_leaves);
+    while width > 1 {
+        if (position ^ 1) < width && directions.next() != Some(position.is_multiple_of(2)) {
+            return false;
+        }
+        position /= 2;
+        width = width.div_ceil(2);
+    }
+    directions.next().is_none()
+}
+
+/// Gets the number of leaves of a tree of fixed depth.
+///
+/// # Parameters
+/// - `depth`: The depth of the tree.
+///
+/// # Returns
+/// - `Option<usize>`: `2^depth`, or `None` if it does not fit in a `usize`.
+fn depth_capacity(depth: usize) -> Option<usize> {
+    u32::try_from(depth).ok().and_then(|depth| 1usize.checked_shl(depth))
+}
+
+/// Represents an append-only Merkle Tree of fixed depth.
+///
+/// Only the frontier (the rightmost filled node of each level) is kept, so
+/// appending a leaf costs `depth` hashes and constant memory per level.
+/// Unfilled positions hold the empty leaf.
+pub struct IncrementalMerkleTree<H: MerkleHasher = Poseidon> {
+    /// The root of the Merkle Tree.
+    pub root: H::Node,
+    /// The depth of the tree.
+    depth: usize,
+    /// The index the next appended leaf will occupy.
+    next_index: usize,
+    /// The last left child seen on each level, waiting for its right sibling.
+    frontier: Vec<H::Node>,
+    /// The roots of empty subtrees on each level.
+    zeros: Vec<H::Node>,
+    /// The hasher used to combine nodes.
+    hasher: H,
+}
+
+impl<H: MerkleHasher<Node = FieldElement> + Default> IncrementalMerkleTree<H> {
+    /// Creates a new empty incremental Merkle Tree with zero as the empty leaf.
+    ///
+    /// # Parameters
+    /// - `depth`: The depth of the tree, allowing up to `2^depth` leaves.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `IncrementalMerkleTree` struct.
+    ///
+    /// # Panics
+    /// - If `2^depth` does not fit in a `usize`.
+    pub fn new(depth: usize) -> Self {
+        IncrementalMerkleTree::with_hasher(H::default(), depth, FieldElement::new(BigInt::from(0)))
+    }
+}
+
+impl<H: MerkleHasher> IncrementalMerkleTree<H> {
+    /// Creates a new empty incremental Merkle Tree using a specific hasher.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hasher used to combine nodes.
+    /// - `depth`: The depth of the tree, allowing up to `2^depth` leaves.
+    /// - `empty_leaf`: The value of unfilled leaf positions.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `IncrementalMerkleTree` struct.
+    ///
+    /// # Panics
+    /// - If `2^depth` does not fit in a `usize`.
+    pub fn with_hasher(hasher: H, depth: usize, empty_leaf: H::Node) -> Self {
+        assert!(depth_capacity(depth).is_some(), "Depth {} is too large for a tree indexed by usize", depth);
+        let mut zeros = vec![hasher.hash_leaf(&empty_leaf)];
+        for level in 0..depth {
+            let zero = hasher.hash_nodes(&zeros[level], &zeros[level]);
+            zeros.push(zero);
+        }
+        IncrementalMerkleTree {
+            root: zeros[depth].clone(),
+            depth,
+            next_index: 0,
+            frontier: zeros[..depth].to_vec(),
+            zeros,
+            hasher,
+        }
+    }
+
+    /// Appends a leaf and updates the root.
+    ///
+    /// # Parameters
+    /// - `leaf`: The leaf value to append.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the appended leaf.
+    ///
+    /// # Panics
+    /// - If the tree is full.
+    pub fn append(&mut self, leaf: H::Node) -> usize {
+        assert!(self.next_index < self.capacity(), "Merkle tree is full");
+        let index = self.next_index;
+        let mut current_index = index;
+        let mut current = self.hasher.hash_leaf(&leaf);
+
+        for level in 0..self.depth {
+            current = if current_index.is_multiple_of(2) {
+                // Left child: remember it and pair it with an empty subtree for now
+                self.frontier[level] = current.clone();
+                self.hasher.hash_nodes(&current, &self.zeros[level])
+            } else {
+                self.hasher.hash_nodes(&self.frontier[level], &current)
+            };
+            current_index /= 2;
+        }
+
+        self.root = current;
+        self.next_index += 1;
+        index
+    }
+
+    /// Gets the number of leaves appended so far.
+    ///
+    /// # Returns
+    /// - `usize`: The number of leaves.
+    pub fn len(&self) -> usize {
+        self.next_index
+    }
+
+    /// Checks whether no leaf has been appended yet.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the tree is empty, otherwise `false`.
+    pub fn is_empty(&self) -> bool {
+        self.next_index == 0
+    }
+
+    /// Gets the maximum number of leaves the tree can hold.
+    ///
+    /// # Returns
+    /// - `usize`: The capacity of the tree.
+    pub fn capacity(&self) -> usize {
+        1 << self.depth
+    }
+
+    /// Gets the depth of the tree.
+    ///
+    /// # Returns
+    /// - `usize`: The depth of the tree.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Creates the given number of distinct field-element leaves.
+    fn leaves(count: u64) -> Vec<FieldElement> {
+        (0..count).map(|value| FieldElement::new(BigInt::from(value + 1))).collect()
+    }
+
+    #[test]
+    fn transitions_are_bound_to_their_index() {
+        let mut tree = PoseidonMerkleTree::new(leaves(5));
+        let update = tree.update_leaves(&[(4, FieldElement::new(BigInt::from(99)))]);
+        assert!(update.verify(tree.hasher()));
+
+        let mut transition = update.transitions[0].clone();
+        assert!(transition.verify(tree.hasher()));
+        for index in 0..4 {
+            transition.index = index;
+            assert!(!transition.verify(tree.hasher()), "index {} accepted", index);
+        }
+    }
+
+    #[test]
+    fn transitions_reject_a_path_for_another_tree_size() {
+        // The last of three leaves is promoted past the first level
+        let mut tree = PoseidonMerkleTree::new(leaves(3));
+        let mut transition = tree.update_leaves(&[(2, FieldElement::new(BigInt::from(99)))]).transitions.remove(0);
+        assert!(transition.verify(tree.hasher()));
+        transition.num_leaves = 4;
+        assert!(!transition.verify(tree.hasher()));
+        transition.num_leaves = 1;
+        assert!(!transition.verify(tree.hasher()));
+    }
+
+    /// Saves a tree to a file in the temporary directory, named after the test.
+    fn saved(tree: &PoseidonMerkleTree, name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("zk-starter-kit-{}-{}.bin", name, std::process::id()));
+        tree.save_to_binary(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn saved_trees_load_back() {
+        let tree = PoseidonMerkleTree::new(leaves(7));
+        let filename = saved(&tree, "merkle-round-trip");
+        let loaded = PoseidonMerkleTree::load_from_binary(&filename).unwrap();
+        std::fs::remove_file(&filename).unwrap();
+        assert_eq!(loaded.root, tree.root);
+        assert_eq!(loaded.levels, tree.levels);
+    }
+
+    #[test]
+    fn corrupted_inner_nodes_are_rejected() {
+        let tree = PoseidonMerkleTree::new(leaves(7));
+        let filename = saved(&tree, "merkle-corrupted");
+        let mut data = std::fs::read(&filename).unwrap();
+
+        // Flip the lowest byte of the value of the first node on the second level
+        let node = bincode::serialize(&tree.levels[1][0]).unwrap();
+        let offset = data.windows(node.len()).position(|window| window == node.as_slice()).unwrap();
+        data[offset + 9] ^= 1;
+        std::fs::write(&filename, &data).unwrap();
+
+        let err = PoseidonMerkleTree::load_from_binary(&filename).err().unwrap();
+        std::fs::remove_file(&filename).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "Inconsistent Merkle Tree levels");
+    }
+
+    #[test]
+    fn fixed_depth_paths_have_depth_entries() {
+        let tree = PoseidonMerkleTree::new_with_depth(3, leaves(5), FieldElement::new(BigInt::from(0)));
+        assert_eq!(tree.leaves.len(), 8);
+        for index in 0..8 {
+            let path = tree.merkle_path(index);
+            assert_eq!(path.len(), 3);
+            assert!(tree.verify_path(&tree.leaves[index], &path));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Too many leaves")]
+    fn fixed_depth_trees_reject_extra_leaves() {
+        PoseidonMerkleTree::new_with_depth(2, leaves(5), FieldElement::new(BigInt::from(0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "too large for a tree indexed by usize")]
+    fn fixed_depth_trees_reject_depths_beyond_usize() {
+        PoseidonMerkleTree::new_with_depth(usize::BITS as usize, leaves(1), FieldElement::new(BigInt::from(0)));
+    }
+
+    #[test]
+    fn incremental_roots_match_the_padded_tree() {
+        let mut incremental = IncrementalMerkleTree::<Poseidon>::new(3);
+        let mut padded = leaves(5);
+        padded.iter().for_each(|leaf| { incremental.append(leaf.clone()); });
+        padded.resize(8, FieldElement::new(BigInt::from(0)));
+        assert_eq!(incremental.len(), 5);
+        assert_eq!(incremental.root, PoseidonMerkleTree::new(padded).root);
+    }
+
+    #[test]
+    #[should_panic(expected = "Merkle tree is full")]
+    fn full_incremental_trees_reject_appends() {
+        let mut tree = IncrementalMerkleTree::<Poseidon>::new(1);
+        for leaf in leaves(3) {
+            tree.append(leaf);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "too large for a tree indexed by usize")]
+    fn incremental_trees_reject_depths_beyond_usize() {
+        IncrementalMerkleTree::<Poseidon>::new(usize::BITS as usize);
+    }
+
+    #[test]
+    fn hasher_ids_follow_the_parameters() {
+        assert_eq!(Poseidon::new().id(), Poseidon::default().id());
+        assert_ne!(Poseidon::new().id(), Poseidon2::new().id());
+        assert_ne!(Poseidon::new().id(), Poseidon::generate(&FieldElement::default_modulus(), 5).unwrap().id());
+        assert_ne!(Poseidon::new().id(), AdditiveHasher.id());
+    }
+
+    #[test]
+    fn trees_saved_with_another_hasher_are_rejected() {
+        let tree = PoseidonMerkleTree::new(leaves(7));
+        let filename = saved(&tree, "merkle-other-hasher");
+        let err = Poseidon2MerkleTree::load_from_binary(&filename).err().unwrap();
+        std::fs::remove_file(&filename).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("Merkle Tree was built with hasher"));
+    }
+
+    #[test]
+    fn trees_saved_with_other_parameters_are_rejected() {
+        let wide = Poseidon::generate(&FieldElement::default_modulus(), 5).unwrap();
+        let tree = MerkleTree::with_hasher(wide, leaves(7));
+        let path = std::env::temp_dir().join(format!("zk-starter-kit-merkle-wide-{}.bin", std::process::id()));
+        tree.save_to_binary(&path).unwrap();
+        let err = PoseidonMerkleTree::load_from_binary(&path).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("Merkle Tree was built with hasher"));
+    }
+}
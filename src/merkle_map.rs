@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
+use crate::ct::ConstantTimeEq;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::poseidon::{DomainTag, Poseidon};
+
+/// Represents a key-value map committed to by a sparse Poseidon Merkle Tree.
+///
+/// Every field element is a valid key and owns the leaf at the position given
+/// by its value, so the tree is as deep as the modulus is wide. A present entry
+/// stores `hash(key, value)` in its leaf, under the Merkle leaf tag; absent
+/// keys keep the zero leaf.
+///
+/// Keys of another field that do not fit in the tree are rejected with an
+/// `Input` error, and proofs about them never verify.
+pub struct MerkleMap {
+    /// The root of the map.
+    pub root: FieldElement,
+    /// The depth of the underlying tree.
+    depth: usize,
+    /// The values stored in the map, keyed by the key's value.
+    entries: HashMap<BigInt, FieldElement>,
+    /// The non-empty nodes of the tree, keyed by `(level, index)` with level 0 for leaves.
+    nodes: HashMap<(usize, u64), FieldElement>,
+    /// The roots of empty subtrees on each level.
+    zeros: Vec<FieldElement>,
+    /// The Poseidon instance used for hashing.
+    poseidon: Poseidon,
+}
+
+/// Represents a proof of the current value of a key in a `MerkleMap`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MerkleMapProof {
+    /// The key the proof is about.
+    pub key: FieldElement,
+    /// The value of the key, or `None` if the key is absent.
+    pub value: Option<FieldElement>,
+    /// The sibling hashes from the leaf up to the root.
+    pub siblings: Vec<FieldElement>,
+}
+
+/// Represents a proof that a `MerkleMap` moved from one root to another by
+/// changing the value of a single key.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MerkleMapUpdateProof {
+    /// The key that was updated.
+    pub key: FieldElement,
+    /// The value before the update, or `None` if the key was absent.
+    pub old_value: Option<FieldElement>,
+    /// The value after the update, or `None` if the key was removed.
+    pub new_value: Option<FieldElement>,
+    /// The sibling hashes from the leaf up to the root, unchanged by the update.
+    pub siblings: Vec<FieldElement>,
+    /// The root before the update.
+    pub old_root: FieldElement,
+    /// The root after the update.
+    pub new_root: FieldElement,
+}
+
+impl Default for MerkleMap {
+    fn default() -> Self {
+        MerkleMap::new()
+    }
+}
+
+impl MerkleMap {
+    /// Creates a new empty Merkle map.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `MerkleMap` struct.
+    pub fn new() -> Self {
+        let poseidon = Poseidon::new();
+        let depth = map_depth();
+
+        let mut zeros = vec![FieldElement::new(BigInt::zero())];
+        for level in 0..depth {
+            let zero = poseidon.hash_two(DomainTag::MERKLE_NODE, &zeros[level], &zeros[level]);
+            zeros.push(zero);
+        }
+
+        MerkleMap {
+            root: zeros[depth].clone(),
+            depth,
+            entries: HashMap::new(),
+            nodes: HashMap::new(),
+            zeros,
+            poseidon,
+        }
+    }
+
+    /// Gets the value stored for a key.
+    ///
+    /// # Parameters
+    /// - `key`: The key to look up.
+    ///
+    /// # Returns
+    /// - `Option<&FieldElement>`: The value if the key is present, otherwise `None`.
+    pub fn get(&self, key: &FieldElement) -> Option<&FieldElement> {
+        self.entries.get(&key.get_value())
+    }
+
+    /// Gets the number of entries in the map.
+    ///
+    /// # Returns
+    /// - `usize`: The number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks whether the map has no entries.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the map is empty, otherwise `false`.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Sets the value of a key and returns a proof of the update.
+    ///
+    /// # Parameters
+    /// - `key`: The key to set.
+    /// - `value`: The new value.
+    ///
+    /// # Returns
+    /// - `Result<MerkleMapUpdateProof>`: The proof of the transition to the new root, or an
+    ///   `Input` error if the key does not fit in the tree.
+    pub fn insert(&mut self, key: FieldElement, value: FieldElement) -> Result<MerkleMapUpdateProof> {
+        self.update(key, Some(value))
+    }
+
+    /// Removes a key and returns a proof of the update.
+    ///
+    /// # Parameters
+    /// - `key`: The key to remove.
+    ///
+    /// # Returns
+    /// - `Result<MerkleMapUpdateProof>`: The proof of the transition to the new root, or an
+    ///   `Input` error if the key does not fit in the tree.
+    pub fn remove(&mut self, key: &FieldElement) -> Result<MerkleMapUpdateProof> {
+        self.update(key.clone(), None)
+    }
+
+    /// Proves the current value (or absence) of a key.
+    ///
+    /// # Parameters
+    /// - `key`: The key to prove.
+    ///
+    /// # Returns
+    /// - `Result<MerkleMapProof>`: The proof of the key's current value, or an `Input` error if
+    ///   the key does not fit in the tree.
+    pub fn prove(&self, key: &FieldElement) -> Result<MerkleMapProof> {
+        Ok(MerkleMapProof {
+            key: key.clone(),
+            value: self.get(key).cloned(),
+            siblings: self.siblings(self.position(key)?),
+        })
+    }
+
+    /// Changes the value of a key, updating only the nodes on its path.
+    ///
+    /// # Parameters
+    /// - `key`: The key to update.
+    /// - `value`: The new value, or `None` to remove the key.
+    ///
+    /// # Returns
+    /// - `Result<MerkleMapUpdateProof>`: The proof of the transition to the new root, or an
+    ///   `Input` error if the key does not fit in the tree.
+    fn update(&mut self, key: FieldElement, value: Option<FieldElement>) -> Result<MerkleMapUpdateProof> {
+        let position = self.position(&key)?;
+        let siblings = self.siblings(position);
+        let old_root = self.root.clone();
+        let old_value = match &value {
+            Some(v) => self.entries.insert(key.get_value(), v.clone()),
+            None => self.entries.remove(&key.get_value()),
+        };
+
+        let mut current = leaf_hash(&self.poseidon, &key, value.as_ref());
+        let mut index = position;
+        for (level, sibling) in siblings.iter().enumerate() {
+            self.set_node(level, index, current.clone());
+            current = if index.is_multiple_of(2) {
+                self.poseidon.hash_two(DomainTag::MERKLE_NODE, &current, sibling)
+            } else {
+                self.poseidon.hash_two(DomainTag::MERKLE_NODE, sibling, &current)
+            };
+            index /= 2;
+        }
+        self.root = current;
+
+        Ok(MerkleMapUpdateProof {
+            key,
+            old_value,
+            new_value: value,
+            siblings,
+            old_root,
+            new_root: self.root.clone(),
+        })
+    }
+
+    /// Collects the sibling hashes on the path from a leaf to the root.
+    ///
+    /// # Parameters
+    /// - `position`: The index of the leaf.
+    ///
+    /// # Returns
+    /// - `Vec<FieldElement>`: The sibling hashes, from the leaf level upwards.
+    fn siblings(&self, position: u64) -> Vec<FieldElement> {
+        (0..self.depth).map(|level| self.node(level, (position >> level) ^ 1)).collect()
+    }
+
+    /// Gets a node of the tree, falling back to the empty subtree root.
+    fn node(&self, level: usize, index: u64) -> FieldElement {
+        self.nodes.get(&(level, index)).cloned().unwrap_or_else(|| self.zeros[level].clone())
+    }
+
+    /// Stores a node of the tree, dropping it when it equals the empty subtree root.
+    fn set_node(&mut self, level: usize, index: u64, node: FieldElement) {
+        if node == self.zeros[level] {
+            self.nodes.remove(&(level, index));
+        } else {
+            self.nodes.insert((level, index), node);
+        }
+    }
+
+    /// Gets the leaf position owned by a key, or an `Input` error if the key does not fit in the tree.
+    fn position(&self, key: &FieldElement) -> Result<u64> {
+        position(key, self.depth)
+            .ok_or_else(|| Error::Input(format!("Key {} does not fit in a map of depth {}", key.get_value(), self.depth)))
+    }
+}
+
+impl MerkleMapProof {
+    /// Verifies the proof against a map root.
+    ///
+    /// # Parameters
+    /// - `root`: The root of the map.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the proof has one sibling per level and the key holds the claimed
+    ///   value under the root, otherwise `false`.
+    pub fn verify(&self, root: &FieldElement) -> bool {
+        let poseidon = Poseidon::new();
+        compute_root(&poseidon, &self.key, self.value.as_ref(), &self.siblings)
+            .is_some_and(|computed| computed.ct_eq(root).into())
+    }
+}
+
+impl MerkleMapUpdateProof {
+    /// Verifies that the update moves the map from `old_root` to `new_root`.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the proof has one sibling per level, the old value is proven
+    ///   under the old root and the new value under the new root with the same siblings,
+    ///   otherwise `false`.
+    pub fn verify(&self) -> bool {
+        let poseidon = Poseidon::new();
+        let old = compute_root(&poseidon, &self.key, self.old_value.as_ref(), &self.siblings);
+        let new = compute_root(&poseidon, &self.key, self.new_value.as_ref(), &self.siblings);
+        match (old, new) {
+            (Some(old), Some(new)) => bool::from(old.ct_eq(&self.old_root) & new.ct_eq(&self.new_root)),
+            _ => false,
+        }
+    }
+}
+
+/// Gets the depth of a map, the width of the default field in bits.
+fn map_depth() -> usize {
+    FieldElement::default_modulus().bits() as usize
+}
+
+/// Gets the leaf position owned by a key.
+///
+/// # Parameters
+/// - `key`: The key.
+/// - `depth`: The depth of the tree.
+///
+/// # Returns
+/// - `Option<u64>`: The position, or `None` if the key is not below `2^depth`.
+fn position(key: &FieldElement, depth: usize) -> Option<u64> {
+    key.get_value().to_u64().filter(|position| depth >= 64 || position >> depth == 0)
+}
+
+/// Computes the leaf stored for a key: `hash(key, value)`, or zero when absent.
+fn leaf_hash(poseidon: &Poseidon, key: &FieldElement, value: Option<&FieldElement>) -> FieldElement {
+    match value {
+        Some(value) => poseidon.hash_two(DomainTag::MERKLE_LEAF, key, value),
+        None => FieldElement::new(BigInt::zero()),
+    }
+}
+
+/// Recomputes the map root from a key, its value and the sibling hashes, or
+/// `None` if there is not one sibling per level or the key does not fit in the tree.
+fn compute_root(poseidon: &Poseidon, key: &FieldElement, value: Option<&FieldElement>, siblings: &[FieldElement]) -> Option<FieldElement> {
+    if siblings.len() != map_depth() {
+        return None;
+    }
+    let mut index = position(key, siblings.len())?;
+    let mut current = leaf_hash(poseidon, key, value);
+    for sibling in siblings {
+        current = if index.is_multiple_of(2) {
+            poseidon.hash_two(DomainTag::MERKLE_NODE, &current, sibling)
+        } else {
+            poseidon.hash_two(DomainTag::MERKLE_NODE, sibling, &current)
+        };
+        index /= 2;
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(value: u64) -> FieldElement {
+        FieldElement::new(BigInt::from(value))
+    }
+
+    #[test]
+    fn proofs_show_present_and_absent_keys() {
+        let mut map = MerkleMap::new();
+        map.insert(element(7), element(70)).unwrap();
+        map.insert(element(9), element(90)).unwrap();
+
+        let present = map.prove(&element(7)).unwrap();
+        assert_eq!(present.value, Some(element(70)));
+        assert!(present.verify(&map.root));
+        let absent = map.prove(&element(8)).unwrap();
+        assert_eq!(absent.value, None);
+        assert!(absent.verify(&map.root));
+    }
+
+    #[test]
+    fn updates_chain_from_root_to_root() {
+        let mut map = MerkleMap::new();
+        let empty = map.root.clone();
+        let insert = map.insert(element(3), element(30)).unwrap();
+        let remove = map.remove(&element(3)).unwrap();
+        assert!(insert.verify() && remove.verify());
+        assert_eq!(insert.old_root, empty);
+        assert_eq!(insert.new_root, remove.old_root);
+        assert_eq!(map.root, empty);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn proofs_of_other_values_are_rejected() {
+        let mut map = MerkleMap::new();
+        map.insert(element(7), element(70)).unwrap();
+        let mut proof = map.prove(&element(7)).unwrap();
+        proof.value = Some(element(71));
+        assert!(!proof.verify(&map.root));
+        proof.value = None;
+        assert!(!proof.verify(&map.root));
+
+        let mut update = map.insert(element(7), element(72)).unwrap();
+        update.new_value = Some(element(73));
+        assert!(!update.verify());
+    }
+
+    #[test]
+    fn proofs_with_the_wrong_number_of_siblings_are_rejected() {
+        let mut map = MerkleMap::new();
+        let mut update = map.insert(element(7), element(70)).unwrap();
+        let mut proof = map.prove(&element(7)).unwrap();
+
+        let top = proof.siblings.pop().unwrap();
+        assert!(!proof.verify(&map.root));
+        proof.siblings.push(top);
+        proof.siblings.push(element(0));
+        assert!(!proof.verify(&map.root));
+        proof.siblings.clear();
+        assert!(!proof.verify(&map.root));
+
+        update.siblings.pop();
+        assert!(!update.verify());
+    }
+
+    #[test]
+    fn keys_outside_the_tree_are_rejected_without_panicking() {
+        // A key of a 127-bit field does not fit in a map over the default field
+        let wide = FieldElement::new_with_modulus(BigInt::from(1u128 << 100), BigInt::from(u128::MAX >> 1));
+        let mut map = MerkleMap::new();
+        assert!(matches!(map.insert(wide.clone(), element(1)), Err(Error::Input(_))));
+        assert!(matches!(map.prove(&wide), Err(Error::Input(_))));
+
+        // A key that fits in a `u64` but is beyond the depth of the tree
+        let deep = FieldElement::new_with_modulus(BigInt::from(1u64 << 40), BigInt::from(u64::MAX >> 3));
+        assert!(matches!(map.remove(&deep), Err(Error::Input(_))));
+
+        let mut proof = map.prove(&element(0)).unwrap();
+        proof.key = wide;
+        assert!(!proof.verify(&map.root));
+        proof.key = deep;
+        assert!(!proof.verify(&map.root));
+    }
+}
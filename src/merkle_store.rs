@@ -0,0 +1,453 @@
+use std::collections::HashMap;
+#[cfg(feature = "sled")]
+use std::path::Path;
+use crate::error::{Error, Result};
+use crate::merkle::{root_from_path, MerkleHasher};
+use crate::parallel::cfg_chunks;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "sled")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "sled")]
+use serde::Serialize;
+
+/// The deepest `StoredMerkleTree`, whose leaf indices still fit in a `u64`.
+pub const MAX_STORED_DEPTH: usize = 63;
+
+/// Represents the position of a node in a Merkle tree, with level 0 for the
+/// leaves and index 0 for the leftmost node of a level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodePosition {
+    /// The level of the node, counted from the leaves.
+    pub level: u32,
+    /// The index of the node within its level.
+    pub index: u64,
+}
+
+impl NodePosition {
+    /// Creates a node position.
+    ///
+    /// # Parameters
+    /// - `level`: The level of the node, counted from the leaves.
+    /// - `index`: The index of the node within its level.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `NodePosition` struct.
+    pub fn new(level: u32, index: u64) -> Self {
+        NodePosition { level, index }
+    }
+
+    /// Encodes the position as a 12-byte big-endian key, so keys sort by level
+    /// and then by index.
+    ///
+    /// # Returns
+    /// - `[u8; 12]`: The key.
+    pub fn to_key(&self) -> [u8; 12] {
+        let mut key = [0; 12];
+        key[..4].copy_from_slice(&self.level.to_be_bytes());
+        key[4..].copy_from_slice(&self.index.to_be_bytes());
+        key
+    }
+}
+
+/// A storage backend holding the nodes of a `StoredMerkleTree` by position.
+///
+/// A position that was never written holds the root of an empty subtree,
+/// which the tree supplies itself, so stores only keep the nodes written.
+pub trait NodeStore<N> {
+    /// Reads a node.
+    ///
+    /// # Parameters
+    /// - `position`: The position of the node.
+    ///
+    /// # Returns
+    /// - `Result<Option<N>>`: The node, `None` if it was never written, or an error if the
+    ///   backend fails.
+    fn get(&self, position: NodePosition) -> Result<Option<N>>;
+
+    /// Writes a node, replacing any node at the same position.
+    ///
+    /// # Parameters
+    /// - `position`: The position of the node.
+    /// - `node`: The node.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the backend fails.
+    fn put(&mut self, position: NodePosition, node: N) -> Result<()>;
+
+    /// Writes several nodes. Backends with atomic batches override it to
+    /// write a whole level of an update at once.
+    ///
+    /// # Parameters
+    /// - `nodes`: The positions and nodes to write.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the backend fails.
+    fn put_batch(&mut self, nodes: Vec<(NodePosition, N)>) -> Result<()> {
+        for (position, node) in nodes {
+            self.put(position, node)?;
+        }
+        Ok(())
+    }
+
+    /// Makes the written nodes durable.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the backend fails.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A `NodeStore` keeping the nodes in a hash map.
+#[derive(Clone, Debug)]
+pub struct MemoryNodeStore<N> {
+    /// The written nodes.
+    nodes: HashMap<NodePosition, N>,
+}
+
+impl<N> Default for MemoryNodeStore<N> {
+    fn default() -> Self {
+        MemoryNodeStore::new()
+    }
+}
+
+impl<N> MemoryNodeStore<N> {
+    /// Creates an empty store.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `MemoryNodeStore` struct.
+    pub fn new() -> Self {
+        MemoryNodeStore { nodes: HashMap::new() }
+    }
+
+    /// Gets the number of nodes written.
+    ///
+    /// # Returns
+    /// - `usize`: The number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Checks whether no node has been written.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the store is empty, otherwise `false`.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl<N: Clone> NodeStore<N> for MemoryNodeStore<N> {
+    fn get(&self, position: NodePosition) -> Result<Option<N>> {
+        Ok(self.nodes.get(&position).cloned())
+    }
+
+    fn put(&mut self, position: NodePosition, node: N) -> Result<()> {
+        self.nodes.insert(position, node);
+        Ok(())
+    }
+}
+
+/// A `NodeStore` keeping bincode-encoded nodes in a sled tree on disk, keyed
+/// by `NodePosition::to_key`.
+#[cfg(feature = "sled")]
+pub struct SledNodeStore {
+    /// The sled tree holding the nodes.
+    tree: sled::Tree,
+}
+
+#[cfg(feature = "sled")]
+impl SledNodeStore {
+    /// Opens a store in a sled database directory, creating it if needed.
+    ///
+    /// # Parameters
+    /// - `path`: The directory of the database.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The store, or an error if the database cannot be opened.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let db = sled::open(path).map_err(|err| Error::Io(err.into()))?;
+        Ok(SledNodeStore { tree: (*db).clone() })
+    }
+
+    /// Creates a store in a tree of an open sled database, so several Merkle
+    /// trees can share one database under different names.
+    ///
+    /// # Parameters
+    /// - `tree`: The sled tree.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `SledNodeStore` struct.
+    pub fn with_tree(tree: sled::Tree) -> Self {
+        SledNodeStore { tree }
+    }
+}
+
+#[cfg(feature = "sled")]
+impl<N: Serialize + DeserializeOwned> NodeStore<N> for SledNodeStore {
+    fn get(&self, position: NodePosition) -> Result<Option<N>> {
+        match self.tree.get(position.to_key()).map_err(|err| Error::Io(err.into()))? {
+            Some(bytes) => Ok(Some(bincode::deserialize(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn put(&mut self, position: NodePosition, node: N) -> Result<()> {
+        self.tree.insert(position.to_key(), bincode::serialize(&node)?).map_err(|err| Error::Io(err.into()))?;
+        Ok(())
+    }
+
+    fn put_batch(&mut self, nodes: Vec<(NodePosition, N)>) -> Result<()> {
+        let mut batch = sled::Batch::default();
+        for (position, node) in nodes {
+            batch.insert(&position.to_key(), bincode::serialize(&node)?);
+        }
+        self.tree.apply_batch(batch).map_err(|err| Error::Io(err.into()))
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.tree.flush().map_err(|err| Error::Io(err.into()))?;
+        Ok(())
+    }
+}
+
+/// Represents a Merkle Tree of fixed depth whose nodes live in a `NodeStore`
+/// rather than in memory, so trees with hundreds of millions of leaves can
+/// be kept on disk.
+///
+/// Unwritten positions hold the empty leaf and the roots of empty subtrees,
+/// computed once per level, so a new tree costs nothing to store. The first
+/// level holds the leaves as written, hashed with `MerkleHasher::hash_leaf`
+/// whenever they are combined or returned as siblings. Paths have
+/// exactly `depth` entries in the format of `MerkleTree::merkle_path`, and
+/// verify against the same root as a `MerkleTree` built with
+/// `new_with_depth` over the same leaves.
+pub struct StoredMerkleTree<H: MerkleHasher, S: NodeStore<H::Node>> {
+    /// The root of the Merkle Tree.
+    pub root: H::Node,
+    /// The depth of the tree.
+    depth: usize,
+    /// The roots of empty subtrees on each level.
+    zeros: Vec<H::Node>,
+    /// The store holding the nodes.
+    store: S,
+    /// The hasher used to combine nodes.
+    hasher: H,
+}
+
+impl<H: MerkleHasher, S: NodeStore<H::Node>> StoredMerkleTree<H, S> {
+    /// Opens a tree over a store, which may be empty or hold the nodes of a
+    /// tree written earlier with the same hasher, depth and empty leaf.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hasher used to combine nodes.
+    /// - `store`: The store holding the nodes.
+    /// - `depth`: The depth of the tree, allowing up to `2^depth` leaves.
+    /// - `empty_leaf`: The value of unwritten leaf positions.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The tree, or an error if the depth exceeds `MAX_STORED_DEPTH` or
+    ///   the root cannot be read.
+    pub fn open(hasher: H, store: S, depth: usize, empty_leaf: H::Node) -> Result<Self> {
+        if depth > MAX_STORED_DEPTH {
+            return Err(Error::Input(format!("A stored Merkle Tree has at most depth {}, got {}", MAX_STORED_DEPTH, depth)));
+        }
+        let mut zero = hasher.hash_leaf(&empty_leaf);
+        let mut zeros = vec![empty_leaf];
+        for _ in 0..depth {
+            zero = hasher.hash_nodes(&zero, &zero);
+            zeros.push(zero.clone());
+        }
+        let mut root = store.get(NodePosition::new(depth as u32, 0))?.unwrap_or_else(|| zeros[depth].clone());
+        if depth == 0 {
+            root = hasher.hash_leaf(&root); // The only leaf is the root
+        }
+        Ok(StoredMerkleTree { root, depth, zeros, store, hasher })
+    }
+
+    /// Reads a node, falling back to the root of an empty subtree.
+    ///
+    /// # Parameters
+    /// - `level`: The level of the node, counted from the leaves.
+    /// - `index`: The index of the node within its level.
+    ///
+    /// # Returns
+    /// - `Result<H::Node>`: The node, or an error if the store fails.
+    pub fn node(&self, level: usize, index: u64) -> Result<H::Node> {
+        Ok(self.store.get(NodePosition::new(level as u32, index))?.unwrap_or_else(|| self.zeros[level].clone()))
+    }
+
+    /// Reads a leaf.
+    ///
+    /// # Parameters
+    /// - `index`: The index of the leaf.
+    ///
+    /// # Returns
+    /// - `Result<H::Node>`: The leaf, or an error if the index is out of range or the store fails.
+    pub fn leaf(&self, index: u64) -> Result<H::Node> {
+        self.check_range(index, 1)?;
+        self.node(0, index)
+    }
+
+    /// Writes consecutive leaves and recomputes the nodes above them, one
+    /// level at a time.
+    ///
+    /// Loading a large tree in chunks of consecutive leaves costs about three
+    /// hashes per leaf plus `depth` per chunk; with the `parallel` feature,
+    /// the pairs of a level are hashed across the thread pool.
+    ///
+    /// # Parameters
+    /// - `start`: The index of the first leaf.
+    /// - `leaves`: The leaves to write from `start` on.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the leaves do not fit in the tree or the store fails.
+    pub fn set_leaves(&mut self, start: u64, leaves: &[H::Node]) -> Result<()> {
+        if leaves.is_empty() {
+            return Ok(());
+        }
+        self.check_range(start, leaves.len() as u64)?;
+        let mut first = start;
+        let mut nodes = leaves.to_vec();
+        self.store.put_batch(positioned(0, first, &nodes))?;
+
+        for level in 0..self.depth {
+            // Pad the range with the stored siblings of its ends, so it covers whole pairs
+            let mut row = Vec::with_capacity(nodes.len() + 2);
+            if !first.is_multiple_of(2) {
+                first -= 1;
+                row.push(self.node(level, first)?);
+            }
+            let end = first + row.len() as u64 + nodes.len() as u64;
+            row.append(&mut nodes);
+            if !end.is_multiple_of(2) {
+                row.push(self.node(level, end)?);
+            }
+
+            let hasher = &self.hasher;
+            nodes = cfg_chunks!(row, 2).map(|pair| match level {
+                0 => hasher.hash_nodes(&hasher.hash_leaf(&pair[0]), &hasher.hash_leaf(&pair[1])),
+                _ => hasher.hash_nodes(&pair[0], &pair[1]),
+            }).collect();
+            first /= 2;
+            self.store.put_batch(positioned(level + 1, first, &nodes))?;
+        }
+        self.root = match self.depth {
+            0 => self.hasher.hash_leaf(&nodes[0]),
+            _ => nodes.swap_remove(0),
+        };
+        Ok(())
+    }
+
+    /// Replaces one leaf and recomputes the nodes on its path.
+    ///
+    /// # Parameters
+    /// - `index`: The index of the leaf.
+    /// - `leaf`: The new leaf value.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the index is out of range or the store fails.
+    pub fn set_leaf(&mut self, index: u64, leaf: H::Node) -> Result<()> {
+        self.set_leaves(index, &[leaf])
+    }
+
+    /// Computes the Merkle path of a leaf from the stored siblings.
+    ///
+    /// # Parameters
+    /// - `index`: The index of the leaf.
+    ///
+    /// # Returns
+    /// - `Result<Vec<(H::Node, bool)>>`: The `depth` sibling hashes with a boolean indicating if
+    ///   the current node is a left sibling, or an error if the index is out of range or the
+    ///   store fails.
+    pub fn merkle_path(&self, index: u64) -> Result<Vec<(H::Node, bool)>> {
+        self.check_range(index, 1)?;
+        let mut current_index = index;
+        let mut path = Vec::with_capacity(self.depth);
+        for level in 0..self.depth {
+            let mut sibling = self.node(level, current_index ^ 1)?;
+            if level == 0 {
+                sibling = self.hasher.hash_leaf(&sibling);
+            }
+            path.push((sibling, current_index.is_multiple_of(2)));
+            current_index /= 2;
+        }
+        Ok(path)
+    }
+
+    /// Verifies a Merkle path for a leaf against the root of the tree.
+    ///
+    /// # Parameters
+    /// - `leaf`: The leaf value.
+    /// - `path`: The Merkle path as returned by `merkle_path`.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the path leads from the leaf to the root, otherwise `false`.
+    pub fn verify_path(&self, leaf: &H::Node, path: &[(H::Node, bool)]) -> bool {
+        root_from_path(&self.hasher, leaf, path) == self.root
+    }
+
+    /// Makes the written nodes durable in the store.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the store fails.
+    pub fn flush(&mut self) -> Result<()> {
+        self.store.flush()
+    }
+
+    /// Gets the maximum number of leaves the tree can hold.
+    ///
+    /// # Returns
+    /// - `u64`: The capacity of the tree.
+    pub fn capacity(&self) -> u64 {
+        1 << self.depth
+    }
+
+    /// Gets the depth of the tree.
+    ///
+    /// # Returns
+    /// - `usize`: The depth of the tree.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Gets the hasher used by the tree, to share with circuit gadgets.
+    ///
+    /// # Returns
+    /// - `&H`: A reference to the hasher.
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Gets the store holding the nodes.
+    ///
+    /// # Returns
+    /// - `&S`: A reference to the store.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Consumes the tree and returns its store.
+    ///
+    /// # Returns
+    /// - `S`: The store.
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    /// Checks that `count` leaves from `start` on fit in the tree.
+    fn check_range(&self, start: u64, count: u64) -> Result<()> {
+        match start.checked_add(count) {
+            Some(end) if end <= self.capacity() => Ok(()),
+            _ => Err(Error::Input(format!("Leaves {}..{} out of range for a tree of {} leaves", start, start.saturating_add(count), self.capacity()))),
+        }
+    }
+}
+
+/// Pairs consecutive nodes of a level with their positions.
+fn positioned<N: Clone>(level: usize, first: u64, nodes: &[N]) -> Vec<(NodePosition, N)> {
+    nodes.iter().enumerate()
+        .map(|(offset, node)| (NodePosition::new(level as u32, first + offset as u64), node.clone()))
+        .collect()
+}
@@ -0,0 +1,63 @@
+#[cfg(feature = "parallel")]
+use alloc::string::ToString;
+#[cfg(feature = "parallel")]
+use crate::error::{Error, Result};
+
+/// Iterates over a collection by reference, in parallel with the `parallel` feature.
+///
+/// Call sites must import `rayon::prelude::*` under the same feature.
+macro_rules! cfg_iter {
+    ($e:expr) => {{
+        #[cfg(feature = "parallel")]
+        let iter = $e.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let iter = $e.iter();
+        iter
+    }};
+}
+
+/// Iterates over a slice in chunks of the given size, in parallel with the `parallel` feature.
+#[cfg(feature = "std")]
+macro_rules! cfg_chunks {
+    ($e:expr, $size:expr) => {{
+        #[cfg(feature = "parallel")]
+        let iter = $e.par_chunks($size);
+        #[cfg(not(feature = "parallel"))]
+        let iter = $e.chunks($size);
+        iter
+    }};
+}
+
+/// Runs two closures, concurrently with the `parallel` feature.
+macro_rules! cfg_join {
+    ($a:expr, $b:expr) => {{
+        #[cfg(feature = "parallel")]
+        let result = rayon::join($a, $b);
+        #[cfg(not(feature = "parallel"))]
+        let result = ($a(), $b());
+        result
+    }};
+}
+
+#[cfg(feature = "std")]
+pub(crate) use cfg_chunks;
+pub(crate) use cfg_iter;
+pub(crate) use cfg_join;
+
+/// Configures the global thread pool used for proving.
+///
+/// Must be called before any parallel work runs; otherwise rayon sizes the
+/// pool to the number of cores.
+///
+/// # Parameters
+/// - `num_threads`: The number of worker threads.
+///
+/// # Returns
+/// - `Result<()>`: An error if the global pool was already initialized.
+#[cfg(feature = "parallel")]
+pub fn init_thread_pool(num_threads: usize) -> Result<()> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build_global()
+        .map_err(|err| Error::External(err.to_string()))
+}
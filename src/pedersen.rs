@@ -0,0 +1,60 @@
+use num_bigint::{BigInt, Sign};
+use sha2::{Digest, Sha256};
+use crate::field::FieldElement;
+
+/// Represents Pedersen commitment parameters over the multiplicative group of a
+/// prime field: `commit(value, blinding) = g^value * h^blinding`.
+///
+/// Both generators are derived from SHA-256, so nobody knows the discrete
+/// logarithm of one to the base of the other. Hiding and binding rest on the
+/// discrete logarithm in the multiplicative group, which is far too small in the
+/// default field for real use: these commitments are for learning and testing circuits.
+#[derive(Clone, Debug)]
+pub struct Pedersen {
+    /// The generator raised to the committed value.
+    pub g: FieldElement,
+    /// The generator raised to the blinding factor.
+    pub h: FieldElement,
+}
+
+impl Default for Pedersen {
+    fn default() -> Self {
+        Pedersen::generate(&FieldElement::default_modulus())
+    }
+}
+
+impl Pedersen {
+    /// Derives the generators for a prime field.
+    ///
+    /// # Parameters
+    /// - `modulus`: The prime modulus of the field.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Pedersen` struct.
+    pub fn generate(modulus: &BigInt) -> Self {
+        Pedersen { g: Self::generator(modulus, b"g"), h: Self::generator(modulus, b"h") }
+    }
+
+    /// Commits to a value.
+    ///
+    /// # Parameters
+    /// - `value`: The committed value.
+    /// - `blinding`: The random blinding factor.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The commitment `g^value * h^blinding`.
+    pub fn commit(&self, value: &BigInt, blinding: &BigInt) -> FieldElement {
+        self.g.pow(value).mul(&self.h.pow(blinding))
+    }
+
+    /// Derives a generator from SHA-256 over a label and the modulus.
+    fn generator(modulus: &BigInt, label: &[u8]) -> FieldElement {
+        let mut hasher = Sha256::new();
+        hasher.update(b"pedersen");
+        hasher.update(label);
+        hasher.update(modulus.to_bytes_be().1);
+        // Squaring keeps the generator away from 0 and 1 unless the hash lands on them
+        let element = FieldElement::new_with_modulus(BigInt::from_bytes_be(Sign::Plus, &hasher.finalize()), modulus.clone());
+        element.mul(&element)
+    }
+}
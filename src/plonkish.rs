@@ -0,0 +1,466 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::{Add, Mul, Neg, Sub};
+use num_bigint::BigInt;
+use num_traits::Zero;
+use sha2::{Digest, Sha256};
+use crate::context::ProverContext;
+use crate::ct::{self, ConstantTimeEq};
+use crate::field::FieldElement;
+use crate::fingerprint::{update_integer, update_length, CircuitId, CircuitVersion};
+use crate::poseidon::DomainTag;
+use crate::proof::Proof;
+
+/// Represents a polynomial over the cells of a row of a PLONKish table and the
+/// rows after it, built from cells and constants with `+`, `-` and `*`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    /// A constant.
+    Constant(FieldElement),
+    /// The cell of a column, `rotation` rows below the row the gate is enabled on.
+    Cell {
+        /// The index of the column.
+        column: usize,
+        /// The offset of the row, 0 for the current row.
+        rotation: usize,
+    },
+    /// The sum of two expressions.
+    Sum(Box<Expression>, Box<Expression>),
+    /// The product of two expressions.
+    Product(Box<Expression>, Box<Expression>),
+    /// The negation of an expression.
+    Negated(Box<Expression>),
+}
+
+impl Expression {
+    /// Creates an expression reading a column of the current row.
+    ///
+    /// # Parameters
+    /// - `column`: The index of the column.
+    ///
+    /// # Returns
+    /// - `Expression`: The cell expression.
+    pub fn cell(column: usize) -> Expression {
+        Expression::Cell { column, rotation: 0 }
+    }
+
+    /// Creates an expression reading a column of the next row.
+    ///
+    /// # Parameters
+    /// - `column`: The index of the column.
+    ///
+    /// # Returns
+    /// - `Expression`: The cell expression.
+    pub fn next(column: usize) -> Expression {
+        Expression::Cell { column, rotation: 1 }
+    }
+
+    /// Creates a constant expression.
+    ///
+    /// # Parameters
+    /// - `value`: The value of the constant.
+    ///
+    /// # Returns
+    /// - `Expression`: The constant expression.
+    pub fn constant(value: FieldElement) -> Expression {
+        Expression::Constant(value)
+    }
+
+    /// Computes the degree of the polynomial in the cells.
+    ///
+    /// # Returns
+    /// - `usize`: The degree, 0 for constants.
+    pub fn degree(&self) -> usize {
+        match self {
+            Expression::Constant(_) => 0,
+            Expression::Cell { .. } => 1,
+            Expression::Sum(a, b) => a.degree().max(b.degree()),
+            Expression::Product(a, b) => a.degree() + b.degree(),
+            Expression::Negated(a) => a.degree(),
+        }
+    }
+
+    /// Evaluates the expression on a row of a table.
+    ///
+    /// # Parameters
+    /// - `rows`: The cells of every row.
+    /// - `row`: The index of the current row.
+    ///
+    /// # Returns
+    /// - `Option<FieldElement>`: The value, or `None` if a cell lies past the last row or column.
+    pub fn evaluate(&self, rows: &[Vec<FieldElement>], row: usize) -> Option<FieldElement> {
+        Some(match self {
+            Expression::Constant(value) => value.clone(),
+            Expression::Cell { column, rotation } => rows.get(row + rotation)?.get(*column)?.clone(),
+            Expression::Sum(a, b) => FieldElement::add(&a.evaluate(rows, row)?, &b.evaluate(rows, row)?),
+            Expression::Product(a, b) => FieldElement::mul(&a.evaluate(rows, row)?, &b.evaluate(rows, row)?),
+            Expression::Negated(a) => a.evaluate(rows, row)?.negate(),
+        })
+    }
+
+    /// Hashes the structure of the expression for the circuit id.
+    fn update(&self, hasher: &mut Sha256) {
+        match self {
+            Expression::Constant(value) => {
+                hasher.update([0u8]);
+                update_integer(hasher, &value.get_value());
+            },
+            Expression::Cell { column, rotation } => {
+                hasher.update([1u8]);
+                update_length(hasher, *column);
+                update_length(hasher, *rotation);
+            },
+            Expression::Sum(a, b) | Expression::Product(a, b) => {
+                hasher.update([if matches!(self, Expression::Sum(..)) { 2u8 } else { 3u8 }]);
+                a.update(hasher);
+                b.update(hasher);
+            },
+            Expression::Negated(a) => {
+                hasher.update([4u8]);
+                a.update(hasher);
+            },
+        }
+    }
+}
+
+impl Add for Expression {
+    type Output = Expression;
+
+    fn add(self, other: Expression) -> Expression {
+        Expression::Sum(Box::new(self), Box::new(other))
+    }
+}
+
+impl Sub for Expression {
+    type Output = Expression;
+
+    fn sub(self, other: Expression) -> Expression {
+        self + -other
+    }
+}
+
+impl Mul for Expression {
+    type Output = Expression;
+
+    fn mul(self, other: Expression) -> Expression {
+        Expression::Product(Box::new(self), Box::new(other))
+    }
+}
+
+impl Neg for Expression {
+    type Output = Expression;
+
+    fn neg(self) -> Expression {
+        Expression::Negated(Box::new(self))
+    }
+}
+
+/// Represents a custom gate: a polynomial over the cells of a row, and possibly
+/// the rows below it, that must vanish on every row where its selector is on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomGate {
+    /// The name of the gate, used in failure reports.
+    pub name: String,
+    /// The polynomial that must vanish.
+    pub polynomial: Expression,
+}
+
+/// Identifies a cell of a PLONKish table by row and column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    /// The index of the row.
+    pub row: usize,
+    /// The index of the column.
+    pub column: usize,
+}
+
+/// Describes a check that the cells of a PLONKish table do not pass.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlonkishFailure {
+    /// A gate does not vanish on a row it is enabled on.
+    Gate {
+        /// The index of the gate.
+        gate: usize,
+        /// The index of the row.
+        row: usize,
+        /// The value of the polynomial, or `None` if it reads past the table.
+        value: Option<FieldElement>,
+    },
+    /// Two cells bound by a copy constraint hold different values.
+    Copy(Cell, Cell),
+}
+
+/// Represents a circuit arithmetized as a PLONKish table: rows of `width`
+/// cells, custom gates switched on per row by selector columns, and copy
+/// constraints between cells.
+///
+/// A custom gate checks an arbitrary polynomial over up to `width` wires of a
+/// row in one place, where R1CS would need a multiplication gate per product.
+/// The selector column of a gate holds the evaluations of its selector
+/// polynomial over the rows, one where the gate applies and zero elsewhere.
+#[derive(Clone, Debug)]
+pub struct PlonkishCircuit {
+    /// The number of cells per row.
+    width: usize,
+    /// The custom gates.
+    gates: Vec<CustomGate>,
+    /// The cells of every row.
+    rows: Vec<Vec<FieldElement>>,
+    /// The selector columns, one per gate, with an entry per row.
+    selectors: Vec<Vec<bool>>,
+    /// The pairs of cells constrained to be equal.
+    copies: Vec<(Cell, Cell)>,
+}
+
+impl PlonkishCircuit {
+    /// Creates an empty table.
+    ///
+    /// # Parameters
+    /// - `width`: The number of cells per row.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `PlonkishCircuit` struct.
+    pub fn new(width: usize) -> Self {
+        PlonkishCircuit { width, gates: Vec::new(), rows: Vec::new(), selectors: Vec::new(), copies: Vec::new() }
+    }
+
+    /// Defines a custom gate, initially enabled on no row.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the gate.
+    /// - `polynomial`: The polynomial that must vanish where the gate is enabled.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the gate.
+    ///
+    /// # Panics
+    /// - If the polynomial reads a column past the width of the table.
+    pub fn add_gate(&mut self, name: &str, polynomial: Expression) -> usize {
+        assert!(max_column(&polynomial) < self.width, "Gate {} reads past the {} columns of the table", name, self.width);
+        self.gates.push(CustomGate { name: String::from(name), polynomial });
+        self.selectors.push(alloc::vec![false; self.rows.len()]);
+        self.gates.len() - 1
+    }
+
+    /// Appends a row and enables gates on it.
+    ///
+    /// # Parameters
+    /// - `cells`: The values of the cells, at most `width` of them, padded with zeros.
+    /// - `gates`: The indices of the gates enabled on the row.
+    ///
+    /// # Returns
+    /// - `usize`: The index of the row.
+    ///
+    /// # Panics
+    /// - If there are more cells than columns or a gate does not exist.
+    pub fn add_row(&mut self, cells: &[FieldElement], gates: &[usize]) -> usize {
+        assert!(cells.len() <= self.width, "A row holds at most {} cells, got {}", self.width, cells.len());
+        let mut row = cells.to_vec();
+        row.resize(self.width, FieldElement::new(BigInt::zero()));
+        self.rows.push(row);
+        for selector in &mut self.selectors {
+            selector.push(false);
+        }
+        let index = self.rows.len() - 1;
+        for &gate in gates {
+            self.selectors[gate][index] = true;
+        }
+        index
+    }
+
+    /// Constrains two cells to hold the same value.
+    ///
+    /// # Parameters
+    /// - `a`: The first cell.
+    /// - `b`: The second cell.
+    pub fn copy(&mut self, a: Cell, b: Cell) {
+        self.copies.push((a, b));
+    }
+
+    /// Gets the custom gates.
+    ///
+    /// # Returns
+    /// - `&[CustomGate]`: The gates, by index.
+    pub fn gates(&self) -> &[CustomGate] {
+        &self.gates
+    }
+
+    /// Gets the cells of every row.
+    ///
+    /// # Returns
+    /// - `&[Vec<FieldElement>]`: The rows, in order.
+    pub fn rows(&self) -> &[Vec<FieldElement>] {
+        &self.rows
+    }
+
+    /// Checks the cells of the table, or of a witness laid out like it, against every gate and copy constraint.
+    ///
+    /// # Parameters
+    /// - `rows`: The cells of every row.
+    ///
+    /// # Returns
+    /// - `Vec<PlonkishFailure>`: The failed checks, empty if the rows satisfy the circuit.
+    pub fn check(&self, rows: &[Vec<FieldElement>]) -> Vec<PlonkishFailure> {
+        let mut failures = Vec::new();
+        for (gate, selector) in self.selectors.iter().enumerate() {
+            for (row, _) in selector.iter().enumerate().filter(|(_, enabled)| **enabled) {
+                let value = self.gates[gate].polynomial.evaluate(rows, row);
+                if !value.as_ref().is_some_and(|value| value.get_value().is_zero()) {
+                    failures.push(PlonkishFailure::Gate { gate, row, value });
+                }
+            }
+        }
+        for (a, b) in &self.copies {
+            let cell = |cell: &Cell| rows.get(cell.row).and_then(|row| row.get(cell.column));
+            if cell(a).is_none() || cell(a) != cell(b) {
+                failures.push(PlonkishFailure::Copy(*a, *b));
+            }
+        }
+        failures
+    }
+
+    /// Computes the id of the circuit from its width, gates, selectors and copy
+    /// constraints, leaving out the cell values.
+    ///
+    /// # Returns
+    /// - `CircuitId`: The id, which every proof of the circuit carries.
+    pub fn circuit_id(&self) -> CircuitId {
+        let mut hasher = Sha256::new();
+        hasher.update(b"zk-plonkish");
+        update_integer(&mut hasher, &FieldElement::default_modulus());
+        update_length(&mut hasher, self.width);
+        update_length(&mut hasher, self.rows.len());
+        update_length(&mut hasher, self.gates.len());
+        for (gate, selector) in self.gates.iter().zip(&self.selectors) {
+            gate.polynomial.update(&mut hasher);
+            hasher.update(selector.iter().map(|&enabled| enabled as u8).collect::<Vec<u8>>());
+        }
+        update_length(&mut hasher, self.copies.len());
+        for (a, b) in &self.copies {
+            for index in [a.row, a.column, b.row, b.column] {
+                update_length(&mut hasher, index);
+            }
+        }
+        CircuitId(hasher.finalize().into())
+    }
+
+    /// Proves the current cells, committing to them row by row.
+    ///
+    /// # Parameters
+    /// - `context`: The precomputed parameters.
+    ///
+    /// # Returns
+    /// - `Proof`: The proof, whose witness holds the cells in row-major order.
+    pub fn prove(&self, context: &ProverContext) -> Proof {
+        let cells: Vec<FieldElement> = self.rows.iter().flatten().cloned().collect();
+        Proof {
+            witness: cells.iter().map(FieldElement::get_value).collect(),
+            public_inputs: Vec::new(),
+            commitment: context.poseidon().hash(DomainTag::COMMITMENT, &cells).get_value(),
+            outputs: Vec::new(),
+            circuit_id: self.circuit_id(),
+            version: CircuitVersion::default(),
+        }
+    }
+
+    /// Verifies a proof against the gates and copy constraints of the circuit,
+    /// which needs the cells, so only an in-memory proof can pass.
+    ///
+    /// # Parameters
+    /// - `context`: The precomputed parameters.
+    /// - `proof`: The proof to verify.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the proof was made for this circuit, its commitment
+    ///   matches and its cells pass every check, otherwise `false`.
+    pub fn verify(&self, context: &ProverContext, proof: &Proof) -> bool {
+        if proof.witness.len() != self.rows.len() * self.width {
+            return false; // The shape of the proof is public
+        }
+        let bound = proof.circuit_id.ct_eq(&self.circuit_id()) & ct::choice(proof.verify_commitment_with(context));
+        let rows: Vec<Vec<FieldElement>> = proof.witness.chunks(self.width.max(1))
+            .map(|row| row.iter().map(|value| FieldElement::new(value.clone())).collect())
+            .collect();
+        bool::from(bound & ct::choice(self.check(&rows).is_empty()))
+    }
+}
+
+/// Finds the largest column an expression reads, or 0 if it reads none.
+fn max_column(expression: &Expression) -> usize {
+    match expression {
+        Expression::Constant(_) => 0,
+        Expression::Cell { column, .. } => *column,
+        Expression::Sum(a, b) | Expression::Product(a, b) => max_column(a).max(max_column(b)),
+        Expression::Negated(a) => max_column(a),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use super::*;
+
+    /// Creates an element of the default field.
+    fn element(value: i64) -> FieldElement {
+        FieldElement::new(BigInt::from(value))
+    }
+
+    /// Builds a table computing `a * b + c` with a multiplication row and an
+    /// addition row, the product copied from the first into the second.
+    fn multiply_add(a: i64, b: i64, product: i64, c: i64, sum: i64) -> PlonkishCircuit {
+        let mut circuit = PlonkishCircuit::new(3);
+        let mul = circuit.add_gate("mul", Expression::cell(0) * Expression::cell(1) - Expression::cell(2));
+        let add = circuit.add_gate("add", Expression::cell(0) + Expression::cell(1) - Expression::cell(2));
+        circuit.add_row(&[element(a), element(b), element(product)], &[mul]);
+        circuit.add_row(&[element(product), element(c), element(sum)], &[add]);
+        circuit.copy(Cell { row: 0, column: 2 }, Cell { row: 1, column: 0 });
+        circuit
+    }
+
+    #[test]
+    fn satisfied_tables_pass_every_check_and_prove() {
+        let circuit = multiply_add(3, 4, 12, 5, 17);
+        assert!(circuit.check(circuit.rows()).is_empty());
+        let context = ProverContext::new();
+        assert!(circuit.verify(&context, &circuit.prove(&context)));
+    }
+
+    #[test]
+    fn violated_gates_are_reported_by_row() {
+        let circuit = multiply_add(3, 4, 12, 5, 18);
+        assert_eq!(circuit.check(circuit.rows()), vec![PlonkishFailure::Gate { gate: 1, row: 1, value: Some(element(-1)) }]);
+        let context = ProverContext::new();
+        assert!(!circuit.verify(&context, &circuit.prove(&context)));
+    }
+
+    #[test]
+    fn violated_copy_constraints_are_reported() {
+        // Both rows hold on their own, but the product is not carried over
+        let mut circuit = multiply_add(3, 4, 12, 5, 17);
+        let mut rows = circuit.rows().to_vec();
+        rows[1] = vec![element(13), element(5), element(18)];
+        assert_eq!(circuit.check(&rows), vec![PlonkishFailure::Copy(Cell { row: 0, column: 2 }, Cell { row: 1, column: 0 })]);
+
+        circuit.copy(Cell { row: 0, column: 0 }, Cell { row: 2, column: 0 });
+        assert_eq!(circuit.check(circuit.rows()).len(), 1);
+    }
+
+    #[test]
+    fn gates_reading_past_the_table_fail() {
+        let mut circuit = PlonkishCircuit::new(1);
+        let chain = circuit.add_gate("double", Expression::next(0) - Expression::cell(0) - Expression::cell(0));
+        circuit.add_row(&[element(1)], &[chain]);
+        circuit.add_row(&[element(2)], &[chain]);
+        assert_eq!(circuit.check(circuit.rows()), vec![PlonkishFailure::Gate { gate: chain, row: 1, value: None }]);
+    }
+
+    #[test]
+    fn proofs_with_tampered_cells_are_rejected() {
+        let circuit = multiply_add(3, 4, 12, 5, 17);
+        let context = ProverContext::new();
+        let mut proof = circuit.prove(&context);
+        proof.witness[5] = BigInt::from(18);
+        assert!(!circuit.verify(&context, &proof));
+    }
+}
@@ -0,0 +1,698 @@
+use alloc::format;
+use alloc::string::ToString;
+use alloc::{vec, vec::Vec};
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use sha2::{Digest, Sha256};
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+
+/// The default width of the permutation state (one capacity element, two rate elements).
+const DEFAULT_WIDTH: usize = 3;
+/// The security level in bits the number of rounds is chosen for.
+const SECURITY_LEVEL: usize = 128;
+/// The largest number of partial rounds searched for.
+const MAX_PARTIAL_ROUNDS: usize = 500;
+/// The largest number of full rounds searched for.
+const MAX_FULL_ROUNDS: usize = 100;
+
+/// Represents the Poseidon permutation over a prime field.
+///
+/// The first state element is the capacity, the remaining `width - 1`
+/// elements are the rate used by the sponge.
+#[derive(Clone, Debug)]
+pub struct Poseidon {
+    /// The modulus of the field the permutation works over.
+    pub modulus: BigInt,
+    /// The width of the permutation state.
+    pub width: usize,
+    /// The number of full rounds.
+    pub full_rounds: usize,
+    /// The number of partial rounds.
+    pub partial_rounds: usize,
+    /// The S-box exponent, the smallest `alpha >= 3` coprime with `modulus - 1`.
+    pub alpha: u64,
+    /// The round constants, one row of `width` elements per round.
+    pub round_constants: Vec<Vec<FieldElement>>,
+    /// The MDS matrix applied at the end of every round.
+    pub mds: Vec<Vec<FieldElement>>,
+}
+
+/// Represents the purpose a hash is computed for.
+///
+/// Every hash takes a tag, which sets the capacity element of the sponge
+/// together with the number of inputs, so the same inputs hashed for two
+/// purposes give unrelated outputs: a Merkle node can never be passed off as
+/// a leaf or as a commitment. Tagging this way costs neither a permutation
+/// nor a constraint. Protocols built on the crate define their own tags with
+/// `DomainTag::new`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DomainTag(&'static str);
+
+/// Represents a duplex sponge built on the Poseidon permutation.
+///
+/// Elements are absorbed into the rate part of the state and challenges are
+/// squeezed from it, permuting lazily whenever the rate is exhausted.
+#[derive(Clone, Debug)]
+pub struct PoseidonSponge {
+    /// The permutation used by the sponge.
+    poseidon: Poseidon,
+    /// The current state.
+    state: Vec<FieldElement>,
+    /// The next rate position to absorb into or squeeze from.
+    position: usize,
+    /// Whether the sponge is squeezing rather than absorbing.
+    squeezing: bool,
+}
+
+impl Default for Poseidon {
+    fn default() -> Self {
+        Poseidon::new()
+    }
+}
+
+impl DomainTag {
+    /// The tag of the hash turning a leaf into a node of a Merkle tree.
+    pub const MERKLE_LEAF: DomainTag = DomainTag::new("merkle leaf");
+    /// The tag of the hash of two children of a Merkle tree into their parent.
+    pub const MERKLE_NODE: DomainTag = DomainTag::new("merkle node");
+    /// The tag of commitments to witnesses and other committed values.
+    pub const COMMITMENT: DomainTag = DomainTag::new("commitment");
+    /// The tag of Fiat-Shamir challenges derived by hashing.
+    pub const CHALLENGE: DomainTag = DomainTag::new("challenge");
+
+    /// Creates a tag from a name, unique to its purpose.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the tag.
+    ///
+    /// # Returns
+    /// - `Self`: The tag.
+    pub const fn new(name: &'static str) -> Self {
+        DomainTag(name)
+    }
+
+    /// Gets the name of the tag.
+    ///
+    /// # Returns
+    /// - `&'static str`: The name.
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
+
+    /// Derives the initial capacity element of a hash from SHA-256 of the
+    /// tag and the number of inputs.
+    ///
+    /// # Parameters
+    /// - `modulus`: The modulus of the field of the hash.
+    /// - `length`: The number of hashed elements.
+    ///
+    /// # Returns
+    /// - `BigInt`: The capacity element, reduced into the field.
+    pub fn capacity(&self, modulus: &BigInt, length: usize) -> BigInt {
+        let mut hasher = Sha256::new();
+        hasher.update(b"domain");
+        hasher.update((self.0.len() as u64).to_be_bytes());
+        hasher.update(self.0.as_bytes());
+        hasher.update((length as u64).to_be_bytes());
+        BigInt::from_bytes_be(Sign::Plus, &hasher.finalize()) % modulus
+    }
+}
+
+impl Poseidon {
+    /// Creates a new Poseidon instance with width 3 for the crate's default field.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Poseidon` struct.
+    pub fn new() -> Self {
+        let modulus = FieldElement::new(BigInt::zero()).get_modulus().clone();
+        Poseidon::generate(&modulus, DEFAULT_WIDTH).expect("The default field supports Poseidon")
+    }
+
+    /// Generates Poseidon parameters for an arbitrary prime field and width.
+    ///
+    /// The number of rounds follows from the size of the prime, the width and
+    /// the S-box exponent, as in `round_numbers`. Round constants are derived
+    /// from SHA-256 over the modulus, width and position so they are
+    /// reproducible, and the MDS matrix is the Cauchy matrix
+    /// `M[i][j] = 1 / (i + width + j)`.
+    ///
+    /// # Parameters
+    /// - `modulus`: The prime modulus of the field.
+    /// - `width`: The width of the permutation state, at least 2.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: A new instance of the `Poseidon` struct, or an error if the width is
+    ///   below 2 or the modulus does not exceed `3 * width - 2`, so that an entry of the MDS
+    ///   matrix would divide by zero.
+    pub fn generate(modulus: &BigInt, width: usize) -> Result<Self> {
+        if width < 2 {
+            return Err(Error::Input("Poseidon needs at least one capacity and one rate element".to_string()));
+        }
+        // The denominators of the MDS matrix run from `width` to `3 * width - 2`
+        if *modulus <= BigInt::from(3 * width - 2) {
+            return Err(Error::Input(format!("Poseidon of width {} needs a modulus above {}, got {}", width, 3 * width - 2, modulus)));
+        }
+        let alpha = Self::select_alpha(modulus);
+        let (full_rounds, partial_rounds) = Self::round_numbers(modulus, width, alpha)
+            .ok_or_else(|| Error::Input(format!("No number of rounds reaches {}-bit security", SECURITY_LEVEL)))?;
+        let rounds = full_rounds + partial_rounds;
+
+        let round_constants = (0..rounds)
+            .map(|round| (0..width).map(|i| Self::round_constant(modulus, width, round, i)).collect())
+            .collect();
+
+        let mds = (0..width)
+            .map(|i| {
+                (0..width)
+                    .map(|j| FieldElement::new_with_modulus(BigInt::from(i + width + j), modulus.clone()).inv())
+                    .collect()
+            })
+            .collect();
+
+        Ok(Poseidon {
+            modulus: modulus.clone(),
+            width,
+            full_rounds,
+            partial_rounds,
+            alpha,
+            round_constants,
+            mds,
+        })
+    }
+
+    /// Generates the parameters of the reference implementation of Poseidon,
+    /// `generate_parameters_grain.sage` by Grassi et al., which draws the round
+    /// constants and then the Cauchy MDS matrix from a Grain LFSR seeded with
+    /// the field, the S-box, the width and the numbers of rounds.
+    ///
+    /// Unlike `generate`, this takes the numbers of rounds as given, so it can
+    /// reproduce published instances and their test vectors, such as the
+    /// BN254 instance of width 3 with 8 full and 57 partial rounds used by
+    /// circomlib. The reference script resamples the MDS matrix when it fails
+    /// its subspace trail checks; this keeps the first sample, as the reference
+    /// does for the published instances.
+    ///
+    /// # Parameters
+    /// - `modulus`: The prime modulus of the field, below `2^4096`.
+    /// - `width`: The width of the permutation state, at least 2.
+    /// - `full_rounds`: The number of full rounds, even.
+    /// - `partial_rounds`: The number of partial rounds.
+    /// - `alpha`: The S-box exponent, coprime with `modulus - 1`.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: A new instance of the `Poseidon` struct, or an error if a
+    ///   parameter is out of range or the S-box is not a permutation of the field.
+    pub fn reference(modulus: &BigInt, width: usize, full_rounds: usize, partial_rounds: usize, alpha: u64) -> Result<Self> {
+        let bits = modulus.bits() as usize;
+        // The parameters are seeded into fields of 12, 12, 10 and 10 bits
+        if !(2..1 << 12).contains(&width) || bits >= 1 << 12 || !full_rounds.is_multiple_of(2) || full_rounds >= 1 << 10 || partial_rounds >= 1 << 10 {
+            return Err(Error::Input(format!(
+                "No reference Poseidon parameters for a {}-bit field, width {}, {} full and {} partial rounds",
+                bits, width, full_rounds, partial_rounds
+            )));
+        }
+        if !BigInt::from(alpha).gcd(&(modulus - BigInt::one())).is_one() || alpha < 3 {
+            return Err(Error::Input(format!("x^{} is not a permutation of the field", alpha)));
+        }
+
+        let mut grain = Grain::new(bits, width, full_rounds, partial_rounds);
+        let round_constants = (0..full_rounds + partial_rounds)
+            .map(|_| (0..width).map(|_| FieldElement::new_with_modulus(grain.sample_below(bits, modulus), modulus.clone())).collect())
+            .collect();
+        let mds = loop {
+            let samples: Vec<BigInt> = (0..2 * width).map(|_| grain.sample(bits) % modulus).collect();
+            if samples.iter().enumerate().any(|(i, sample)| samples[..i].contains(sample)) {
+                continue;
+            }
+            let (xs, ys) = samples.split_at(width);
+            let sums: Vec<Vec<BigInt>> = xs.iter().map(|x| ys.iter().map(|y| (x + y) % modulus).collect()).collect();
+            if sums.iter().flatten().all(|sum| !sum.is_zero()) {
+                break sums.into_iter()
+                    .map(|row| row.into_iter().map(|sum| FieldElement::new_with_modulus(sum, modulus.clone()).inv()).collect())
+                    .collect();
+            }
+        };
+
+        Ok(Poseidon {
+            modulus: modulus.clone(),
+            width,
+            full_rounds,
+            partial_rounds,
+            alpha,
+            round_constants,
+            mds,
+        })
+    }
+
+    /// Checks whether a round applies the S-box to the whole state.
+    ///
+    /// # Parameters
+    /// - `round`: The index of the round.
+    ///
+    /// # Returns
+    /// - `bool`: `true` for full rounds, `false` for partial rounds.
+    pub fn is_full_round(&self, round: usize) -> bool {
+        let half = self.full_rounds / 2;
+        round < half || round >= half + self.partial_rounds
+    }
+
+    /// Applies the Poseidon permutation to a state in place.
+    ///
+    /// # Parameters
+    /// - `state`: The state to permute, of length `width`.
+    pub fn permute(&self, state: &mut [FieldElement]) {
+        assert_eq!(state.len(), self.width, "State length must match the permutation width");
+        let exponent = BigInt::from(self.alpha);
+
+        for round in 0..self.full_rounds + self.partial_rounds {
+            // Add round constants
+            for (element, constant) in state.iter_mut().zip(&self.round_constants[round]) {
+                *element = element.add(constant);
+            }
+
+            // Apply the S-box
+            if self.is_full_round(round) {
+                for element in state.iter_mut() {
+                    *element = element.pow(&exponent);
+                }
+            } else {
+                state[0] = state[0].pow(&exponent);
+            }
+
+            // Mix the state with the MDS matrix
+            let mixed: Vec<FieldElement> = self.mds.iter().map(|row| {
+                row.iter().zip(state.iter()).fold(self.element(BigInt::zero()), |acc, (m, s)| acc.add(&m.mul(s)))
+            }).collect();
+            state.clone_from_slice(&mixed);
+        }
+    }
+
+    /// Hashes a sequence of field elements into one.
+    ///
+    /// The capacity element is derived from the tag and the number of
+    /// inputs, so neither hashes for other purposes nor inputs of different
+    /// lengths share a sponge state.
+    ///
+    /// # Parameters
+    /// - `tag`: The purpose of the hash.
+    /// - `inputs`: The elements to hash.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The hash of the inputs.
+    pub fn hash(&self, tag: DomainTag, inputs: &[FieldElement]) -> FieldElement {
+        let mut sponge = PoseidonSponge::with_capacity(self.clone(), self.element(tag.capacity(&self.modulus, inputs.len())));
+        for input in inputs {
+            sponge.absorb(input);
+        }
+        sponge.squeeze()
+    }
+
+    /// Hashes two field elements into one.
+    ///
+    /// # Parameters
+    /// - `tag`: The purpose of the hash.
+    /// - `left`: The left input.
+    /// - `right`: The right input.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The hash of the two inputs.
+    pub fn hash_two(&self, tag: DomainTag, left: &FieldElement, right: &FieldElement) -> FieldElement {
+        self.hash(tag, &[left.clone(), right.clone()])
+    }
+
+    /// Creates an element of the permutation's field.
+    ///
+    /// # Parameters
+    /// - `value`: The value of the element.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The field element.
+    pub fn element(&self, value: BigInt) -> FieldElement {
+        FieldElement::new_with_modulus(value, self.modulus.clone())
+    }
+
+    /// Derives a round constant from SHA-256 so the parameters are reproducible.
+    ///
+    /// # Parameters
+    /// - `modulus`: The modulus of the field.
+    /// - `width`: The width of the permutation state.
+    /// - `round`: The index of the round.
+    /// - `position`: The position in the state.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The round constant.
+    fn round_constant(modulus: &BigInt, width: usize, round: usize, position: usize) -> FieldElement {
+        let mut hasher = Sha256::new();
+        hasher.update(b"poseidon");
+        hasher.update(modulus.to_bytes_be().1);
+        hasher.update((width as u64).to_be_bytes());
+        hasher.update((round as u64).to_be_bytes());
+        hasher.update((position as u64).to_be_bytes());
+        FieldElement::new_with_modulus(BigInt::from_bytes_be(Sign::Plus, &hasher.finalize()), modulus.clone())
+    }
+
+    /// Chooses the numbers of full and partial rounds with the fewest S-boxes that
+    /// satisfy the security inequalities of the Poseidon paper at `SECURITY_LEVEL`:
+    /// the statistical, interpolation and Gröbner basis bounds, and the bound of
+    /// ePrint 2023/537 on the algebraic attack of Bariant et al. As the reference
+    /// script does, two full rounds and 7.5% of the partial rounds are then added
+    /// as a security margin.
+    ///
+    /// # Parameters
+    /// - `modulus`: The prime modulus of the field.
+    /// - `width`: The width of the permutation state.
+    /// - `alpha`: The S-box exponent.
+    ///
+    /// # Returns
+    /// - `Option<(usize, usize)>`: The full and partial rounds, or `None` if no number of rounds within
+    ///   the searched range reaches the security level.
+    fn round_numbers(modulus: &BigInt, width: usize, alpha: u64) -> Option<(usize, usize)> {
+        let (t, security) = (width as i64, SECURITY_LEVEL as i64);
+        let bits = modulus.bits() as i64;
+        let bound = BigInt::one() << SECURITY_LEVEL;
+        let base = BigInt::from(alpha);
+        // The smallest `k` with `alpha^(step * k) >= target`
+        let log = |step: u32, target: &BigInt| {
+            let factor = base.pow(step);
+            let (mut power, mut k) = (BigInt::one(), 0i64);
+            while power < *target {
+                power *= &factor;
+                k += 1;
+            }
+            k
+        };
+
+        // The bounds on the full rounds that do not depend on the partial rounds
+        let statistical = if security <= (bits - 1 - (alpha as i64 - 1) / 2) * (t + 1) { 6 } else { 10 };
+        let interpolation = 1 + log(1, &(BigInt::one() << (SECURITY_LEVEL.min(bits as usize)))) + log(1, &BigInt::from(width));
+        let groebner_1 = log(1, core::cmp::min(&bound, modulus));
+        let groebner_2 = t - 1 + log(width as u32 + 1, &bound).min(log(2, modulus));
+        let groebner_3 = log(2, &bound) + t - 2;
+
+        let mut best: Option<(usize, usize)> = None;
+        let cost = |(full, partial): (usize, usize)| width * full + partial;
+        for partial in 1..MAX_PARTIAL_ROUNDS {
+            let with_margin = (43 * partial).div_ceil(40);
+            // More partial rounds can only cost more once the fewest full rounds are needed
+            if best.is_some_and(|best| cost((6, with_margin)) > cost(best)) {
+                break;
+            }
+            let rp = partial as i64;
+            let numerator = groebner_3 - rp;
+            let groebner_3 = if numerator > 0 { (numerator + t - 2) / (t - 1) } else { numerator / (t - 1) };
+            let least = statistical.max(interpolation - rp).max(groebner_1 - rp).max(groebner_2 - rp).max(groebner_3).max(4) as usize;
+            let full = (least.next_multiple_of(2)..MAX_FULL_ROUNDS).step_by(2)
+                .find(|&full| Self::resists_algebraic_attacks(width, full, partial, alpha));
+            if let Some(full) = full {
+                let candidate = (full + 2, with_margin);
+                if best.is_none_or(|best| cost(candidate) < cost(best) || (cost(candidate) == cost(best) && candidate.0 < best.0)) {
+                    best = Some(candidate);
+                }
+            }
+        }
+        best
+    }
+
+    /// Checks the bound of ePrint 2023/537, which requires the binomial coefficient
+    /// `C(over, under)` of the attack to have at least `SECURITY_LEVEL / 2` bits.
+    ///
+    /// # Parameters
+    /// - `width`: The width of the permutation state.
+    /// - `full`: The number of full rounds, even.
+    /// - `partial`: The number of partial rounds.
+    /// - `alpha`: The S-box exponent.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the attack costs at least `2^SECURITY_LEVEL`, otherwise `false`.
+    fn resists_algebraic_attacks(width: usize, full: usize, partial: usize, alpha: u64) -> bool {
+        let skipped = (width / 3) as u128;
+        let (full, partial, alpha) = (full as u128, partial as u128, alpha as u128);
+        let over = (full - 1) * width as u128 + partial + skipped + skipped * (full / 2) + partial + alpha;
+        let under = skipped * (full / 2) + partial + alpha;
+        // Each partial product is the binomial coefficient `C(over - k + i, i)`, so it stays
+        // exact; stop once it is large enough, before it can overflow
+        let k = under.min(over - under);
+        let cap = 1u128 << (SECURITY_LEVEL / 2 + 1);
+        let mut binomial = 1u128;
+        for i in 1..=k {
+            binomial = binomial * (over - k + i) / i;
+            if binomial >= cap {
+                return true;
+            }
+        }
+        // The attack costs `binomial^2`, rounded up to a power of two
+        binomial.checked_mul(binomial).is_none_or(|square| square > 1u128 << (SECURITY_LEVEL - 1))
+    }
+
+    /// Selects the smallest S-box exponent that is a permutation of the field.
+    ///
+    /// # Parameters
+    /// - `modulus`: The field modulus.
+    ///
+    /// # Returns
+    /// - `u64`: The S-box exponent.
+    fn select_alpha(modulus: &BigInt) -> u64 {
+        let order = modulus - BigInt::one();
+        (3u64..)
+            .find(|alpha| BigInt::from(*alpha).gcd(&order).is_one())
+            .expect("An S-box exponent always exists")
+    }
+}
+
+/// Represents the self-shrinking Grain LFSR of the reference implementation,
+/// an 80-bit register of which only every other bit is kept.
+struct Grain {
+    /// The register, read from `head` onwards.
+    bits: [bool; 80],
+    /// The position of the oldest bit of the register.
+    head: usize,
+}
+
+impl Grain {
+    /// Seeds the register with the parameters of an instance over a prime
+    /// field with an `x^alpha` S-box, then discards its first 160 bits.
+    fn new(bits: usize, width: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        let mut grain = Grain { bits: [true; 80], head: 0 };
+        let fields = [(1, 2), (0, 4), (bits, 12), (width, 12), (full_rounds, 10), (partial_rounds, 10)];
+        let seed = fields.iter().flat_map(|&(value, length)| (0..length).rev().map(move |bit| (value >> bit) & 1 == 1));
+        for (slot, bit) in grain.bits.iter_mut().zip(seed) {
+            *slot = bit;
+        }
+        for _ in 0..160 {
+            grain.step();
+        }
+        grain
+    }
+
+    /// Shifts the register by one bit and returns the new bit.
+    fn step(&mut self) -> bool {
+        let tap = |offset: usize| self.bits[(self.head + offset) % 80];
+        let bit = tap(62) ^ tap(51) ^ tap(38) ^ tap(23) ^ tap(13) ^ tap(0);
+        self.bits[self.head] = bit;
+        self.head = (self.head + 1) % 80;
+        bit
+    }
+
+    /// Returns the second bit of the first pair of bits starting with one.
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let (keep, bit) = (self.step(), self.step());
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// Reads an integer of `bits` bits, most significant first.
+    fn sample(&mut self, bits: usize) -> BigInt {
+        (0..bits).fold(BigInt::zero(), |value, _| (value << 1) + u8::from(self.next_bit()))
+    }
+
+    /// Reads integers of `bits` bits until one is below the modulus.
+    fn sample_below(&mut self, bits: usize, modulus: &BigInt) -> BigInt {
+        loop {
+            let value = self.sample(bits);
+            if value < *modulus {
+                return value;
+            }
+        }
+    }
+}
+
+impl PoseidonSponge {
+    /// Creates a new sponge with a zero capacity element.
+    ///
+    /// # Parameters
+    /// - `poseidon`: The permutation used by the sponge.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `PoseidonSponge` struct.
+    pub fn new(poseidon: Poseidon) -> Self {
+        let capacity = poseidon.element(BigInt::zero());
+        PoseidonSponge::with_capacity(poseidon, capacity)
+    }
+
+    /// Creates a new sponge with a given initial capacity element.
+    ///
+    /// # Parameters
+    /// - `poseidon`: The permutation used by the sponge.
+    /// - `capacity`: The initial value of the capacity element.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `PoseidonSponge` struct.
+    pub fn with_capacity(poseidon: Poseidon, capacity: FieldElement) -> Self {
+        let mut state = vec![poseidon.element(BigInt::zero()); poseidon.width];
+        state[0] = capacity;
+        PoseidonSponge {
+            poseidon,
+            state,
+            position: 0,
+            squeezing: false,
+        }
+    }
+
+    /// Absorbs a field element into the sponge.
+    ///
+    /// # Parameters
+    /// - `element`: The element to absorb.
+    pub fn absorb(&mut self, element: &FieldElement) {
+        if self.squeezing || self.position == self.rate() {
+            self.poseidon.permute(&mut self.state);
+            self.position = 0;
+            self.squeezing = false;
+        }
+        self.state[1 + self.position] = self.state[1 + self.position].add(element);
+        self.position += 1;
+    }
+
+    /// Squeezes a field element out of the sponge.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The squeezed element.
+    pub fn squeeze(&mut self) -> FieldElement {
+        if !self.squeezing || self.position == self.rate() {
+            self.poseidon.permute(&mut self.state);
+            self.position = 0;
+            self.squeezing = true;
+        }
+        let output = self.state[1 + self.position].clone();
+        self.position += 1;
+        output
+    }
+
+    /// Gets the number of rate elements of the sponge.
+    ///
+    /// # Returns
+    /// - `usize`: The rate of the sponge.
+    fn rate(&self) -> usize {
+        self.poseidon.width - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs() -> Vec<FieldElement> {
+        vec![FieldElement::new(BigInt::from(1)), FieldElement::new(BigInt::from(2))]
+    }
+
+    #[test]
+    fn hashes_are_deterministic_per_tag() {
+        let poseidon = Poseidon::new();
+        assert_eq!(poseidon.hash(DomainTag::COMMITMENT, &inputs()), poseidon.hash(DomainTag::COMMITMENT, &inputs()));
+        assert_eq!(poseidon.hash_two(DomainTag::MERKLE_NODE, &inputs()[0], &inputs()[1]), poseidon.hash(DomainTag::MERKLE_NODE, &inputs()));
+    }
+
+    #[test]
+    fn different_tags_give_different_hashes() {
+        let poseidon = Poseidon::new();
+        let tags = [DomainTag::MERKLE_LEAF, DomainTag::MERKLE_NODE, DomainTag::COMMITMENT, DomainTag::CHALLENGE, DomainTag::new("custom")];
+        let hashes: Vec<FieldElement> = tags.iter().map(|tag| poseidon.hash(*tag, &inputs())).collect();
+        for (i, first) in hashes.iter().enumerate() {
+            assert!(hashes[i + 1..].iter().all(|second| first != second), "{} collides", tags[i].name());
+        }
+    }
+
+    #[test]
+    fn capacities_depend_on_the_tag_and_length() {
+        let modulus = FieldElement::default_modulus();
+        let capacity = DomainTag::COMMITMENT.capacity(&modulus, 2);
+        assert!(capacity < modulus);
+        assert_ne!(capacity, DomainTag::COMMITMENT.capacity(&modulus, 3));
+        assert_ne!(capacity, DomainTag::CHALLENGE.capacity(&modulus, 2));
+    }
+
+    #[test]
+    fn large_primes_get_the_reference_round_numbers() {
+        // The reference script gives these for BN254 with x^5, as used by Poseidon2
+        let modulus = crate::config::Field::Bn254.modulus();
+        for (width, partial_rounds) in [(3, 56), (5, 56), (8, 57), (16, 57)] {
+            let poseidon = Poseidon::generate(&modulus, width).unwrap();
+            assert_eq!((poseidon.alpha, poseidon.full_rounds, poseidon.partial_rounds), (5, 8, partial_rounds));
+        }
+    }
+
+    #[test]
+    fn small_primes_get_more_rounds() {
+        // A 30-bit prime cannot make 128-bit statistical attacks fail in 6 full rounds
+        let poseidon = Poseidon::new();
+        assert_eq!((poseidon.alpha, poseidon.full_rounds, poseidon.partial_rounds), (3, 12, 24));
+        assert_eq!(poseidon.round_constants.len(), 36);
+
+        let tiny = Poseidon::generate(&BigInt::from(11), 3).unwrap();
+        assert_eq!((tiny.full_rounds, tiny.partial_rounds), (12, 24));
+        let mut state = vec![tiny.element(BigInt::from(1)), tiny.element(BigInt::from(2)), tiny.element(BigInt::from(3))];
+        tiny.permute(&mut state);
+    }
+
+    #[test]
+    fn primes_too_small_for_the_mds_matrix_are_rejected() {
+        // With width 3 the denominators of the Cauchy matrix run from 3 to 7
+        for modulus in [2, 3, 5, 7] {
+            assert!(matches!(Poseidon::generate(&BigInt::from(modulus), 3), Err(Error::Input(_))));
+        }
+        assert!(Poseidon::generate(&BigInt::from(11), 3).is_ok());
+        assert!(Poseidon::generate(&BigInt::from(11), 5).is_err());
+        assert!(Poseidon::generate(&FieldElement::default_modulus(), 1).is_err());
+    }
+
+    #[test]
+    fn reference_parameters_match_the_reference_implementation() {
+        // poseidonperm_x5_254_3 of the reference implementation, also circomlib's Poseidon of two inputs
+        let modulus = crate::config::Field::Bn254.modulus();
+        let poseidon = Poseidon::reference(&modulus, 3, 8, 57, 5).unwrap();
+        let hex = |digits: &str| poseidon.element(BigInt::parse_bytes(digits.as_bytes(), 16).unwrap());
+        assert_eq!(poseidon.round_constants[0][0], hex("0ee9a592ba9a9518d05986d656f40c2114c4993c11bb29938d21d47304cd8e6e"));
+        assert_eq!(poseidon.mds[0][0], hex("109b7f411ba0e4c9b2b70caf5c36a7b194be7c11ad24378bfedb68592ba8118b"));
+
+        let mut state: Vec<FieldElement> = (0..3).map(|i| poseidon.element(BigInt::from(i))).collect();
+        poseidon.permute(&mut state);
+        assert_eq!(state, vec![
+            hex("115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a"),
+            hex("0fca49b798923ab0239de1c9e7a4a9a2210312b6a2f616d18b5a87f9b628ae29"),
+            hex("0e7ae82e40091e63cbd4f16a6d16310b3729d4b6e138fcf54110e2867045a30c"),
+        ]);
+    }
+
+    #[test]
+    fn reference_parameters_out_of_range_are_rejected() {
+        let modulus = crate::config::Field::Bn254.modulus();
+        assert!(Poseidon::reference(&modulus, 3, 7, 57, 5).is_err());
+        assert!(Poseidon::reference(&modulus, 1, 8, 57, 5).is_err());
+        assert!(Poseidon::reference(&modulus, 3, 8, 1 << 10, 5).is_err());
+        // x^3 is not a permutation of the BN254 scalar field
+        assert!(Poseidon::reference(&modulus, 3, 8, 57, 3).is_err());
+    }
+
+    #[test]
+    fn padding_with_zeros_changes_the_hash() {
+        // The input length is part of the capacity, so a trailing zero is not absorbed silently
+        let poseidon = Poseidon::new();
+        let mut padded = inputs();
+        padded.push(FieldElement::new(BigInt::zero()));
+        assert_ne!(poseidon.hash(DomainTag::COMMITMENT, &inputs()), poseidon.hash(DomainTag::COMMITMENT, &padded));
+    }
+}
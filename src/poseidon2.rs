@@ -0,0 +1,231 @@
+use alloc::{vec, vec::Vec};
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use sha2::{Digest, Sha256};
+use crate::field::FieldElement;
+use crate::poseidon::DomainTag;
+
+/// The default width of the permutation state (one capacity element, two rate elements).
+const DEFAULT_WIDTH: usize = 3;
+/// The number of external (full) rounds, split evenly before and after the internal rounds.
+const EXTERNAL_ROUNDS: usize = 8;
+/// The number of internal (partial) rounds.
+const INTERNAL_ROUNDS: usize = 56;
+
+/// Represents the Poseidon2 permutation over a prime field.
+///
+/// Poseidon2 replaces the dense MDS matrix of Poseidon with cheap external
+/// and internal matrices and adds a single round constant per internal
+/// round, which roughly halves the linear work per round. Widths 2 and 3
+/// are supported, with the matrices from the Poseidon2 paper.
+#[derive(Clone, Debug)]
+pub struct Poseidon2 {
+    /// The modulus of the field the permutation works over.
+    pub modulus: BigInt,
+    /// The width of the permutation state.
+    pub width: usize,
+    /// The number of external rounds.
+    pub external_rounds: usize,
+    /// The number of internal rounds.
+    pub internal_rounds: usize,
+    /// The S-box exponent, the smallest `alpha >= 3` coprime with `modulus - 1`.
+    pub alpha: u64,
+    /// The round constants of the external rounds, `width` elements per round.
+    pub external_constants: Vec<Vec<FieldElement>>,
+    /// The round constants of the internal rounds, added to the first element.
+    pub internal_constants: Vec<FieldElement>,
+    /// The diagonal `d` of the internal matrix `J + diag(d)`, with `J` the all-ones matrix.
+    pub internal_diagonal: Vec<FieldElement>,
+}
+
+impl Default for Poseidon2 {
+    fn default() -> Self {
+        Poseidon2::new()
+    }
+}
+
+impl Poseidon2 {
+    /// Creates a new Poseidon2 instance with width 3 for the crate's default field.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Poseidon2` struct.
+    pub fn new() -> Self {
+        let modulus = FieldElement::new(BigInt::zero()).get_modulus().clone();
+        Poseidon2::generate(&modulus, DEFAULT_WIDTH)
+    }
+
+    /// Generates Poseidon2 parameters for an arbitrary prime field.
+    ///
+    /// # Parameters
+    /// - `modulus`: The prime modulus of the field.
+    /// - `width`: The width of the permutation state, 2 or 3.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Poseidon2` struct.
+    pub fn generate(modulus: &BigInt, width: usize) -> Self {
+        assert!(width == 2 || width == 3, "Poseidon2 supports widths 2 and 3");
+        let element = |value: BigInt| FieldElement::new_with_modulus(value, modulus.clone());
+
+        let external_constants = (0..EXTERNAL_ROUNDS)
+            .map(|round| (0..width).map(|i| Self::round_constant(modulus, width, b"external", round, i)).collect())
+            .collect();
+        let internal_constants = (0..INTERNAL_ROUNDS)
+            .map(|round| Self::round_constant(modulus, width, b"internal", round, 0))
+            .collect();
+
+        // M_I = J + diag(1, ..., 1, 2)
+        let internal_diagonal = (0..width)
+            .map(|i| element(BigInt::from(if i + 1 == width { 2 } else { 1 })))
+            .collect();
+
+        let order = modulus - BigInt::one();
+        let alpha = (3u64..)
+            .find(|alpha| BigInt::from(*alpha).gcd(&order).is_one())
+            .expect("An S-box exponent always exists");
+
+        Poseidon2 {
+            modulus: modulus.clone(),
+            width,
+            external_rounds: EXTERNAL_ROUNDS,
+            internal_rounds: INTERNAL_ROUNDS,
+            alpha,
+            external_constants,
+            internal_constants,
+            internal_diagonal,
+        }
+    }
+
+    /// Applies the Poseidon2 permutation to a state in place.
+    ///
+    /// # Parameters
+    /// - `state`: The state to permute, of length `width`.
+    pub fn permute(&self, state: &mut [FieldElement]) {
+        assert_eq!(state.len(), self.width, "State length must match the permutation width");
+        let exponent = BigInt::from(self.alpha);
+        let half = self.external_rounds / 2;
+
+        self.external_matrix(state);
+
+        for round in 0..half {
+            self.external_round(state, round, &exponent);
+        }
+
+        for round in 0..self.internal_rounds {
+            state[0] = state[0].add(&self.internal_constants[round]).pow(&exponent);
+            self.internal_matrix(state);
+        }
+
+        for round in half..self.external_rounds {
+            self.external_round(state, round, &exponent);
+        }
+    }
+
+    /// Hashes a sequence of field elements into one, with the same sponge
+    /// construction and tagged capacity as `Poseidon::hash`.
+    ///
+    /// # Parameters
+    /// - `tag`: The purpose of the hash.
+    /// - `inputs`: The elements to hash.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The hash of the inputs.
+    pub fn hash(&self, tag: DomainTag, inputs: &[FieldElement]) -> FieldElement {
+        let rate = self.width - 1;
+        let mut state = vec![self.element(BigInt::zero()); self.width];
+        state[0] = self.element(tag.capacity(&self.modulus, inputs.len()));
+
+        for (chunk_index, chunk) in inputs.chunks(rate).enumerate() {
+            if chunk_index > 0 {
+                self.permute(&mut state);
+            }
+            for (position, input) in chunk.iter().enumerate() {
+                state[1 + position] = state[1 + position].add(input);
+            }
+        }
+
+        self.permute(&mut state);
+        state[1].clone()
+    }
+
+    /// Hashes two field elements into one.
+    ///
+    /// # Parameters
+    /// - `tag`: The purpose of the hash.
+    /// - `left`: The left input.
+    /// - `right`: The right input.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The hash of the two inputs.
+    pub fn hash_two(&self, tag: DomainTag, left: &FieldElement, right: &FieldElement) -> FieldElement {
+        self.hash(tag, &[left.clone(), right.clone()])
+    }
+
+    /// Creates an element of the permutation's field.
+    ///
+    /// # Parameters
+    /// - `value`: The value of the element.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The field element.
+    pub fn element(&self, value: BigInt) -> FieldElement {
+        FieldElement::new_with_modulus(value, self.modulus.clone())
+    }
+
+    /// Applies one external round: constants, full S-box layer and external matrix.
+    ///
+    /// # Parameters
+    /// - `state`: The state to update.
+    /// - `round`: The index of the external round.
+    /// - `exponent`: The S-box exponent.
+    fn external_round(&self, state: &mut [FieldElement], round: usize, exponent: &BigInt) {
+        for (element, constant) in state.iter_mut().zip(&self.external_constants[round]) {
+            *element = element.add(constant).pow(exponent);
+        }
+        self.external_matrix(state);
+    }
+
+    /// Multiplies the state by the external matrix `J + I`: every element gains the sum of the state.
+    ///
+    /// # Parameters
+    /// - `state`: The state to update.
+    fn external_matrix(&self, state: &mut [FieldElement]) {
+        let sum = state.iter().fold(self.element(BigInt::zero()), |acc, s| acc.add(s));
+        for element in state.iter_mut() {
+            *element = element.add(&sum);
+        }
+    }
+
+    /// Multiplies the state by the internal matrix `J + diag(d)`.
+    ///
+    /// # Parameters
+    /// - `state`: The state to update.
+    fn internal_matrix(&self, state: &mut [FieldElement]) {
+        let sum = state.iter().fold(self.element(BigInt::zero()), |acc, s| acc.add(s));
+        for (element, diagonal) in state.iter_mut().zip(&self.internal_diagonal) {
+            *element = sum.add(&element.mul(diagonal));
+        }
+    }
+
+    /// Derives a round constant from SHA-256 so the parameters are reproducible.
+    ///
+    /// # Parameters
+    /// - `modulus`: The modulus of the field.
+    /// - `width`: The width of the permutation state.
+    /// - `kind`: The kind of round, `external` or `internal`.
+    /// - `round`: The index of the round.
+    /// - `position`: The position in the state.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The round constant.
+    fn round_constant(modulus: &BigInt, width: usize, kind: &[u8], round: usize, position: usize) -> FieldElement {
+        let mut hasher = Sha256::new();
+        hasher.update(b"poseidon2");
+        hasher.update(kind);
+        hasher.update(modulus.to_bytes_be().1);
+        hasher.update((width as u64).to_be_bytes());
+        hasher.update((round as u64).to_be_bytes());
+        hasher.update((position as u64).to_be_bytes());
+        FieldElement::new_with_modulus(BigInt::from_bytes_be(Sign::Plus, &hasher.finalize()), modulus.clone())
+    }
+}
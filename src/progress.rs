@@ -0,0 +1,157 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use num_bigint::BigInt;
+use crate::compiler::CompiledCircuit;
+use crate::context::ProverContext;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::poseidon::{DomainTag, PoseidonSponge};
+use crate::proof::Proof;
+use crate::trace::trace_phase;
+
+/// Represents a phase of proving.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Evaluating the gates to assign every wire.
+    Witness,
+    /// Building the R1CS and QAP from the gates.
+    Constraints,
+    /// Hashing the witness into the proof commitment.
+    Commitment,
+}
+
+/// Represents how far proving has progressed within a phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// The current phase.
+    pub phase: Phase,
+    /// The number of steps of the phase completed so far.
+    pub done: usize,
+    /// The total number of steps of the phase.
+    pub total: usize,
+}
+
+impl Progress {
+    /// Gets the completed fraction of the phase.
+    ///
+    /// # Returns
+    /// - `f64`: The fraction, from 0 to 1.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 { 1.0 } else { self.done as f64 / self.total as f64 }
+    }
+}
+
+/// Lets another thread cancel a proving job, a setup or a Merkle build; clones
+/// share the same flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    /// Whether cancellation was requested.
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `CancellationToken` struct.
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Requests cancellation of the jobs observing this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Checks whether cancellation was requested.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if `cancel` was called on this token or a clone.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns `Error::Cancelled` if cancellation was requested, for long-running
+    /// operations to call at each phase or chunk boundary.
+    ///
+    /// # Returns
+    /// - `Result<()>`: `Ok` if the job may continue, otherwise `Error::Cancelled`.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() { Err(Error::Cancelled) } else { Ok(()) }
+    }
+}
+
+/// Forwards progress to a callback at most once per percent and checks for cancellation.
+struct Reporter<'a, F: FnMut(Progress)> {
+    on_progress: F,
+    cancel: &'a CancellationToken,
+    last: Option<(Phase, usize)>,
+}
+
+impl<F: FnMut(Progress)> Reporter<'_, F> {
+    /// Reports a step of a phase, or returns `Error::Cancelled` if cancellation was requested.
+    fn report(&mut self, phase: Phase, done: usize, total: usize) -> Result<()> {
+        self.cancel.check()?;
+        let percent = (done * 100).checked_div(total).unwrap_or(100);
+        if self.last != Some((phase, percent)) {
+            self.last = Some((phase, percent));
+            (self.on_progress)(Progress { phase, done, total });
+        }
+        Ok(())
+    }
+}
+
+/// Proves a compiled circuit, reporting the progress of each phase and
+/// stopping early once the token is cancelled.
+///
+/// The callback runs on the proving thread at most once per percent of each
+/// phase, so it can forward updates to a progress bar; run this on a worker
+/// thread and keep a clone of the token to abort from elsewhere. The proof is
+/// the same as the one of `Circuit::prove`.
+///
+/// # Parameters
+/// - `compiled`: The compiled circuit.
+/// - `inputs`: The values of the input wires, in declaration order.
+/// - `context`: The precomputed parameters.
+/// - `on_progress`: Called with the progress of the current phase.
+/// - `cancel`: The token checked between steps.
+///
+/// # Returns
+/// - `Result<Proof>`: The proof, `Error::Cancelled` if the token was cancelled, or an
+///   error if the inputs do not match the circuit.
+pub fn prove(
+    compiled: &CompiledCircuit,
+    inputs: &[FieldElement],
+    context: &ProverContext,
+    on_progress: impl FnMut(Progress),
+    cancel: &CancellationToken,
+) -> Result<Proof> {
+    let mut reporter = Reporter { on_progress, cancel, last: None };
+
+    let gates = compiled.gates.len();
+    reporter.report(Phase::Witness, 0, gates)?;
+    let circuit = compiled.assign_with(inputs, |done| reporter.report(Phase::Witness, done, gates))?;
+    if circuit.wires().is_empty() {
+        return Err(Error::Input("No inputs available to generate proof".to_string()));
+    }
+
+    reporter.report(Phase::Constraints, 0, 1)?;
+    let r1cs = circuit.to_r1cs();
+    reporter.report(Phase::Constraints, 1, 1)?;
+
+    // Absorb the witness as `Poseidon::hash` does, one element at a time
+    let witness: Vec<BigInt> = r1cs.generate_witness().iter().map(|w| w.get_value()).collect();
+    trace_phase!("commitment", witness = witness.len());
+    let poseidon = context.poseidon();
+    let mut sponge = PoseidonSponge::with_capacity(poseidon.clone(), poseidon.element(DomainTag::COMMITMENT.capacity(&poseidon.modulus, witness.len())));
+    reporter.report(Phase::Commitment, 0, witness.len())?;
+    for (index, value) in witness.iter().enumerate() {
+        sponge.absorb(&FieldElement::new(value.clone()));
+        reporter.report(Phase::Commitment, index + 1, witness.len())?;
+    }
+    let commitment = sponge.squeeze().get_value();
+
+    let public_inputs = r1cs.public_inputs.iter().map(|&index| witness[index].clone()).collect();
+    let outputs = r1cs.outputs.iter().map(|&index| witness[index].clone()).collect();
+    Ok(Proof { witness, public_inputs, commitment, outputs, circuit_id: r1cs.circuit_id(), version: r1cs.version })
+}
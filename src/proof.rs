@@ -1,62 +1,305 @@
-use std::fs::File;
-use std::io;
-use std::io::Write;
-use num_bigint::BigInt;
-use num_traits::Zero;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::path::Path;
+use num_bigint::{BigInt, Sign};
+use num_traits::Num;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use crate::container::{Compression, Container, FORMAT_VERSION, PROOF_MAGIC};
+use crate::error::{Error, Result};
+use crate::context::ProverContext;
+use crate::ct::{self, Choice, ConstantTimeEq};
 use crate::field::FieldElement;
-use crate::r1cs::{Operation, R1CS};
+use crate::fingerprint::{CircuitId, CircuitVersion};
+use crate::parallel::cfg_iter;
+use crate::poseidon::{DomainTag, Poseidon};
+#[cfg(feature = "std")]
+use crate::prover::{Verifier, VerifyingKey};
+use crate::r1cs::R1CS;
+use crate::trace::trace_phase;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The JSON schema of the documents written by `Proof::to_pretty_json`, also
+/// published as `schemas/proof.schema.json`.
+pub const PROOF_JSON_SCHEMA: &str = include_str!("../schemas/proof.schema.json");
+
+/// The oldest container format version whose proofs can be loaded; earlier
+/// proofs carry the private witness instead of the public inputs.
+#[cfg(feature = "std")]
+const PROOF_MIN_FORMAT_VERSION: u32 = 6;
+
+/// The version of the format written by `Proof::to_pretty_json`; earlier
+/// documents carry the private witness instead of the public inputs, and are rejected.
+const PRETTY_JSON_VERSION: u32 = 3;
 
 /// Represents a cryptographic proof.
+///
+/// Only the public values and the commitment are serialized. The witness
+/// stays with the prover, so a decoded proof has an empty witness. The
+/// built-in proof system has no argument that the committed witness satisfies
+/// the constraints, so a decoded proof cannot be verified on its own: it is
+/// only valid once its witness is restored, as by whoever holds the inputs,
+/// and checked with `Proof::verify_proof` or `Verifier::verify`. A verifier
+/// that must not see the witness needs the Groth16 backend.
 #[derive(Serialize, Deserialize)]
 pub struct Proof {
-    /// The witness values used for proof generation.
+    /// The witness values used for proof generation, kept in memory only.
+    #[serde(skip)]
     pub witness: Vec<BigInt>,
+    /// The values of the public inputs, in the order the R1CS designates them.
+    pub public_inputs: Vec<BigInt>,
     /// Commitment to the witness, for verification.
     pub commitment: BigInt,
+    /// The values of the public outputs, in the order the R1CS designates them.
+    pub outputs: Vec<BigInt>,
+    /// The id of the constraint system the proof was generated for.
+    pub circuit_id: CircuitId,
+    /// The version of the circuit the proof was generated with.
+    pub version: CircuitVersion,
 }
 
 impl Proof {
     /// Generates a proof from R1CS and witness.
     ///
+    /// This builds the Poseidon parameters on every call; use a
+    /// `ProverContext` to reuse them across proofs.
+    ///
     /// # Parameters
-    /// - `_r1cs`: The R1CS constraints.
+    /// - `r1cs`: The R1CS constraints.
     /// - `witness`: A vector of `FieldElement` representing the witness.
     ///
     /// # Returns
     /// - `Proof`: The generated proof.
-    pub fn generate_proof(_r1cs: &R1CS, witness: &Vec<FieldElement>) -> Proof {
-        // Create a commitment based on the witness
-        let mut commitment_input = BigInt::zero();
-        let witness_bigint: Vec<BigInt> = witness.iter().map(|w| w.get_value()).collect(); // Convert to Vec<BigInt>
-
-        for w in &witness_bigint {
-            commitment_input += w; // Use the BigInt value directly
-        }
+    pub fn generate_proof(r1cs: &R1CS, witness: &[FieldElement]) -> Proof {
+        Self::generate_proof_with(&ProverContext::new(), r1cs, witness)
+    }
 
-        let commitment = Self::hash(&commitment_input, &BigInt::zero()); // Simplified hash, use appropriate values
+    /// Generates a proof from R1CS and witness with cached parameters.
+    ///
+    /// # Parameters
+    /// - `context`: The precomputed parameters.
+    /// - `r1cs`: The R1CS constraints, whose public input and output variables the proof exposes.
+    /// - `witness`: A vector of `FieldElement` representing the witness.
+    ///
+    /// # Returns
+    /// - `Proof`: The generated proof.
+    ///
+    /// # Panics
+    /// - If a public input or output variable is missing from the witness.
+    pub fn generate_proof_with(context: &ProverContext, r1cs: &R1CS, witness: &[FieldElement]) -> Proof {
+        trace_phase!("commitment", witness = witness.len());
+        // Create a commitment based on the witness
+        let witness_bigint: Vec<BigInt> = cfg_iter!(witness).map(|w| w.get_value()).collect(); // Convert to Vec<BigInt>
+        let commitment = Self::commit(context.poseidon(), &witness_bigint);
 
+        let public_inputs = r1cs.public_inputs.iter().map(|&index| witness_bigint[index].clone()).collect();
+        let outputs = r1cs.outputs.iter().map(|&index| witness_bigint[index].clone()).collect();
         Proof {
             witness: witness_bigint, // Assign the converted Vec<BigInt>
+            public_inputs,
             commitment,
+            outputs,
+            circuit_id: r1cs.circuit_id(),
+            version: r1cs.version,
+        }
+    }
+
+    /// Saves the proof to a binary file, wrapped in a `ZKPF` container.
+    ///
+    /// # Parameters
+    /// - `filename`: The path of the file to save the proof to.
+    ///
+    /// # Returns
+    /// - `Result<()>`: The result of the file operation.
+    #[cfg(feature = "std")]
+    pub fn save_to_binary(&self, filename: impl AsRef<Path>) -> Result<()> {
+        let payload = bincode::serialize(self)?;
+        Container::new(PROOF_MAGIC, FieldElement::default_modulus(), payload).write_to_file(filename, Compression::None)
+    }
+
+    /// Loads a proof from a binary file written by `save_to_binary`, which may
+    /// also have been zstd-compressed.
+    ///
+    /// Proofs from format versions before 6 carry the private witness instead
+    /// of the public inputs, so they are rejected. The loaded proof has an
+    /// empty witness.
+    ///
+    /// # Parameters
+    /// - `filename`: The path of the file to load the proof from.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The loaded proof, an `UnsupportedVersion` error for proofs older
+    ///   than version 6, or an error if the container is invalid or was written for another field.
+    #[cfg(feature = "std")]
+    pub fn load_from_binary(filename: impl AsRef<Path>) -> Result<Self> {
+        let container = Container::read_from_file(PROOF_MAGIC, filename)?;
+        if container.version < PROOF_MIN_FORMAT_VERSION {
+            return Err(Error::UnsupportedVersion { found: container.version, min: PROOF_MIN_FORMAT_VERSION, max: FORMAT_VERSION });
+        }
+        container.expect_modulus(&FieldElement::default_modulus())?;
+        Ok(bincode::deserialize(&container.payload)?)
+    }
+
+    /// Encodes the proof as CBOR.
+    ///
+    /// # Returns
+    /// - `Vec<u8>`: The encoded proof.
+    #[cfg(feature = "cbor")]
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(self, &mut bytes).expect("Failed to serialize proof");
+        bytes
+    }
+
+    /// Decodes a proof from CBOR.
+    ///
+    /// # Parameters
+    /// - `bytes`: The encoded proof.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The decoded proof.
+    #[cfg(feature = "cbor")]
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        ciborium::de::from_reader(bytes).map_err(|err| Error::Serialization(err.to_string()))
+    }
+
+    /// Encodes the proof as indented JSON with named fields and hexadecimal
+    /// field elements, following `PROOF_JSON_SCHEMA`, for inspecting, logging
+    /// or sending proofs over text-only channels.
+    ///
+    /// # Returns
+    /// - `String`: The JSON document.
+    pub fn to_pretty_json(&self) -> String {
+        let pretty = PrettyProof {
+            version: PRETTY_JSON_VERSION,
+            circuit_id: self.circuit_id.to_string(),
+            circuit_version: self.version.to_string(),
+            public_inputs: self.public_inputs.iter().map(to_hex).collect(),
+            commitment: to_hex(&self.commitment),
+            outputs: self.outputs.iter().map(to_hex).collect(),
+        };
+        serde_json::to_string_pretty(&pretty).expect("Failed to serialize proof")
+    }
+
+    /// Decodes a proof from the JSON written by `to_pretty_json`.
+    ///
+    /// # Parameters
+    /// - `json`: The JSON document.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The decoded proof, without a witness, or an error if the document does not
+    ///   follow `PROOF_JSON_SCHEMA`.
+    pub fn from_pretty_json(json: &str) -> Result<Self> {
+        let version = serde_json::from_str::<PrettyVersion>(json)?.version;
+        if version != PRETTY_JSON_VERSION {
+            return Err(Error::Serialization(format!("Unsupported proof JSON version {}", version)));
+        }
+        let pretty: PrettyProof = serde_json::from_str(json)?;
+        Ok(Proof {
+            witness: Vec::new(),
+            public_inputs: pretty.public_inputs.iter().map(|value| from_hex(value)).collect::<Result<_>>()?,
+            commitment: from_hex(&pretty.commitment)?,
+            outputs: pretty.outputs.iter().map(|value| from_hex(value)).collect::<Result<_>>()?,
+            circuit_id: pretty.circuit_id.parse()?,
+            version: pretty.circuit_version.parse()?,
+        })
+    }
+
+    /// Encodes the proof in its canonical binary form, the single encoding
+    /// `from_canonical_bytes` accepts, so equal proofs always have equal bytes.
+    ///
+    /// The layout is the 32 bytes of the circuit id, the major, minor and patch
+    /// circuit version as little-endian `u32`s, the commitment, then the
+    /// public inputs and the outputs, each as a little-endian `u32` count followed by
+    /// the values. The witness is not encoded. Every value is a big-endian integer padded to the byte
+    /// length of the modulus.
+    ///
+    /// # Returns
+    /// - `Result<Vec<u8>>`: The encoded proof, or an error if the proof is not canonical.
+    pub fn to_canonical_bytes(&self) -> Result<Vec<u8>> {
+        if !self.is_canonical() {
+            return Err(Error::Serialization("The proof holds values outside the field".to_string()));
+        }
+        let modulus = FieldElement::default_modulus();
+        let width = ct::field_width(&modulus);
+        let mut bytes = Vec::with_capacity(32 + 12 + width * (1 + self.public_inputs.len() + self.outputs.len()) + 8);
+        bytes.extend_from_slice(&self.circuit_id.0);
+        for component in [self.version.major, self.version.minor, self.version.patch] {
+            bytes.extend_from_slice(&component.to_le_bytes());
+        }
+        push_canonical(&mut bytes, &self.commitment, width);
+        for values in [&self.public_inputs, &self.outputs] {
+            bytes.extend_from_slice(&(values.len() as u32).to_le_bytes());
+            values.iter().for_each(|value| push_canonical(&mut bytes, value, width));
         }
+        Ok(bytes)
     }
 
-    /// Saves the proof to a binary file.
+    /// Decodes a proof written by `to_canonical_bytes`, rejecting every other
+    /// encoding: truncated input, trailing bytes and values not below the modulus.
     ///
     /// # Parameters
-    /// - `filename`: The name of the file to save the proof to.
+    /// - `bytes`: The encoded proof.
     ///
     /// # Returns
-    /// - `io::Result<()>`: The result of the file operation.
-    pub fn save_to_binary(&self, filename: &str) -> io::Result<()> {
-        let mut file = File::create(filename)?;
-        let encoded: Vec<u8> = bincode::serialize(self).expect("Failed to serialize proof");
-        file.write_all(&encoded)?;
-        Ok(())
+    /// - `Result<Self>`: The decoded proof, without a witness, or an error if the encoding is not canonical.
+    pub fn from_canonical_bytes(bytes: &[u8]) -> Result<Self> {
+        let modulus = FieldElement::default_modulus();
+        let mut reader = CanonicalReader { bytes, modulus: &modulus, width: ct::field_width(&modulus) };
+        let mut circuit_id = [0u8; 32];
+        circuit_id.copy_from_slice(reader.take(32)?);
+        let version = CircuitVersion::new(reader.u32()?, reader.u32()?, reader.u32()?);
+        let commitment = reader.value()?;
+        let public_inputs = reader.values()?;
+        let outputs = reader.values()?;
+        if !reader.bytes.is_empty() {
+            return Err(Error::Serialization(format!("{} trailing bytes after the proof", reader.bytes.len())));
+        }
+        Ok(Proof { witness: Vec::new(), public_inputs, commitment, outputs, circuit_id: CircuitId(circuit_id), version })
+    }
+
+    /// Checks that every value of the proof is a canonical field element, at
+    /// least zero and below the modulus.
+    ///
+    /// Values are reduced before being hashed or checked against constraints,
+    /// so without this check adding the modulus to a value would give another
+    /// proof that verifies.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if every value is in range, otherwise `false`.
+    pub fn is_canonical(&self) -> bool {
+        let modulus = FieldElement::default_modulus();
+        let in_range = |value: &BigInt| value.sign() != Sign::Minus && *value < modulus;
+        in_range(&self.commitment)
+            && self.public_inputs.iter().all(in_range)
+            && self.outputs.iter().all(in_range)
+            && self.witness.iter().all(in_range)
+    }
+
+    /// Verifies the proof against a verifying key and the claimed values of
+    /// the public inputs, like `Verifier::verify`.
+    ///
+    /// The proof must hold its witness; a proof without one is unverifiable
+    /// and never valid.
+    ///
+    /// # Parameters
+    /// - `verifying_key`: The key of the circuit, from `Prover::verifying_key`.
+    /// - `public_inputs`: The claimed values of the public inputs, in the order of `VerifyingKey::public_input_names`.
+    ///
+    /// # Returns
+    /// - `Result<bool>`: `true` if the proof is valid for these public inputs, otherwise `false`, or
+    ///   `Error::VersionMismatch` if the proof was generated by a release of the circuit the key does not
+    ///   accept, or an error if the number of public inputs does not match the circuit.
+    #[cfg(feature = "std")]
+    pub fn verify(&self, verifying_key: &VerifyingKey, public_inputs: &[FieldElement]) -> Result<bool> {
+        Verifier::verify(verifying_key, public_inputs, self)
     }
 
-    /// Verifies a proof against the R1CS constraints.
+    /// Verifies a proof against the R1CS constraints, which needs the witness
+    /// the proof was generated from.
     ///
     /// # Parameters
     /// - `proof`: The proof to verify.
@@ -65,62 +308,250 @@ impl Proof {
     /// # Returns
     /// - `bool`: `true` if the proof is valid, otherwise `false`.
     pub fn verify_proof(proof: &Proof, r1cs: &R1CS) -> bool {
-        // Check if the commitment matches the expected hash
-        let mut commitment_input = BigInt::zero();
-        for w in &proof.witness {
-            commitment_input += w; // Combine witness values
-        }
-        let expected_commitment = Self::hash(&commitment_input, &BigInt::zero()); // Use the same hash function
+        Self::verify_proof_with(&ProverContext::new(), proof, r1cs)
+    }
 
-        if proof.commitment != expected_commitment {
-            return false; // Commitment mismatch
-        }
+    /// Verifies a proof against the R1CS constraints with cached parameters,
+    /// checking its circuit version, that its values are canonical, its circuit id, its commitment, its public inputs and outputs
+    /// and every constraint on its witness.
+    ///
+    /// This is the check of whoever holds the witness, such as the prover or
+    /// an auditor: a decoded proof has no witness and fails it.
+    ///
+    /// Every check runs even after one fails, and commitments and values are
+    /// compared in constant time, so a verifier embedded in a service does not
+    /// reveal through its timing how far a rejected proof got.
+    ///
+    /// # Parameters
+    /// - `context`: The precomputed parameters.
+    /// - `proof`: The proof to verify.
+    /// - `r1cs`: The R1CS constraints.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the proof is valid, otherwise `false`.
+    pub fn verify_proof_with(context: &ProverContext, proof: &Proof, r1cs: &R1CS) -> bool {
+        trace_phase!("verify", constraints = r1cs.constraints.len(), witness = proof.witness.len());
+        let bound = proof.binding(context, r1cs);
+
+        // The proof's own witness, not the values the R1CS was built with, must satisfy every
+        // constraint; it is checked even when the binding failed, so the time taken does not tell
+        bool::from(bound & ct::choice(r1cs.verify_witness(&proof.field_witness(&r1cs.modulus()))))
+    }
+
+    /// Checks the public side of the binding: the circuit version, that the
+    /// values are canonical, the circuit id and the number of public inputs
+    /// and outputs, without branching on any of them.
+    ///
+    /// # Parameters
+    /// - `r1cs`: The R1CS constraints.
+    ///
+    /// # Returns
+    /// - `Choice`: `1` if the public values of the proof fit the constraint system, otherwise `0`.
+    fn public_binding(&self, r1cs: &R1CS) -> Choice {
+        ct::choice(self.version.is_compatible_with(&r1cs.version)) // Generated by a release of the circuit this one accepts
+            & ct::choice(self.is_canonical()) // Values outside the field would make the proof malleable
+            & self.circuit_id.ct_eq(&r1cs.circuit_id()) // Generated for this constraint system
+            & ct::choice(self.public_inputs.len() == r1cs.public_inputs.len())
+            & ct::choice(self.outputs.len() == r1cs.outputs.len())
+    }
+
+    /// Checks everything but the constraints: the public binding, the
+    /// commitment and that the public inputs and outputs are the values of
+    /// their wires. Every check runs, and their outcomes are combined without
+    /// branching, so the time taken does not tell which one failed.
+    ///
+    /// # Parameters
+    /// - `context`: The precomputed parameters.
+    /// - `r1cs`: The R1CS constraints.
+    ///
+    /// # Returns
+    /// - `Choice`: `1` if the proof and its witness belong to the constraint system, otherwise `0`.
+    pub(crate) fn binding(&self, context: &ProverContext, r1cs: &R1CS) -> Choice {
+        self.public_binding(r1cs)
+            & ct::choice(self.verify_commitment_with(context))
+            & ct::choice(self.public_inputs_match(&r1cs.public_inputs))
+            & ct::choice(self.outputs_match(&r1cs.outputs))
+    }
+
+    /// Converts the witness of the proof into elements of a field.
+    ///
+    /// # Parameters
+    /// - `modulus`: The modulus of the field.
+    ///
+    /// # Returns
+    /// - `Vec<FieldElement>`: The witness values.
+    pub(crate) fn field_witness(&self, modulus: &BigInt) -> Vec<FieldElement> {
+        cfg_iter!(self.witness)
+            .map(|value| FieldElement::new_with_modulus(value.clone(), modulus.clone()))
+            .collect()
+    }
+
+    /// Gets the public outputs of the proof.
+    ///
+    /// # Returns
+    /// - `Vec<FieldElement>`: The output values, in the order the circuit designates them.
+    pub fn public_outputs(&self) -> Vec<FieldElement> {
+        self.outputs.iter().map(|value| FieldElement::new(value.clone())).collect()
+    }
+
+    /// Checks that the public inputs of the proof are the witness values of the public input wires,
+    /// comparing every value in constant time.
+    ///
+    /// # Parameters
+    /// - `wires`: The indices of the public input wires, in order.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if there is one public input per wire holding its value, otherwise `false`.
+    pub fn public_inputs_match(&self, wires: &[usize]) -> bool {
+        self.wires_match(wires, &self.public_inputs)
+    }
 
-        // Check if the proof's witness satisfies the R1CS constraints
-        for constraint in &r1cs.constraints {
-            let left_eval = constraint.left.iter().map(|(var, coeff)| {
-                var.value.clone() * coeff // This produces FieldElement
-            }).map(|fe| fe.get_value()).sum::<BigInt>(); // Convert to BigInt and sum
-
-            let right_eval = constraint.right.iter().map(|(var, coeff)| {
-                var.value.clone() * coeff
-            }).map(|fe| fe.get_value()).sum::<BigInt>();
-
-            let output_eval = constraint.output.iter().map(|(var, coeff)| {
-                var.value.clone() * coeff
-            }).map(|fe| fe.get_value()).sum::<BigInt>();
-
-            // Verify the specific operation
-            match constraint.operation {
-                Operation::Add => {
-                    if left_eval + right_eval != output_eval {
-                        return false; // Constraint not satisfied
-                    }
-                },
-                Operation::Mul => {
-                    if left_eval * right_eval != output_eval {
-                        return false; // Constraint not satisfied
-                    }
-                },
-                Operation::Hash => todo!(),
-            }
+    /// Checks that the public outputs of the proof are the witness values of the output wires,
+    /// comparing every value in constant time.
+    ///
+    /// # Parameters
+    /// - `wires`: The indices of the output wires, in order.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if there is one output per wire holding its value, otherwise `false`.
+    pub fn outputs_match(&self, wires: &[usize]) -> bool {
+        self.wires_match(wires, &self.outputs)
+    }
+
+    /// Checks that public values are the witness values of their wires, in constant time.
+    fn wires_match(&self, wires: &[usize], public: &[BigInt]) -> bool {
+        let values: Option<Vec<BigInt>> = wires.iter().map(|&wire| self.witness.get(wire).cloned()).collect();
+        match values {
+            Some(values) => ct::bigints_eq(&values, public, ct::field_width(&FieldElement::default_modulus())).into(),
+            None => false,
         }
+    }
+
+    /// Checks that the commitment of the proof matches its witness.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the commitment is the hash of the witness, otherwise `false`.
+    pub fn verify_commitment(&self) -> bool {
+        self.verify_commitment_with(&ProverContext::new())
+    }
 
-        true // If all checks pass, the proof is valid
+    /// Checks that the commitment of the proof matches its witness with cached parameters,
+    /// comparing the commitments in constant time.
+    ///
+    /// # Parameters
+    /// - `context`: The precomputed parameters.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the commitment is the hash of the witness, otherwise `false`.
+    pub fn verify_commitment_with(&self, context: &ProverContext) -> bool {
+        let commitment = Self::commit(context.poseidon(), &self.witness);
+        ct::bigint_eq(&self.commitment, &commitment, ct::field_width(&context.poseidon().modulus)).into()
     }
 
-    /// A simple hashing mechanism combining two BigInt values.
+    /// Commits to the witness values with the Poseidon hash, under the commitment tag.
     ///
     /// # Parameters
-    /// - `left`: The left `BigInt` value.
-    /// - `right`: The right `BigInt` value.
+    /// - `poseidon`: The Poseidon parameters.
+    /// - `witness`: The witness values.
     ///
     /// # Returns
-    /// - `BigInt`: The hash of the two values.
-    fn hash(left: &BigInt, right: &BigInt) -> BigInt {
-        // For simplicity, we will just combine the two values
-        // and take the modulo of a large prime number.
-        let combined = left + right; // Simple addition as the "hashing" operation
-        combined % BigInt::from(1_000_000_007) // A large prime for modulo
+    /// - `BigInt`: The commitment to the witness.
+    fn commit(poseidon: &Poseidon, witness: &[BigInt]) -> BigInt {
+        let elements: Vec<FieldElement> = cfg_iter!(witness).map(|w| FieldElement::new(w.clone())).collect();
+        poseidon.hash(DomainTag::COMMITMENT, &elements).get_value()
+    }
+}
+
+/// Mirrors `PROOF_JSON_SCHEMA`, with field elements as hexadecimal strings.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PrettyProof {
+    version: u32,
+    circuit_id: String,
+    circuit_version: String,
+    public_inputs: Vec<String>,
+    commitment: String,
+    outputs: Vec<String>,
+}
+
+/// Reads the version of a proof JSON document, whatever its other fields.
+#[derive(Deserialize)]
+struct PrettyVersion {
+    version: u32,
+}
+
+/// Formats an integer as `0x`-prefixed lowercase hexadecimal.
+pub(crate) fn to_hex(value: &BigInt) -> String {
+    match value.sign() {
+        Sign::Minus => format!("-0x{:x}", -value),
+        _ => format!("0x{:x}", value),
     }
-}
\ No newline at end of file
+}
+
+/// Parses an integer written by `to_hex`.
+pub(crate) fn from_hex(value: &str) -> Result<BigInt> {
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value),
+    };
+    let parsed = digits.strip_prefix("0x")
+        .filter(|hex| !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)))
+        .and_then(|hex| BigInt::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| Error::Serialization(format!("Invalid hexadecimal field element: {}", value)))?;
+    Ok(if negative { -parsed } else { parsed })
+}
+
+/// Appends a value to a canonical encoding, as a big-endian integer padded to `width` bytes.
+fn push_canonical(bytes: &mut Vec<u8>, value: &BigInt, width: usize) {
+    let digits = value.to_bytes_be().1;
+    bytes.resize(bytes.len() + width - digits.len(), 0);
+    bytes.extend_from_slice(&digits);
+}
+
+/// Reads the fields of a canonical encoding in order.
+struct CanonicalReader<'a> {
+    /// The bytes left to read.
+    bytes: &'a [u8],
+    /// The modulus every value must be below.
+    modulus: &'a BigInt,
+    /// The byte length of a value.
+    width: usize,
+}
+
+impl<'a> CanonicalReader<'a> {
+    /// Takes the next `len` bytes, failing on truncated input.
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(Error::Serialization("Truncated canonical proof".to_string()));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    /// Reads a value, failing unless it is below the modulus.
+    fn value(&mut self) -> Result<BigInt> {
+        let value = BigInt::from_bytes_be(Sign::Plus, self.take(self.width)?);
+        if value >= *self.modulus {
+            return Err(Error::Serialization(format!("Non-canonical field element {}", value)));
+        }
+        Ok(value)
+    }
+
+    /// Reads a little-endian `u32`.
+    fn u32(&mut self) -> Result<u32> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    /// Reads a count followed by that many values.
+    fn values(&mut self) -> Result<Vec<BigInt>> {
+        let count = self.u32()? as usize;
+        // Check the length up front so a forged count cannot reserve a huge buffer
+        if count > self.bytes.len() / self.width {
+            return Err(Error::Serialization("Truncated canonical proof".to_string()));
+        }
+        (0..count).map(|_| self.value()).collect()
+    }
+}
@@ -0,0 +1,364 @@
+use std::convert::{TryFrom, TryInto};
+use num_bigint::{BigInt, Sign};
+use prost::Message;
+use crate::error::{Error, Result};
+use crate::field::{FieldElement, ModulusPolicy};
+use crate::fingerprint::{CircuitId, CircuitVersion};
+use crate::proof::Proof;
+use crate::qap::{Polynomial, QAP};
+use crate::r1cs::{Constraint, Operation, Variable, R1CS};
+
+/// The types generated from the definitions in `proto/`.
+pub mod proto {
+    include!(concat!(env!("OUT_DIR"), "/zk.v1.rs"));
+}
+
+impl Proof {
+    /// Encodes the proof as a protobuf `zk.v1.Proof` message.
+    ///
+    /// # Returns
+    /// - `Vec<u8>`: The encoded message.
+    pub fn to_protobuf(&self) -> Vec<u8> {
+        proto::Proof {
+            public_inputs: self.public_inputs.iter().map(integer_to_bytes).collect(),
+            commitment: integer_to_bytes(&self.commitment),
+            outputs: self.outputs.iter().map(integer_to_bytes).collect(),
+            circuit_id: self.circuit_id.0.to_vec(),
+            version: version_to_proto(&self.version),
+        }.encode_to_vec()
+    }
+
+    /// Decodes a proof from a protobuf `zk.v1.Proof` message. The proof has no
+    /// witness, since the message does not carry it.
+    ///
+    /// # Parameters
+    /// - `bytes`: The encoded message.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The decoded proof.
+    pub fn from_protobuf(bytes: &[u8]) -> Result<Self> {
+        let message = proto::Proof::decode(bytes).map_err(decode_error)?;
+        Ok(Proof {
+            witness: Vec::new(),
+            public_inputs: message.public_inputs.iter().map(|value| integer_from_bytes(value)).collect(),
+            commitment: integer_from_bytes(&message.commitment),
+            outputs: message.outputs.iter().map(|value| integer_from_bytes(value)).collect(),
+            circuit_id: CircuitId(message.circuit_id.as_slice().try_into()
+                .map_err(|_| Error::Serialization(format!("Circuit ids are 32 bytes, got {}", message.circuit_id.len())))?),
+            version: version_from_proto(&message.version)?,
+        })
+    }
+}
+
+impl R1CS {
+    /// Encodes the R1CS as a protobuf `zk.v1.R1cs` message.
+    ///
+    /// # Returns
+    /// - `Vec<u8>`: The encoded message.
+    pub fn to_protobuf(&self) -> Vec<u8> {
+        let terms = |side: &[(Variable, BigInt)]| side.iter()
+            .map(|(variable, coeff)| proto::Term {
+                variable: Some(variable_to_proto(variable)),
+                coefficient: integer_to_bytes(coeff),
+            })
+            .collect();
+
+        proto::R1cs {
+            variables: self.variables.iter().map(variable_to_proto).collect(),
+            constraints: self.constraints.iter().map(|constraint| proto::Constraint {
+                left: terms(&constraint.left),
+                right: terms(&constraint.right),
+                output: terms(&constraint.output),
+                operation: match constraint.operation {
+                    Operation::Add => proto::Operation::Add,
+                    Operation::Mul => proto::Operation::Mul,
+                    Operation::Hash => proto::Operation::Hash,
+                } as i32,
+            }).collect(),
+            qap: Some(proto::Qap {
+                left: Some(polynomial_to_proto(&self.qap.left)),
+                right: Some(polynomial_to_proto(&self.qap.right)),
+                output: Some(polynomial_to_proto(&self.qap.output)),
+            }),
+            outputs: self.outputs.iter().map(|&index| index as u64).collect(),
+            public_inputs: self.public_inputs.iter().map(|&index| index as u64).collect(),
+            version: version_to_proto(&self.version),
+        }.encode_to_vec()
+    }
+
+    /// Decodes an R1CS from a protobuf `zk.v1.R1cs` message.
+    ///
+    /// # Parameters
+    /// - `bytes`: The encoded message.
+    /// - `policy`: The requirements on the field modulus of the variables.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The decoded R1CS, or an error if a required field is missing or
+    ///   the modulus fails the policy.
+    pub fn from_protobuf(bytes: &[u8], policy: &ModulusPolicy) -> Result<Self> {
+        let message = proto::R1cs::decode(bytes).map_err(decode_error)?;
+        let terms = |side: Vec<proto::Term>| side.into_iter()
+            .map(|term| Ok((variable_from_proto(required(term.variable, "Term.variable")?)?, integer_from_bytes(&term.coefficient))))
+            .collect::<Result<Vec<_>>>();
+
+        let constraints = message.constraints.into_iter().map(|constraint| {
+            let operation = match proto::Operation::try_from(constraint.operation) {
+                Ok(proto::Operation::Add) => Operation::Add,
+                Ok(proto::Operation::Mul) => Operation::Mul,
+                Ok(proto::Operation::Hash) => Operation::Hash,
+                Err(_) => return Err(Error::Serialization(format!("Unknown operation {}", constraint.operation))),
+            };
+            Ok(Constraint {
+                left: terms(constraint.left)?,
+                right: terms(constraint.right)?,
+                output: terms(constraint.output)?,
+                operation,
+            })
+        }).collect::<Result<Vec<_>>>()?;
+
+        let qap = required(message.qap, "R1cs.qap")?;
+        let r1cs = R1CS {
+            variables: message.variables.into_iter().map(variable_from_proto).collect::<Result<Vec<_>>>()?,
+            constraints,
+            public_inputs: message.public_inputs.iter().map(|&index| index as usize).collect(),
+            outputs: message.outputs.iter().map(|&index| index as usize).collect(),
+            qap: QAP {
+                left: polynomial_from_proto(required(qap.left, "Qap.left")?)?,
+                right: polynomial_from_proto(required(qap.right, "Qap.right")?)?,
+                output: polynomial_from_proto(required(qap.output, "Qap.output")?)?,
+            },
+            version: version_from_proto(&message.version)?,
+        };
+        r1cs.check_field(policy)?;
+        Ok(r1cs)
+    }
+}
+
+#[cfg(feature = "ark-backend")]
+pub use self::verifying_key::{verifying_key_from_protobuf, verifying_key_to_protobuf};
+
+/// Protobuf encoding of Groth16 verifying keys.
+#[cfg(feature = "ark-backend")]
+mod verifying_key {
+    use ark_bn254::{Bn254, Fq, Fq2, G1Affine, G2Affine};
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_groth16::VerifyingKey;
+    use num_bigint::{BigInt, Sign};
+    use prost::Message;
+    use crate::ark::modulus;
+    use crate::error::{Error, Result};
+    use super::{decode_error, proto, required};
+
+    /// Encodes a Groth16 verifying key as a protobuf `zk.v1.VerifyingKey` message.
+    ///
+    /// # Parameters
+    /// - `key`: The verifying key.
+    ///
+    /// # Returns
+    /// - `Vec<u8>`: The encoded message.
+    pub fn verifying_key_to_protobuf(key: &VerifyingKey<Bn254>) -> Vec<u8> {
+        proto::VerifyingKey {
+            alpha_g1: Some(g1_to_proto(&key.alpha_g1)),
+            beta_g2: Some(g2_to_proto(&key.beta_g2)),
+            gamma_g2: Some(g2_to_proto(&key.gamma_g2)),
+            delta_g2: Some(g2_to_proto(&key.delta_g2)),
+            gamma_abc_g1: key.gamma_abc_g1.iter().map(g1_to_proto).collect(),
+        }.encode_to_vec()
+    }
+
+    /// Decodes a Groth16 verifying key from a protobuf `zk.v1.VerifyingKey` message.
+    ///
+    /// # Parameters
+    /// - `bytes`: The encoded message.
+    ///
+    /// # Returns
+    /// - `Result<VerifyingKey<Bn254>>`: The verifying key, or an error if a point is invalid.
+    pub fn verifying_key_from_protobuf(bytes: &[u8]) -> Result<VerifyingKey<Bn254>> {
+        let message = proto::VerifyingKey::decode(bytes).map_err(decode_error)?;
+        Ok(VerifyingKey {
+            alpha_g1: g1_from_proto(required(message.alpha_g1, "VerifyingKey.alpha_g1")?)?,
+            beta_g2: g2_from_proto(required(message.beta_g2, "VerifyingKey.beta_g2")?)?,
+            gamma_g2: g2_from_proto(required(message.gamma_g2, "VerifyingKey.gamma_g2")?)?,
+            delta_g2: g2_from_proto(required(message.delta_g2, "VerifyingKey.delta_g2")?)?,
+            gamma_abc_g1: message.gamma_abc_g1.into_iter().map(g1_from_proto).collect::<Result<Vec<_>>>()?,
+        })
+    }
+
+    /// Converts a G1 point, with the identity as zeros.
+    fn g1_to_proto(point: &G1Affine) -> proto::G1Point {
+        if point.infinity {
+            return proto::G1Point::default();
+        }
+        proto::G1Point { x: coordinate(&point.x), y: coordinate(&point.y) }
+    }
+
+    /// Converts a G2 point, with the identity as zeros.
+    fn g2_to_proto(point: &G2Affine) -> proto::G2Point {
+        if point.infinity {
+            return proto::G2Point::default();
+        }
+        proto::G2Point {
+            x_c0: coordinate(&point.x.c0),
+            x_c1: coordinate(&point.x.c1),
+            y_c0: coordinate(&point.y.c0),
+            y_c1: coordinate(&point.y.c1),
+        }
+    }
+
+    /// Converts a G1 point back, checking that it lies in the prime-order subgroup.
+    fn g1_from_proto(point: proto::G1Point) -> Result<G1Affine> {
+        let (x, y) = (base_field(&point.x)?, base_field(&point.y)?);
+        if x == Fq::from(0u8) && y == Fq::from(0u8) {
+            return Ok(G1Affine::identity());
+        }
+        let point = G1Affine::new_unchecked(x, y);
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(Error::Serialization("Invalid G1 point".to_string()));
+        }
+        Ok(point)
+    }
+
+    /// Converts a G2 point back, checking that it lies in the prime-order subgroup.
+    fn g2_from_proto(point: proto::G2Point) -> Result<G2Affine> {
+        let x = Fq2::new(base_field(&point.x_c0)?, base_field(&point.x_c1)?);
+        let y = Fq2::new(base_field(&point.y_c0)?, base_field(&point.y_c1)?);
+        if x == Fq2::from(0u8) && y == Fq2::from(0u8) {
+            return Ok(G2Affine::identity());
+        }
+        let point = G2Affine::new_unchecked(x, y);
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(Error::Serialization("Invalid G2 point".to_string()));
+        }
+        Ok(point)
+    }
+
+    /// Encodes a base field coordinate as big-endian bytes.
+    fn coordinate(value: &Fq) -> Vec<u8> {
+        BigInt::from_bytes_be(Sign::Plus, &value.into_bigint().to_bytes_be()).to_signed_bytes_be()
+    }
+
+    /// Decodes a base field coordinate, rejecting non-canonical values.
+    fn base_field(bytes: &[u8]) -> Result<Fq> {
+        let value = BigInt::from_signed_bytes_be(bytes);
+        if value.sign() == Sign::Minus || value >= modulus::<Fq>() {
+            return Err(Error::Serialization("Non-canonical base field coordinate".to_string()));
+        }
+        Ok(Fq::from_le_bytes_mod_order(&value.to_bytes_le().1))
+    }
+}
+
+/// Converts a variable to its message.
+fn variable_to_proto(variable: &Variable) -> proto::Variable {
+    proto::Variable {
+        index: variable.index as u64,
+        value: Some(field_to_proto(&variable.value)),
+    }
+}
+
+/// Converts a variable message back.
+fn variable_from_proto(variable: proto::Variable) -> Result<Variable> {
+    Ok(Variable {
+        index: variable.index as usize,
+        value: field_from_proto(required(variable.value, "Variable.value")?)?,
+    })
+}
+
+/// Converts a polynomial to its message.
+fn polynomial_to_proto(polynomial: &Polynomial) -> proto::Polynomial {
+    proto::Polynomial {
+        coefficients: polynomial.coefficients().iter()
+            .map(|(index, coeff)| (*index as u64, field_to_proto(coeff)))
+            .collect(),
+    }
+}
+
+/// Converts a polynomial message back.
+fn polynomial_from_proto(polynomial: proto::Polynomial) -> Result<Polynomial> {
+    let coefficients = polynomial.coefficients.into_iter()
+        .map(|(index, coeff)| Ok((index as usize, field_from_proto(coeff)?)))
+        .collect::<Result<_>>()?;
+    Ok(Polynomial::from_coefficients(coefficients))
+}
+
+/// Converts a field element to its message.
+fn field_to_proto(element: &FieldElement) -> proto::FieldElement {
+    proto::FieldElement {
+        value: integer_to_bytes(&element.get_value()),
+        modulus: integer_to_bytes(element.get_modulus()),
+    }
+}
+
+/// Converts a field element message back, rejecting non-positive moduli.
+fn field_from_proto(element: proto::FieldElement) -> Result<FieldElement> {
+    let modulus = integer_from_bytes(&element.modulus);
+    if modulus.sign() != Sign::Plus {
+        return Err(Error::Serialization("Field element modulus must be positive".to_string()));
+    }
+    Ok(FieldElement::new_with_modulus(integer_from_bytes(&element.value), modulus))
+}
+
+/// Encodes a circuit version as `major.minor.patch`, with `0.0.0` as the empty string.
+fn version_to_proto(version: &CircuitVersion) -> String {
+    if *version == CircuitVersion::default() { String::new() } else { version.to_string() }
+}
+
+/// Decodes a circuit version written by `version_to_proto`.
+fn version_from_proto(version: &str) -> Result<CircuitVersion> {
+    if version.is_empty() {
+        return Ok(CircuitVersion::default());
+    }
+    version.parse().map_err(|_| Error::Serialization(format!("Invalid circuit version `{}`", version)))
+}
+
+/// Encodes an integer as big-endian two's complement bytes, with zero as no bytes.
+pub(crate) fn integer_to_bytes(value: &BigInt) -> Vec<u8> {
+    if value.sign() == Sign::NoSign {
+        return Vec::new();
+    }
+    value.to_signed_bytes_be()
+}
+
+/// Decodes an integer from big-endian two's complement bytes.
+pub(crate) fn integer_from_bytes(bytes: &[u8]) -> BigInt {
+    BigInt::from_signed_bytes_be(bytes)
+}
+
+/// Unwraps a message field that proto3 leaves optional but the type requires.
+fn required<T>(field: Option<T>, name: &str) -> Result<T> {
+    field.ok_or_else(|| Error::Serialization(format!("Missing field {}", name)))
+}
+
+/// Wraps a protobuf decoding error.
+fn decode_error(err: prost::DecodeError) -> Error {
+    Error::Serialization(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::{Circuit, Gate};
+
+    /// Builds the R1CS of `3 * 4 = 12` over the default field.
+    fn product() -> R1CS {
+        let mut circuit = Circuit::new();
+        let x = circuit.add_input(FieldElement::new(BigInt::from(3)));
+        let y = circuit.add_input(FieldElement::new(BigInt::from(4)));
+        let z = circuit.add_input(FieldElement::new(BigInt::from(12)));
+        circuit.add_gate(Gate::Mul(x, y, z));
+        circuit.to_r1cs()
+    }
+
+    #[test]
+    fn r1cs_messages_round_trip_under_their_policy() {
+        let r1cs = product();
+        let decoded = R1CS::from_protobuf(&r1cs.to_protobuf(), &ModulusPolicy::toy()).unwrap();
+        assert_eq!(decoded.circuit_id(), r1cs.circuit_id());
+        assert!(decoded.verify_witness(&decoded.generate_witness()));
+    }
+
+    #[test]
+    fn r1cs_messages_over_toy_fields_need_the_opt_in() {
+        let err = R1CS::from_protobuf(&product().to_protobuf(), &ModulusPolicy::default()).unwrap_err();
+        assert!(matches!(err, Error::Input(_)));
+    }
+}
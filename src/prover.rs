@@ -0,0 +1,332 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+use serde::{Deserialize, Serialize};
+use crate::compiler::CompiledCircuit;
+use crate::config::{Backend, Config};
+use crate::context::ProverContext;
+use crate::ct;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::fingerprint::{CircuitId, CircuitVersion};
+use crate::inputs::Inputs;
+use crate::progress::{self, CancellationToken};
+use crate::proof::Proof;
+use crate::verification::{InputMismatch, VerificationReport};
+
+/// Represents everything a verifier needs to check proofs of one circuit.
+///
+/// The built-in proof system is transparent, so there is no trusted setup and
+/// the key is the compiled circuit itself.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerifyingKey {
+    /// The compiled circuit the proofs are checked against.
+    pub circuit: CompiledCircuit,
+    /// The id of the circuit's constraint system, which every proof must carry.
+    pub circuit_id: CircuitId,
+    /// The version of the circuit, which every proof must be compatible with.
+    pub version: CircuitVersion,
+}
+
+impl VerifyingKey {
+    /// Gets the names of the public inputs, in the order `Verifier::verify` expects their values.
+    ///
+    /// # Returns
+    /// - `Vec<&str>`: The names of the public inputs.
+    pub fn public_input_names(&self) -> Vec<&str> {
+        self.circuit.public_inputs.iter().map(|&index| self.circuit.wire_names[index].as_str()).collect()
+    }
+
+    /// Gets the names of the public outputs, in the order `Verifier::verify_outputs` returns their values.
+    ///
+    /// # Returns
+    /// - `Vec<&str>`: The names of the public outputs.
+    pub fn public_output_names(&self) -> Vec<&str> {
+        self.circuit.public_outputs.iter().map(|&index| self.circuit.wire_names[index].as_str()).collect()
+    }
+
+    /// Serializes the verifying key to bytes.
+    ///
+    /// # Returns
+    /// - `Result<Vec<u8>>`: The encoded key.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Deserializes a verifying key from bytes.
+    ///
+    /// # Parameters
+    /// - `bytes`: The encoded key.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The decoded key.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
+
+/// Proves a compiled circuit end to end: assigning the inputs, building the
+/// R1CS and committing to the witness, with the precomputation of a
+/// `ProverContext` shared across proofs.
+pub struct Prover {
+    /// The circuit being proven.
+    circuit: CompiledCircuit,
+    /// The configuration, which selects the backend and the artifact directories.
+    config: Config,
+    /// The cached proving parameters.
+    context: ProverContext,
+}
+
+impl Prover {
+    /// Creates a prover for a circuit.
+    ///
+    /// # Parameters
+    /// - `circuit`: The compiled circuit.
+    /// - `config`: The project configuration.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The prover, or an error if the configuration is invalid or selects a
+    ///   backend that cannot prove compiled circuits.
+    pub fn new(circuit: CompiledCircuit, config: Config) -> Result<Self> {
+        config.validate()?;
+        // Compiled circuits are defined over the default field, which Groth16 does not support
+        if config.backend != Backend::Builtin {
+            return Err(Error::Input(format!("Compiled circuits can only be proven with the built-in backend, but {:?} is configured", config.backend)));
+        }
+        Ok(Prover { circuit, config, context: ProverContext::new() })
+    }
+
+    /// Gets the key verifiers check this prover's proofs with.
+    ///
+    /// # Returns
+    /// - `VerifyingKey`: The verifying key.
+    pub fn verifying_key(&self) -> VerifyingKey {
+        VerifyingKey { circuit: self.circuit.clone(), circuit_id: self.circuit.circuit_id(), version: self.circuit.version }
+    }
+
+    /// Proves the circuit with named inputs.
+    ///
+    /// # Parameters
+    /// - `inputs`: The named inputs, each in the section matching its visibility.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, or an error if an input is missing, unknown or has the wrong visibility.
+    pub fn prove(&self, inputs: &Inputs) -> Result<Proof> {
+        let circuit = self.circuit.assign_inputs(inputs)?;
+        if circuit.wires().is_empty() {
+            return Err(Error::Input("No inputs available to generate proof".to_string()));
+        }
+        let r1cs = circuit.to_r1cs();
+        Ok(self.context.generate_proof(&r1cs, &r1cs.generate_witness()))
+    }
+
+    /// Proves the circuit with named inputs, stopping early once the token is cancelled.
+    ///
+    /// The token is checked after each gate and each element absorbed into the
+    /// commitment, so a server can abort a job from another thread without
+    /// waiting for the proof. The proof is the same as the one of `prove`.
+    ///
+    /// # Parameters
+    /// - `inputs`: The named inputs, each in the section matching its visibility.
+    /// - `cancel`: The token checked between steps.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, `Error::Cancelled` if the token was cancelled, or an error
+    ///   if an input is missing, unknown or has the wrong visibility.
+    pub fn prove_with_cancel(&self, inputs: &Inputs, cancel: &CancellationToken) -> Result<Proof> {
+        let values = self.circuit.input_values(inputs)?;
+        progress::prove(&self.circuit, &values, &self.context, |_| {}, cancel)
+    }
+
+    /// Saves a proof to the proofs directory of the configuration, creating it if needed.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to save.
+    /// - `name`: The name of the circuit, which names the file.
+    ///
+    /// # Returns
+    /// - `Result<PathBuf>`: The path of the saved proof.
+    pub fn save_proof(&self, proof: &Proof, name: &str) -> Result<PathBuf> {
+        let path = self.config.artifacts(name).proof;
+        fs::create_dir_all(&self.config.artifacts.proofs)?;
+        proof.save_to_binary(&path)?;
+        Ok(path)
+    }
+}
+
+/// Verifies proofs against a verifying key and the claimed public inputs.
+pub struct Verifier;
+
+impl Verifier {
+    /// Verifies a proof.
+    ///
+    /// # Parameters
+    /// - `verifying_key`: The key of the circuit, from `Prover::verifying_key`.
+    /// - `public_inputs`: The claimed values of the public inputs, in the order of `VerifyingKey::public_input_names`.
+    /// - `proof`: The proof to verify.
+    ///
+    /// # Returns
+    /// - `Result<bool>`: `true` if the proof is valid for these public inputs, otherwise `false`,
+    ///   including for a proof without its witness, which the built-in proof system cannot verify, or
+    ///   `Error::VersionMismatch` if the proof was generated by a release of the circuit the key does not
+    ///   accept, or an error if the number of public inputs does not match the circuit.
+    pub fn verify(verifying_key: &VerifyingKey, public_inputs: &[FieldElement], proof: &Proof) -> Result<bool> {
+        Ok(Self::check(verifying_key, public_inputs, proof)?.is_valid())
+    }
+
+    /// Verifies a proof and returns the values of its public outputs. Outputs are
+    /// returned only once the witness of the proof has been checked against every
+    /// constraint and its output wires, so a proof without its witness yields none.
+    ///
+    /// # Parameters
+    /// - `verifying_key`: The key of the circuit, from `Prover::verifying_key`.
+    /// - `public_inputs`: The claimed values of the public inputs, in the order of `VerifyingKey::public_input_names`.
+    /// - `proof`: The proof to verify.
+    ///
+    /// # Returns
+    /// - `Result<Option<Vec<FieldElement>>>`: The outputs in the order of `VerifyingKey::public_output_names`,
+    ///   `None` if the proof is invalid or unverifiable, `Error::VersionMismatch` if the key does not accept the version of
+    ///   the proof, or an error if the number of public inputs does not match the circuit.
+    pub fn verify_outputs(verifying_key: &VerifyingKey, public_inputs: &[FieldElement], proof: &Proof) -> Result<Option<Vec<FieldElement>>> {
+        let valid = Self::check(verifying_key, public_inputs, proof)?.is_valid();
+        Ok(if valid { Some(proof.public_outputs()) } else { None })
+    }
+
+    /// Verifies a proof like `verify`, reporting every check, including the
+    /// public inputs whose claimed values the proof does not carry. A proof
+    /// without its witness is reported as unverifiable.
+    ///
+    /// # Parameters
+    /// - `verifying_key`: The key of the circuit, from `Prover::verifying_key`.
+    /// - `public_inputs`: The claimed values of the public inputs, in the order of `VerifyingKey::public_input_names`.
+    /// - `proof`: The proof to verify.
+    ///
+    /// # Returns
+    /// - `Result<VerificationReport>`: The outcome of each check, or an error if the number of
+    ///   public inputs does not match the circuit, the key's circuit id or version does not match its
+    ///   circuit, or the key does not accept the version of the proof.
+    pub fn check(verifying_key: &VerifyingKey, public_inputs: &[FieldElement], proof: &Proof) -> Result<VerificationReport> {
+        let start = Instant::now();
+        let circuit = &verifying_key.circuit;
+        if public_inputs.len() != circuit.public_inputs.len() {
+            return Err(Error::Input(format!("Expected {} public inputs, got {}", circuit.public_inputs.len(), public_inputs.len())));
+        }
+        if circuit.circuit_id() != verifying_key.circuit_id {
+            return Err(Error::Input(format!("The verifying key was made for circuit {}, not for its own circuit", verifying_key.circuit_id)));
+        }
+        if circuit.version != verifying_key.version {
+            return Err(Error::Input(format!("The verifying key was made for version {}, not for the version {} of its circuit", verifying_key.version, circuit.version)));
+        }
+        proof.version.check_compatible(&verifying_key.version)?;
+        let mut report = circuit.verify_report(proof)?;
+        // The proof must carry the claimed public values
+        report.input_mismatches = circuit.public_inputs.iter().zip(public_inputs).enumerate()
+            .filter(|(position, (_, value))| !proof.public_inputs.get(*position)
+                .is_some_and(|proven| ct::bigint_eq(proven, &value.get_value(), ct::field_width(value.get_modulus())).into()))
+            .map(|(position, (&wire, value))| InputMismatch {
+                name: circuit.wire_names[wire].clone(),
+                wire,
+                claimed: value.clone(),
+                proven: proof.public_inputs.get(position).map(|proven| FieldElement::new(proven.clone())),
+            })
+            .collect();
+        report.duration = start.elapsed();
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use num_bigint::BigInt;
+    use super::*;
+    use crate::compiler::{CircuitSource, GateSource};
+
+    /// Compiles `out = (a + b) * c`, with `c` and `out` public.
+    fn affine() -> CompiledCircuit {
+        CompiledCircuit::compile(&CircuitSource {
+            inputs: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            public: vec!["c".to_string()],
+            outputs: vec!["out".to_string()],
+            gates: vec![
+                GateSource::Add { left: "a".to_string(), right: "b".to_string(), output: "sum".to_string() },
+                GateSource::Mul { left: "sum".to_string(), right: "c".to_string(), output: "out".to_string() },
+            ],
+            version: None,
+        }).unwrap()
+    }
+
+    fn element(value: i64) -> FieldElement {
+        FieldElement::new(BigInt::from(value))
+    }
+
+    #[test]
+    fn proofs_without_their_witness_are_unverifiable() {
+        let prover = Prover::new(affine(), Config::default()).unwrap();
+        let inputs = Inputs {
+            public: BTreeMap::from([("c".to_string(), element(5))]),
+            private: BTreeMap::from([("a".to_string(), element(2)), ("b".to_string(), element(4))]),
+        };
+        let proof = prover.prove(&inputs).unwrap();
+        let key = VerifyingKey::from_bytes(&prover.verifying_key().to_bytes().unwrap()).unwrap();
+        assert_eq!(Verifier::verify_outputs(&key, &[element(5)], &proof).unwrap(), Some(vec![element(30)]));
+        assert!(proof.verify(&key, &[element(5)]).unwrap());
+
+        // The decoded proof holds the public input and the output, but not the private inputs
+        let decoded = Proof::from_canonical_bytes(&proof.to_canonical_bytes().unwrap()).unwrap();
+        assert!(decoded.witness.is_empty());
+        assert_eq!(decoded.public_inputs, [BigInt::from(5)]);
+        let report = Verifier::check(&key, &[element(5)], &decoded).unwrap();
+        assert!(!report.witness_checked);
+        assert!(!report.is_valid());
+        assert!(report.to_string().contains("unverifiable"));
+        assert_eq!(Verifier::verify_outputs(&key, &[element(5)], &decoded).unwrap(), None);
+        assert!(!decoded.verify(&key, &[element(5)]).unwrap());
+        assert!(!key.circuit.verify(&decoded).unwrap());
+    }
+
+    #[test]
+    fn proofs_with_tampered_outputs_are_rejected() {
+        let prover = Prover::new(affine(), Config::default()).unwrap();
+        let inputs = Inputs {
+            public: BTreeMap::from([("c".to_string(), element(5))]),
+            private: BTreeMap::from([("a".to_string(), element(2)), ("b".to_string(), element(4))]),
+        };
+        let key = prover.verifying_key();
+
+        let mut tampered = prover.prove(&inputs).unwrap();
+        tampered.outputs[0] = BigInt::from(31);
+        assert!(!Verifier::verify(&key, &[element(5)], &tampered).unwrap());
+        assert_eq!(Verifier::verify_outputs(&key, &[element(5)], &tampered).unwrap(), None);
+
+        // A forged proof with the right counts but no witness is not accepted either
+        let mut forged = Proof::from_canonical_bytes(&prover.prove(&inputs).unwrap().to_canonical_bytes().unwrap()).unwrap();
+        forged.outputs[0] = BigInt::from(31);
+        assert!(!Verifier::verify(&key, &[element(5)], &forged).unwrap());
+        assert!(!key.circuit.verify(&forged).unwrap());
+    }
+
+    #[test]
+    fn proofs_are_rejected_for_other_public_inputs() {
+        let prover = Prover::new(affine(), Config::default()).unwrap();
+        let inputs = Inputs {
+            public: BTreeMap::from([("c".to_string(), element(5))]),
+            private: BTreeMap::from([("a".to_string(), element(2)), ("b".to_string(), element(4))]),
+        };
+        let proof = Proof::from_canonical_bytes(&prover.prove(&inputs).unwrap().to_canonical_bytes().unwrap()).unwrap();
+        let key = prover.verifying_key();
+
+        let report = Verifier::check(&key, &[element(6)], &proof).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(report.input_mismatches.len(), 1);
+        assert_eq!(report.input_mismatches[0].proven, Some(element(5)));
+        assert!(Verifier::check(&key, &[], &proof).is_err());
+
+        // A proof that drops its public input does not fit the circuit
+        let mut truncated = Proof::from_canonical_bytes(&proof.to_canonical_bytes().unwrap()).unwrap();
+        truncated.public_inputs.clear();
+        let report = key.circuit.verify_report(&truncated).unwrap();
+        assert!(!report.public_inputs_valid);
+        assert!(!report.is_valid());
+    }
+}
@@ -1,8 +1,11 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
 use num_bigint::BigInt;
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
 use num_traits::Zero;
-use crate::field::FieldElement;
+use crate::domain::Domain;
+use crate::field::{Accumulator, FieldElement};
+use crate::parallel::cfg_join;
 
 /// Represents a variable in the QAP.
 #[derive(Clone, Serialize, Deserialize)]
@@ -14,14 +17,24 @@ pub struct Variable {
 }
 
 /// Represents a polynomial with coefficients keyed by variable index.
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Polynomial {
     /// The coefficients of the polynomial.
-    coefficients: HashMap<usize, FieldElement>,
+    coefficients: BTreeMap<usize, FieldElement>,
 }
 
+impl Default for Polynomial {
+    fn default() -> Self {
+        Polynomial::new()
+    }
+}
+
+/// The `(variable index, coefficient)` terms of one side of a constraint.
+pub type Terms = Vec<(usize, FieldElement)>;
+
 /// Represents a Quadratic Arithmetic Program (QAP).
-#[derive(Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct QAP {
     /// The left polynomial.
     pub left: Polynomial,
@@ -31,6 +44,12 @@ pub struct QAP {
     pub output: Polynomial,
 }
 
+impl Default for QAP {
+    fn default() -> Self {
+        QAP::new()
+    }
+}
+
 impl QAP {
     /// Creates a new QAP instance.
     ///
@@ -63,6 +82,22 @@ impl QAP {
         }
     }
 
+    /// Adds a batch of constraints to the QAP, accumulating the left, right and
+    /// output polynomials concurrently with the `parallel` feature.
+    ///
+    /// # Parameters
+    /// - `rows`: The left, right and output coefficients of each constraint.
+    pub fn add_constraints(&mut self, rows: &[(Terms, Terms, Terms)]) {
+        let QAP { left, right, output } = self;
+        cfg_join!(
+            || left.accumulate(rows.iter().map(|row| &row.0)),
+            || cfg_join!(
+                || right.accumulate(rows.iter().map(|row| &row.1)),
+                || output.accumulate(rows.iter().map(|row| &row.2))
+            )
+        );
+    }
+
     /// Evaluates the QAP with a given assignment.
     ///
     /// # Parameters
@@ -70,7 +105,7 @@ impl QAP {
     ///
     /// # Returns
     /// - `FieldElement`: The result of the evaluation.
-    pub fn evaluate(&self, assignment: &Vec<FieldElement>) -> FieldElement {
+    pub fn evaluate(&self, assignment: &[FieldElement]) -> FieldElement {
         let left_eval = self.left.evaluate(assignment);
         let right_eval = self.right.evaluate(assignment);
         let output_eval = self.output.evaluate(assignment);
@@ -86,7 +121,26 @@ impl Polynomial {
     /// # Returns
     /// - `Self`: A new instance of the `Polynomial` struct.
     pub fn new() -> Self {
-        Polynomial { coefficients: HashMap::new() }
+        Polynomial { coefficients: BTreeMap::new() }
+    }
+
+    /// Creates a polynomial from its coefficients.
+    ///
+    /// # Parameters
+    /// - `coefficients`: The coefficients keyed by variable index.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Polynomial` struct.
+    pub fn from_coefficients(coefficients: BTreeMap<usize, FieldElement>) -> Self {
+        Polynomial { coefficients }
+    }
+
+    /// Gets the coefficients of the polynomial.
+    ///
+    /// # Returns
+    /// - `&BTreeMap<usize, FieldElement>`: The coefficients keyed by variable index.
+    pub fn coefficients(&self) -> &BTreeMap<usize, FieldElement> {
+        &self.coefficients
     }
 
     /// Adds a term to the polynomial.
@@ -98,6 +152,13 @@ impl Polynomial {
         self.coefficients.insert(index, coefficient);
     }
 
+    /// Adds the coefficients of many constraints to the polynomial.
+    fn accumulate<'a>(&mut self, sides: impl Iterator<Item = &'a Terms>) {
+        for (index, coeff) in sides.flatten() {
+            *self.coefficients.entry(*index).or_insert(FieldElement::new(BigInt::zero())) += coeff.clone();
+        }
+    }
+
     /// Evaluates the polynomial with a given assignment.
     ///
     /// # Parameters
@@ -105,50 +166,30 @@ impl Polynomial {
     ///
     /// # Returns
     /// - `FieldElement`: The result of the evaluation.
-    pub fn evaluate(&self, assignment: &Vec<FieldElement>) -> FieldElement {
-        let mut result = FieldElement::new(BigInt::zero()); // Use the same modulus
+    pub fn evaluate(&self, assignment: &[FieldElement]) -> FieldElement {
+        let mut result = Accumulator::new(FieldElement::default_modulus()); // Use the same modulus
         for (index, coefficient) in &self.coefficients {
-            result = result.add(&coefficient.mul(&assignment[*index]));
+            result.add_product(coefficient, &assignment[*index]);
         }
-        result
+        result.finish()
     }
 
     /// Perform Lagrange interpolation to find a polynomial that passes through all given points.
     ///
+    /// This builds a `Domain` over the x-coordinates on every call; reuse a
+    /// cached domain to interpolate several polynomials over the same points.
+    ///
     /// # Parameters
-    /// - `points`: A slice of tuples representing the points (x, y).
+    /// - `points`: A slice of tuples representing the points (x, y), with distinct x-coordinates.
     /// - `_modulus`: The modulus for the field elements.
     ///
     /// # Returns
-    /// - `Polynomial`: The interpolated polynomial.
+    /// - `Polynomial`: The interpolated polynomial, with coefficients keyed by degree.
     pub fn interpolate(points: &[(FieldElement, FieldElement)], _modulus: &BigInt) -> Polynomial {
-        let mut result = Polynomial::new();
-
-        for (i, &(ref x_i, ref y_i)) in points.iter().enumerate() {
-            // Start with y_i
-            let mut term = vec![(0, y_i.clone())];
-
-            // Compute the Lagrange basis polynomial L_i(x)
-            for (j, &(ref x_j, _)) in points.iter().enumerate() {
-                if i != j {
-                    let denom = x_i.sub(x_j).inv();
-                    let negated_x_j = x_j.negate();
-                    let coeff = denom.mul(&negated_x_j);
-
-                    term.push((1, denom)); // L_i(x) = product (x - x_j) / (x_i - x_j)
-
-                    for k in 0..term.len() {
-                        term[k] = (term[k].0, term[k].1.mul(&coeff));
-                    }
-                }
-            }
-
-            // Add L_i(x) * y_i to the result
-            for (index, coeff) in term.iter() {
-                *result.coefficients.entry(*index).or_insert(FieldElement::new(BigInt::zero())) += coeff.clone();
-            }
+        if points.is_empty() {
+            return Polynomial::new();
         }
-
-        result
+        let (xs, ys): (Vec<FieldElement>, Vec<FieldElement>) = points.iter().cloned().unzip();
+        Domain::new(xs).interpolate(&ys)
     }
 }
\ No newline at end of file
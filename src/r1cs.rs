@@ -1,14 +1,27 @@
-use crate::field::FieldElement;
+use crate::ct::ConstantTimeEq;
+use crate::field::{Accumulator, FieldElement, ModulusPolicy};
 use num_bigint::BigInt;
+use alloc::vec::Vec;
+use alloc::string::String;
 use serde::{Serialize, Deserialize};
-use std::fs::File;
-use std::io::Write;
-use num_traits::Zero;
+#[cfg(feature = "cbor")]
+use alloc::string::ToString;
+#[cfg(feature = "std")]
+use crate::container::{Compression, Container, R1CS_MAGIC, WITNESS_MAGIC};
+use alloc::format;
+#[cfg(feature = "std")]
+use std::path::Path;
+use crate::error::{Error, Result};
+use crate::fingerprint::CircuitVersion;
+use num_traits::One;
 use crate::proof::Proof;
-use crate::qap::QAP;
+use crate::parallel::cfg_iter;
+use crate::qap::{Terms, QAP};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 /// Represents a variable in the R1CS.
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Variable {
     /// The index of the variable.
     pub index: usize,
@@ -17,7 +30,7 @@ pub struct Variable {
 }
 
 /// Represents an operation in the R1CS.
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Operation {
     Add,
     Mul,
@@ -25,7 +38,7 @@ pub enum Operation {
 }
 
 /// Represents a constraint in the R1CS.
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Constraint {
     /// The left side of the constraint.
     pub left: Vec<(Variable, BigInt)>,
@@ -37,15 +50,46 @@ pub struct Constraint {
     pub operation: Operation,
 }
 
+/// Describes a constraint that a witness does not satisfy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnsatisfiedConstraint {
+    /// The index of the constraint.
+    pub index: usize,
+    /// The operation of the constraint.
+    pub operation: Operation,
+    /// A readable form of the constraint, such as `(w0) * (2·w1) = (w2)`.
+    pub label: String,
+    /// The evaluated `A` side, the sum of the left and right sides for an addition constraint.
+    pub left: FieldElement,
+    /// The evaluated `B` side, one for an addition constraint.
+    pub right: FieldElement,
+    /// The evaluated output side.
+    pub output: FieldElement,
+    /// The value the output side should have, the product of the `A` and `B` sides.
+    pub expected: FieldElement,
+}
+
 /// Represents a Rank-1 Constraint System (R1CS).
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct R1CS {
     /// The variables in the R1CS.
     pub variables: Vec<Variable>,
     /// The constraints in the R1CS.
     pub constraints: Vec<Constraint>,
+    /// The indices of the variables supplied as public inputs, in order.
+    pub public_inputs: Vec<usize>,
+    /// The indices of the variables exposed as public outputs, in order.
+    pub outputs: Vec<usize>,
     /// The QAP representation of the R1CS.
     pub qap: QAP,
+    /// The version of the circuit, copied into every proof of it.
+    pub version: CircuitVersion,
+}
+
+impl Default for R1CS {
+    fn default() -> Self {
+        R1CS::new()
+    }
 }
 
 impl R1CS {
@@ -57,7 +101,10 @@ impl R1CS {
         R1CS {
             variables: Vec::new(),
             constraints: Vec::new(),
+            public_inputs: Vec::new(),
+            outputs: Vec::new(),
             qap: QAP::new(),
+            version: CircuitVersion::default(),
         }
     }
 
@@ -70,6 +117,34 @@ impl R1CS {
     /// - `modulus`: The modulus for the field elements.
     pub fn add_constraint(&mut self, left_coeffs: &[(usize, FieldElement)], right_coeffs: &[(usize, FieldElement)], output_coeffs: &[(usize, FieldElement)], modulus: &BigInt) {
         self.qap.add_constraint(left_coeffs, right_coeffs, output_coeffs, modulus);
+        self.push_constraint(left_coeffs, right_coeffs, output_coeffs);
+    }
+
+    /// Adds a batch of `A * B = C` constraints over the variables, accumulating the
+    /// left, right and output polynomials of the QAP concurrently with the `parallel` feature.
+    ///
+    /// Each constraint is recorded as a multiplication constraint, so additions
+    /// and constants must already be folded into the linear combinations.
+    ///
+    /// # Parameters
+    /// - `rows`: The left, right and output coefficients of each constraint.
+    ///
+    /// # Panics
+    /// - If a coefficient refers to a variable that has not been added.
+    pub fn add_constraints(&mut self, rows: &[(Terms, Terms, Terms)]) {
+        self.qap.add_constraints(rows);
+        for (left, right, output) in rows {
+            self.push_constraint(left, right, output);
+        }
+    }
+
+    /// Records a multiplication constraint from the coefficients of its sides.
+    fn push_constraint(&mut self, left: &[(usize, FieldElement)], right: &[(usize, FieldElement)], output: &[(usize, FieldElement)]) {
+        let side = |terms: &[(usize, FieldElement)]| terms.iter()
+            .map(|(index, coeff)| (self.variables[*index].clone(), coeff.get_value()))
+            .collect();
+        let constraint = Constraint { left: side(left), right: side(right), output: side(output), operation: Operation::Mul };
+        self.constraints.push(constraint);
     }
 
     /// Generates a witness based on the variable values.
@@ -77,7 +152,7 @@ impl R1CS {
     /// # Returns
     /// - `Vec<FieldElement>`: The generated witness.
     pub fn generate_witness(&self) -> Vec<FieldElement> {
-        self.variables.iter().map(|var| var.value.clone()).collect()
+        cfg_iter!(self.variables).map(|var| var.value.clone()).collect()
     }
 
     /// Generates a proof based on the current constraints and witness.
@@ -87,7 +162,7 @@ impl R1CS {
     ///
     /// # Returns
     /// - `Proof`: The generated proof.
-    pub fn generate_proof(&self, witness: &Vec<FieldElement>) -> Proof {
+    pub fn generate_proof(&self, witness: &[FieldElement]) -> Proof {
         Proof::generate_proof(self, witness)
     }
 
@@ -114,27 +189,145 @@ impl R1CS {
         index
     }
 
-    /// Saves the R1CS to a binary file.
+    /// Designates a variable as a public input, whose value every proof carries
+    /// for the verifier to compare with the value it expects.
     ///
     /// # Parameters
-    /// - `filename`: The name of the file to save the R1CS to.
-    pub fn save_to_binary(&self, filename: &str) {
-        let mut file = File::create(filename).expect("Could not create proof file");
-        let encoded: Vec<u8> = bincode::serialize(&self).expect("Failed to serialize proof");
-        file.write_all(&encoded).expect("Failed to write proof to file");
+    /// - `index`: The index of the variable.
+    ///
+    /// # Panics
+    /// - If the variable has not been added.
+    pub fn add_public_input(&mut self, index: usize) {
+        assert!(index < self.variables.len(), "Public input variable {} has not been added", index);
+        self.public_inputs.push(index);
     }
 
-    /// Loads the R1CS from a binary file.
+    /// Designates a variable as a public output, whose value every proof exposes.
     ///
     /// # Parameters
-    /// - `filename`: The name of the file to load the R1CS from.
+    /// - `index`: The index of the variable.
+    ///
+    /// # Panics
+    /// - If the variable has not been added.
+    pub fn add_output(&mut self, index: usize) {
+        assert!(index < self.variables.len(), "Output variable {} has not been added", index);
+        self.outputs.push(index);
+    }
+
+    /// Gets the modulus of the field the R1CS is defined over.
     ///
     /// # Returns
-    /// - `Self`: The loaded R1CS instance.
-    pub fn load_from_binary(filename: &str) -> Self {
-        let file = File::open(filename).expect("Could not open file");
-        let r1cs: R1CS = bincode::deserialize_from(file).expect("Failed to deserialize R1CS");
-        r1cs
+    /// - `BigInt`: The modulus of the variables, or the default modulus without variables.
+    pub fn modulus(&self) -> BigInt {
+        self.variables.first()
+            .map(|variable| variable.value.get_modulus().clone())
+            .unwrap_or_else(FieldElement::default_modulus)
+    }
+
+    /// Checks that every variable shares one modulus and that it satisfies a
+    /// policy, for constraint systems decoded from outside the crate.
+    ///
+    /// # Parameters
+    /// - `policy`: The requirements on the modulus.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the modulus fails the policy or a variable has another one.
+    pub fn check_field(&self, policy: &ModulusPolicy) -> Result<()> {
+        let modulus = self.modulus();
+        policy.check(&modulus)?;
+        if let Some(variable) = self.variables.iter().find(|variable| *variable.value.get_modulus() != modulus) {
+            return Err(Error::Serialization(format!(
+                "Variable {} has modulus {}, but the first variable has {}",
+                variable.index, variable.value.get_modulus(), modulus
+            )));
+        }
+        Ok(())
+    }
+
+    /// Saves the R1CS to a binary file, wrapped in a `ZKRC` container.
+    ///
+    /// # Parameters
+    /// - `filename`: The path of the file to save the R1CS to.
+    ///
+    /// # Returns
+    /// - `Result<()>`: The result of the file operation.
+    #[cfg(feature = "std")]
+    pub fn save_to_binary(&self, filename: impl AsRef<Path>) -> Result<()> {
+        self.save_to_binary_with(filename, Compression::None)
+    }
+
+    /// Saves the R1CS to a binary file with the given compression.
+    ///
+    /// # Parameters
+    /// - `filename`: The path of the file to save the R1CS to.
+    /// - `compression`: The compression applied to the file.
+    ///
+    /// # Returns
+    /// - `Result<()>`: The result of the file operation.
+    #[cfg(feature = "std")]
+    pub fn save_to_binary_with(&self, filename: impl AsRef<Path>, compression: Compression) -> Result<()> {
+        Container::new(R1CS_MAGIC, self.modulus(), bincode::serialize(self)?).write_to_file(filename, compression)
+    }
+
+    /// Loads the R1CS from a binary file written by `save_to_binary` or
+    /// `save_to_binary_with`, detecting compression automatically.
+    ///
+    /// Files from format version 1 are upgraded in memory to an R1CS without
+    /// public outputs, files before version 4 to an R1CS of version `0.0.0`,
+    /// and files before version 6 to an R1CS without public inputs.
+    ///
+    /// # Parameters
+    /// - `filename`: The path of the file to load the R1CS from.
+    /// - `policy`: The requirements on the field modulus recorded in the header.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The loaded R1CS, or an error if the container is invalid, its
+    ///   modulus fails the policy or its variables do not belong to the field recorded
+    ///   in the header.
+    #[cfg(feature = "std")]
+    pub fn load_from_binary(filename: impl AsRef<Path>, policy: &ModulusPolicy) -> Result<Self> {
+        let container = Container::read_from_file(R1CS_MAGIC, filename)?;
+        policy.check(&container.modulus)?;
+        let r1cs: R1CS = match container.version {
+            1 => bincode::deserialize::<R1csV1>(&container.payload)?.into(),
+            2 | 3 => bincode::deserialize::<R1csV3>(&container.payload)?.into(),
+            4 | 5 => bincode::deserialize::<R1csV5>(&container.payload)?.into(),
+            _ => bincode::deserialize(&container.payload)?,
+        };
+        if let Some(variable) = r1cs.variables.iter().find(|variable| *variable.value.get_modulus() != container.modulus) {
+            return Err(Error::Serialization(format!(
+                "Variable {} has modulus {}, but the file header records {}",
+                variable.index, variable.value.get_modulus(), container.modulus
+            )));
+        }
+        Ok(r1cs)
+    }
+
+    /// Encodes the R1CS as CBOR.
+    ///
+    /// # Returns
+    /// - `Vec<u8>`: The encoded R1CS.
+    #[cfg(feature = "cbor")]
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(self, &mut bytes).expect("Failed to serialize R1CS");
+        bytes
+    }
+
+    /// Decodes an R1CS from CBOR.
+    ///
+    /// # Parameters
+    /// - `bytes`: The encoded R1CS.
+    /// - `policy`: The requirements on the field modulus of the variables.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The decoded R1CS, or an error if its modulus fails the policy
+    ///   or its variables do not all share it.
+    #[cfg(feature = "cbor")]
+    pub fn from_cbor(bytes: &[u8], policy: &ModulusPolicy) -> Result<Self> {
+        let r1cs: R1CS = ciborium::de::from_reader(bytes).map_err(|err| Error::Serialization(err.to_string()))?;
+        r1cs.check_field(policy)?;
+        Ok(r1cs)
     }
 
     /// Verifies the witness against the R1CS constraints.
@@ -143,36 +336,428 @@ impl R1CS {
     /// - `witness`: A slice of `FieldElement` representing the witness.
     ///
     /// # Returns
-    /// - `bool`: `true` if the witness satisfies all constraints, otherwise `false`.
+    /// - `bool`: `true` if the witness satisfies all constraints, otherwise `false`,
+    ///   including when the witness is too short or in another field.
     pub fn verify_witness(&self, witness: &[FieldElement]) -> bool {
-        for constraint in &self.constraints {
-            let mut left_eval = FieldElement::new(BigInt::zero());
-            let mut right_eval = FieldElement::new(BigInt::zero());
-
-            // Evaluate the left side of the constraint
-            for (var_index, coeff) in &constraint.left {
-                let var_value = &witness[var_index.index];
-                left_eval = left_eval + (var_value.clone() * coeff);
-            }
-
-            // Evaluate the right side of the constraint
-            for (var_index, coeff) in &constraint.right {
-                let var_value = &witness[var_index.index];
-                right_eval = right_eval + (var_value.clone() * coeff);
-            }
-
-            // Evaluate the output side of the constraint
-            let mut output_eval = FieldElement::new(BigInt::zero());
-            for (var_index, coeff) in &constraint.output {
-                let var_value = &witness[var_index.index];
-                output_eval = output_eval + (var_value.clone() * coeff);
-            }
-
-            // Check if the constraint is satisfied
-            if left_eval != right_eval || right_eval != output_eval {
-                return false;
-            }
+        matches!(self.check_witness(witness), Ok(failures) if failures.is_empty())
+    }
+
+    /// Checks the witness against every constraint and reports the ones it fails.
+    ///
+    /// Every constraint is checked as `<A, w> * <B, w> = <C, w>` over the field.
+    /// Multiplication and hash constraints take their left and right sides as `A`
+    /// and `B`, while addition constraints fold into `A = left + right` and `B = 1`.
+    ///
+    /// # Parameters
+    /// - `witness`: A slice of `FieldElement` representing the witness.
+    ///
+    /// # Returns
+    /// - `Result<Vec<UnsatisfiedConstraint>>`: The unsatisfied constraints in order, or an
+    ///   error if a constraint refers to a variable missing from the witness or the
+    ///   witness values are not all in the same field.
+    pub fn check_witness(&self, witness: &[FieldElement]) -> Result<Vec<UnsatisfiedConstraint>> {
+        let modulus = witness.first()
+            .map(|value| value.get_modulus().clone())
+            .unwrap_or_else(|| self.modulus());
+        if let Some(index) = witness.iter().position(|value| *value.get_modulus() != modulus) {
+            return Err(Error::Input(format!("Witness value {} is not in the field of modulus {}", index, modulus)));
         }
-        true
+
+        let results = cfg_iter!(self.constraints)
+            .enumerate()
+            .map(|(index, constraint)| check_constraint(index, constraint, witness, &modulus))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(results.into_iter().flatten().collect())
+    }
+}
+
+/// Checks a single constraint as `<A, w> * <B, w> = <C, w>`, returning its
+/// diagnostics if the witness does not satisfy it.
+fn check_constraint(index: usize, constraint: &Constraint, witness: &[FieldElement], modulus: &BigInt) -> Result<Option<UnsatisfiedConstraint>> {
+    let (left, right, output) = sides(index, constraint, witness, modulus)?;
+    let expected = left.mul(&right);
+    if bool::from(expected.ct_eq(&output)) {
+        return Ok(None);
+    }
+
+    let symbol = if constraint.operation == Operation::Add { "+" } else { "*" };
+    Ok(Some(UnsatisfiedConstraint {
+        index,
+        operation: constraint.operation,
+        label: format!(
+            "({}) {} ({}) = ({})",
+            describe(&constraint.left), symbol, describe(&constraint.right), describe(&constraint.output)
+        ),
+        left,
+        right,
+        output,
+        expected,
+    }))
+}
+
+/// Evaluates the sides `A`, `B` and `C` of a constraint checked as `A * B = C`,
+/// failing on variables missing from the witness.
+pub(crate) fn sides(index: usize, constraint: &Constraint, witness: &[FieldElement], modulus: &BigInt) -> Result<(FieldElement, FieldElement, FieldElement)> {
+    let left = evaluate(index, &constraint.left, witness, modulus)?;
+    let right = evaluate(index, &constraint.right, witness, modulus)?;
+    let output = evaluate(index, &constraint.output, witness, modulus)?;
+
+    // An addition left + right = output is the linear combination (left + right) * 1 = output
+    Ok(match constraint.operation {
+        Operation::Add => (&left + &right, FieldElement::new_with_modulus(BigInt::one(), modulus.clone()), output),
+        Operation::Mul | Operation::Hash => (left, right, output),
+    })
+}
+
+/// Evaluates one side of a constraint, failing on variables missing from the witness.
+pub(crate) fn evaluate(constraint: usize, terms: &[(Variable, BigInt)], witness: &[FieldElement], modulus: &BigInt) -> Result<FieldElement> {
+    let mut sum = Accumulator::new(modulus.clone());
+    for (variable, coeff) in terms {
+        let value = witness.get(variable.index).ok_or_else(|| Error::Input(format!(
+            "Constraint {} refers to variable {}, but the witness has {} values",
+            constraint, variable.index, witness.len()
+        )))?;
+        sum.add_scaled(value, coeff);
     }
-}
\ No newline at end of file
+    Ok(sum.finish())
+}
+
+/// Formats one side of a constraint as a sum of weighted variables.
+fn describe(terms: &[(Variable, BigInt)]) -> String {
+    if terms.is_empty() {
+        return String::from("0");
+    }
+    let terms: Vec<String> = terms.iter()
+        .map(|(variable, coeff)| if coeff.is_one() {
+            format!("w{}", variable.index)
+        } else {
+            format!("{}·w{}", coeff, variable.index)
+        })
+        .collect();
+    terms.join(" + ")
+}
+
+/// Mirrors the layout of R1CS files in format version 1, before public outputs.
+#[cfg(feature = "std")]
+#[derive(Deserialize)]
+struct R1csV1 {
+    variables: Vec<Variable>,
+    constraints: Vec<Constraint>,
+    qap: QAP,
+}
+
+#[cfg(feature = "std")]
+impl From<R1csV1> for R1CS {
+    fn from(legacy: R1csV1) -> Self {
+        R1CS { variables: legacy.variables, constraints: legacy.constraints, public_inputs: Vec::new(), outputs: Vec::new(), qap: legacy.qap, version: CircuitVersion::default() }
+    }
+}
+
+/// Mirrors the layout of R1CS files in format versions 2 and 3, before circuit versions.
+#[cfg(feature = "std")]
+#[derive(Deserialize)]
+struct R1csV3 {
+    variables: Vec<Variable>,
+    constraints: Vec<Constraint>,
+    outputs: Vec<usize>,
+    qap: QAP,
+}
+
+#[cfg(feature = "std")]
+impl From<R1csV3> for R1CS {
+    fn from(legacy: R1csV3) -> Self {
+        R1CS { variables: legacy.variables, constraints: legacy.constraints, public_inputs: Vec::new(), outputs: legacy.outputs, qap: legacy.qap, version: CircuitVersion::default() }
+    }
+}
+
+/// Mirrors the layout of R1CS files in format versions 4 and 5, before public inputs.
+#[cfg(feature = "std")]
+#[derive(Deserialize)]
+struct R1csV5 {
+    variables: Vec<Variable>,
+    constraints: Vec<Constraint>,
+    outputs: Vec<usize>,
+    qap: QAP,
+    version: CircuitVersion,
+}
+
+#[cfg(feature = "std")]
+impl From<R1csV5> for R1CS {
+    fn from(legacy: R1csV5) -> Self {
+        R1CS { variables: legacy.variables, constraints: legacy.constraints, public_inputs: Vec::new(), outputs: legacy.outputs, qap: legacy.qap, version: legacy.version }
+    }
+}
+
+/// Saves a witness to a binary file, wrapped in a `ZKWT` container.
+///
+/// # Parameters
+/// - `witness`: The witness values, all in the same field.
+/// - `filename`: The path of the file to save the witness to.
+/// - `compression`: The compression applied to the file.
+///
+/// # Returns
+/// - `Result<()>`: The result of the file operation.
+#[cfg(feature = "std")]
+pub fn save_witness(witness: &[FieldElement], filename: impl AsRef<Path>, compression: Compression) -> Result<()> {
+    let modulus = witness.first()
+        .map(|value| value.get_modulus().clone())
+        .unwrap_or_else(FieldElement::default_modulus);
+    Container::new(WITNESS_MAGIC, modulus, bincode::serialize(witness)?).write_to_file(filename, compression)
+}
+
+/// Loads a witness from a binary file written by `save_witness`, detecting
+/// compression automatically.
+///
+/// # Parameters
+/// - `filename`: The path of the file to load the witness from.
+/// - `policy`: The requirements on the field modulus recorded in the header.
+///
+/// # Returns
+/// - `Result<Vec<FieldElement>>`: The witness, or an error if the container is invalid,
+///   its modulus fails the policy or a value does not belong to the field recorded in
+///   the header.
+#[cfg(feature = "std")]
+pub fn load_witness(filename: impl AsRef<Path>, policy: &ModulusPolicy) -> Result<Vec<FieldElement>> {
+    let container = Container::read_from_file(WITNESS_MAGIC, filename)?;
+    policy.check(&container.modulus)?;
+    let witness: Vec<FieldElement> = bincode::deserialize(&container.payload)?;
+    if witness.iter().any(|value| *value.get_modulus() != container.modulus) {
+        return Err(Error::Serialization(format!("Witness values do not match the field modulus {}", container.modulus)));
+    }
+    Ok(witness)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use super::*;
+
+    /// Creates an element of the default field.
+    fn element(value: i64) -> FieldElement {
+        FieldElement::new(BigInt::from(value))
+    }
+
+    /// Builds an R1CS over the given variable values with a single `A * B = C` constraint.
+    fn single_constraint(values: &[i64], row: (Terms, Terms, Terms)) -> R1CS {
+        let mut r1cs = R1CS::new();
+        for value in values {
+            r1cs.add_variable(element(*value));
+        }
+        r1cs.add_constraints(&[row]);
+        r1cs
+    }
+
+    /// Builds an R1CS over three variables `a`, `b` and `c` with a single hand-written constraint.
+    fn raw_constraint(values: [i64; 3], operation: Operation) -> R1CS {
+        let mut r1cs = R1CS::new();
+        let variables: Vec<Variable> = values.iter().map(|value| {
+            let index = r1cs.add_variable(element(*value));
+            r1cs.variables[index].clone()
+        }).collect();
+        r1cs.constraints.push(Constraint {
+            left: vec![(variables[0].clone(), BigInt::one())],
+            right: vec![(variables[1].clone(), BigInt::one())],
+            output: vec![(variables[2].clone(), BigInt::one())],
+            operation,
+        });
+        r1cs
+    }
+
+    #[test]
+    fn multiplication_holds_only_for_the_product() {
+        let r1cs = raw_constraint([3, 4, 12], Operation::Mul);
+        assert!(r1cs.verify_witness(&[element(3), element(4), element(12)]));
+        assert!(!r1cs.verify_witness(&[element(3), element(4), element(7)]));
+    }
+
+    #[test]
+    fn addition_folds_into_a_linear_combination() {
+        let r1cs = raw_constraint([3, 4, 7], Operation::Add);
+        assert!(r1cs.verify_witness(&[element(3), element(4), element(7)]));
+        assert!(!r1cs.verify_witness(&[element(3), element(4), element(12)]));
+
+        let failures = r1cs.check_witness(&[element(3), element(4), element(8)]).unwrap();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].left, element(7));
+        assert_eq!(failures[0].right, element(1));
+        assert_eq!(failures[0].expected, element(7));
+    }
+
+    #[test]
+    fn equal_sides_do_not_satisfy_a_constraint() {
+        // left == right == output is not what either operation means
+        for operation in [Operation::Add, Operation::Mul] {
+            let r1cs = raw_constraint([5, 5, 5], operation);
+            assert!(!r1cs.verify_witness(&[element(5), element(5), element(5)]));
+        }
+    }
+
+    #[test]
+    fn linear_combinations_are_evaluated_with_coefficients() {
+        // (2a + 3b + 4 * one) * one = c
+        let two_a_three_b = vec![(0, element(2)), (1, element(3)), (3, element(4))];
+        let r1cs = single_constraint(&[5, 7, 35, 1], (two_a_three_b, vec![(3, element(1))], vec![(2, element(1))]));
+        assert!(r1cs.verify_witness(&[element(5), element(7), element(35), element(1)]));
+        assert!(!r1cs.verify_witness(&[element(5), element(7), element(31), element(1)]));
+        // A wrong constant one breaks the constraint too
+        assert!(!r1cs.verify_witness(&[element(5), element(7), element(35), element(2)]));
+    }
+
+    #[test]
+    fn products_wrap_around_the_modulus() {
+        let modulus = FieldElement::default_modulus();
+        let large = FieldElement::new(&modulus - BigInt::one());
+        // (p - 1) * (p - 1) = 1 mod p
+        let r1cs = single_constraint(&[0, 0, 1], (vec![(0, element(1))], vec![(1, element(1))], vec![(2, element(1))]));
+        assert!(r1cs.verify_witness(&[large.clone(), large.clone(), element(1)]));
+        assert!(!r1cs.verify_witness(&[large.clone(), large, element(0)]));
+    }
+
+    #[test]
+    fn short_witnesses_are_rejected() {
+        let r1cs = raw_constraint([3, 4, 12], Operation::Mul);
+        assert!(!r1cs.verify_witness(&[element(3), element(4)]));
+        assert!(r1cs.check_witness(&[element(3), element(4)]).is_err());
+    }
+
+    #[test]
+    fn proofs_are_checked_against_their_own_witness() {
+        let r1cs = raw_constraint([3, 4, 12], Operation::Mul);
+        let proof = r1cs.generate_proof(&r1cs.generate_witness());
+        assert!(Proof::verify_proof(&proof, &r1cs));
+
+        // A forged witness with a matching commitment still fails the constraint
+        let forged = [element(3), element(4), element(13)];
+        let forged = r1cs.generate_proof(&forged);
+        assert!(!Proof::verify_proof(&forged, &r1cs));
+    }
+
+    #[test]
+    fn proofs_have_a_single_accepted_encoding() {
+        let r1cs = raw_constraint([3, 4, 12], Operation::Mul);
+        let proof = r1cs.generate_proof(&r1cs.generate_witness());
+        let bytes = proof.to_canonical_bytes().unwrap();
+        let decoded = Proof::from_canonical_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_canonical_bytes().unwrap(), bytes);
+        // The encoding drops the witness, so the constraints only check once it is restored
+        assert!(!Proof::verify_proof(&decoded, &r1cs));
+        let mut restored = decoded;
+        restored.witness = proof.witness.clone();
+        assert!(Proof::verify_proof(&restored, &r1cs));
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(Proof::from_canonical_bytes(&trailing).is_err());
+        assert!(Proof::from_canonical_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        // Adding the modulus to a value keeps its commitment and constraints, but not its canonical form
+        let mut shifted = r1cs.generate_proof(&r1cs.generate_witness());
+        shifted.witness[0] += FieldElement::default_modulus();
+        assert!(shifted.verify_commitment());
+        assert!(!Proof::verify_proof(&shifted, &r1cs));
+        shifted.commitment += FieldElement::default_modulus();
+        assert!(shifted.to_canonical_bytes().is_err());
+        // The commitment follows the id and the version, before the two empty counts
+        let width = bytes.len() - 32 - 12 - 8;
+        let mut overflowing = bytes;
+        overflowing[44..44 + width].fill(0xff);
+        assert!(Proof::from_canonical_bytes(&overflowing).is_err());
+    }
+
+    #[test]
+    fn decoded_proofs_verify_only_with_their_witness() {
+        let mut r1cs = raw_constraint([3, 4, 12], Operation::Mul);
+        r1cs.add_public_input(0);
+        r1cs.add_output(2);
+        let proof = r1cs.generate_proof(&r1cs.generate_witness());
+        assert!(Proof::verify_proof(&proof, &r1cs));
+
+        // Neither encoding carries the private witness, so neither verifies on its own
+        let decoded = Proof::from_canonical_bytes(&proof.to_canonical_bytes().unwrap()).unwrap();
+        let stripped = Proof::from_pretty_json(&proof.to_pretty_json()).unwrap();
+        for mut decoded in [decoded, stripped] {
+            assert!(decoded.witness.is_empty());
+            assert_eq!(decoded.public_inputs, [BigInt::from(3)]);
+            assert!(!Proof::verify_proof(&decoded, &r1cs));
+            decoded.witness = proof.witness.clone();
+            assert!(Proof::verify_proof(&decoded, &r1cs));
+
+            decoded.outputs[0] = BigInt::from(13);
+            assert!(!Proof::verify_proof(&decoded, &r1cs));
+        }
+
+        // A claim on the public input must match the witness
+        let mut forged = r1cs.generate_proof(&r1cs.generate_witness());
+        forged.public_inputs[0] = BigInt::from(5);
+        assert!(!Proof::verify_proof(&forged, &r1cs));
+    }
+
+    #[test]
+    fn proofs_verify_only_under_compatible_versions() {
+        use crate::fingerprint::CircuitVersion;
+
+        let mut r1cs = raw_constraint([3, 4, 12], Operation::Mul);
+        r1cs.version = CircuitVersion::new(1, 2, 0);
+        let proof = r1cs.generate_proof(&r1cs.generate_witness());
+        assert_eq!(proof.version, CircuitVersion::new(1, 2, 0));
+        assert_eq!(Proof::from_canonical_bytes(&proof.to_canonical_bytes().unwrap()).unwrap().version, proof.version);
+
+        for (version, accepted) in [((1, 2, 0), true), ((1, 3, 1), true), ((1, 1, 9), false), ((2, 0, 0), false)] {
+            r1cs.version = CircuitVersion::new(version.0, version.1, version.2);
+            assert_eq!(Proof::verify_proof(&proof, &r1cs), accepted, "verifier at {}", r1cs.version);
+        }
+        assert!(CircuitVersion::new(0, 3, 0).is_compatible_with(&CircuitVersion::new(0, 3, 2)));
+        assert!(!CircuitVersion::new(0, 2, 0).is_compatible_with(&CircuitVersion::new(0, 3, 0)));
+        assert!(!CircuitVersion::new(0, 0, 1).is_compatible_with(&CircuitVersion::new(0, 0, 2)));
+        assert!(matches!(
+            CircuitVersion::new(2, 0, 0).check_compatible(&CircuitVersion::new(1, 0, 0)),
+            Err(Error::VersionMismatch { .. })
+        ));
+        assert_eq!("1.2.3".parse::<CircuitVersion>().unwrap(), CircuitVersion::new(1, 2, 3));
+        assert!("1.2".parse::<CircuitVersion>().is_err());
+        assert!("1.+2.3".parse::<CircuitVersion>().is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn circuit_gates_become_constraints() {
+        use crate::circuit::{Circuit, Gate};
+
+        let mut circuit = Circuit::new();
+        let x = circuit.add_input(element(3));
+        let y = circuit.add_input(element(4));
+        let sum = circuit.add_input(element(7));
+        circuit.add_gate(Gate::Add(x, y, sum));
+        circuit.alloc_mul(x, y);
+        circuit.alloc_linear(&[(x, element(2))], element(5));
+
+        let r1cs = circuit.to_r1cs();
+        assert_eq!(r1cs.constraints.len(), 3);
+        let witness = r1cs.generate_witness();
+        assert!(r1cs.verify_witness(&witness));
+
+        // Tampering with the output of any gate breaks its constraint alone
+        for output in 2..5 {
+            let mut tampered = witness.clone();
+            tampered[output] = tampered[output].add(&element(1));
+            let failures = r1cs.check_witness(&tampered).unwrap();
+            assert_eq!(failures.len(), 1);
+            assert_eq!(failures[0].index, output - 2);
+        }
+    }
+
+    #[test]
+    fn fields_are_checked_against_the_policy() {
+        let r1cs = raw_constraint([3, 4, 12], Operation::Mul);
+        assert!(r1cs.check_field(&ModulusPolicy::toy()).is_ok());
+        assert!(r1cs.check_field(&ModulusPolicy::default()).is_err());
+
+        let mut mixed = raw_constraint([3, 4, 12], Operation::Mul);
+        mixed.variables[2].value = FieldElement::new_with_modulus(BigInt::from(12), BigInt::from(65_537));
+        assert!(mixed.check_field(&ModulusPolicy::toy()).is_err());
+
+        let mut composite = R1CS::new();
+        composite.add_variable(FieldElement::new_with_modulus(BigInt::from(3), BigInt::from(1_000_000_008)));
+        assert!(composite.check_field(&ModulusPolicy::toy()).is_err());
+    }
+}
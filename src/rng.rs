@@ -0,0 +1,101 @@
+use num_bigint::BigInt;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+use crate::fingerprint::{update_integer, update_length, CircuitId};
+
+pub use rand::rngs::OsRng;
+pub use rand::{CryptoRng, RngCore};
+
+/// Creates a deterministic generator from a seed, for reproducible tests and
+/// benchmarks.
+///
+/// Every function taking randomness has a `_with_rng` variant accepting any
+/// `RngCore + CryptoRng`, and the variant without it uses `OsRng`. Passing the
+/// same seeded generator reproduces keys, blinding factors and setups exactly;
+/// anyone who knows the seed knows them too, so seeds must never reach
+/// production.
+///
+/// # Parameters
+/// - `seed`: The seed.
+///
+/// # Returns
+/// - `ChaCha20Rng`: A ChaCha20 generator seeded from `seed`.
+pub fn seeded(seed: u64) -> ChaCha20Rng {
+    ChaCha20Rng::seed_from_u64(seed)
+}
+
+/// Derives a generator from a prover secret, a witness and the id of its
+/// circuit, in the manner of RFC 6979 nonces, so proving the same statement
+/// twice with the same secret draws the same blinding factors and gives
+/// byte-identical proofs.
+///
+/// The seed is SHA-256 of a domain label, the secret, the circuit id and
+/// every witness value. The secret is what keeps the blinding factors
+/// hidden: the circuit id and often the witness are guessable, and without
+/// it anyone holding the proving key could recompute the blinding factors
+/// for a candidate witness and confirm it against a proof. The secret must
+/// therefore be drawn uniformly at random, for instance from `OsRng`, and
+/// never leave the prover. Identical proofs do reveal that the same witness
+/// was proven twice with the same secret, which matters when proofs must be
+/// unlinkable.
+///
+/// # Parameters
+/// - `secret`: The prover's private, uniformly random 32-byte secret.
+/// - `circuit_id`: The id of the circuit being proven.
+/// - `witness`: The values of every variable of the circuit.
+///
+/// # Returns
+/// - `ChaCha20Rng`: A ChaCha20 generator seeded from the secret, the circuit and the witness.
+pub fn deterministic(secret: &[u8; 32], circuit_id: &CircuitId, witness: &[BigInt]) -> ChaCha20Rng {
+    let mut hasher = Sha256::new();
+    hasher.update(b"zk-starter-kit deterministic proving");
+    hasher.update(secret);
+    hasher.update(circuit_id.0);
+    update_length(&mut hasher, witness.len());
+    for value in witness {
+        update_integer(&mut hasher, value);
+    }
+    ChaCha20Rng::from_seed(hasher.finalize().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draws the first 32 bytes of a generator.
+    fn first_bytes(mut rng: ChaCha20Rng) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes);
+        bytes
+    }
+
+    fn witness(values: &[i64]) -> Vec<BigInt> {
+        values.iter().map(|value| BigInt::from(*value)).collect()
+    }
+
+    #[test]
+    fn deterministic_generators_repeat_for_the_same_inputs() {
+        let id = CircuitId([7; 32]);
+        assert_eq!(
+            first_bytes(deterministic(&[1; 32], &id, &witness(&[3, 9]))),
+            first_bytes(deterministic(&[1; 32], &id, &witness(&[3, 9]))),
+        );
+    }
+
+    #[test]
+    fn deterministic_generators_depend_on_every_input() {
+        let id = CircuitId([7; 32]);
+        let base = first_bytes(deterministic(&[1; 32], &id, &witness(&[3, 9])));
+        assert_ne!(base, first_bytes(deterministic(&[2; 32], &id, &witness(&[3, 9]))));
+        assert_ne!(base, first_bytes(deterministic(&[1; 32], &CircuitId([8; 32]), &witness(&[3, 9]))));
+        assert_ne!(base, first_bytes(deterministic(&[1; 32], &id, &witness(&[3, 10]))));
+        assert_ne!(base, first_bytes(deterministic(&[1; 32], &id, &witness(&[3, 9, 0]))));
+    }
+
+    #[test]
+    fn seeded_generators_repeat_for_the_same_seed() {
+        assert_eq!(first_bytes(seeded(1)), first_bytes(seeded(1)));
+        assert_ne!(first_bytes(seeded(1)), first_bytes(seeded(2)));
+    }
+}
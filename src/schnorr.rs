@@ -0,0 +1,208 @@
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::One;
+use crate::edwards::{EdwardsCurve, Point};
+use crate::field::FieldElement;
+use crate::hash::{CircuitHash, DomainTag};
+use crate::rng::{CryptoRng, OsRng, RngCore};
+
+/// The domain tag of the challenge of a signature.
+pub const CHALLENGE_TAG: DomainTag = DomainTag::new("schnorr challenge");
+
+/// Represents a Schnorr signature `(R, s)` with `s * G = R + e * A`, where
+/// `A` is the public key and `e = H(R.x, R.y, A.x, A.y, message)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchnorrSignature {
+    /// The commitment to the nonce, `R = k * G`.
+    pub r: Point,
+    /// The response `s = k + e * secret` modulo the subgroup order.
+    pub s: BigInt,
+}
+
+/// Represents a Schnorr signing key over a twisted Edwards curve.
+///
+/// Unlike `signature::SigningKey`, its signatures verify natively as well as
+/// in a circuit, with `gadgets::schnorr::enforce_signature`, which makes it
+/// suited to authorizations checked both inside and outside proofs. The
+/// challenge is hashed with a `CircuitHash` over the base field of the curve.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchnorrKey {
+    /// The secret scalar, below the subgroup order.
+    pub secret: BigInt,
+}
+
+impl SchnorrKey {
+    /// Creates a signing key from a secret scalar.
+    ///
+    /// # Parameters
+    /// - `secret`: The secret scalar.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `SchnorrKey` struct.
+    pub fn new(secret: BigInt) -> Self {
+        SchnorrKey { secret }
+    }
+
+    /// Creates a signing key with a secret from `OsRng`.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve of the scheme.
+    ///
+    /// # Returns
+    /// - `Self`: A new signing key.
+    pub fn random(curve: &EdwardsCurve) -> Self {
+        SchnorrKey::random_with_rng(curve, &mut OsRng)
+    }
+
+    /// Creates a signing key with a secret from a given generator.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve of the scheme.
+    /// - `rng`: The source of randomness.
+    ///
+    /// # Returns
+    /// - `Self`: A new signing key.
+    pub fn random_with_rng<R: RngCore + CryptoRng>(curve: &EdwardsCurve, rng: &mut R) -> Self {
+        SchnorrKey { secret: rng.gen_bigint_range(&BigInt::one(), &curve.order) }
+    }
+
+    /// Computes the public key.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve of the scheme.
+    ///
+    /// # Returns
+    /// - `Point`: The public key `secret * G`.
+    pub fn public_key(&self, curve: &EdwardsCurve) -> Point {
+        curve.mul(&self.secret, &curve.generator)
+    }
+
+    /// Signs a message with a nonce from `OsRng`.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve of the scheme.
+    /// - `hasher`: The hash function of the challenge, over the base field of the curve.
+    /// - `message`: The message, an element of the base field.
+    ///
+    /// # Returns
+    /// - `SchnorrSignature`: The signature.
+    pub fn sign<H: CircuitHash>(&self, curve: &EdwardsCurve, hasher: &H, message: &FieldElement) -> SchnorrSignature {
+        self.sign_with_rng(curve, hasher, message, &mut OsRng)
+    }
+
+    /// Signs a message with a nonce from a given generator.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve of the scheme.
+    /// - `hasher`: The hash function of the challenge, over the base field of the curve.
+    /// - `message`: The message, an element of the base field.
+    /// - `rng`: The source of the nonce, which must never repeat across messages.
+    ///
+    /// # Returns
+    /// - `SchnorrSignature`: The signature.
+    pub fn sign_with_rng<H: CircuitHash, R: RngCore + CryptoRng>(&self, curve: &EdwardsCurve, hasher: &H, message: &FieldElement, rng: &mut R) -> SchnorrSignature {
+        let nonce = rng.gen_bigint_range(&BigInt::one(), &curve.order);
+        let r = curve.mul(&nonce, &curve.generator);
+        let e = challenge(hasher, &r, &self.public_key(curve), message).get_value();
+        SchnorrSignature { r, s: (nonce + e * &self.secret) % &curve.order }
+    }
+}
+
+/// Computes the challenge of a signature, `H(R.x, R.y, A.x, A.y, message)`
+/// under `CHALLENGE_TAG`.
+///
+/// # Parameters
+/// - `hasher`: The hash function of the challenge.
+/// - `r`: The nonce commitment of the signature.
+/// - `public_key`: The public key of the signer.
+/// - `message`: The signed message.
+///
+/// # Returns
+/// - `FieldElement`: The challenge, used as a scalar.
+pub fn challenge<H: CircuitHash>(hasher: &H, r: &Point, public_key: &Point, message: &FieldElement) -> FieldElement {
+    hasher.hash(CHALLENGE_TAG, &[r.x.clone(), r.y.clone(), public_key.x.clone(), public_key.y.clone(), message.clone()])
+}
+
+/// Verifies a signature natively.
+///
+/// The public key must lie in the prime-order subgroup: with the identity or
+/// a point of small order as the key, `e * A` vanishes for some challenges
+/// and anyone can sign.
+///
+/// # Parameters
+/// - `curve`: The curve of the scheme.
+/// - `hasher`: The hash function of the challenge.
+/// - `public_key`: The public key of the signer.
+/// - `message`: The signed message.
+/// - `signature`: The signature.
+///
+/// # Returns
+/// - `bool`: `true` if the signature is valid, otherwise `false`.
+pub fn verify<H: CircuitHash>(curve: &EdwardsCurve, hasher: &H, public_key: &Point, message: &FieldElement, signature: &SchnorrSignature) -> bool {
+    if *public_key == curve.identity() || !curve.is_in_subgroup(public_key) || !curve.is_on_curve(&signature.r) || signature.s < BigInt::from(0) || signature.s >= curve.order {
+        return false;
+    }
+    let e = challenge(hasher, &signature.r, public_key, message).get_value();
+    curve.mul(&signature.s, &curve.generator) == curve.add(&signature.r, &curve.mul(&e, public_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poseidon::Poseidon;
+    use crate::rng;
+
+    /// Creates the key with the given seed and signs a message with it.
+    fn signed(seed: u64, message: u64) -> (EdwardsCurve, Point, FieldElement, SchnorrSignature) {
+        let curve = EdwardsCurve::default();
+        let mut rng = rng::seeded(seed);
+        let key = SchnorrKey::random_with_rng(&curve, &mut rng);
+        let message = curve.element(BigInt::from(message));
+        let signature = key.sign_with_rng(&curve, &Poseidon::new(), &message, &mut rng);
+        (curve.clone(), key.public_key(&curve), message, signature)
+    }
+
+    #[test]
+    fn signatures_verify_under_their_key() {
+        for seed in 0..4 {
+            let (curve, public_key, message, signature) = signed(seed, 42 + seed);
+            assert!(verify(&curve, &Poseidon::new(), &public_key, &message, &signature));
+        }
+    }
+
+    #[test]
+    fn signatures_of_another_message_are_rejected() {
+        let (curve, public_key, message, signature) = signed(0, 42);
+        let tampered = message.add(&curve.element(BigInt::one()));
+        assert!(!verify(&curve, &Poseidon::new(), &public_key, &tampered, &signature));
+    }
+
+    #[test]
+    fn signatures_under_another_key_are_rejected() {
+        let (curve, _, message, signature) = signed(0, 42);
+        let (_, other, _, _) = signed(1, 42);
+        assert!(!verify(&curve, &Poseidon::new(), &other, &message, &signature));
+    }
+
+    #[test]
+    fn tampered_signatures_are_rejected() {
+        let (curve, public_key, message, signature) = signed(0, 42);
+        let shifted = SchnorrSignature { r: signature.r.clone(), s: (&signature.s + 1u32) % &curve.order };
+        let moved = SchnorrSignature { r: curve.add(&signature.r, &curve.generator), s: signature.s.clone() };
+        let unreduced = SchnorrSignature { r: signature.r.clone(), s: &signature.s + &curve.order };
+        for tampered in [shifted, moved, unreduced].iter() {
+            assert!(!verify(&curve, &Poseidon::new(), &public_key, &message, tampered));
+        }
+    }
+
+    #[test]
+    fn keys_outside_the_subgroup_are_rejected() {
+        // With the identity as the key, s * G = R holds for any message
+        let curve = EdwardsCurve::default();
+        let s = BigInt::from(7u32);
+        let forged = SchnorrSignature { r: curve.mul(&s, &curve.generator), s };
+        let message = curve.element(BigInt::from(42u32));
+        assert!(!verify(&curve, &Poseidon::new(), &curve.identity(), &message, &forged));
+        let torsion = Point { x: curve.element(BigInt::from(0)), y: curve.element(BigInt::from(-1)) };
+        assert!(!verify(&curve, &Poseidon::new(), &torsion, &message, &forged));
+    }
+}
@@ -0,0 +1,77 @@
+use num_bigint::RandBigInt;
+use crate::field::FieldElement;
+use crate::hash::{CircuitHash, DomainTag};
+use crate::rng::{CryptoRng, OsRng, RngCore};
+
+/// The domain tag of the public key, the hash of the secret.
+pub const PUBLIC_KEY_TAG: DomainTag = DomainTag::new("signature public key");
+/// The domain tag of the public tag of a signature, the hash of the secret and the message.
+pub const SIGNATURE_TAG: DomainTag = DomainTag::new("signature tag");
+
+/// Represents a signing key whose public key is its hash, `H(secret)`.
+///
+/// A signature on a message is a proof, built with
+/// `gadgets::signature::enforce_signature`, of knowledge of the key behind the
+/// public key, bound to the message by the public tag `H(secret, message)`.
+/// Unlike Schnorr or EdDSA signatures, it can only be checked through the proof.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SigningKey {
+    /// The secret key.
+    pub secret: FieldElement,
+}
+
+impl SigningKey {
+    /// Creates a signing key from a secret.
+    ///
+    /// # Parameters
+    /// - `secret`: The secret key.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `SigningKey` struct.
+    pub fn new(secret: FieldElement) -> Self {
+        SigningKey { secret }
+    }
+
+    /// Creates a signing key with a secret in the default field from `OsRng`.
+    ///
+    /// # Returns
+    /// - `Self`: A new signing key.
+    pub fn random() -> Self {
+        SigningKey::random_with_rng(&mut OsRng)
+    }
+
+    /// Creates a signing key with a secret in the default field from a given generator.
+    ///
+    /// # Parameters
+    /// - `rng`: The source of randomness.
+    ///
+    /// # Returns
+    /// - `Self`: A new signing key.
+    pub fn random_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let modulus = FieldElement::default_modulus();
+        SigningKey { secret: FieldElement::new(rng.gen_bigint_range(&0.into(), &modulus)) }
+    }
+
+    /// Computes the public key.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the scheme.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The public key `H(secret)`.
+    pub fn public_key<H: CircuitHash>(&self, hasher: &H) -> FieldElement {
+        hasher.hash(PUBLIC_KEY_TAG, std::slice::from_ref(&self.secret))
+    }
+
+    /// Computes the tag binding a message to the key.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the scheme.
+    /// - `message`: The signed message.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The tag `H(secret, message)`.
+    pub fn tag<H: CircuitHash>(&self, hasher: &H, message: &FieldElement) -> FieldElement {
+        hasher.hash_two(SIGNATURE_TAG, &self.secret, message)
+    }
+}
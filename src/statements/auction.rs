@@ -0,0 +1,216 @@
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use rand::Rng;
+use crate::circuit::Circuit;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::gadgets;
+use crate::hash::CircuitHash;
+use crate::pedersen::Pedersen;
+use crate::proof::Proof;
+use crate::rng::{CryptoRng, OsRng, RngCore};
+use crate::signature::SigningKey;
+use crate::statements::leading_values;
+
+/// The number of bits of a bid amount and of its blinding factor.
+pub const BID_BITS: usize = 28;
+
+/// Represents a sealed bid: an amount hidden in a Pedersen commitment until the auction closes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SealedBid {
+    /// The bid amount, below `2^BID_BITS`.
+    pub amount: u32,
+    /// The blinding factor of the commitment, below `2^BID_BITS`.
+    pub blinding: u32,
+}
+
+impl SealedBid {
+    /// Creates a sealed bid from an amount and a blinding factor.
+    ///
+    /// # Parameters
+    /// - `amount`: The bid amount.
+    /// - `blinding`: The blinding factor.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The bid, or an error if either value does not fit in `BID_BITS` bits.
+    pub fn new(amount: u32, blinding: u32) -> Result<Self> {
+        if amount >> BID_BITS != 0 || blinding >> BID_BITS != 0 {
+            return Err(Error::Input(format!("Bid amounts and blinding factors must fit in {} bits", BID_BITS)));
+        }
+        Ok(SealedBid { amount, blinding })
+    }
+
+    /// Seals an amount with a blinding factor from `OsRng`.
+    ///
+    /// # Parameters
+    /// - `amount`: The bid amount.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The bid, or an error if the amount does not fit in `BID_BITS` bits.
+    pub fn seal(amount: u32) -> Result<Self> {
+        SealedBid::seal_with_rng(amount, &mut OsRng)
+    }
+
+    /// Seals an amount with a blinding factor from a given generator.
+    ///
+    /// # Parameters
+    /// - `amount`: The bid amount.
+    /// - `rng`: The source of the blinding factor.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The bid, or an error if the amount does not fit in `BID_BITS` bits.
+    pub fn seal_with_rng<R: RngCore + CryptoRng>(amount: u32, rng: &mut R) -> Result<Self> {
+        SealedBid::new(amount, rng.gen_range(0..1 << BID_BITS))
+    }
+
+    /// Computes the public commitment to the bid, `g^amount * h^blinding`.
+    ///
+    /// # Parameters
+    /// - `pedersen`: The commitment parameters of the auction.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The commitment.
+    pub fn commitment(&self, pedersen: &Pedersen) -> FieldElement {
+        pedersen.commit(&BigInt::from(self.amount), &BigInt::from(self.blinding))
+    }
+}
+
+/// Represents the claim "this commitment seals a bid between `min_bid` and
+/// `max_bid`, signed by the bidder holding `public_key`", without revealing the amount.
+///
+/// The signature tag `H(secret, commitment)` binds the sealed bid to the bidder,
+/// so a bid cannot be replayed under another identity nor a signature moved to another bid.
+pub struct BidStatement<H: CircuitHash> {
+    /// The hash function of the signatures, with the same parameters as the prover's.
+    pub hasher: H,
+    /// The commitment parameters of the auction.
+    pub pedersen: Pedersen,
+    /// The public commitment to the bid.
+    pub commitment: FieldElement,
+    /// The public key of the bidder.
+    pub public_key: FieldElement,
+    /// The public signature tag of the bidder on the commitment.
+    pub tag: FieldElement,
+    /// The public minimum bid.
+    pub min_bid: u32,
+    /// The public maximum bid.
+    pub max_bid: u32,
+}
+
+impl<H: CircuitHash> BidStatement<H> {
+    /// Creates a bid statement.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the signatures.
+    /// - `pedersen`: The commitment parameters of the auction.
+    /// - `commitment`: The public commitment to the bid.
+    /// - `public_key`: The public key of the bidder.
+    /// - `tag`: The signature tag of the bidder on the commitment.
+    /// - `min_bid`: The minimum bid.
+    /// - `max_bid`: The maximum bid.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `BidStatement` struct.
+    pub fn new(hasher: H, pedersen: Pedersen, commitment: FieldElement, public_key: FieldElement, tag: FieldElement, min_bid: u32, max_bid: u32) -> Self {
+        BidStatement { hasher, pedersen, commitment, public_key, tag, min_bid, max_bid }
+    }
+
+    /// Creates the statement a bidder publishes for a sealed bid.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the signatures.
+    /// - `pedersen`: The commitment parameters of the auction.
+    /// - `bid`: The sealed bid.
+    /// - `key`: The signing key of the bidder.
+    /// - `min_bid`: The minimum bid.
+    /// - `max_bid`: The maximum bid.
+    ///
+    /// # Returns
+    /// - `Self`: The statement.
+    pub fn for_bid(hasher: H, pedersen: Pedersen, bid: &SealedBid, key: &SigningKey, min_bid: u32, max_bid: u32) -> Self {
+        let commitment = bid.commitment(&pedersen);
+        let public_key = key.public_key(&hasher);
+        let tag = key.tag(&hasher, &commitment);
+        BidStatement { hasher, pedersen, commitment, public_key, tag, min_bid, max_bid }
+    }
+
+    /// Builds the circuit opening the commitment, checking the bounds and the signature.
+    ///
+    /// The amount, the blinding factor and the secret key occupy wires 0, 1 and 2.
+    ///
+    /// # Parameters
+    /// - `bid`: The private bid.
+    /// - `key`: The private signing key of the bidder.
+    ///
+    /// # Returns
+    /// - `Result<Circuit>`: The assigned circuit, or an error if the bounds are empty or do not fit in `BID_BITS` bits.
+    pub fn circuit(&self, bid: &SealedBid, key: &SigningKey) -> Result<Circuit> {
+        if self.min_bid > self.max_bid || self.max_bid >> BID_BITS != 0 {
+            return Err(Error::Input(format!("Invalid bid bounds [{}, {}]", self.min_bid, self.max_bid)));
+        }
+        let mut circuit = Circuit::new();
+        let amount = circuit.add_input(FieldElement::new(BigInt::from(bid.amount)));
+        let blinding = circuit.add_input(FieldElement::new(BigInt::from(bid.blinding)));
+        let secret = circuit.add_input(key.secret.clone());
+
+        // The commitment gadget range-checks both exponents to BID_BITS bits
+        let computed = gadgets::pedersen::commit(&mut circuit, &self.pedersen, amount, blinding, BID_BITS);
+        let commitment = circuit.alloc_linear(&[], self.commitment.clone());
+        circuit.enforce_equal(computed, commitment);
+
+        let min_bid = circuit.alloc_linear(&[], FieldElement::new(BigInt::from(self.min_bid)));
+        let max_bid = circuit.alloc_linear(&[], FieldElement::new(BigInt::from(self.max_bid)));
+        gadgets::range::enforce_less_or_equal(&mut circuit, min_bid, amount, BID_BITS);
+        gadgets::range::enforce_less_or_equal(&mut circuit, amount, max_bid, BID_BITS);
+
+        gadgets::signature::enforce_signature(&mut circuit, &self.hasher, secret, commitment, &self.public_key, &self.tag);
+        Ok(circuit)
+    }
+
+    /// Proves that a sealed bid satisfies the statement.
+    ///
+    /// # Parameters
+    /// - `bid`: The private bid.
+    /// - `key`: The private signing key of the bidder.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, or an error if the bid does not open the commitment,
+    ///   is out of bounds or the key did not sign it.
+    pub fn prove(&self, bid: &SealedBid, key: &SigningKey) -> Result<Proof> {
+        let circuit = self.circuit(bid, key)?;
+        if bid.commitment(&self.pedersen) != self.commitment {
+            return Err(Error::Input("The bid does not open the commitment".to_string()));
+        }
+        if bid.amount < self.min_bid || bid.amount > self.max_bid {
+            return Err(Error::Input(format!("The bid {} is outside [{}, {}]", bid.amount, self.min_bid, self.max_bid)));
+        }
+        if key.public_key(&self.hasher) != self.public_key || key.tag(&self.hasher, &self.commitment) != self.tag {
+            return Err(Error::Input("The key did not sign the bid".to_string()));
+        }
+        Ok(circuit.prove())
+    }
+
+    /// Checks a bid proof against the witness it carries, as its prover can
+    /// before publishing it.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to check, with its witness.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the witness satisfies the statement, otherwise `false`, including for a proof without its witness.
+    pub fn self_check(&self, proof: &Proof) -> bool {
+        let values = match leading_values(proof, 3) {
+            Some(values) => values,
+            None => return false,
+        };
+        // Values that do not fit in a u32 cannot satisfy the range checks anyway
+        let bid = match (values[0].get_value().to_u32(), values[1].get_value().to_u32()) {
+            (Some(amount), Some(blinding)) => SealedBid { amount, blinding },
+            _ => return false,
+        };
+        match self.circuit(&bid, &SigningKey::new(values[2].clone())) {
+            Ok(circuit) => circuit.verify(proof),
+            Err(_) => false,
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::ToPrimitive;
+use crate::circuit::Circuit;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::gadgets;
+use crate::hash::{CircuitHash, DomainTag};
+use crate::proof::Proof;
+use crate::rng::{CryptoRng, OsRng, RngCore};
+use crate::statements::leading_values;
+
+/// The number of bits of a `YYYYMMDD` date, enough for any year up to 9999.
+const DATE_BITS: usize = 27;
+/// The domain tag of the salted commitment to a birthdate.
+pub const COMMITMENT_TAG: DomainTag = DomainTag::new("birthdate commitment");
+
+/// Represents a credential attesting a birthdate, committed to with a random salt
+/// so the commitment reveals nothing about the date.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Credential {
+    /// The birthdate as the decimal number `YYYYMMDD`, so dates compare like numbers.
+    pub birthdate: u32,
+    /// The salt of the commitment.
+    pub salt: FieldElement,
+}
+
+impl Credential {
+    /// Creates a credential from a birthdate and a salt.
+    ///
+    /// # Parameters
+    /// - `birthdate`: The birthdate as `YYYYMMDD`.
+    /// - `salt`: The salt of the commitment.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Credential` struct.
+    pub fn new(birthdate: u32, salt: FieldElement) -> Self {
+        Credential { birthdate, salt }
+    }
+
+    /// Issues a credential for a birthdate with a salt in the default field from `OsRng`.
+    ///
+    /// # Parameters
+    /// - `birthdate`: The birthdate as `YYYYMMDD`.
+    ///
+    /// # Returns
+    /// - `Self`: The credential.
+    pub fn issue(birthdate: u32) -> Self {
+        Credential::issue_with_rng(birthdate, &mut OsRng)
+    }
+
+    /// Issues a credential for a birthdate with a salt in the default field from a given generator.
+    ///
+    /// # Parameters
+    /// - `birthdate`: The birthdate as `YYYYMMDD`.
+    /// - `rng`: The source of the salt.
+    ///
+    /// # Returns
+    /// - `Self`: The credential.
+    pub fn issue_with_rng<R: RngCore + CryptoRng>(birthdate: u32, rng: &mut R) -> Self {
+        let modulus = FieldElement::default_modulus();
+        Credential { birthdate, salt: FieldElement::new(rng.gen_bigint_range(&0.into(), &modulus)) }
+    }
+
+    /// Computes the public commitment to the credential, `H(birthdate, salt)`.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the issuer.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The commitment.
+    pub fn commitment<H: CircuitHash>(&self, hasher: &H) -> FieldElement {
+        hasher.hash_two(COMMITMENT_TAG, &FieldElement::new(BigInt::from(self.birthdate)), &self.salt)
+    }
+}
+
+/// Represents the claim "the birthdate behind this commitment makes its holder
+/// at least `min_age` years old on `today`", without revealing the date.
+///
+/// With dates written `YYYYMMDD`, the holder is old enough exactly when
+/// `birthdate <= today - min_age * 10000`, which the circuit checks with a range proof.
+pub struct AgeStatement<H: CircuitHash> {
+    /// The hash function of the issuer, with the same parameters as the prover's.
+    pub hasher: H,
+    /// The public commitment to the credential.
+    pub commitment: FieldElement,
+    /// The public date of the check as `YYYYMMDD`.
+    pub today: u32,
+    /// The public minimum age in years.
+    pub min_age: u32,
+}
+
+impl<H: CircuitHash> AgeStatement<H> {
+    /// Creates an age statement.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the issuer.
+    /// - `commitment`: The public commitment to the credential.
+    /// - `today`: The date of the check as `YYYYMMDD`.
+    /// - `min_age`: The minimum age in years.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `AgeStatement` struct.
+    pub fn new(hasher: H, commitment: FieldElement, today: u32, min_age: u32) -> Self {
+        AgeStatement { hasher, commitment, today, min_age }
+    }
+
+    /// Gets the latest birthdate old enough on `today`.
+    ///
+    /// # Returns
+    /// - `Result<u32>`: The cutoff as `YYYYMMDD`, or an error if the minimum age predates year 0.
+    pub fn cutoff(&self) -> Result<u32> {
+        self.min_age.checked_mul(10_000)
+            .and_then(|offset| self.today.checked_sub(offset))
+            .ok_or_else(|| Error::Input(format!("A minimum age of {} predates year 0 on {}", self.min_age, self.today)))
+    }
+
+    /// Builds the circuit opening the commitment and comparing the birthdate with the cutoff.
+    ///
+    /// The birthdate and the salt occupy wires 0 and 1.
+    ///
+    /// # Parameters
+    /// - `credential`: The private credential.
+    ///
+    /// # Returns
+    /// - `Result<Circuit>`: The assigned circuit, or an error if the minimum age predates year 0.
+    pub fn circuit(&self, credential: &Credential) -> Result<Circuit> {
+        let cutoff = self.cutoff()?;
+        let mut circuit = Circuit::new();
+        let birthdate = circuit.add_input(FieldElement::new(BigInt::from(credential.birthdate)));
+        let salt = circuit.add_input(credential.salt.clone());
+
+        let computed = self.hasher.hash_two_gadget(&mut circuit, COMMITMENT_TAG, birthdate, salt);
+        let commitment = circuit.alloc_linear(&[], self.commitment.clone());
+        circuit.enforce_equal(computed, commitment);
+
+        // Both dates fit in DATE_BITS bits, so birthdate <= cutoff is a range check on the difference
+        gadgets::range::enforce_range(&mut circuit, birthdate, DATE_BITS);
+        let cutoff = circuit.alloc_linear(&[], FieldElement::new(BigInt::from(cutoff)));
+        gadgets::range::enforce_less_or_equal(&mut circuit, birthdate, cutoff, DATE_BITS);
+        Ok(circuit)
+    }
+
+    /// Proves that a credential satisfies the statement.
+    ///
+    /// # Parameters
+    /// - `credential`: The private credential.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, or an error if the credential does not open the commitment
+    ///   or its holder is too young.
+    pub fn prove(&self, credential: &Credential) -> Result<Proof> {
+        let circuit = self.circuit(credential)?;
+        if credential.commitment(&self.hasher) != self.commitment {
+            return Err(Error::Input("The credential does not open the commitment".to_string()));
+        }
+        if credential.birthdate > self.cutoff()? {
+            return Err(Error::Input(format!("The holder is younger than {}", self.min_age)));
+        }
+        Ok(circuit.prove())
+    }
+
+    /// Checks an age proof against the witness it carries, as its prover can
+    /// before publishing it.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to check, with its witness.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the witness satisfies the statement, otherwise `false`, including for a proof without its witness.
+    pub fn self_check(&self, proof: &Proof) -> bool {
+        let values = match leading_values(proof, 2) {
+            Some(values) => values,
+            None => return false,
+        };
+        // A birthdate that does not fit in a date cannot satisfy the range check anyway
+        let birthdate = match values[0].get_value().to_u32() {
+            Some(birthdate) => birthdate,
+            None => return false,
+        };
+        match self.circuit(&Credential::new(birthdate, values[1].clone())) {
+            Ok(circuit) => circuit.verify(proof),
+            Err(_) => false,
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::ToPrimitive;
+use crate::circuit::Circuit;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::gadgets;
+use crate::hash::{CircuitHash, DomainTag};
+use crate::proof::Proof;
+use crate::rng::{CryptoRng, OsRng, RngCore};
+use crate::statements::leading_values;
+
+/// The number of bits of a set element, the most the range gadgets can compare in the default field.
+pub const ELEMENT_BITS: usize = 28;
+/// The domain tag of the salted commitment to a set.
+pub const COMMITMENT_TAG: DomainTag = DomainTag::new("set commitment");
+
+/// Represents a set of small integers committed to with a random salt.
+///
+/// The elements are kept sorted, so a set has a single commitment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommittedSet {
+    /// The elements of the set, in increasing order, each below `2^ELEMENT_BITS`.
+    pub elements: Vec<u32>,
+    /// The salt of the commitment.
+    pub salt: FieldElement,
+}
+
+impl CommittedSet {
+    /// Creates a set from its elements and a salt.
+    ///
+    /// # Parameters
+    /// - `elements`: The elements, in any order.
+    /// - `salt`: The salt of the commitment.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The set, or an error if an element repeats or does not fit in `ELEMENT_BITS` bits.
+    pub fn new(mut elements: Vec<u32>, salt: FieldElement) -> Result<Self> {
+        elements.sort_unstable();
+        if let Some(element) = elements.iter().find(|&&element| element >> ELEMENT_BITS != 0) {
+            return Err(Error::Input(format!("Element {} does not fit in {} bits", element, ELEMENT_BITS)));
+        }
+        if let Some(pair) = elements.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(Error::Input(format!("Element {} appears more than once", pair[0])));
+        }
+        Ok(CommittedSet { elements, salt })
+    }
+
+    /// Creates a set with a salt in the default field from `OsRng`.
+    ///
+    /// # Parameters
+    /// - `elements`: The elements, in any order.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The set, or an error if an element repeats or does not fit in `ELEMENT_BITS` bits.
+    pub fn with_random_salt(elements: Vec<u32>) -> Result<Self> {
+        CommittedSet::with_random_salt_with_rng(elements, &mut OsRng)
+    }
+
+    /// Creates a set with a salt in the default field from a given generator.
+    ///
+    /// # Parameters
+    /// - `elements`: The elements, in any order.
+    /// - `rng`: The source of the salt.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The set, or an error if an element repeats or does not fit in `ELEMENT_BITS` bits.
+    pub fn with_random_salt_with_rng<R: RngCore + CryptoRng>(elements: Vec<u32>, rng: &mut R) -> Result<Self> {
+        let modulus = FieldElement::default_modulus();
+        CommittedSet::new(elements, FieldElement::new(rng.gen_bigint_range(&0.into(), &modulus)))
+    }
+
+    /// Computes the public commitment to the set, the hash of its sorted elements followed by the salt.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the parties.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The commitment.
+    pub fn commitment<H: CircuitHash>(&self, hasher: &H) -> FieldElement {
+        hasher.hash(COMMITMENT_TAG, &self.hash_inputs())
+    }
+
+    /// Counts the elements shared with another set.
+    ///
+    /// # Parameters
+    /// - `other`: The other set.
+    ///
+    /// # Returns
+    /// - `usize`: The size of the intersection.
+    pub fn intersection_size(&self, other: &CommittedSet) -> usize {
+        self.elements.iter().filter(|element| other.elements.binary_search(element).is_ok()).count()
+    }
+
+    /// Gets the elements followed by the salt, as hashed by the commitment.
+    fn hash_inputs(&self) -> Vec<FieldElement> {
+        self.elements.iter()
+            .map(|&element| FieldElement::new(BigInt::from(element)))
+            .chain(std::iter::once(self.salt.clone()))
+            .collect()
+    }
+}
+
+/// Represents the claim "the sets behind these two commitments share at least
+/// `threshold` elements", without revealing the sets or which elements they share.
+///
+/// Each party publishes the commitment to its set; the prover, who learns both
+/// sets in a joint computation, proves the bound on their intersection. The
+/// circuit checks that both lists are sorted without duplicates, so counting
+/// the equal pairs across them gives exactly the size of the intersection.
+pub struct IntersectionStatement<H: CircuitHash> {
+    /// The hash function of the parties, with the same parameters as the prover's.
+    pub hasher: H,
+    /// The public commitment to the first set.
+    pub commitment_a: FieldElement,
+    /// The public commitment to the second set.
+    pub commitment_b: FieldElement,
+    /// The public size of the first set.
+    pub size_a: usize,
+    /// The public size of the second set.
+    pub size_b: usize,
+    /// The public lower bound on the size of the intersection.
+    pub threshold: usize,
+}
+
+impl<H: CircuitHash> IntersectionStatement<H> {
+    /// Creates the statement two committed sets satisfy.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the parties.
+    /// - `a`: The first set.
+    /// - `b`: The second set.
+    /// - `threshold`: The lower bound on the size of the intersection.
+    ///
+    /// # Returns
+    /// - `Self`: The statement about the commitments and sizes of `a` and `b`.
+    pub fn for_sets(hasher: H, a: &CommittedSet, b: &CommittedSet, threshold: usize) -> Self {
+        IntersectionStatement {
+            commitment_a: a.commitment(&hasher),
+            commitment_b: b.commitment(&hasher),
+            size_a: a.elements.len(),
+            size_b: b.elements.len(),
+            threshold,
+            hasher,
+        }
+    }
+
+    /// Builds the circuit opening both commitments and counting the shared elements.
+    ///
+    /// The elements and the salt of the first set occupy the first wires, followed
+    /// by the elements and the salt of the second set.
+    ///
+    /// # Parameters
+    /// - `a`: The private first set.
+    /// - `b`: The private second set.
+    ///
+    /// # Returns
+    /// - `Result<Circuit>`: The assigned circuit, or an error if a set does not have the public size.
+    pub fn circuit(&self, a: &CommittedSet, b: &CommittedSet) -> Result<Circuit> {
+        if a.elements.len() != self.size_a || b.elements.len() != self.size_b {
+            return Err(Error::Input(format!(
+                "Expected sets of {} and {} elements, got {} and {}",
+                self.size_a, self.size_b, a.elements.len(), b.elements.len()
+            )));
+        }
+        let mut circuit = Circuit::new();
+        let wires_a: Vec<usize> = a.hash_inputs().into_iter().map(|value| circuit.add_input(value)).collect();
+        let wires_b: Vec<usize> = b.hash_inputs().into_iter().map(|value| circuit.add_input(value)).collect();
+
+        // Open both commitments, then check each list is sorted without duplicates
+        for (wires, commitment) in [(&wires_a, &self.commitment_a), (&wires_b, &self.commitment_b)] {
+            let computed = self.hasher.hash_gadget(&mut circuit, COMMITMENT_TAG, wires);
+            let expected = circuit.alloc_linear(&[], commitment.clone());
+            circuit.enforce_equal(computed, expected);
+
+            let elements = &wires[..wires.len() - 1];
+            for &element in elements {
+                gadgets::range::enforce_range(&mut circuit, element, ELEMENT_BITS);
+            }
+            gadgets::range::enforce_increasing(&mut circuit, elements, ELEMENT_BITS);
+        }
+
+        // Count the equal pairs and compare the count with the threshold
+        let one = FieldElement::new(BigInt::from(1));
+        let terms: Vec<(usize, FieldElement)> = wires_a[..self.size_a].iter()
+            .flat_map(|&x| wires_b[..self.size_b].iter().map(move |&y| (x, y)))
+            .map(|(x, y)| (gadgets::equality::is_equal(&mut circuit, x, y), one.clone()))
+            .collect();
+        let count = circuit.alloc_linear(&terms, FieldElement::new(BigInt::from(0)));
+        let threshold = circuit.alloc_linear(&[], FieldElement::new(BigInt::from(self.threshold)));
+        gadgets::range::enforce_less_or_equal(&mut circuit, threshold, count, ELEMENT_BITS);
+        Ok(circuit)
+    }
+
+    /// Proves the bound on the intersection of two sets.
+    ///
+    /// # Parameters
+    /// - `a`: The private first set.
+    /// - `b`: The private second set.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, or an error if a set does not open its commitment or the
+    ///   intersection is smaller than the threshold.
+    pub fn prove(&self, a: &CommittedSet, b: &CommittedSet) -> Result<Proof> {
+        let circuit = self.circuit(a, b)?;
+        if a.commitment(&self.hasher) != self.commitment_a || b.commitment(&self.hasher) != self.commitment_b {
+            return Err(Error::Input("The sets do not open the commitments".to_string()));
+        }
+        let size = a.intersection_size(b);
+        if size < self.threshold {
+            return Err(Error::Input(format!("The sets share {} elements, fewer than {}", size, self.threshold)));
+        }
+        Ok(circuit.prove())
+    }
+
+    /// Checks a proof of the bound on the intersection against the witness it carries, as its prover can
+    /// before publishing it.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to check, with its witness.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the witness satisfies the statement, otherwise `false`, including for a proof without its witness.
+    pub fn self_check(&self, proof: &Proof) -> bool {
+        let values = match leading_values(proof, self.size_a + self.size_b + 2) {
+            Some(values) => values,
+            None => return false,
+        };
+        // Rebuild the sets as proven, without sorting, so the circuit checks the order itself
+        let set = |values: &[FieldElement]| -> Option<CommittedSet> {
+            let (salt, elements) = values.split_last()?;
+            let elements = elements.iter().map(|value| value.get_value().to_u32()).collect::<Option<Vec<u32>>>()?;
+            Some(CommittedSet { elements, salt: salt.clone() })
+        };
+        let (values_a, values_b) = values.split_at(self.size_a + 1);
+        match (set(values_a), set(values_b)) {
+            (Some(a), Some(b)) => self.circuit(&a, &b).map(|circuit| circuit.verify(proof)).unwrap_or(false),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::circuit::Circuit;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::gadgets;
+use crate::hash::CircuitHash;
+use crate::merkle::{self, MerkleTree};
+use crate::proof::Proof;
+use crate::statements::{leading_values, path_from_values};
+
+/// Represents the claim "I know a leaf of the Merkle tree with this root",
+/// the basis of allowlist-style proofs.
+///
+/// The root and the depth of the tree are public; the leaf and its path are private.
+pub struct MembershipStatement<H: CircuitHash> {
+    /// The hash function of the tree, with the same parameters as the prover's.
+    pub hasher: H,
+    /// The public root.
+    pub root: FieldElement,
+    /// The depth of the tree, which fixes the length of every path.
+    pub depth: usize,
+}
+
+impl<H: CircuitHash> MembershipStatement<H> {
+    /// Creates a statement about a public root.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the tree.
+    /// - `root`: The public root.
+    /// - `depth`: The depth of the tree.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `MembershipStatement` struct.
+    pub fn new(hasher: H, root: FieldElement, depth: usize) -> Self {
+        MembershipStatement { hasher, root, depth }
+    }
+
+    /// Builds the circuit recomputing the root from the leaf and its path.
+    ///
+    /// The leaf occupies wire 0, followed by the sibling and the direction bit of each level.
+    ///
+    /// # Parameters
+    /// - `leaf`: The private leaf.
+    /// - `path`: The private Merkle path, as returned by `MerkleTree::merkle_path`.
+    ///
+    /// # Returns
+    /// - `Result<Circuit>`: The assigned circuit, or an error if the path does not match the depth.
+    pub fn circuit(&self, leaf: &FieldElement, path: &[(FieldElement, bool)]) -> Result<Circuit> {
+        if path.len() != self.depth {
+            return Err(Error::Input(format!("Expected a path of {} levels, got {}", self.depth, path.len())));
+        }
+        let mut circuit = Circuit::new();
+        let leaf = circuit.add_input(leaf.clone());
+        gadgets::merkle::enforce_membership(&mut circuit, &self.hasher, leaf, path, &self.root);
+        Ok(circuit)
+    }
+
+    /// Checks a proof of membership under the root against the witness it carries, as its prover can
+    /// before publishing it.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to check, with its witness.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the witness satisfies the statement, otherwise `false`, including for a proof without its witness.
+    pub fn self_check(&self, proof: &Proof) -> bool {
+        let values = match leading_values(proof, 1 + 2 * self.depth) {
+            Some(values) => values,
+            None => return false,
+        };
+        match self.circuit(&values[0], &path_from_values(&values[1..])) {
+            Ok(circuit) => circuit.verify(proof),
+            Err(_) => false,
+        }
+    }
+}
+
+impl<H: CircuitHash + Sync + Clone> MembershipStatement<H> {
+    /// Creates the statement about the root of a tree.
+    ///
+    /// The tree should be built with `MerkleTree::new_with_depth`, so every path has the same length.
+    ///
+    /// # Parameters
+    /// - `tree`: The Merkle tree.
+    ///
+    /// # Returns
+    /// - `Self`: The statement about the root of `tree`.
+    pub fn for_tree(tree: &MerkleTree<H>) -> Self {
+        MembershipStatement { hasher: tree.hasher().clone(), root: tree.root.clone(), depth: tree.merkle_path(0).len() }
+    }
+
+    /// Proves membership of a leaf with its path.
+    ///
+    /// # Parameters
+    /// - `leaf`: The private leaf.
+    /// - `path`: The private Merkle path, as returned by `MerkleTree::merkle_path`.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, or an error if the path does not match the depth or does not lead to the root.
+    pub fn prove(&self, leaf: &FieldElement, path: &[(FieldElement, bool)]) -> Result<Proof> {
+        let circuit = self.circuit(leaf, path)?;
+        if merkle::root_from_path(&self.hasher, leaf, path) != self.root {
+            return Err(Error::Input("The path does not lead from the leaf to the root".to_string()));
+        }
+        Ok(circuit.prove())
+    }
+
+    /// Proves membership of the leaf at a position of a tree, computing its path.
+    ///
+    /// # Parameters
+    /// - `tree`: The Merkle tree, whose root must be the statement's.
+    /// - `index`: The position of the leaf.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, or an error if the position is out of range or the tree has another root.
+    pub fn prove_leaf(&self, tree: &MerkleTree<H>, index: usize) -> Result<Proof> {
+        let leaf = tree.leaves.get(index)
+            .ok_or_else(|| Error::Input(format!("Leaf {} is out of range for a tree of {} leaves", index, tree.leaves.len())))?;
+        self.prove(leaf, &tree.merkle_path(index))
+    }
+}
@@ -0,0 +1,231 @@
+use num_bigint::RandBigInt;
+use crate::circuit::Circuit;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::gadgets;
+use crate::hash::{CircuitHash, DomainTag};
+use crate::merkle::{self, MerkleTree};
+use crate::proof::Proof;
+use crate::rng::{CryptoRng, OsRng, RngCore};
+use crate::statements::{leading_values, path_from_values};
+
+/// The domain tag of the commitment to a note.
+pub const COMMITMENT_TAG: DomainTag = DomainTag::new("mixer commitment");
+/// The domain tag of the nullifier hash of a note.
+pub const NULLIFIER_TAG: DomainTag = DomainTag::new("mixer nullifier");
+
+/// Represents a deposit note of the mixer: two random values only the depositor knows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Note {
+    /// The nullifier, whose hash is revealed on withdrawal to prevent spending twice.
+    pub nullifier: FieldElement,
+    /// The secret, which keeps the commitment hiding.
+    pub secret: FieldElement,
+}
+
+impl Note {
+    /// Creates a note from its values.
+    ///
+    /// # Parameters
+    /// - `nullifier`: The nullifier.
+    /// - `secret`: The secret.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Note` struct.
+    pub fn new(nullifier: FieldElement, secret: FieldElement) -> Self {
+        Note { nullifier, secret }
+    }
+
+    /// Creates a note with values in the default field from `OsRng`.
+    ///
+    /// # Returns
+    /// - `Self`: A new note.
+    pub fn random() -> Self {
+        Note::random_with_rng(&mut OsRng)
+    }
+
+    /// Creates a note with values in the default field from a given generator.
+    ///
+    /// # Parameters
+    /// - `rng`: The source of randomness.
+    ///
+    /// # Returns
+    /// - `Self`: A new note.
+    pub fn random_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let modulus = FieldElement::default_modulus();
+        let mut sample = || FieldElement::new(rng.gen_bigint_range(&0.into(), &modulus));
+        Note { nullifier: sample(), secret: sample() }
+    }
+
+    /// Computes the commitment inserted in the tree on deposit, `H(nullifier, secret)`.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the mixer.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The commitment.
+    pub fn commitment<H: CircuitHash>(&self, hasher: &H) -> FieldElement {
+        hasher.hash_two(COMMITMENT_TAG, &self.nullifier, &self.secret)
+    }
+
+    /// Computes the nullifier hash revealed on withdrawal, `H(nullifier)`.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the mixer.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The nullifier hash.
+    pub fn nullifier_hash<H: CircuitHash>(&self, hasher: &H) -> FieldElement {
+        hasher.hash(NULLIFIER_TAG, std::slice::from_ref(&self.nullifier))
+    }
+}
+
+/// Represents a withdrawal from a Tornado-style mixer: "I know the note behind
+/// one of the commitments of the tree with this root, it has this nullifier
+/// hash, and the funds go to this recipient".
+///
+/// The root, the nullifier hash and the recipient are public; the note and the
+/// path of its commitment are private. Binding the recipient stops a relayer
+/// from redirecting the withdrawal, and a contract rejecting known nullifier
+/// hashes stops a note from being withdrawn twice.
+pub struct WithdrawStatement<H: CircuitHash> {
+    /// The hash function of the mixer, with the same parameters as the prover's.
+    pub hasher: H,
+    /// The public root of the commitment tree.
+    pub root: FieldElement,
+    /// The public nullifier hash of the note.
+    pub nullifier_hash: FieldElement,
+    /// The public recipient of the withdrawal, such as an address encoded as a field element.
+    pub recipient: FieldElement,
+    /// The depth of the commitment tree, which fixes the length of every path.
+    pub depth: usize,
+}
+
+impl<H: CircuitHash> WithdrawStatement<H> {
+    /// Creates a withdrawal statement.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the mixer.
+    /// - `root`: The public root of the commitment tree.
+    /// - `nullifier_hash`: The public nullifier hash.
+    /// - `recipient`: The public recipient.
+    /// - `depth`: The depth of the commitment tree.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `WithdrawStatement` struct.
+    pub fn new(hasher: H, root: FieldElement, nullifier_hash: FieldElement, recipient: FieldElement, depth: usize) -> Self {
+        WithdrawStatement { hasher, root, nullifier_hash, recipient, depth }
+    }
+
+    /// Builds the withdrawal circuit.
+    ///
+    /// The nullifier and the secret occupy wires 0 and 1, followed by the
+    /// sibling and the direction bit of each level of the path.
+    ///
+    /// # Parameters
+    /// - `note`: The private note.
+    /// - `path`: The private Merkle path of the note's commitment.
+    ///
+    /// # Returns
+    /// - `Result<Circuit>`: The assigned circuit, or an error if the path does not match the depth.
+    pub fn circuit(&self, note: &Note, path: &[(FieldElement, bool)]) -> Result<Circuit> {
+        if path.len() != self.depth {
+            return Err(Error::Input(format!("Expected a path of {} levels, got {}", self.depth, path.len())));
+        }
+        let mut circuit = Circuit::new();
+        let nullifier = circuit.add_input(note.nullifier.clone());
+        let secret = circuit.add_input(note.secret.clone());
+        let path = gadgets::merkle::alloc_path(&mut circuit, path);
+
+        // The commitment is a leaf of the tree with the public root
+        let commitment = self.hasher.hash_two_gadget(&mut circuit, COMMITMENT_TAG, nullifier, secret);
+        let computed_root = gadgets::merkle::root_from_path(&mut circuit, &self.hasher, commitment, &path);
+        let root = circuit.alloc_linear(&[], self.root.clone());
+        circuit.enforce_equal(computed_root, root);
+
+        // The nullifier hash is derived from the same note
+        let computed_nullifier_hash = self.hasher.hash_gadget(&mut circuit, NULLIFIER_TAG, &[nullifier]);
+        let nullifier_hash = circuit.alloc_linear(&[], self.nullifier_hash.clone());
+        circuit.enforce_equal(computed_nullifier_hash, nullifier_hash);
+
+        // Squaring the recipient ties it to the constraints, as the Tornado circuit does
+        let recipient = circuit.alloc_linear(&[], self.recipient.clone());
+        circuit.alloc_mul(recipient, recipient);
+        Ok(circuit)
+    }
+
+    /// Checks a withdrawal proof against the witness it carries, as its prover can
+    /// before publishing it.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to check, with its witness.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the witness satisfies the statement, otherwise `false`, including for a proof without its witness.
+    pub fn self_check(&self, proof: &Proof) -> bool {
+        let values = match leading_values(proof, 2 + 2 * self.depth) {
+            Some(values) => values,
+            None => return false,
+        };
+        let note = Note::new(values[0].clone(), values[1].clone());
+        match self.circuit(&note, &path_from_values(&values[2..])) {
+            Ok(circuit) => circuit.verify(proof),
+            Err(_) => false,
+        }
+    }
+}
+
+impl<H: CircuitHash + Sync + Clone> WithdrawStatement<H> {
+    /// Creates the statement withdrawing a note deposited in a tree.
+    ///
+    /// The tree should be built with `MerkleTree::new_with_depth`, so every path has the same length.
+    ///
+    /// # Parameters
+    /// - `tree`: The commitment tree.
+    /// - `note`: The note to withdraw, which fixes the nullifier hash.
+    /// - `recipient`: The public recipient.
+    ///
+    /// # Returns
+    /// - `Self`: The withdrawal statement.
+    pub fn for_note(tree: &MerkleTree<H>, note: &Note, recipient: FieldElement) -> Self {
+        let hasher = tree.hasher().clone();
+        let nullifier_hash = note.nullifier_hash(&hasher);
+        WithdrawStatement { hasher, root: tree.root.clone(), nullifier_hash, recipient, depth: tree.merkle_path(0).len() }
+    }
+
+    /// Proves a withdrawal.
+    ///
+    /// # Parameters
+    /// - `note`: The private note.
+    /// - `path`: The private Merkle path of the note's commitment.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, or an error if the path does not lead from the commitment
+    ///   to the root or the note does not match the nullifier hash.
+    pub fn prove(&self, note: &Note, path: &[(FieldElement, bool)]) -> Result<Proof> {
+        let circuit = self.circuit(note, path)?;
+        if merkle::root_from_path(&self.hasher, &note.commitment(&self.hasher), path) != self.root {
+            return Err(Error::Input("The note's commitment is not in the tree".to_string()));
+        }
+        if note.nullifier_hash(&self.hasher) != self.nullifier_hash {
+            return Err(Error::Input("The note does not match the nullifier hash".to_string()));
+        }
+        Ok(circuit.prove())
+    }
+
+    /// Proves a withdrawal of the note whose commitment is at a position of a tree.
+    ///
+    /// # Parameters
+    /// - `tree`: The commitment tree, whose root must be the statement's.
+    /// - `index`: The position of the note's commitment.
+    /// - `note`: The private note.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, or an error if the commitment at that position is not the note's.
+    pub fn prove_deposit(&self, tree: &MerkleTree<H>, index: usize, note: &Note) -> Result<Proof> {
+        if tree.leaves.get(index) != Some(&note.commitment(&self.hasher)) {
+            return Err(Error::Input(format!("Leaf {} is not the note's commitment", index)));
+        }
+        self.prove(note, &tree.merkle_path(index))
+    }
+}
@@ -0,0 +1,34 @@
+//! Ready-made statements: circuits for common claims, built from the gadgets,
+//! with native helpers to prove them with the built-in proof system.
+//!
+//! The statements have no verifier. Their `self_check` rebuilds the circuit from
+//! the public values and the leading private wires of the proof, then checks every
+//! gate against its witness, so it is a check for whoever holds the witness, such
+//! as the prover before publishing a proof, and a decoded proof fails it. A party
+//! that must not learn the private values needs a proof system with a succinct
+//! argument, such as the Groth16 backend in `ark`.
+
+pub mod auction;
+pub mod credential;
+pub mod intersection;
+pub mod membership;
+pub mod mixer;
+pub mod preimage;
+pub mod presentation;
+pub mod sudoku;
+pub mod voting;
+
+use num_traits::Zero;
+use crate::field::FieldElement;
+use crate::proof::Proof;
+
+/// Reads the first `count` private wire values of a proof, or `None` if its witness is shorter.
+pub(crate) fn leading_values(proof: &Proof, count: usize) -> Option<Vec<FieldElement>> {
+    proof.witness.get(..count).map(|values| values.iter().map(|value| FieldElement::new(value.clone())).collect())
+}
+
+/// Decodes a Merkle path from its sibling and direction bit wires, as allocated by `gadgets::merkle::alloc_path`.
+pub(crate) fn path_from_values(values: &[FieldElement]) -> Vec<(FieldElement, bool)> {
+    // Each level holds the sibling, then a bit that is 1 when the current node is the right child
+    values.chunks(2).map(|level| (level[0].clone(), level[1].get_value().is_zero())).collect()
+}
@@ -0,0 +1,101 @@
+use crate::circuit::Circuit;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::hash::{CircuitHash, DomainTag};
+use crate::proof::Proof;
+use crate::statements::leading_values;
+
+/// The domain tag of the digests the statement is about.
+pub const DIGEST_TAG: DomainTag = DomainTag::new("preimage digest");
+
+/// Represents the claim "I know a preimage of this digest" under a hash with a
+/// circuit gadget, the canonical first statement to prove.
+///
+/// The digest and the length of the preimage are public; the preimage is private.
+pub struct PreimageStatement<H: CircuitHash> {
+    /// The hash function, with the same parameters as the prover's.
+    pub hasher: H,
+    /// The public digest.
+    pub digest: FieldElement,
+    /// The number of elements of the preimage, which fixes the shape of the circuit.
+    pub length: usize,
+}
+
+impl<H: CircuitHash> PreimageStatement<H> {
+    /// Creates a statement about a public digest.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function.
+    /// - `digest`: The public digest.
+    /// - `length`: The number of elements of the preimage.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `PreimageStatement` struct.
+    pub fn new(hasher: H, digest: FieldElement, length: usize) -> Self {
+        PreimageStatement { hasher, digest, length }
+    }
+
+    /// Creates the statement a preimage satisfies, hashing it natively.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function.
+    /// - `preimage`: The private preimage.
+    ///
+    /// # Returns
+    /// - `Self`: The statement about the digest of `preimage`.
+    pub fn from_preimage(hasher: H, preimage: &[FieldElement]) -> Self {
+        let digest = hasher.hash(DIGEST_TAG, preimage);
+        PreimageStatement { hasher, digest, length: preimage.len() }
+    }
+
+    /// Builds the circuit hashing the preimage and constraining the result to the digest.
+    ///
+    /// The preimage occupies the first `length` wires.
+    ///
+    /// # Parameters
+    /// - `preimage`: The private preimage.
+    ///
+    /// # Returns
+    /// - `Result<Circuit>`: The assigned circuit, or an error if the preimage has the wrong length.
+    pub fn circuit(&self, preimage: &[FieldElement]) -> Result<Circuit> {
+        if preimage.len() != self.length {
+            return Err(Error::Input(format!("Expected a preimage of {} elements, got {}", self.length, preimage.len())));
+        }
+        let mut circuit = Circuit::new();
+        let inputs: Vec<usize> = preimage.iter().map(|value| circuit.add_input(value.clone())).collect();
+        let hash = self.hasher.hash_gadget(&mut circuit, DIGEST_TAG, &inputs);
+        let digest = circuit.alloc_linear(&[], self.digest.clone());
+        circuit.enforce_equal(hash, digest);
+        Ok(circuit)
+    }
+
+    /// Proves knowledge of a preimage.
+    ///
+    /// # Parameters
+    /// - `preimage`: The private preimage.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, or an error if the preimage has the wrong length or does not hash to the digest.
+    pub fn prove(&self, preimage: &[FieldElement]) -> Result<Proof> {
+        let circuit = self.circuit(preimage)?;
+        if self.hasher.hash(DIGEST_TAG, preimage) != self.digest {
+            return Err(Error::Input("The preimage does not hash to the digest".to_string()));
+        }
+        Ok(circuit.prove())
+    }
+
+    /// Checks a proof of knowledge of a preimage of the digest against the witness it carries, as its prover can
+    /// before publishing it.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to check, with its witness.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the witness satisfies the statement, otherwise `false`, including for a proof without its witness.
+    pub fn self_check(&self, proof: &Proof) -> bool {
+        match leading_values(proof, self.length).map(|preimage| self.circuit(&preimage)) {
+            Some(Ok(circuit)) => circuit.verify(proof),
+            _ => false,
+        }
+    }
+}
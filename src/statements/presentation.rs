@@ -0,0 +1,276 @@
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::One;
+use crate::circuit::Circuit;
+use crate::edwards::{EdwardsCurve, Point};
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::gadgets;
+use crate::hash::{CircuitHash, DomainTag};
+use crate::proof::Proof;
+use crate::rng::{CryptoRng, OsRng, RngCore};
+use crate::schnorr::{self, SchnorrKey, SchnorrSignature};
+use crate::statements::leading_values;
+
+/// The domain tag of the salted commitment to the attributes of a credential.
+pub const COMMITMENT_TAG: DomainTag = DomainTag::new("credential attributes");
+
+/// Represents a credential over any number of attributes: the issuer signs
+/// the salted commitment `H(attributes..., salt)` with a Schnorr key, and the
+/// holder keeps the attributes, the salt and the signature.
+///
+/// Presenting it with a `PresentationStatement` opens the commitment and
+/// checks the signature inside the circuit, so the commitment itself, which
+/// would link presentations together, is never revealed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignedCredential {
+    /// The attributes, such as a birthdate, a country code or an account id.
+    pub attributes: Vec<FieldElement>,
+    /// The salt of the commitment.
+    pub salt: FieldElement,
+    /// The signature of the issuer on the commitment.
+    pub signature: SchnorrSignature,
+}
+
+impl SignedCredential {
+    /// Issues a credential with a salt from `OsRng`.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve of the issuer's key, defined over the default field.
+    /// - `hasher`: The hash function of the commitment and the signature.
+    /// - `issuer`: The signing key of the issuer.
+    /// - `attributes`: The attributes.
+    ///
+    /// # Returns
+    /// - `Self`: The credential.
+    pub fn issue<H: CircuitHash>(curve: &EdwardsCurve, hasher: &H, issuer: &SchnorrKey, attributes: Vec<FieldElement>) -> Self {
+        SignedCredential::issue_with_rng(curve, hasher, issuer, attributes, &mut OsRng)
+    }
+
+    /// Issues a credential with a salt and a signing nonce from a given generator.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve of the issuer's key, defined over the default field.
+    /// - `hasher`: The hash function of the commitment and the signature.
+    /// - `issuer`: The signing key of the issuer.
+    /// - `attributes`: The attributes.
+    /// - `rng`: The source of randomness.
+    ///
+    /// # Returns
+    /// - `Self`: The credential.
+    pub fn issue_with_rng<H: CircuitHash, R: RngCore + CryptoRng>(curve: &EdwardsCurve, hasher: &H, issuer: &SchnorrKey, attributes: Vec<FieldElement>, rng: &mut R) -> Self {
+        let salt = FieldElement::new(rng.gen_bigint_range(&0.into(), &FieldElement::default_modulus()));
+        let commitment = commitment(hasher, &attributes, &salt);
+        let signature = issuer.sign_with_rng(curve, hasher, &commitment, rng);
+        SignedCredential { attributes, salt, signature }
+    }
+
+    /// Computes the commitment the issuer signed, `H(attributes..., salt)`.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the issuer.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The commitment.
+    pub fn commitment<H: CircuitHash>(&self, hasher: &H) -> FieldElement {
+        commitment(hasher, &self.attributes, &self.salt)
+    }
+
+    /// Checks natively that the issuer signed the credential.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve of the issuer's key.
+    /// - `hasher`: The hash function of the issuer.
+    /// - `issuer`: The public key of the issuer.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the signature is valid, otherwise `false`.
+    pub fn verify<H: CircuitHash>(&self, curve: &EdwardsCurve, hasher: &H, issuer: &Point) -> bool {
+        schnorr::verify(curve, hasher, issuer, &self.commitment(hasher), &self.signature)
+    }
+}
+
+/// Represents a condition on one attribute, checked in the circuit without
+/// revealing the attribute unless the condition is an equality.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Predicate {
+    /// The attribute has this value, which discloses it.
+    Equal(FieldElement),
+    /// The attribute differs from this value, such as a revoked account id.
+    NotEqual(FieldElement),
+    /// The attribute, an integer below `2^bits`, is at least this bound.
+    AtLeast(u64),
+    /// The attribute, an integer below `2^bits`, is at most this bound.
+    AtMost(u64),
+}
+
+impl Predicate {
+    /// Checks the predicate natively.
+    ///
+    /// # Parameters
+    /// - `value`: The value of the attribute.
+    /// - `bits`: The number of bits of attributes compared with bounds.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the value satisfies the predicate, otherwise `false`.
+    pub fn holds(&self, value: &FieldElement, bits: usize) -> bool {
+        let in_range = value.get_value() < BigInt::one() << bits;
+        match self {
+            Predicate::Equal(expected) => value == expected,
+            Predicate::NotEqual(excluded) => value != excluded,
+            Predicate::AtLeast(bound) => in_range && value.get_value() >= BigInt::from(*bound),
+            Predicate::AtMost(bound) => in_range && value.get_value() <= BigInt::from(*bound),
+        }
+    }
+}
+
+/// Represents the presentation of a credential: "the issuer with this public
+/// key signed a credential of `attributes` attributes, and they satisfy these
+/// predicates", revealing only the attributes fixed by `Predicate::Equal`.
+///
+/// Logging in with a credential, as zk-login schemes do, is a presentation
+/// disclosing the account id and bounding the expiry date, say, while the
+/// rest of the credential stays hidden.
+pub struct PresentationStatement<H: CircuitHash> {
+    /// The curve of the issuer's key, defined over the default field.
+    pub curve: EdwardsCurve,
+    /// The hash function of the issuer, with the same parameters as the prover's.
+    pub hasher: H,
+    /// The public key of the issuer.
+    pub issuer: Point,
+    /// The number of attributes of the credential.
+    pub attributes: usize,
+    /// The predicates, each on the attribute at its index.
+    pub predicates: Vec<(usize, Predicate)>,
+    /// The number of bits of attributes compared with bounds.
+    pub bits: usize,
+}
+
+impl<H: CircuitHash> PresentationStatement<H> {
+    /// Creates a presentation statement.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve of the issuer's key.
+    /// - `hasher`: The hash function of the issuer.
+    /// - `issuer`: The public key of the issuer.
+    /// - `attributes`: The number of attributes of the credential.
+    /// - `predicates`: The predicates, each on the attribute at its index.
+    /// - `bits`: The number of bits of attributes compared with bounds.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `PresentationStatement` struct.
+    pub fn new(curve: EdwardsCurve, hasher: H, issuer: Point, attributes: usize, predicates: Vec<(usize, Predicate)>, bits: usize) -> Self {
+        PresentationStatement { curve, hasher, issuer, attributes, predicates, bits }
+    }
+
+    /// Builds the circuit opening the commitment, checking the issuer's
+    /// signature on it and enforcing every predicate.
+    ///
+    /// The attributes occupy the first wires, followed by the salt, then the
+    /// x- and y-coordinates of the signature's `R` and its response `s`.
+    ///
+    /// # Parameters
+    /// - `credential`: The private credential.
+    ///
+    /// # Returns
+    /// - `Result<Circuit>`: The assigned circuit, or an error if the credential has another
+    ///   number of attributes, a predicate refers to a missing attribute, or a bound does not
+    ///   fit in `bits` bits.
+    pub fn circuit(&self, credential: &SignedCredential) -> Result<Circuit> {
+        if credential.attributes.len() != self.attributes {
+            return Err(Error::Input(format!("Expected {} attributes, got {}", self.attributes, credential.attributes.len())));
+        }
+        let mut circuit = Circuit::new();
+        let mut inputs: Vec<usize> = credential.attributes.iter().map(|value| circuit.add_input(value.clone())).collect();
+        inputs.push(circuit.add_input(credential.salt.clone()));
+        let signature = gadgets::schnorr::alloc_signature(&mut circuit, &credential.signature);
+
+        let commitment = self.hasher.hash_gadget(&mut circuit, COMMITMENT_TAG, &inputs);
+        let issuer = gadgets::edwards::constant_point(&mut circuit, &self.issuer);
+        gadgets::schnorr::enforce_signature(&mut circuit, &self.curve, &self.hasher, &issuer, commitment, &signature);
+
+        for (index, predicate) in &self.predicates {
+            let attribute = *inputs[..self.attributes].get(*index)
+                .ok_or_else(|| Error::Input(format!("Predicate on attribute {} of {}", index, self.attributes)))?;
+            match predicate {
+                Predicate::Equal(value) => {
+                    let value = circuit.alloc_linear(&[], value.clone());
+                    circuit.enforce_equal(attribute, value);
+                },
+                Predicate::NotEqual(value) => {
+                    let value = circuit.alloc_linear(&[], value.clone());
+                    gadgets::equality::enforce_not_equal(&mut circuit, attribute, value);
+                },
+                Predicate::AtLeast(bound) => {
+                    let bound = self.bound(&mut circuit, *bound)?;
+                    gadgets::range::enforce_range(&mut circuit, attribute, self.bits);
+                    gadgets::range::enforce_less_or_equal(&mut circuit, bound, attribute, self.bits);
+                },
+                Predicate::AtMost(bound) => {
+                    let bound = self.bound(&mut circuit, *bound)?;
+                    gadgets::range::enforce_range(&mut circuit, attribute, self.bits);
+                    gadgets::range::enforce_less_or_equal(&mut circuit, attribute, bound, self.bits);
+                },
+            }
+        }
+        Ok(circuit)
+    }
+
+    /// Allocates a bound as a constant wire, checking it fits in `bits` bits.
+    fn bound(&self, circuit: &mut Circuit, bound: u64) -> Result<usize> {
+        if BigInt::from(bound) >= BigInt::one() << self.bits {
+            return Err(Error::Input(format!("The bound {} does not fit in {} bits", bound, self.bits)));
+        }
+        Ok(circuit.alloc_linear(&[], FieldElement::new(BigInt::from(bound))))
+    }
+
+    /// Proves that a credential satisfies the statement.
+    ///
+    /// # Parameters
+    /// - `credential`: The private credential.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, or an error if the circuit cannot be built, the issuer did
+    ///   not sign the credential or an attribute fails its predicate.
+    pub fn prove(&self, credential: &SignedCredential) -> Result<Proof> {
+        let circuit = self.circuit(credential)?;
+        if !credential.verify(&self.curve, &self.hasher, &self.issuer) {
+            return Err(Error::Input("The issuer did not sign the credential".to_string()));
+        }
+        if let Some((index, predicate)) = self.predicates.iter().find(|(index, predicate)| !predicate.holds(&credential.attributes[*index], self.bits)) {
+            return Err(Error::Input(format!("Attribute {} does not satisfy {:?}", index, predicate)));
+        }
+        Ok(circuit.prove())
+    }
+
+    /// Checks a presentation proof against the witness it carries, as its prover can
+    /// before publishing it.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to check, with its witness.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the witness satisfies the statement, otherwise `false`, including for a proof without its witness.
+    pub fn self_check(&self, proof: &Proof) -> bool {
+        let values = match leading_values(proof, self.attributes + 4) {
+            Some(values) => values,
+            None => return false,
+        };
+        let (attributes, rest) = values.split_at(self.attributes);
+        let credential = SignedCredential {
+            attributes: attributes.to_vec(),
+            salt: rest[0].clone(),
+            signature: SchnorrSignature { r: Point { x: rest[1].clone(), y: rest[2].clone() }, s: rest[3].get_value() },
+        };
+        match self.circuit(&credential) {
+            Ok(circuit) => circuit.verify(proof),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Computes the salted commitment to attributes, `H(attributes..., salt)`.
+fn commitment<H: CircuitHash>(hasher: &H, attributes: &[FieldElement], salt: &FieldElement) -> FieldElement {
+    let mut inputs = attributes.to_vec();
+    inputs.push(salt.clone());
+    hasher.hash(COMMITMENT_TAG, &inputs)
+}
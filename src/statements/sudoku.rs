@@ -0,0 +1,157 @@
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use crate::circuit::Circuit;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::gadgets;
+use crate::proof::Proof;
+use crate::statements::leading_values;
+
+/// Represents the claim "I know a solution of this Sudoku puzzle".
+///
+/// A puzzle with boxes of `box_size x box_size` cells has a grid of `n x n`
+/// cells with `n = box_size^2`. The puzzle is public, with 0 for blank cells;
+/// the solution is private. The circuit checks that every cell holds one of
+/// `1..=n`, that the cells of every row, column and box are pairwise distinct,
+/// and that the solution agrees with the given cells.
+pub struct SudokuStatement {
+    /// The side of a box, 3 for the classic 9x9 grid.
+    pub box_size: usize,
+    /// The cells of the puzzle in row-major order, with 0 for blank cells.
+    pub puzzle: Vec<u32>,
+}
+
+impl SudokuStatement {
+    /// Creates a statement about a puzzle.
+    ///
+    /// # Parameters
+    /// - `box_size`: The side of a box.
+    /// - `puzzle`: The cells of the puzzle in row-major order, with 0 for blank cells.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The statement, or an error if the grid has the wrong number of cells or a cell is out of range.
+    pub fn new(box_size: usize, puzzle: Vec<u32>) -> Result<Self> {
+        let statement = SudokuStatement { box_size, puzzle };
+        statement.check_grid(&statement.puzzle, 0)?;
+        Ok(statement)
+    }
+
+    /// Gets the side of the grid, which is also the largest digit.
+    ///
+    /// # Returns
+    /// - `usize`: The number of cells per row.
+    pub fn size(&self) -> usize {
+        self.box_size * self.box_size
+    }
+
+    /// Checks natively that a grid solves the puzzle.
+    ///
+    /// # Parameters
+    /// - `solution`: The cells of the solution in row-major order.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the solution is complete, valid and agrees with the puzzle, otherwise `false`.
+    pub fn is_solution(&self, solution: &[u32]) -> bool {
+        self.check_grid(solution, 1).is_ok()
+            && self.puzzle.iter().zip(solution).all(|(given, cell)| *given == 0 || given == cell)
+            && self.groups().iter().all(|group| {
+                let mut digits: Vec<u32> = group.iter().map(|&cell| solution[cell]).collect();
+                digits.sort_unstable();
+                digits.windows(2).all(|pair| pair[0] != pair[1])
+            })
+    }
+
+    /// Builds the circuit checking a solution.
+    ///
+    /// The cells of the solution occupy the first `n^2` wires, in row-major order.
+    ///
+    /// # Parameters
+    /// - `solution`: The cells of the solution in row-major order.
+    ///
+    /// # Returns
+    /// - `Result<Circuit>`: The assigned circuit, or an error if the grid has the wrong number of cells.
+    pub fn circuit(&self, solution: &[u32]) -> Result<Circuit> {
+        let cells = self.size() * self.size();
+        if solution.len() != cells {
+            return Err(Error::Input(format!("Expected {} cells, got {}", cells, solution.len())));
+        }
+        let mut circuit = Circuit::new();
+        let wires: Vec<usize> = solution.iter().map(|&cell| circuit.add_input(FieldElement::new(BigInt::from(cell)))).collect();
+
+        // Every cell holds a digit and agrees with the puzzle
+        let digits: Vec<FieldElement> = (1..=self.size()).map(|digit| FieldElement::new(BigInt::from(digit))).collect();
+        for (&wire, &given) in wires.iter().zip(&self.puzzle) {
+            gadgets::range::enforce_one_of(&mut circuit, wire, &digits);
+            if given != 0 {
+                let given = circuit.alloc_linear(&[], FieldElement::new(BigInt::from(given)));
+                circuit.enforce_equal(wire, given);
+            }
+        }
+
+        // The digits of every row, column and box are pairwise distinct
+        for group in self.groups() {
+            for (position, &a) in group.iter().enumerate() {
+                for &b in &group[position + 1..] {
+                    gadgets::equality::enforce_not_equal(&mut circuit, wires[a], wires[b]);
+                }
+            }
+        }
+        Ok(circuit)
+    }
+
+    /// Proves knowledge of a solution.
+    ///
+    /// # Parameters
+    /// - `solution`: The cells of the solution in row-major order.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, or an error if the grid does not solve the puzzle.
+    pub fn prove(&self, solution: &[u32]) -> Result<Proof> {
+        let circuit = self.circuit(solution)?;
+        if !self.is_solution(solution) {
+            return Err(Error::Input("The grid does not solve the puzzle".to_string()));
+        }
+        Ok(circuit.prove())
+    }
+
+    /// Checks a proof of knowledge of a solution against the witness it carries, as its prover can
+    /// before publishing it.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to check, with its witness.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the witness satisfies the statement, otherwise `false`, including for a proof without its witness.
+    pub fn self_check(&self, proof: &Proof) -> bool {
+        let solution: Option<Vec<u32>> = leading_values(proof, self.size() * self.size())
+            .and_then(|values| values.iter().map(|value| value.get_value().to_u32()).collect());
+        match solution.map(|solution| self.circuit(&solution)) {
+            Some(Ok(circuit)) => circuit.verify(proof),
+            _ => false,
+        }
+    }
+
+    /// Gets the cell indices of every row, column and box.
+    fn groups(&self) -> Vec<Vec<usize>> {
+        let (size, box_size) = (self.size(), self.box_size);
+        let rows = (0..size).map(|row| (0..size).map(|column| row * size + column).collect());
+        let columns = (0..size).map(|column| (0..size).map(|row| row * size + column).collect());
+        let boxes = (0..size).map(|b| {
+            let (top, left) = (b / box_size * box_size, b % box_size * box_size);
+            (0..size).map(|i| (top + i / box_size) * size + left + i % box_size).collect()
+        });
+        rows.chain(columns).chain(boxes).collect()
+    }
+
+    /// Checks that a grid has `n^2` cells holding values between `min` and `n`.
+    fn check_grid(&self, grid: &[u32], min: u32) -> Result<()> {
+        let cells = self.size() * self.size();
+        if grid.len() != cells {
+            return Err(Error::Input(format!("Expected {} cells, got {}", cells, grid.len())));
+        }
+        match grid.iter().find(|&&cell| cell < min || cell as usize > self.size()) {
+            Some(cell) => Err(Error::Input(format!("Cell value {} is out of range", cell))),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::HashSet;
+use num_bigint::{BigInt, RandBigInt};
+use crate::circuit::Circuit;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::gadgets;
+use crate::hash::{CircuitHash, DomainTag};
+use crate::merkle::{self, MerkleTree};
+use crate::proof::Proof;
+use crate::rng::{CryptoRng, OsRng, RngCore};
+use crate::statements::{leading_values, path_from_values};
+
+/// The domain tag of the commitment of a voter.
+pub const COMMITMENT_TAG: DomainTag = DomainTag::new("voter commitment");
+/// The domain tag of the nullifier of a voter in an election.
+pub const NULLIFIER_TAG: DomainTag = DomainTag::new("vote nullifier");
+
+/// Represents a registered voter, identified by a secret only they know.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Voter {
+    /// The secret of the voter.
+    pub secret: FieldElement,
+}
+
+impl Voter {
+    /// Creates a voter from a secret.
+    ///
+    /// # Parameters
+    /// - `secret`: The secret of the voter.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Voter` struct.
+    pub fn new(secret: FieldElement) -> Self {
+        Voter { secret }
+    }
+
+    /// Creates a voter with a secret in the default field from `OsRng`.
+    ///
+    /// # Returns
+    /// - `Self`: A new voter.
+    pub fn random() -> Self {
+        Voter::random_with_rng(&mut OsRng)
+    }
+
+    /// Creates a voter with a secret in the default field from a given generator.
+    ///
+    /// # Parameters
+    /// - `rng`: The source of randomness.
+    ///
+    /// # Returns
+    /// - `Self`: A new voter.
+    pub fn random_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let modulus = FieldElement::default_modulus();
+        Voter { secret: FieldElement::new(rng.gen_bigint_range(&0.into(), &modulus)) }
+    }
+
+    /// Computes the commitment registered in the voter tree, `H(secret)`.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the election.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The commitment.
+    pub fn commitment<H: CircuitHash>(&self, hasher: &H) -> FieldElement {
+        hasher.hash(COMMITMENT_TAG, std::slice::from_ref(&self.secret))
+    }
+
+    /// Computes the nullifier revealed when voting, `H(secret, election)`, which
+    /// is the same for every ballot of the voter in one election.
+    ///
+    /// # Parameters
+    /// - `hasher`: The hash function of the election.
+    /// - `election`: The identifier of the election.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The nullifier.
+    pub fn nullifier<H: CircuitHash>(&self, hasher: &H, election: &FieldElement) -> FieldElement {
+        hasher.hash_two(NULLIFIER_TAG, &self.secret, election)
+    }
+}
+
+/// Builds the voter tree of fixed depth over the voters' commitments, padded with zeros.
+///
+/// # Parameters
+/// - `hasher`: The hash function of the election.
+/// - `depth`: The depth of the tree, allowing up to `2^depth` voters.
+/// - `voters`: The registered voters, in registration order.
+///
+/// # Returns
+/// - `MerkleTree<H>`: The voter tree.
+///
+/// # Panics
+/// - If there are more than `2^depth` voters.
+pub fn voter_tree<H: CircuitHash + Sync>(hasher: H, depth: usize, voters: &[Voter]) -> MerkleTree<H> {
+    assert!(voters.len() <= 1 << depth, "Too many voters for a tree of depth {}", depth);
+    let mut leaves: Vec<FieldElement> = voters.iter().map(|voter| voter.commitment(&hasher)).collect();
+    leaves.resize(1 << depth, FieldElement::new(BigInt::from(0)));
+    MerkleTree::with_hasher(hasher, leaves)
+}
+
+/// Represents an anonymous ballot: "I am one of the voters of the tree with
+/// this root, this is my nullifier for this election, and I vote for this option".
+///
+/// Everything but the voter's secret and path is public. The nullifier lets an
+/// `Election` reject a second ballot from the same voter without learning who they are.
+pub struct VoteStatement<H: CircuitHash> {
+    /// The hash function of the election, with the same parameters as the prover's.
+    pub hasher: H,
+    /// The public root of the voter tree.
+    pub root: FieldElement,
+    /// The public identifier of the election.
+    pub election: FieldElement,
+    /// The public nullifier of the voter for this election.
+    pub nullifier: FieldElement,
+    /// The public vote, the index of the chosen option.
+    pub vote: usize,
+    /// The number of options, which bounds the vote.
+    pub options: usize,
+    /// The depth of the voter tree, which fixes the length of every path.
+    pub depth: usize,
+}
+
+impl<H: CircuitHash> VoteStatement<H> {
+    /// Builds the ballot circuit.
+    ///
+    /// The secret occupies wire 0, followed by the sibling and the direction bit of each level of the path.
+    ///
+    /// # Parameters
+    /// - `voter`: The private voter.
+    /// - `path`: The private Merkle path of the voter's commitment.
+    ///
+    /// # Returns
+    /// - `Result<Circuit>`: The assigned circuit, or an error if the path does not match the depth.
+    pub fn circuit(&self, voter: &Voter, path: &[(FieldElement, bool)]) -> Result<Circuit> {
+        if path.len() != self.depth {
+            return Err(Error::Input(format!("Expected a path of {} levels, got {}", self.depth, path.len())));
+        }
+        let mut circuit = Circuit::new();
+        let secret = circuit.add_input(voter.secret.clone());
+        let path = gadgets::merkle::alloc_path(&mut circuit, path);
+
+        // The voter's commitment is a leaf of the voter tree
+        let commitment = self.hasher.hash_gadget(&mut circuit, COMMITMENT_TAG, &[secret]);
+        let computed_root = gadgets::merkle::root_from_path(&mut circuit, &self.hasher, commitment, &path);
+        let root = circuit.alloc_linear(&[], self.root.clone());
+        circuit.enforce_equal(computed_root, root);
+
+        // The nullifier is derived from the same secret and this election
+        let election = circuit.alloc_linear(&[], self.election.clone());
+        let computed_nullifier = self.hasher.hash_two_gadget(&mut circuit, NULLIFIER_TAG, secret, election);
+        let nullifier = circuit.alloc_linear(&[], self.nullifier.clone());
+        circuit.enforce_equal(computed_nullifier, nullifier);
+
+        // The vote is one of the options
+        let vote = circuit.alloc_linear(&[], FieldElement::new(BigInt::from(self.vote)));
+        let options: Vec<FieldElement> = (0..self.options).map(|option| FieldElement::new(BigInt::from(option))).collect();
+        gadgets::range::enforce_one_of(&mut circuit, vote, &options);
+        Ok(circuit)
+    }
+
+    /// Checks a ballot proof against the witness it carries, as its prover can
+    /// before publishing it.
+    ///
+    /// # Parameters
+    /// - `proof`: The proof to check, with its witness.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the witness satisfies the statement, otherwise `false`, including for a proof without its witness.
+    pub fn self_check(&self, proof: &Proof) -> bool {
+        let values = match leading_values(proof, 1 + 2 * self.depth) {
+            Some(values) => values,
+            None => return false,
+        };
+        match self.circuit(&Voter::new(values[0].clone()), &path_from_values(&values[1..])) {
+            Ok(circuit) => circuit.verify(proof),
+            Err(_) => false,
+        }
+    }
+}
+
+impl<H: CircuitHash + Sync> VoteStatement<H> {
+    /// Proves a ballot.
+    ///
+    /// # Parameters
+    /// - `voter`: The private voter.
+    /// - `path`: The private Merkle path of the voter's commitment.
+    ///
+    /// # Returns
+    /// - `Result<Proof>`: The proof, or an error if the voter is not in the tree, the nullifier
+    ///   is not the voter's or the vote is not one of the options.
+    pub fn prove(&self, voter: &Voter, path: &[(FieldElement, bool)]) -> Result<Proof> {
+        let circuit = self.circuit(voter, path)?;
+        if merkle::root_from_path(&self.hasher, &voter.commitment(&self.hasher), path) != self.root {
+            return Err(Error::Input("The voter is not in the voter tree".to_string()));
+        }
+        if voter.nullifier(&self.hasher, &self.election) != self.nullifier {
+            return Err(Error::Input("The nullifier is not the voter's".to_string()));
+        }
+        if self.vote >= self.options {
+            return Err(Error::Input(format!("Vote {} is not one of the {} options", self.vote, self.options)));
+        }
+        Ok(circuit.prove())
+    }
+}
+
+/// Represents an election over a voter tree, which checks ballots, rejects
+/// reused nullifiers and tallies the votes.
+///
+/// Ballots are checked with `VoteStatement::self_check`, which reads their witness,
+/// so the election must be run by a tallier trusted with the voters' secrets.
+pub struct Election<H: CircuitHash> {
+    /// The hash function of the election.
+    pub hasher: H,
+    /// The identifier of the election, which keeps nullifiers unlinkable across elections.
+    pub id: FieldElement,
+    /// The root of the voter tree.
+    pub root: FieldElement,
+    /// The depth of the voter tree.
+    pub depth: usize,
+    /// The number of votes for each option.
+    tally: Vec<usize>,
+    /// The values of the nullifiers of the ballots cast so far.
+    nullifiers: HashSet<BigInt>,
+}
+
+impl<H: CircuitHash + Sync + Clone> Election<H> {
+    /// Opens an election over a voter tree built with `voter_tree`.
+    ///
+    /// # Parameters
+    /// - `id`: The identifier of the election.
+    /// - `tree`: The voter tree.
+    /// - `options`: The number of options.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `Election` struct, with no ballots cast.
+    pub fn new(id: FieldElement, tree: &MerkleTree<H>, options: usize) -> Self {
+        Election {
+            hasher: tree.hasher().clone(),
+            id,
+            root: tree.root.clone(),
+            depth: tree.merkle_path(0).len(),
+            tally: vec![0; options],
+            nullifiers: HashSet::new(),
+        }
+    }
+
+    /// Gets the statement of a ballot in this election.
+    ///
+    /// # Parameters
+    /// - `nullifier`: The nullifier of the voter.
+    /// - `vote`: The chosen option.
+    ///
+    /// # Returns
+    /// - `VoteStatement<H>`: The ballot statement.
+    pub fn statement(&self, nullifier: FieldElement, vote: usize) -> VoteStatement<H> {
+        VoteStatement {
+            hasher: self.hasher.clone(),
+            root: self.root.clone(),
+            election: self.id.clone(),
+            nullifier,
+            vote,
+            options: self.tally.len(),
+            depth: self.depth,
+        }
+    }
+
+    /// Proves a voter's ballot, computing the nullifier and the path from the voter tree.
+    ///
+    /// # Parameters
+    /// - `tree`: The voter tree of the election.
+    /// - `index`: The position of the voter in the tree.
+    /// - `voter`: The private voter.
+    /// - `vote`: The chosen option.
+    ///
+    /// # Returns
+    /// - `Result<(FieldElement, Proof)>`: The nullifier and the proof to cast, or an error if the voter
+    ///   is not at that position or the vote is not one of the options.
+    pub fn prove_vote(&self, tree: &MerkleTree<H>, index: usize, voter: &Voter, vote: usize) -> Result<(FieldElement, Proof)> {
+        let nullifier = voter.nullifier(&self.hasher, &self.id);
+        let proof = self.statement(nullifier.clone(), vote).prove(voter, &tree.merkle_path(index))?;
+        Ok((nullifier, proof))
+    }
+
+    /// Casts a ballot, counting the vote if the witness of its proof satisfies the statement
+    /// and the nullifier is unused.
+    ///
+    /// # Parameters
+    /// - `nullifier`: The nullifier of the ballot.
+    /// - `vote`: The chosen option.
+    /// - `proof`: The ballot proof, with its witness.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the vote is not one of the options, the voter already voted or the proof is invalid.
+    pub fn cast(&mut self, nullifier: &FieldElement, vote: usize, proof: &Proof) -> Result<()> {
+        if vote >= self.tally.len() {
+            return Err(Error::Input(format!("Vote {} is not one of the {} options", vote, self.tally.len())));
+        }
+        if self.has_voted(nullifier) {
+            return Err(Error::Input("The nullifier has already been used".to_string()));
+        }
+        if !self.statement(nullifier.clone(), vote).self_check(proof) {
+            return Err(Error::Input("The ballot proof is invalid".to_string()));
+        }
+        self.nullifiers.insert(nullifier.get_value());
+        self.tally[vote] += 1;
+        Ok(())
+    }
+
+    /// Checks whether a ballot with a nullifier has been cast.
+    ///
+    /// # Parameters
+    /// - `nullifier`: The nullifier.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the nullifier has been used, otherwise `false`.
+    pub fn has_voted(&self, nullifier: &FieldElement) -> bool {
+        self.nullifiers.contains(&nullifier.get_value())
+    }
+
+    /// Gets the number of votes for each option.
+    ///
+    /// # Returns
+    /// - `&[usize]`: The tally, indexed by option.
+    pub fn tally(&self) -> &[usize] {
+        &self.tally
+    }
+}
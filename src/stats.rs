@@ -0,0 +1,99 @@
+use std::time::Duration;
+use crate::circuit::Gate;
+use crate::compiler::CompiledCircuit;
+use crate::fingerprint::CircuitId;
+use crate::r1cs::R1CS;
+
+/// The estimated proving cost per wire, covering the multi-scalar multiplications.
+const NANOS_PER_WIRE: u64 = 20_000;
+/// The estimated proving cost per constraint, covering the FFTs of the quotient polynomial.
+const NANOS_PER_CONSTRAINT: u64 = 10_000;
+/// The size of a compressed BN254 G1 point in the proving key.
+const G1_BYTES: usize = 64;
+/// The size of a compressed BN254 G2 point in the proving key.
+const G2_BYTES: usize = 128;
+/// The size of a matrix entry: a 32-byte field element and an 8-byte wire index.
+const ENTRY_BYTES: usize = 40;
+
+/// Summarizes the size of a constraint system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitStats {
+    /// The number of rank-1 constraints.
+    pub constraints: usize,
+    /// The number of wires, or variables, of the constraint system.
+    pub wires: usize,
+    /// The number of public inputs, if the format records them.
+    pub public_inputs: Option<usize>,
+    /// The number of nonzero entries across the A, B and C matrices.
+    pub nonzero_entries: usize,
+    /// The id of the constraint system.
+    pub circuit_id: CircuitId,
+}
+
+impl CircuitStats {
+    /// Computes the statistics of an R1CS.
+    ///
+    /// # Parameters
+    /// - `r1cs`: The constraint system.
+    ///
+    /// # Returns
+    /// - `Self`: The statistics, without a public input count since R1CS files do not record it.
+    pub fn from_r1cs(r1cs: &R1CS) -> Self {
+        CircuitStats {
+            constraints: r1cs.constraints.len(),
+            wires: r1cs.variables.len(),
+            public_inputs: None,
+            nonzero_entries: r1cs.constraints.iter()
+                .map(|constraint| constraint.left.len() + constraint.right.len() + constraint.output.len())
+                .sum(),
+            circuit_id: r1cs.circuit_id(),
+        }
+    }
+
+    /// Computes the statistics of a compiled circuit, counting one constraint per gate
+    /// as laid out by the arkworks backend.
+    ///
+    /// # Parameters
+    /// - `compiled`: The compiled circuit.
+    ///
+    /// # Returns
+    /// - `Self`: The statistics.
+    pub fn from_compiled(compiled: &CompiledCircuit) -> Self {
+        CircuitStats {
+            constraints: compiled.gates.len(),
+            wires: compiled.wire_names.len(),
+            public_inputs: Some(compiled.public_inputs.len()),
+            nonzero_entries: compiled.gates.iter()
+                .map(|gate| match gate {
+                    // (a + b) * 1 = output
+                    Gate::Add(..) => 4,
+                    // a * b = output
+                    Gate::Mul(..) => 3,
+                    // (terms + constant) * 1 = output
+                    Gate::Linear(terms, _, _) => terms.len() + 3,
+                })
+                .sum(),
+            circuit_id: compiled.circuit_id(),
+        }
+    }
+
+    /// Estimates the single-threaded Groth16 proving time over BN254.
+    ///
+    /// This is a rough, order-of-magnitude figure meant for comparing circuits.
+    ///
+    /// # Returns
+    /// - `Duration`: The estimated proving time.
+    pub fn estimated_proving_time(&self) -> Duration {
+        Duration::from_nanos(self.wires as u64 * NANOS_PER_WIRE + self.constraints as u64 * NANOS_PER_CONSTRAINT)
+    }
+
+    /// Estimates the memory used by Groth16 proving over BN254: the proving key
+    /// (three G1 points and one G2 point per wire, one G1 point per constraint)
+    /// plus the constraint matrices.
+    ///
+    /// # Returns
+    /// - `usize`: The estimated memory in bytes.
+    pub fn estimated_memory(&self) -> usize {
+        self.wires * (3 * G1_BYTES + G2_BYTES) + self.constraints * G1_BYTES + self.nonzero_entries * ENTRY_BYTES
+    }
+}
@@ -0,0 +1,563 @@
+use std::fmt;
+use std::path::Path;
+use num_bigint::BigInt;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::blake2s;
+use crate::compiler::{CircuitSource, CompiledCircuit, GateSource};
+use crate::config::HashFunction;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::merkle::MerkleTree;
+use crate::poseidon::{DomainTag, Poseidon};
+use crate::poseidon2::Poseidon2;
+use crate::proof::{from_hex, to_hex, Proof};
+use crate::rng;
+
+/// The golden vectors generated by this release, also published as
+/// `vectors/golden.json`.
+pub const GOLDEN_VECTORS: &str = include_str!("../vectors/golden.json");
+
+/// The version of the format written by `TestVectors::to_json`.
+const TEST_VECTORS_VERSION: u32 = 4;
+
+/// The seed of the generator drawing the operands of the vectors.
+const VECTOR_SEED: u64 = 0x0076_6563_746f_7273;
+
+/// The source of the vectors this crate computes itself, which pin its
+/// current behaviour but cannot show that it is correct.
+pub const SELF_GENERATED: &str = "self-generated";
+
+/// The BN254 scalar field, the field of the reference Poseidon vector.
+const BN254_MODULUS: &str = "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
+
+/// Represents a set of canonical input/output vectors, with every value in a
+/// stable, language-neutral encoding: field elements and integers as
+/// `0x`-prefixed lowercase hexadecimal, byte strings as lowercase hexadecimal.
+///
+/// `GOLDEN_VECTORS` holds the vectors of this release. Checking them with
+/// `check` after a change, or recomputing them in another implementation,
+/// makes any difference in the field arithmetic, the hashes, the Merkle trees
+/// or the proof encodings visible. Every vector names its source: most are
+/// `SELF_GENERATED`, and only the known answers come from specifications and
+/// reference implementations, so only they can show the crate is wrong rather
+/// than changed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestVectors {
+    /// The version of the format.
+    pub version: u32,
+    /// The modulus of the field of every vector.
+    pub modulus: String,
+    /// The field operation vectors.
+    pub field: Vec<FieldVector>,
+    /// The hash vectors.
+    pub hashes: Vec<HashVector>,
+    /// The Merkle root vectors.
+    pub merkle: Vec<MerkleVector>,
+    /// The proof vectors.
+    pub proofs: Vec<ProofVector>,
+    /// The known-answer vectors from outside the crate.
+    pub known_answers: Vec<KnownAnswerVector>,
+}
+
+/// Represents a field operation of a vector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldOperation {
+    /// `inputs[0] + inputs[1]`.
+    Add,
+    /// `inputs[0] - inputs[1]`.
+    Sub,
+    /// `inputs[0] * inputs[1]`.
+    Mul,
+    /// `-inputs[0]`.
+    Neg,
+    /// The inverse of `inputs[0]`.
+    Inv,
+    /// `inputs[0]` raised to the integer `inputs[1]`.
+    Pow,
+}
+
+/// Represents a field operation and its result.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldVector {
+    /// The source of the vector, `SELF_GENERATED`.
+    pub source: String,
+    /// The operation.
+    pub operation: FieldOperation,
+    /// The operands.
+    pub inputs: Vec<String>,
+    /// The result.
+    pub output: String,
+}
+
+/// Represents a hash of a sequence of field elements.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HashVector {
+    /// The source of the vector, `SELF_GENERATED`.
+    pub source: String,
+    /// The hash function, with its default parameters.
+    pub hash: HashFunction,
+    /// The name of the domain tag, one of the tags built into `DomainTag`.
+    pub tag: String,
+    /// The hashed elements.
+    pub inputs: Vec<String>,
+    /// The hash.
+    pub output: String,
+}
+
+/// Represents the root of a Merkle tree over given leaves.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MerkleVector {
+    /// The source of the vector, `SELF_GENERATED`.
+    pub source: String,
+    /// The hash function of the tree, with its default parameters.
+    pub hash: HashFunction,
+    /// The leaves, in order.
+    pub leaves: Vec<String>,
+    /// The root.
+    pub root: String,
+}
+
+/// Represents a proof of a circuit for given inputs, in both encodings.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProofVector {
+    /// The source of the vector, `SELF_GENERATED`.
+    pub source: String,
+    /// The circuit description.
+    pub circuit: CircuitSource,
+    /// The values of the inputs, in the order the circuit declares them.
+    pub inputs: Vec<String>,
+    /// The proof as written by `Proof::to_pretty_json`.
+    pub proof: Value,
+    /// The proof as encoded by `api::prove`, in hexadecimal.
+    pub encoded: String,
+}
+
+/// Represents a known-answer vector: an output published by a specification
+/// or a reference implementation, independent of this crate.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KnownAnswerVector {
+    /// The publication or implementation the output comes from.
+    pub source: String,
+    /// The computation and its published output.
+    pub answer: KnownAnswer,
+}
+
+/// Represents a computation with a published output.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "function", rename_all = "snake_case", deny_unknown_fields)]
+pub enum KnownAnswer {
+    /// The BLAKE2s-256 digest of a message, both as lowercase hexadecimal bytes.
+    Blake2s {
+        /// The message.
+        message: String,
+        /// The digest.
+        digest: String,
+    },
+    /// The Poseidon permutation with the parameters of `Poseidon::reference`.
+    PoseidonPermutation {
+        /// The modulus of the field.
+        modulus: String,
+        /// The width of the state.
+        width: usize,
+        /// The number of full rounds.
+        full_rounds: usize,
+        /// The number of partial rounds.
+        partial_rounds: usize,
+        /// The S-box exponent.
+        alpha: u64,
+        /// The state before the permutation.
+        inputs: Vec<String>,
+        /// The state after the permutation.
+        outputs: Vec<String>,
+    },
+}
+
+/// Represents a vector whose recorded output differs from the recomputed one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VectorMismatch {
+    /// The section of the vector, such as `field` or `proofs`.
+    pub section: &'static str,
+    /// The index of the vector in its section.
+    pub index: usize,
+    /// The recorded output.
+    pub expected: String,
+    /// The recomputed output.
+    pub actual: String,
+}
+
+impl fmt::Display for VectorMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: expected {}, got {}", self.section, self.index, self.expected, self.actual)
+    }
+}
+
+impl TestVectors {
+    /// Generates the vectors of this release.
+    ///
+    /// Generation is deterministic: the operands come from fixed edge cases
+    /// and a seeded generator, so two runs of the same release produce the
+    /// same document, and a diff against `GOLDEN_VECTORS` shows exactly which
+    /// outputs changed.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The vectors, or an error if a proof cannot be generated.
+    pub fn generate() -> Result<Self> {
+        let modulus = FieldElement::default_modulus();
+        let mut rng = rng::seeded(VECTOR_SEED);
+        let mut sample = || FieldElement::new(BigInt::from(rng.next_u64()));
+        let edges = [BigInt::from(0), BigInt::from(1), BigInt::from(2), &modulus - 1];
+        let mut operands: Vec<FieldElement> = edges.iter().cloned().map(FieldElement::new).collect();
+        operands.extend((0..4).map(|_| sample()));
+
+        let mut field = Vec::new();
+        for (i, a) in operands.iter().enumerate() {
+            let b = &operands[(i + 1) % operands.len()];
+            for operation in [FieldOperation::Add, FieldOperation::Sub, FieldOperation::Mul, FieldOperation::Pow] {
+                field.push(field_vector(operation, &[a.clone(), b.clone()]));
+            }
+            field.push(field_vector(FieldOperation::Neg, std::slice::from_ref(a)));
+            if a.get_value() != BigInt::from(0) {
+                field.push(field_vector(FieldOperation::Inv, std::slice::from_ref(a)));
+            }
+        }
+
+        let mut hashes = Vec::new();
+        let mut merkle = Vec::new();
+        for hash in [HashFunction::Poseidon, HashFunction::Poseidon2] {
+            let tags = [DomainTag::COMMITMENT, DomainTag::MERKLE_LEAF, DomainTag::MERKLE_NODE, DomainTag::CHALLENGE, DomainTag::COMMITMENT];
+            for (&len, &tag) in [0, 1, 2, 3, 5].iter().zip(tags.iter()) {
+                let inputs: Vec<FieldElement> = (0..len).map(|_| sample()).collect();
+                let output = hash_elements(hash, tag, &inputs);
+                hashes.push(HashVector { source: SELF_GENERATED.to_string(), hash, tag: tag.name().to_string(), inputs: encode_elements(&inputs), output: to_hex(&output.get_value()) });
+            }
+            for len in [1, 2, 3, 4, 7] {
+                let leaves: Vec<FieldElement> = (0..len).map(|_| sample()).collect();
+                let root = merkle_root(hash, leaves.clone());
+                merkle.push(MerkleVector { source: SELF_GENERATED.to_string(), hash, leaves: encode_elements(&leaves), root: to_hex(&root.get_value()) });
+            }
+        }
+
+        let proofs = vec![
+            proof_vector(product_circuit(), &[sample(), sample()])?,
+            proof_vector(affine_circuit(), &[sample(), sample(), sample()])?,
+        ];
+
+        Ok(TestVectors { version: TEST_VECTORS_VERSION, modulus: to_hex(&modulus), field, hashes, merkle, proofs, known_answers: known_answers() })
+    }
+
+    /// Recomputes every vector and reports those whose output differs.
+    ///
+    /// Known answers are recomputed like the other vectors, so a mismatch in
+    /// the `known_answers` section means the crate disagrees with the
+    /// specification or implementation named by the vector.
+    ///
+    /// Proof vectors are checked both ways: the proof generated from the
+    /// inputs must match both recorded encodings, and the recorded encodings,
+    /// with the witness restored from the inputs, must decode to a proof the
+    /// compiled circuit accepts.
+    ///
+    /// # Returns
+    /// - `Result<Vec<VectorMismatch>>`: The mismatching vectors, empty if all of them match, or an
+    ///   error if the document is malformed or was written for another version or field.
+    pub fn check(&self) -> Result<Vec<VectorMismatch>> {
+        if self.version != TEST_VECTORS_VERSION {
+            return Err(Error::UnsupportedVersion { found: self.version, min: TEST_VECTORS_VERSION, max: TEST_VECTORS_VERSION });
+        }
+        if from_hex(&self.modulus)? != FieldElement::default_modulus() {
+            return Err(Error::Input(format!("Vectors were generated over the modulus {}", self.modulus)));
+        }
+
+        let mut mismatches = Vec::new();
+        let mut compare = |section: &'static str, index: usize, expected: &str, actual: String| {
+            if expected != actual {
+                mismatches.push(VectorMismatch { section, index, expected: expected.to_string(), actual });
+            }
+        };
+        for (index, vector) in self.field.iter().enumerate() {
+            let actual = field_vector(vector.operation, &decode_elements(&vector.inputs)?).output;
+            compare("field", index, &vector.output, actual);
+        }
+        for (index, vector) in self.hashes.iter().enumerate() {
+            let actual = hash_elements(vector.hash, builtin_tag(&vector.tag)?, &decode_elements(&vector.inputs)?);
+            compare("hashes", index, &vector.output, to_hex(&actual.get_value()));
+        }
+        for (index, vector) in self.merkle.iter().enumerate() {
+            let actual = merkle_root(vector.hash, decode_elements(&vector.leaves)?);
+            compare("merkle", index, &vector.root, to_hex(&actual.get_value()));
+        }
+        for (index, vector) in self.proofs.iter().enumerate() {
+            let actual = proof_vector(vector.circuit.clone(), &decode_elements(&vector.inputs)?)?;
+            compare("proofs", index, &vector.proof.to_string(), actual.proof.to_string());
+            compare("proofs", index, &vector.encoded, actual.encoded);
+
+            // Neither encoding carries the witness, so it is restored from the inputs
+            let compiled = CompiledCircuit::compile(&vector.circuit)?;
+            let witness: Vec<_> = compiled.assign(&decode_elements(&vector.inputs)?)?.to_r1cs()
+                .generate_witness().iter().map(FieldElement::get_value).collect();
+            let mut decoded: [Proof; 2] = [
+                Proof::from_pretty_json(&vector.proof.to_string())?,
+                bincode::deserialize(&decode_bytes(&vector.encoded)?)?,
+            ];
+            for proof in &mut decoded {
+                proof.witness = witness.clone();
+                if !compiled.verify(proof)? {
+                    compare("proofs", index, "a verifying proof", "a rejected proof".to_string());
+                }
+            }
+        }
+        for (index, vector) in self.known_answers.iter().enumerate() {
+            let (expected, actual) = known_answer(&vector.answer)?;
+            compare("known_answers", index, &expected, actual);
+        }
+        Ok(mismatches)
+    }
+
+    /// Serializes the vectors to pretty-printed JSON.
+    ///
+    /// # Returns
+    /// - `Result<String>`: The JSON document.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Deserializes vectors from JSON, such as `GOLDEN_VECTORS`.
+    ///
+    /// # Parameters
+    /// - `json`: The JSON document.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The vectors, or an error if the document is malformed.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Writes the vectors to a JSON file.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the file.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the file cannot be written.
+    pub fn to_file(&self, path: &Path) -> Result<()> {
+        Ok(std::fs::write(path, self.to_json()? + "\n")?)
+    }
+
+    /// Reads vectors from a JSON file.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the file.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The vectors, or an error if the file cannot be read or is malformed.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// Applies a field operation to its operands.
+fn field_vector(operation: FieldOperation, inputs: &[FieldElement]) -> FieldVector {
+    let output = match operation {
+        FieldOperation::Add => inputs[0].add(&inputs[1]),
+        FieldOperation::Sub => inputs[0].sub(&inputs[1]),
+        FieldOperation::Mul => inputs[0].mul(&inputs[1]),
+        FieldOperation::Neg => inputs[0].negate(),
+        FieldOperation::Inv => inputs[0].inv(),
+        FieldOperation::Pow => inputs[0].pow(&inputs[1].get_value()),
+    };
+    FieldVector { source: SELF_GENERATED.to_string(), operation, inputs: encode_elements(inputs), output: to_hex(&output.get_value()) }
+}
+
+/// Lists the known answers, each with the publication or implementation it comes from.
+fn known_answers() -> Vec<KnownAnswerVector> {
+    let blake2s = |source: &str, message: &[u8], digest: &str| KnownAnswerVector {
+        source: source.to_string(),
+        answer: KnownAnswer::Blake2s { message: encode_bytes(message), digest: digest.to_string() },
+    };
+    let reference = "BLAKE2 reference implementation, as Python's hashlib.blake2s";
+    let prefix: Vec<u8> = (0..100).collect();
+    let hex = |digits: &[&str]| digits.iter().map(|digits| format!("0x{}", digits)).collect();
+    vec![
+        blake2s("RFC 7693, Appendix B", b"abc", "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"),
+        blake2s(reference, b"", "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9"),
+        blake2s(reference, &prefix[..64], "56f34e8b96557e90c1f24b52d0c89d51086acf1b00f634cf1dde9233b8eaaa3e"),
+        blake2s(reference, &prefix, "81dcc3a505eace3f879d8f702776770f9df50e521d1428a85daf04f9ad2150e0"),
+        KnownAnswerVector {
+            source: "Poseidon reference implementation (Grassi et al.), test vector of poseidonperm_x5_254_3".to_string(),
+            answer: KnownAnswer::PoseidonPermutation {
+                modulus: BN254_MODULUS.to_string(),
+                width: 3,
+                full_rounds: 8,
+                partial_rounds: 57,
+                alpha: 5,
+                inputs: hex(&["0", "1", "2"]),
+                outputs: hex(&[
+                    "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a",
+                    "fca49b798923ab0239de1c9e7a4a9a2210312b6a2f616d18b5a87f9b628ae29",
+                    "e7ae82e40091e63cbd4f16a6d16310b3729d4b6e138fcf54110e2867045a30c",
+                ]),
+            },
+        },
+    ]
+}
+
+/// Recomputes a known answer.
+///
+/// # Returns
+/// - `Result<(String, String)>`: The published output and the recomputed one, or an error if the
+///   vector is malformed.
+fn known_answer(answer: &KnownAnswer) -> Result<(String, String)> {
+    match answer {
+        KnownAnswer::Blake2s { message, digest } => {
+            Ok((digest.clone(), encode_bytes(&blake2s::hash(&decode_bytes(message)?))))
+        }
+        KnownAnswer::PoseidonPermutation { modulus, width, full_rounds, partial_rounds, alpha, inputs, outputs } => {
+            let poseidon = Poseidon::reference(&from_hex(modulus)?, *width, *full_rounds, *partial_rounds, *alpha)?;
+            let mut state = inputs.iter().map(|value| from_hex(value).map(|value| poseidon.element(value))).collect::<Result<Vec<_>>>()?;
+            if state.len() != *width {
+                return Err(Error::Input(format!("Expected a state of {} elements, got {}", width, state.len())));
+            }
+            poseidon.permute(&mut state);
+            Ok((outputs.join(","), encode_elements(&state).join(",")))
+        }
+    }
+}
+
+/// Hashes elements with the default parameters of a hash function.
+fn hash_elements(hash: HashFunction, tag: DomainTag, inputs: &[FieldElement]) -> FieldElement {
+    match hash {
+        HashFunction::Poseidon => Poseidon::default().hash(tag, inputs),
+        HashFunction::Poseidon2 => Poseidon2::default().hash(tag, inputs),
+    }
+}
+
+/// Finds the domain tag built into `DomainTag` with a given name.
+fn builtin_tag(name: &str) -> Result<DomainTag> {
+    [DomainTag::MERKLE_LEAF, DomainTag::MERKLE_NODE, DomainTag::COMMITMENT, DomainTag::CHALLENGE]
+        .iter()
+        .copied()
+        .find(|tag| tag.name() == name)
+        .ok_or_else(|| Error::Input(format!("Unknown domain tag {}", name)))
+}
+
+/// Computes the root of a Merkle tree with the default parameters of a hash function.
+fn merkle_root(hash: HashFunction, leaves: Vec<FieldElement>) -> FieldElement {
+    match hash {
+        HashFunction::Poseidon => MerkleTree::<Poseidon>::new(leaves).root,
+        HashFunction::Poseidon2 => MerkleTree::<Poseidon2>::new(leaves).root,
+    }
+}
+
+/// Proves a circuit for given inputs and records the proof in both encodings.
+fn proof_vector(circuit: CircuitSource, inputs: &[FieldElement]) -> Result<ProofVector> {
+    let proof = CompiledCircuit::compile(&circuit)?.assign(inputs)?.prove();
+    Ok(ProofVector {
+        source: SELF_GENERATED.to_string(),
+        circuit,
+        inputs: encode_elements(inputs),
+        proof: serde_json::from_str(&proof.to_pretty_json())?,
+        encoded: encode_bytes(&bincode::serialize(&proof)?),
+    })
+}
+
+/// Describes `z = x * y`, with `z` public.
+fn product_circuit() -> CircuitSource {
+    CircuitSource {
+        inputs: vec!["x".to_string(), "y".to_string()],
+        public: vec![],
+        outputs: vec!["z".to_string()],
+        gates: vec![GateSource::Mul { left: "x".to_string(), right: "y".to_string(), output: "z".to_string() }],
+        version: None,
+    }
+}
+
+/// Describes `out = (a + b) * c`, with `c` and `out` public, at version `1.0.0`.
+fn affine_circuit() -> CircuitSource {
+    CircuitSource {
+        inputs: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        public: vec!["c".to_string()],
+        outputs: vec!["out".to_string()],
+        gates: vec![
+            GateSource::Add { left: "a".to_string(), right: "b".to_string(), output: "sum".to_string() },
+            GateSource::Mul { left: "sum".to_string(), right: "c".to_string(), output: "out".to_string() },
+        ],
+        version: Some("1.0.0".to_string()),
+    }
+}
+
+/// Encodes field elements with `to_hex`.
+fn encode_elements(elements: &[FieldElement]) -> Vec<String> {
+    elements.iter().map(|element| to_hex(&element.get_value())).collect()
+}
+
+/// Decodes field elements written by `encode_elements`.
+fn decode_elements(values: &[String]) -> Result<Vec<FieldElement>> {
+    values.iter().map(|value| from_hex(value).map(FieldElement::new)).collect()
+}
+
+/// Encodes bytes as lowercase hexadecimal.
+fn encode_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decodes bytes written by `encode_bytes`.
+fn decode_bytes(value: &str) -> Result<Vec<u8>> {
+    let invalid = || Error::Serialization(format!("Invalid hexadecimal bytes: {}", value));
+    if !value.len().is_multiple_of(2) || !value.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)) {
+        return Err(invalid());
+    }
+    (0..value.len()).step_by(2).map(|i| u8::from_str_radix(&value[i..i + 2], 16).map_err(|_| invalid())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn golden_vectors_match() {
+        let vectors = TestVectors::from_json(GOLDEN_VECTORS).unwrap();
+        let mismatches = vectors.check().unwrap();
+        assert!(mismatches.is_empty(), "{}", mismatches.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"));
+    }
+
+    #[test]
+    fn only_known_answers_come_from_outside_the_crate() {
+        let vectors = TestVectors::from_json(GOLDEN_VECTORS).unwrap();
+        let generated = vectors.field.iter().map(|vector| &vector.source)
+            .chain(vectors.hashes.iter().map(|vector| &vector.source))
+            .chain(vectors.merkle.iter().map(|vector| &vector.source))
+            .chain(vectors.proofs.iter().map(|vector| &vector.source));
+        assert!(generated.into_iter().all(|source| source == SELF_GENERATED));
+        assert!(vectors.known_answers.iter().any(|vector| matches!(vector.answer, KnownAnswer::Blake2s { .. })));
+        assert!(vectors.known_answers.iter().any(|vector| matches!(vector.answer, KnownAnswer::PoseidonPermutation { .. })));
+        assert!(vectors.known_answers.iter().all(|vector| vector.source != SELF_GENERATED));
+    }
+
+    #[test]
+    fn wrong_known_answers_are_reported() {
+        let mut vectors = TestVectors::from_json(GOLDEN_VECTORS).unwrap();
+        for vector in &mut vectors.known_answers {
+            match &mut vector.answer {
+                KnownAnswer::Blake2s { message, .. } => message.push_str("00"),
+                KnownAnswer::PoseidonPermutation { inputs, .. } => inputs[0] = "0x3".to_string(),
+            }
+        }
+        let mismatches = vectors.check().unwrap();
+        assert_eq!(mismatches.len(), vectors.known_answers.len());
+        assert!(mismatches.iter().all(|mismatch| mismatch.section == "known_answers"));
+    }
+
+    #[test]
+    fn generation_is_deterministic() {
+        let generated = TestVectors::generate().unwrap().to_json().unwrap();
+        assert_eq!(generated + "\n", GOLDEN_VECTORS);
+    }
+}
@@ -0,0 +1,222 @@
+use num_bigint::{BigInt, Sign};
+use num_traits::One;
+use crate::field::FieldElement;
+use crate::qap::Terms;
+use crate::r1cs::R1CS;
+#[cfg(feature = "arbitrary")]
+use arbitrary::{Arbitrary, Unstructured};
+use crate::r1cs::{Constraint, Operation, Variable};
+#[cfg(feature = "proptest")]
+use proptest::collection::{vec, SizeRange};
+#[cfg(feature = "proptest")]
+use proptest::prelude::*;
+
+/// The most terms on one side of a generated constraint.
+const MAX_TERMS: usize = 3;
+
+/// The most free variables of an `R1CS` generated by `Arbitrary`.
+#[cfg(feature = "arbitrary")]
+const MAX_ARBITRARY_VARIABLES: u32 = 16;
+
+/// The most constraints of an `R1CS` generated by `Arbitrary`.
+#[cfg(feature = "arbitrary")]
+const MAX_ARBITRARY_CONSTRAINTS: usize = 64;
+
+/// Builds a satisfied system over the default field.
+///
+/// The free variables hold `values`. Each row gives the left and right sides
+/// of a constraint, with indices wrapped around the variables allocated so
+/// far, and allocates a variable holding the product as the output side.
+/// The variables then hold a witness satisfying every constraint.
+///
+/// # Panics
+/// - If `values` is empty.
+fn satisfied_system(values: Vec<FieldElement>, rows: Vec<(Terms, Terms)>) -> R1CS {
+    assert!(!values.is_empty(), "A generated system needs at least one free variable");
+    let mut r1cs = R1CS::new();
+    let mut witness = values;
+    for value in &witness {
+        r1cs.add_variable(value.clone());
+    }
+
+    let mut constraints = Vec::with_capacity(rows.len());
+    for (left, right) in rows {
+        let wrap = |terms: Terms| -> Terms { terms.into_iter().map(|(index, coeff)| (index % witness.len(), coeff)).collect() };
+        let (left, right) = (wrap(left), wrap(right));
+        let product = combine(&left, &witness).mul(&combine(&right, &witness));
+        let output = r1cs.add_variable(product.clone());
+        witness.push(product);
+        constraints.push((left, right, vec![(output, FieldElement::new(BigInt::one()))]));
+    }
+    r1cs.add_constraints(&constraints);
+    r1cs
+}
+
+/// Evaluates a linear combination over a witness.
+fn combine(terms: &[(usize, FieldElement)], witness: &[FieldElement]) -> FieldElement {
+    terms.iter().fold(FieldElement::new(BigInt::from(0)), |sum, (index, coeff)| &sum + &coeff.mul(&witness[*index]))
+}
+
+/// Reduces random bytes into a field, 16 bytes beyond the size of the modulus
+/// keeping the bias below `2^-128`.
+fn reduce(bytes: &[u8], modulus: &BigInt) -> FieldElement {
+    FieldElement::new_with_modulus(BigInt::from_bytes_le(Sign::Plus, bytes), modulus.clone())
+}
+
+/// Gets the number of random bytes `reduce` takes for a modulus.
+fn sample_bytes(modulus: &BigInt) -> usize {
+    (modulus.bits() as usize).div_ceil(8) + 16
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> Arbitrary<'a> for FieldElement {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let modulus = FieldElement::default_modulus();
+        let mut bytes = vec![0; sample_bytes(&modulus)];
+        u.fill_buffer(&mut bytes)?;
+        Ok(reduce(&bytes, &modulus))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> Arbitrary<'a> for Operation {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(*u.choose(&[Operation::Add, Operation::Mul, Operation::Hash])?)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> Arbitrary<'a> for Variable {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Variable { index: u.arbitrary()?, value: u.arbitrary()? })
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> Arbitrary<'a> for Constraint {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let side = |u: &mut Unstructured<'a>| -> arbitrary::Result<Vec<(Variable, BigInt)>> {
+            let len = u.int_in_range(1..=MAX_TERMS)?;
+            (0..len).map(|_| Ok((u.arbitrary()?, u.arbitrary::<FieldElement>()?.get_value()))).collect()
+        };
+        Ok(Constraint { left: side(u)?, right: side(u)?, output: side(u)?, operation: u.arbitrary()? })
+    }
+}
+
+/// Generates a satisfied system: its variables hold a witness satisfying every
+/// constraint, returned by `R1CS::generate_witness`.
+#[cfg(feature = "arbitrary")]
+impl<'a> Arbitrary<'a> for R1CS {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let free = u.int_in_range(1..=MAX_ARBITRARY_VARIABLES)?;
+        let values = (0..free).map(|_| u.arbitrary()).collect::<arbitrary::Result<_>>()?;
+        let terms = |u: &mut Unstructured<'a>| -> arbitrary::Result<Terms> {
+            let len = u.int_in_range(1..=MAX_TERMS)?;
+            (0..len).map(|_| Ok((u.arbitrary()?, u.arbitrary()?))).collect()
+        };
+        let constraints = u.int_in_range(0..=MAX_ARBITRARY_CONSTRAINTS)?;
+        let rows = (0..constraints).map(|_| Ok((terms(u)?, terms(u)?))).collect::<arbitrary::Result<_>>()?;
+        Ok(satisfied_system(values, rows))
+    }
+}
+
+/// Generates elements of the default field, with 0, 1 and -1 more likely than
+/// uniform sampling would draw them.
+///
+/// # Returns
+/// - `impl Strategy<Value = FieldElement>`: The strategy.
+#[cfg(feature = "proptest")]
+pub fn field_element() -> impl Strategy<Value = FieldElement> {
+    field_element_with_modulus(FieldElement::default_modulus())
+}
+
+/// Generates elements of a given field, with 0, 1 and -1 more likely than
+/// uniform sampling would draw them.
+///
+/// # Parameters
+/// - `modulus`: The modulus of the field.
+///
+/// # Returns
+/// - `impl Strategy<Value = FieldElement>`: The strategy.
+#[cfg(feature = "proptest")]
+pub fn field_element_with_modulus(modulus: BigInt) -> impl Strategy<Value = FieldElement> {
+    let edges = [BigInt::from(0), BigInt::one(), &modulus - 1].map(|value| FieldElement::new_with_modulus(value, modulus.clone()));
+    let uniform = vec(any::<u8>(), sample_bytes(&modulus)).prop_map(move |bytes| reduce(&bytes, &modulus));
+    prop_oneof![
+        1 => proptest::sample::select(edges.to_vec()),
+        4 => uniform,
+    ]
+}
+
+/// Generates witnesses of elements of the default field.
+///
+/// # Parameters
+/// - `len`: The length of the witnesses, or a range of lengths.
+///
+/// # Returns
+/// - `impl Strategy<Value = Vec<FieldElement>>`: The strategy.
+#[cfg(feature = "proptest")]
+pub fn witness(len: impl Into<SizeRange>) -> impl Strategy<Value = Vec<FieldElement>> {
+    vec(field_element(), len)
+}
+
+/// Generates the terms of one side of a constraint over given variables.
+#[cfg(feature = "proptest")]
+fn terms(variables: usize) -> impl Strategy<Value = Terms> {
+    vec((0..variables, field_element()), 1..=MAX_TERMS)
+}
+
+/// Generates standalone constraints over variables below an index, of any
+/// operation and with random values, for testing code that inspects
+/// constraints rather than checks them.
+///
+/// # Parameters
+/// - `variables`: The number of variables the terms may refer to, at least 1.
+///
+/// # Returns
+/// - `impl Strategy<Value = Constraint>`: The strategy.
+///
+/// # Panics
+/// - If `variables` is 0.
+#[cfg(feature = "proptest")]
+pub fn constraint(variables: usize) -> impl Strategy<Value = Constraint> {
+    assert!(variables > 0, "A generated constraint needs at least one variable");
+    let side = || vec(((0..variables, field_element()).prop_map(|(index, value)| Variable { index, value }), field_element()), 1..=MAX_TERMS)
+        .prop_map(|terms| terms.into_iter().map(|(variable, coeff)| (variable, coeff.get_value())).collect::<Vec<_>>());
+    let operation = proptest::sample::select(vec![Operation::Add, Operation::Mul, Operation::Hash]);
+    (side(), side(), side(), operation).prop_map(|(left, right, output, operation)| Constraint { left, right, output, operation })
+}
+
+/// Generates satisfied systems over the default field.
+///
+/// Each system has free variables followed by one variable per constraint,
+/// holding the product of the two sides of the constraint. The variables hold
+/// a witness satisfying every constraint, returned by
+/// `R1CS::generate_witness`; perturbing it gives witnesses to reject.
+///
+/// # Parameters
+/// - `max_variables`: The most free variables, at least 1.
+/// - `max_constraints`: The most constraints.
+///
+/// # Returns
+/// - `impl Strategy<Value = R1CS>`: The strategy.
+///
+/// # Panics
+/// - If `max_variables` is 0.
+#[cfg(feature = "proptest")]
+pub fn r1cs(max_variables: usize, max_constraints: usize) -> impl Strategy<Value = R1CS> {
+    assert!(max_variables > 0, "A generated system needs at least one free variable");
+    let free = vec(field_element(), 1..=max_variables);
+    let rows = vec((terms(max_variables + max_constraints), terms(max_variables + max_constraints)), 0..=max_constraints);
+    (free, rows).prop_map(|(values, rows)| satisfied_system(values, rows))
+}
+
+#[cfg(feature = "proptest")]
+impl proptest::arbitrary::Arbitrary for FieldElement {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        field_element().boxed()
+    }
+}
@@ -0,0 +1,41 @@
+#[cfg(feature = "trace")]
+use std::time::Instant;
+
+/// Enters a tracing span for a phase of proving until the end of the
+/// enclosing scope, with the `trace` feature; without it, the fields are not
+/// even evaluated.
+///
+/// When the scope ends, early returns included, an event records the
+/// duration of the phase as `elapsed_us`.
+macro_rules! trace_phase {
+    ($name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "trace")]
+        let _phase = crate::trace::PhaseSpan::enter(tracing::info_span!($name $(, $field = $value)*));
+    };
+}
+
+pub(crate) use trace_phase;
+
+/// Keeps the span of a phase entered and reports its duration when dropped.
+#[cfg(feature = "trace")]
+pub(crate) struct PhaseSpan {
+    /// The entered span, exited after the duration is reported.
+    _span: tracing::span::EnteredSpan,
+    /// When the phase started.
+    start: Instant,
+}
+
+#[cfg(feature = "trace")]
+impl PhaseSpan {
+    /// Enters the span of a phase and starts timing it.
+    pub(crate) fn enter(span: tracing::Span) -> Self {
+        PhaseSpan { _span: span.entered(), start: Instant::now() }
+    }
+}
+
+#[cfg(feature = "trace")]
+impl Drop for PhaseSpan {
+    fn drop(&mut self) {
+        tracing::info!(elapsed_us = self.start.elapsed().as_micros() as u64, "phase finished");
+    }
+}
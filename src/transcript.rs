@@ -0,0 +1,161 @@
+#[cfg(feature = "merlin")]
+use alloc::vec;
+use num_bigint::{BigInt, Sign};
+use sha2::{Digest, Sha256};
+use crate::field::FieldElement;
+use crate::poseidon::{Poseidon, PoseidonSponge};
+
+/// Represents a Fiat-Shamir transcript: the prover and the verifier append the
+/// same messages in the same order, and derive the same challenges from them.
+///
+/// Every message and challenge carries a label, so protocols appending the
+/// same values under different labels derive different challenges.
+pub trait Transcript {
+    /// Appends a field element to the transcript.
+    ///
+    /// # Parameters
+    /// - `label`: The label of the message.
+    /// - `element`: The element.
+    fn append(&mut self, label: &'static [u8], element: &FieldElement);
+
+    /// Derives a challenge from everything appended so far.
+    ///
+    /// # Parameters
+    /// - `label`: The label of the challenge.
+    ///
+    /// # Returns
+    /// - `FieldElement`: The challenge, in the field of the transcript.
+    fn challenge(&mut self, label: &'static [u8]) -> FieldElement;
+}
+
+/// Represents a transcript built on the Poseidon sponge, the default backend.
+///
+/// Labels are hashed into field elements with SHA-256 and absorbed before the
+/// message or challenge they name. Labels are fixed when a circuit is built,
+/// so `gadgets::poseidon::Sponge` can replay the transcript in a circuit.
+#[derive(Clone, Debug)]
+pub struct PoseidonTranscript {
+    /// The sponge absorbing the messages.
+    sponge: PoseidonSponge,
+    /// The modulus of the field of the permutation.
+    modulus: BigInt,
+}
+
+impl PoseidonTranscript {
+    /// Creates a transcript over the default Poseidon parameters.
+    ///
+    /// # Parameters
+    /// - `domain`: The name of the protocol, which initializes the capacity element.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `PoseidonTranscript` struct.
+    pub fn new(domain: &'static [u8]) -> Self {
+        PoseidonTranscript::with_poseidon(Poseidon::default(), domain)
+    }
+
+    /// Creates a transcript over given Poseidon parameters.
+    ///
+    /// # Parameters
+    /// - `poseidon`: The permutation of the sponge, whose field is the field of the challenges.
+    /// - `domain`: The name of the protocol, which initializes the capacity element.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `PoseidonTranscript` struct.
+    pub fn with_poseidon(poseidon: Poseidon, domain: &'static [u8]) -> Self {
+        let modulus = poseidon.modulus.clone();
+        let capacity = label_element(&modulus, domain);
+        PoseidonTranscript { sponge: PoseidonSponge::with_capacity(poseidon, capacity), modulus }
+    }
+}
+
+impl Transcript for PoseidonTranscript {
+    fn append(&mut self, label: &'static [u8], element: &FieldElement) {
+        self.sponge.absorb(&label_element(&self.modulus, label));
+        self.sponge.absorb(element);
+    }
+
+    fn challenge(&mut self, label: &'static [u8]) -> FieldElement {
+        self.sponge.absorb(&label_element(&self.modulus, label));
+        self.sponge.squeeze()
+    }
+}
+
+/// Represents a transcript delegating to merlin's STROBE-based transcripts,
+/// so challenges match other Rust proof systems built on merlin.
+///
+/// Elements are appended as little-endian bytes padded to the length of the
+/// modulus. Challenges reduce 16 bytes more than the modulus takes, so their
+/// bias is below `2^-128`.
+#[cfg(feature = "merlin")]
+#[derive(Clone)]
+pub struct MerlinTranscript {
+    /// The underlying merlin transcript.
+    transcript: merlin::Transcript,
+    /// The modulus of the field of the challenges.
+    modulus: BigInt,
+}
+
+#[cfg(feature = "merlin")]
+impl MerlinTranscript {
+    /// Creates a transcript over the default field.
+    ///
+    /// # Parameters
+    /// - `domain`: The name of the protocol, passed to `merlin::Transcript::new`.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `MerlinTranscript` struct.
+    pub fn new(domain: &'static [u8]) -> Self {
+        MerlinTranscript::from_transcript(merlin::Transcript::new(domain), FieldElement::default_modulus())
+    }
+
+    /// Wraps an existing merlin transcript, to share it with another library.
+    ///
+    /// # Parameters
+    /// - `transcript`: The merlin transcript.
+    /// - `modulus`: The modulus of the field of the challenges.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `MerlinTranscript` struct.
+    pub fn from_transcript(transcript: merlin::Transcript, modulus: BigInt) -> Self {
+        MerlinTranscript { transcript, modulus }
+    }
+
+    /// Unwraps the merlin transcript, to hand it back to another library.
+    ///
+    /// # Returns
+    /// - `merlin::Transcript`: The underlying transcript.
+    pub fn into_inner(self) -> merlin::Transcript {
+        self.transcript
+    }
+
+    /// Gets the number of bytes of an encoded element.
+    ///
+    /// # Returns
+    /// - `usize`: The number of bytes of the modulus.
+    fn element_bytes(&self) -> usize {
+        (self.modulus.bits() as usize).div_ceil(8)
+    }
+}
+
+#[cfg(feature = "merlin")]
+impl Transcript for MerlinTranscript {
+    fn append(&mut self, label: &'static [u8], element: &FieldElement) {
+        let mut bytes = element.get_value().to_bytes_le().1;
+        bytes.resize(self.element_bytes(), 0);
+        self.transcript.append_message(label, &bytes);
+    }
+
+    fn challenge(&mut self, label: &'static [u8]) -> FieldElement {
+        let mut bytes = vec![0u8; self.element_bytes() + 16];
+        self.transcript.challenge_bytes(label, &mut bytes);
+        FieldElement::new_with_modulus(BigInt::from_bytes_le(Sign::Plus, &bytes), self.modulus.clone())
+    }
+}
+
+/// Hashes a label into a field element with SHA-256.
+fn label_element(modulus: &BigInt, label: &[u8]) -> FieldElement {
+    let mut hasher = Sha256::new();
+    hasher.update(b"transcript");
+    hasher.update(label);
+    FieldElement::new_with_modulus(BigInt::from_bytes_be(Sign::Plus, &hasher.finalize()), modulus.clone())
+}
@@ -0,0 +1,157 @@
+use ark_bn254::{Fr, G1Affine};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseUVPolynomial, EvaluationDomain, Radix2EvaluationDomain};
+use crate::ark::to_ark;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::kzg::KzgParams;
+use crate::rng::{CryptoRng, OsRng, RngCore};
+
+/// Represents a commitment to a vector of BN254 scalars.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VectorCommitment(pub G1Affine);
+
+/// Represents a proof that a committed vector holds given values at given
+/// positions: a single group element, whatever the length of the vector and
+/// the number of positions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Opening(pub G1Affine);
+
+/// Commits to vectors with KZG, as an alternative to Merkle trees when
+/// openings must stay constant-size.
+///
+/// The vector is the evaluations of a polynomial over a power-of-two domain,
+/// position `i` sitting at the `i`-th root of unity, and an opening is a KZG
+/// proof at the roots of the opened positions.
+#[derive(Clone, Debug)]
+pub struct VectorCommitmentKey {
+    /// The KZG parameters.
+    params: KzgParams,
+    /// The domain whose points hold the positions.
+    domain: Radix2EvaluationDomain<Fr>,
+}
+
+impl VectorCommitmentKey {
+    /// Generates a key for vectors up to a given length, sampling the KZG secret from `OsRng`.
+    ///
+    /// # Parameters
+    /// - `length`: The largest length of a committed vector, and of a batch opening.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The key, or an error if the length exceeds the two-adicity of the BN254 scalar field.
+    pub fn setup(length: usize) -> Result<Self> {
+        VectorCommitmentKey::setup_with_rng(length, &mut OsRng)
+    }
+
+    /// Generates a key for vectors up to a given length, sampling the KZG secret from a given generator.
+    ///
+    /// # Parameters
+    /// - `length`: The largest length of a committed vector, and of a batch opening.
+    /// - `rng`: The source of the KZG secret.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The key, or an error if the length exceeds the two-adicity of the BN254 scalar field.
+    pub fn setup_with_rng<R: RngCore + CryptoRng>(length: usize, rng: &mut R) -> Result<Self> {
+        let domain = Radix2EvaluationDomain::new(length.max(1))
+            .ok_or_else(|| Error::Input(format!("Cannot commit to vectors of length {}", length)))?;
+        let params = KzgParams::setup_with_rng(domain.size() - 1, domain.size(), rng);
+        Ok(VectorCommitmentKey { params, domain })
+    }
+
+    /// Gets the largest length of a committed vector.
+    ///
+    /// # Returns
+    /// - `usize`: The size of the domain.
+    pub fn capacity(&self) -> usize {
+        self.domain.size()
+    }
+
+    /// Commits to a vector.
+    ///
+    /// # Parameters
+    /// - `values`: The vector, whose elements must be BN254 scalars; missing positions up to the capacity are zero.
+    ///
+    /// # Returns
+    /// - `Result<VectorCommitment>`: The commitment, or an error if the vector is too long or not over BN254.
+    pub fn commit(&self, values: &[FieldElement]) -> Result<VectorCommitment> {
+        Ok(VectorCommitment(self.params.commit(&self.polynomial(values)?)?))
+    }
+
+    /// Opens a vector at one position.
+    ///
+    /// # Parameters
+    /// - `values`: The committed vector.
+    /// - `index`: The position to open.
+    ///
+    /// # Returns
+    /// - `Result<Opening>`: The opening, or an error if the position is out of range.
+    pub fn open(&self, values: &[FieldElement], index: usize) -> Result<Opening> {
+        self.open_batch(values, &[index])
+    }
+
+    /// Opens a vector at several positions with a single proof.
+    ///
+    /// # Parameters
+    /// - `values`: The committed vector.
+    /// - `indices`: The distinct positions to open.
+    ///
+    /// # Returns
+    /// - `Result<Opening>`: The opening, or an error if a position is out of range or repeated.
+    pub fn open_batch(&self, values: &[FieldElement], indices: &[usize]) -> Result<Opening> {
+        let points = self.points(indices)?;
+        Ok(Opening(self.params.open(&self.polynomial(values)?, &points)?))
+    }
+
+    /// Verifies that a committed vector holds a value at a position.
+    ///
+    /// # Parameters
+    /// - `commitment`: The commitment to the vector.
+    /// - `index`: The opened position.
+    /// - `value`: The claimed value.
+    /// - `opening`: The opening from `open`.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the vector holds the value at the position, otherwise `false`.
+    pub fn verify(&self, commitment: &VectorCommitment, index: usize, value: &FieldElement, opening: &Opening) -> bool {
+        self.verify_batch(commitment, &[(index, value.clone())], opening)
+    }
+
+    /// Verifies that a committed vector holds values at several positions.
+    ///
+    /// # Parameters
+    /// - `commitment`: The commitment to the vector.
+    /// - `entries`: The opened `(position, value)` pairs, in the order they were opened.
+    /// - `opening`: The opening from `open_batch`.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the vector holds every value at its position, otherwise `false`.
+    pub fn verify_batch(&self, commitment: &VectorCommitment, entries: &[(usize, FieldElement)], opening: &Opening) -> bool {
+        let indices: Vec<usize> = entries.iter().map(|(index, _)| *index).collect();
+        let (Ok(points), Ok(values)) = (
+            self.points(&indices),
+            entries.iter().map(|(_, value)| to_ark::<Fr>(value)).collect::<Result<Vec<Fr>>>(),
+        ) else {
+            return false;
+        };
+        self.params.verify(&commitment.0, &points, &values, &opening.0)
+    }
+
+    /// Interpolates a vector over the domain.
+    fn polynomial(&self, values: &[FieldElement]) -> Result<DensePolynomial<Fr>> {
+        if values.len() > self.domain.size() {
+            return Err(Error::Input(format!("Cannot commit to {} values with a key for {}", values.len(), self.domain.size())));
+        }
+        let evaluations = values.iter().map(to_ark::<Fr>).collect::<Result<Vec<Fr>>>()?;
+        Ok(DensePolynomial::from_coefficients_vec(self.domain.ifft(&evaluations)))
+    }
+
+    /// Maps positions to the points of the domain holding them.
+    fn points(&self, indices: &[usize]) -> Result<Vec<Fr>> {
+        indices.iter()
+            .map(|&index| match index < self.domain.size() {
+                true => Ok(self.domain.element(index)),
+                false => Err(Error::Input(format!("Position {} is out of range for a key of length {}", index, self.domain.size()))),
+            })
+            .collect()
+    }
+}
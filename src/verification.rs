@@ -0,0 +1,194 @@
+use std::fmt;
+use std::time::Duration;
+use crate::field::FieldElement;
+use crate::fingerprint::{CircuitId, CircuitVersion};
+use crate::proof::Proof;
+use crate::r1cs::UnsatisfiedConstraint;
+
+/// Describes a gate that does not hold on the witness of a proof.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FailedGate {
+    /// The index of the gate.
+    pub index: usize,
+    /// The index of the output wire of the gate.
+    pub output: usize,
+    /// The value the gate computes from its input wires.
+    pub expected: FieldElement,
+    /// The value the witness assigns to the output wire.
+    pub actual: FieldElement,
+}
+
+/// Describes a public input whose claimed value differs from the value the proof carries.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputMismatch {
+    /// The name of the public input.
+    pub name: String,
+    /// The index of the wire of the public input.
+    pub wire: usize,
+    /// The value claimed by the verifier.
+    pub claimed: FieldElement,
+    /// The value the proof carries, or `None` if it carries too few public inputs.
+    pub proven: Option<FieldElement>,
+}
+
+/// Reports the outcome of every check made when verifying a proof, so callers
+/// can log and act on the specific way a proof fails.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerificationReport {
+    /// The number of values the witness must hold, one per wire of the circuit and the constant one,
+    /// or `None` if the proof was checked from its public values alone.
+    pub expected_wires: Option<usize>,
+    /// The number of values in the witness of the proof.
+    pub witness_len: usize,
+    /// The id of the circuit the proof is checked against, or `None` if it could not be rebuilt.
+    pub circuit_id: Option<CircuitId>,
+    /// The id of the circuit the proof was generated for.
+    pub proof_circuit_id: CircuitId,
+    /// The version of the circuit the proof is checked against.
+    pub version: CircuitVersion,
+    /// The version of the circuit the proof was generated with.
+    pub proof_version: CircuitVersion,
+    /// Whether every value of the proof is a canonical field element, below the modulus.
+    pub canonical: bool,
+    /// Whether the constraints were checked on the witness of the proof. The built-in proof
+    /// system has no argument that a committed witness satisfies them, so a proof without its
+    /// witness, such as one decoded from bytes, is unverifiable rather than valid.
+    pub witness_checked: bool,
+    /// Whether the commitment of the proof matches its witness, or `None` if the proof was checked
+    /// from its public values alone.
+    pub commitment_valid: Option<bool>,
+    /// Whether the public inputs of the proof are the values of the circuit's public input wires,
+    /// or, without the witness, whether there is one per public input.
+    pub public_inputs_valid: bool,
+    /// Whether the public outputs of the proof are the values of the circuit's output wires,
+    /// or, without the witness, whether there is one per output.
+    pub outputs_valid: bool,
+    /// The gates that do not hold on the witness, empty if the witness has the wrong length.
+    pub failed_gates: Vec<FailedGate>,
+    /// The R1CS constraints the witness does not satisfy, empty if the witness has the wrong length.
+    pub unsatisfied_constraints: Vec<UnsatisfiedConstraint>,
+    /// The public inputs whose claimed values the proof does not commit to.
+    pub input_mismatches: Vec<InputMismatch>,
+    /// The time taken to verify.
+    pub duration: Duration,
+}
+
+impl VerificationReport {
+    /// Starts the report of a proof checked against its witness, with its witness length, version, values and commitment
+    /// checked, before its circuit id, public values and gates are.
+    pub(crate) fn new(expected_wires: usize, version: CircuitVersion, proof: &Proof) -> Self {
+        Self::start(Some(expected_wires), Some(proof.verify_commitment()), true, version, proof)
+    }
+
+    /// Starts the report of a proof without its witness, with its version and values checked, before its circuit id
+    /// and public values are. The report is unverifiable, since the constraints cannot be checked.
+    pub(crate) fn public(version: CircuitVersion, proof: &Proof) -> Self {
+        Self::start(None, None, false, version, proof)
+    }
+
+    /// Starts a report with the checks every proof goes through.
+    fn start(expected_wires: Option<usize>, commitment_valid: Option<bool>, witness_checked: bool, version: CircuitVersion, proof: &Proof) -> Self {
+        VerificationReport {
+            expected_wires,
+            witness_len: proof.witness.len(),
+            circuit_id: None,
+            proof_circuit_id: proof.circuit_id,
+            version,
+            proof_version: proof.version,
+            canonical: proof.is_canonical(),
+            witness_checked,
+            commitment_valid,
+            public_inputs_valid: true,
+            outputs_valid: true,
+            failed_gates: Vec::new(),
+            unsatisfied_constraints: Vec::new(),
+            input_mismatches: Vec::new(),
+            duration: Duration::default(),
+        }
+    }
+
+    /// Checks whether every check passed.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the proof is valid, otherwise `false`, including when it is unverifiable.
+    pub fn is_valid(&self) -> bool {
+        self.witness_checked
+            && self.expected_wires.is_none_or(|wires| wires == self.witness_len)
+            && self.circuit_id == Some(self.proof_circuit_id)
+            && self.proof_version.is_compatible_with(&self.version)
+            && self.canonical
+            && self.commitment_valid != Some(false)
+            && self.public_inputs_valid
+            && self.outputs_valid
+            && self.failed_gates.is_empty()
+            && self.unsatisfied_constraints.is_empty()
+            && self.input_mismatches.is_empty()
+    }
+
+    /// Describes each failed check on its own line.
+    ///
+    /// # Returns
+    /// - `Vec<String>`: The failures, empty if the proof is valid.
+    pub fn failures(&self) -> Vec<String> {
+        let mut failures = Vec::new();
+        if !self.witness_checked {
+            failures.push("The proof carries no witness, and the built-in proof system cannot check the constraints without it".to_string());
+        }
+        if let Some(wires) = self.expected_wires.filter(|&wires| wires != self.witness_len) {
+            failures.push(format!("The witness holds {} values, but the circuit needs {}", self.witness_len, wires));
+        }
+        if let Some(circuit_id) = self.circuit_id.filter(|id| *id != self.proof_circuit_id) {
+            failures.push(format!("The proof was generated for circuit {}, but this circuit is {}", self.proof_circuit_id, circuit_id));
+        }
+        if !self.proof_version.is_compatible_with(&self.version) {
+            failures.push(format!("The proof was generated with circuit version {}, which version {} does not accept", self.proof_version, self.version));
+        }
+        if !self.canonical {
+            failures.push("The proof holds values outside the field".to_string());
+        }
+        if self.commitment_valid == Some(false) {
+            failures.push("The commitment does not match the witness".to_string());
+        }
+        if !self.public_inputs_valid {
+            failures.push("The public inputs do not match the public input wires of the circuit".to_string());
+        }
+        if !self.outputs_valid {
+            failures.push("The public outputs do not match the output wires of the circuit".to_string());
+        }
+        for gate in &self.failed_gates {
+            failures.push(format!(
+                "Gate {} computes {} but wire {} holds {}",
+                gate.index, gate.expected.get_value(), gate.output, gate.actual.get_value()
+            ));
+        }
+        for constraint in &self.unsatisfied_constraints {
+            failures.push(format!(
+                "Constraint {} {} expects {} but the output is {}",
+                constraint.index, constraint.label, constraint.expected.get_value(), constraint.output.get_value()
+            ));
+        }
+        for mismatch in &self.input_mismatches {
+            let proven = mismatch.proven.as_ref().map_or_else(|| "nothing".to_string(), |value| value.get_value().to_string());
+            failures.push(format!(
+                "Public input `{}` is claimed to be {} but the proof commits to {}",
+                mismatch.name, mismatch.claimed.get_value(), proven
+            ));
+        }
+        failures
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = match (self.is_valid(), self.witness_checked) {
+            (true, _) => "valid",
+            (false, true) => "invalid",
+            (false, false) => "unverifiable",
+        };
+        write!(f, "Proof is {} ({:.3} ms)", verdict, self.duration.as_secs_f64() * 1000.0)?;
+        for failure in self.failures() {
+            write!(f, "\n  {}", failure)?;
+        }
+        Ok(())
+    }
+}
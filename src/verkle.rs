@@ -0,0 +1,261 @@
+use std::convert::TryFrom;
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField, Zero};
+use crate::ark::{from_ark, to_ark};
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::vector_commitment::{Opening, VectorCommitment, VectorCommitmentKey};
+
+/// Represents a membership proof in a Verkle tree: one node commitment and one
+/// vector opening per level, so its size grows with the depth of the tree but
+/// not with its arity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerkleProof {
+    /// The commitments to the nodes on the path, from the leaf level up, without the root.
+    pub commitments: Vec<VectorCommitment>,
+    /// The opening of every node on the path at the position of its child, from the leaf level up.
+    pub openings: Vec<Opening>,
+}
+
+/// Represents a Verkle tree: a wide-arity tree whose nodes are vector
+/// commitments to their children, so a path opens one position per level
+/// instead of carrying every sibling like a Merkle path.
+///
+/// The arity is the capacity of the vector commitment key. A child node enters
+/// its parent as the x-coordinate of its commitment reduced into the scalar
+/// field. This is a prototype for experimenting with the layout: openings are
+/// not aggregated across levels, as production Verkle trees do.
+#[derive(Clone, Debug)]
+pub struct VerkleTree {
+    /// The key committing to every node.
+    key: VectorCommitmentKey,
+    /// The values of every level, from the leaves up, `arity` per node.
+    levels: Vec<Vec<FieldElement>>,
+    /// The commitments to the nodes of every level, from the leaf level up.
+    commitments: Vec<Vec<VectorCommitment>>,
+}
+
+impl VerkleTree {
+    /// Builds a tree over a list of leaves.
+    ///
+    /// # Parameters
+    /// - `key`: The key committing to the nodes, whose capacity is the arity of the tree.
+    /// - `depth`: The number of levels of nodes, at least one.
+    /// - `leaves`: The leaves, BN254 scalars; missing leaves up to `arity^depth` are zero.
+    ///
+    /// # Returns
+    /// - `Result<Self>`: The tree, or an error if the leaves do not fit or are not over BN254.
+    pub fn new(key: VectorCommitmentKey, depth: usize, leaves: &[FieldElement]) -> Result<Self> {
+        let width = key.capacity();
+        let capacity = capacity(width, depth)
+            .ok_or_else(|| Error::Input(format!("Cannot build a Verkle tree of arity {} and depth {}", width, depth)))?;
+        if leaves.len() > capacity {
+            return Err(Error::Input(format!("Cannot store {} leaves in a Verkle tree of {}", leaves.len(), capacity)));
+        }
+        let mut level = leaves.to_vec();
+        level.resize(capacity, from_ark(&Fr::zero()));
+        let mut levels = Vec::with_capacity(depth);
+        let mut commitments = Vec::with_capacity(depth);
+        for _ in 0..depth {
+            let nodes = level.chunks(width).map(|node| key.commit(node)).collect::<Result<Vec<_>>>()?;
+            let parent = nodes.iter().map(node_value).collect();
+            levels.push(level);
+            commitments.push(nodes);
+            level = parent;
+        }
+        Ok(VerkleTree { key, levels, commitments })
+    }
+
+    /// Gets the commitment to the root node.
+    ///
+    /// # Returns
+    /// - `&VectorCommitment`: The root of the tree.
+    pub fn root(&self) -> &VectorCommitment {
+        &self.commitments[self.commitments.len() - 1][0]
+    }
+
+    /// Gets the number of levels of nodes.
+    ///
+    /// # Returns
+    /// - `usize`: The depth of the tree.
+    pub fn depth(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Gets the number of children of every node.
+    ///
+    /// # Returns
+    /// - `usize`: The arity of the tree.
+    pub fn arity(&self) -> usize {
+        self.key.capacity()
+    }
+
+    /// Gets a leaf.
+    ///
+    /// # Parameters
+    /// - `index`: The index of the leaf.
+    ///
+    /// # Returns
+    /// - `Option<&FieldElement>`: The leaf, or `None` if the index is out of range.
+    pub fn get(&self, index: usize) -> Option<&FieldElement> {
+        self.levels[0].get(index)
+    }
+
+    /// Replaces a leaf and recommits to the nodes on its path.
+    ///
+    /// # Parameters
+    /// - `index`: The index of the leaf.
+    /// - `value`: The new value, a BN254 scalar.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the index is out of range or the value is not over BN254.
+    pub fn update(&mut self, index: usize, value: FieldElement) -> Result<()> {
+        self.check_index(index)?;
+        to_ark::<Fr>(&value)?;
+        let width = self.arity();
+        let mut position = index;
+        let mut value = value;
+        for level in 0..self.depth() {
+            self.levels[level][position] = value;
+            let node = position / width;
+            let commitment = self.key.commit(&self.levels[level][node * width..(node + 1) * width])?;
+            self.commitments[level][node] = commitment;
+            value = node_value(&commitment);
+            position = node;
+        }
+        Ok(())
+    }
+
+    /// Proves that a leaf is in the tree.
+    ///
+    /// # Parameters
+    /// - `index`: The index of the leaf.
+    ///
+    /// # Returns
+    /// - `Result<VerkleProof>`: The proof, or an error if the index is out of range.
+    pub fn prove(&self, index: usize) -> Result<VerkleProof> {
+        self.check_index(index)?;
+        let width = self.arity();
+        let mut position = index;
+        let mut commitments = Vec::with_capacity(self.depth() - 1);
+        let mut openings = Vec::with_capacity(self.depth());
+        for level in 0..self.depth() {
+            let node = position / width;
+            openings.push(self.key.open(&self.levels[level][node * width..(node + 1) * width], position % width)?);
+            if level + 1 < self.depth() {
+                commitments.push(self.commitments[level][node]);
+            }
+            position = node;
+        }
+        Ok(VerkleProof { commitments, openings })
+    }
+
+    /// Fails unless a leaf index is in range.
+    fn check_index(&self, index: usize) -> Result<()> {
+        if index >= self.levels[0].len() {
+            return Err(Error::Input(format!("Leaf {} is out of range for a Verkle tree of {}", index, self.levels[0].len())));
+        }
+        Ok(())
+    }
+}
+
+impl VerkleProof {
+    /// Verifies that a leaf is in a tree.
+    ///
+    /// # Parameters
+    /// - `key`: The key the tree was built with.
+    /// - `root`: The root of the tree.
+    /// - `depth`: The depth of the tree.
+    /// - `index`: The index of the leaf.
+    /// - `value`: The claimed value of the leaf.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the tree holds the value at the index, otherwise `false`.
+    pub fn verify(&self, key: &VectorCommitmentKey, root: &VectorCommitment, depth: usize, index: usize, value: &FieldElement) -> bool {
+        let width = key.capacity();
+        if depth == 0 || self.openings.len() != depth || self.commitments.len() != depth - 1
+            || capacity(width, depth).is_none_or(|capacity| index >= capacity) {
+            return false;
+        }
+        let mut position = index;
+        let mut value = value.clone();
+        for (level, opening) in self.openings.iter().enumerate() {
+            let commitment = self.commitments.get(level).unwrap_or(root);
+            if !key.verify(commitment, position % width, &value, opening) {
+                return false;
+            }
+            value = node_value(commitment);
+            position /= width;
+        }
+        true
+    }
+}
+
+/// Computes the number of leaves of a tree, `width^depth`.
+fn capacity(width: usize, depth: usize) -> Option<usize> {
+    match depth {
+        0 => None,
+        _ => width.checked_pow(u32::try_from(depth).ok()?),
+    }
+}
+
+/// Maps a node commitment to the scalar its parent commits to.
+fn node_value(commitment: &VectorCommitment) -> FieldElement {
+    from_ark(&Fr::from_le_bytes_mod_order(&commitment.0.x.into_bigint().to_bytes_le()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+
+    /// Maps an integer to a BN254 scalar.
+    fn scalar(value: u64) -> FieldElement {
+        from_ark(&Fr::from(value))
+    }
+
+    /// Builds a tree of arity 4 and depth 2 holding `100 + i` at leaf `i`.
+    fn tree() -> (VectorCommitmentKey, VerkleTree) {
+        let key = VectorCommitmentKey::setup_with_rng(4, &mut rng::seeded(0)).unwrap();
+        let leaves: Vec<FieldElement> = (0..16).map(|i| scalar(100 + i)).collect();
+        (key.clone(), VerkleTree::new(key, 2, &leaves).unwrap())
+    }
+
+    #[test]
+    fn every_leaf_proves_its_value() {
+        let (key, tree) = tree();
+        for index in 0..16 {
+            let proof = tree.prove(index).unwrap();
+            assert!(proof.verify(&key, tree.root(), 2, index, &scalar(100 + index as u64)));
+        }
+    }
+
+    #[test]
+    fn proofs_of_a_wrong_value_are_rejected() {
+        let (key, tree) = tree();
+        let proof = tree.prove(5).unwrap();
+        assert!(!proof.verify(&key, tree.root(), 2, 5, &scalar(106)));
+    }
+
+    #[test]
+    fn proofs_at_a_wrong_index_are_rejected() {
+        let (key, tree) = tree();
+        let proof = tree.prove(5).unwrap();
+        // A sibling in the same node, a leaf in another node, and one out of range
+        for &index in [6, 9, 16].iter() {
+            assert!(!proof.verify(&key, tree.root(), 2, index, &scalar(105)));
+            assert!(!proof.verify(&key, tree.root(), 2, index, &scalar(100 + index as u64)));
+        }
+        assert!(!proof.verify(&key, tree.root(), 1, 5, &scalar(105)));
+    }
+
+    #[test]
+    fn updates_change_the_root_and_invalidate_old_proofs() {
+        let (key, mut tree) = tree();
+        let proof = tree.prove(5).unwrap();
+        tree.update(5, scalar(7)).unwrap();
+        assert!(!proof.verify(&key, tree.root(), 2, 5, &scalar(105)));
+        assert!(tree.prove(5).unwrap().verify(&key, tree.root(), 2, 5, &scalar(7)));
+        assert_eq!(tree.get(5), Some(&scalar(7)));
+    }
+}
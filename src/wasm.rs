@@ -0,0 +1,47 @@
+use wasm_bindgen::prelude::*;
+use crate::api;
+use crate::error::Error;
+
+/// Converts a crate error into a JavaScript error.
+fn to_js(err: Error) -> JsValue {
+    JsValue::from_str(&err.to_string())
+}
+
+/// Compiles a circuit from its JSON description.
+///
+/// # Parameters
+/// - `circuit_json`: The circuit description.
+///
+/// # Returns
+/// - `Result<Vec<u8>, JsValue>`: The compiled circuit as a `Uint8Array`.
+#[wasm_bindgen(js_name = compileCircuit)]
+pub fn compile_circuit(circuit_json: &str) -> Result<Vec<u8>, JsValue> {
+    api::compile_circuit(circuit_json).map_err(to_js)
+}
+
+/// Proves a compiled circuit with the given inputs.
+///
+/// # Parameters
+/// - `compiled`: The compiled circuit.
+/// - `inputs_json`: A JSON array with one value per input, or an object of named inputs.
+///
+/// # Returns
+/// - `Result<Vec<u8>, JsValue>`: The proof as a `Uint8Array`.
+#[wasm_bindgen]
+pub fn prove(compiled: &[u8], inputs_json: &str) -> Result<Vec<u8>, JsValue> {
+    api::prove(compiled, inputs_json).map_err(to_js)
+}
+
+/// Verifies a proof against a compiled circuit.
+///
+/// # Parameters
+/// - `compiled`: The compiled circuit.
+/// - `proof`: The proof.
+///
+/// # Returns
+/// - `Result<bool, JsValue>`: `true` if the proof is valid, otherwise `false`, including for
+///   proof bytes without a witness, which the built-in proof system cannot verify.
+#[wasm_bindgen]
+pub fn verify(compiled: &[u8], proof: &[u8]) -> Result<bool, JsValue> {
+    api::verify(compiled, proof).map_err(to_js)
+}
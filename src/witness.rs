@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use crate::compiler::CompiledCircuit;
+use crate::container::Compression;
+use crate::error::{Error, Result};
+use crate::field::FieldElement;
+use crate::r1cs;
+
+/// The number of wires per segment used by `StreamingWitness::new`.
+pub const DEFAULT_SEGMENT_SIZE: usize = 1 << 16;
+
+/// Receives the witness of a circuit as consecutive segments of wire values.
+pub trait WitnessSink {
+    /// Writes the next segment of the witness.
+    ///
+    /// # Parameters
+    /// - `start`: The index of the first wire of the segment.
+    /// - `values`: The values of the wires, in order.
+    ///
+    /// # Returns
+    /// - `Result<()>`: An error if the segment could not be written.
+    fn write_segment(&mut self, start: usize, values: &[FieldElement]) -> Result<()>;
+}
+
+/// Collects the whole witness in memory.
+impl WitnessSink for Vec<FieldElement> {
+    fn write_segment(&mut self, _start: usize, values: &[FieldElement]) -> Result<()> {
+        self.extend_from_slice(values);
+        Ok(())
+    }
+}
+
+/// Writes each segment of a witness to its own `ZKWT` file, readable with
+/// `r1cs::load_witness`.
+#[derive(Clone, Debug)]
+pub struct SegmentFiles {
+    /// The directory the segments are written to.
+    pub dir: PathBuf,
+    /// The name of the witness, which prefixes the segment files.
+    pub name: String,
+    /// The compression applied to each segment.
+    pub compression: Compression,
+    /// The paths of the segments written so far, in order.
+    pub paths: Vec<PathBuf>,
+}
+
+impl SegmentFiles {
+    /// Creates a sink writing `<dir>/<name>.<segment>.wtns.bin` files.
+    ///
+    /// # Parameters
+    /// - `dir`: The directory the segments are written to, which must exist.
+    /// - `name`: The name of the witness.
+    /// - `compression`: The compression applied to each segment.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `SegmentFiles` struct.
+    pub fn new(dir: impl Into<PathBuf>, name: &str, compression: Compression) -> Self {
+        SegmentFiles { dir: dir.into(), name: name.to_string(), compression, paths: Vec::new() }
+    }
+}
+
+impl WitnessSink for SegmentFiles {
+    fn write_segment(&mut self, _start: usize, values: &[FieldElement]) -> Result<()> {
+        let path = self.dir.join(format!("{}.{}.wtns.bin", self.name, self.paths.len()));
+        r1cs::save_witness(values, &path, self.compression)?;
+        self.paths.push(path);
+        Ok(())
+    }
+}
+
+/// Generates the witness of a compiled circuit without holding it in memory.
+///
+/// Gates are evaluated in order and every wire value is kept only until the
+/// last gate reading it, so memory grows with the number of simultaneously
+/// live wires rather than the size of the circuit. Completed wires are handed
+/// to a `WitnessSink` in segments, in wire order.
+#[derive(Clone, Debug)]
+pub struct StreamingWitness<'a> {
+    /// The circuit whose witness is generated.
+    pub circuit: &'a CompiledCircuit,
+    /// The number of wires buffered before a segment is written.
+    pub segment_size: usize,
+}
+
+impl<'a> StreamingWitness<'a> {
+    /// Creates a generator writing segments of `DEFAULT_SEGMENT_SIZE` wires.
+    ///
+    /// # Parameters
+    /// - `circuit`: The circuit whose witness is generated.
+    ///
+    /// # Returns
+    /// - `Self`: A new instance of the `StreamingWitness` struct.
+    pub fn new(circuit: &'a CompiledCircuit) -> Self {
+        StreamingWitness { circuit, segment_size: DEFAULT_SEGMENT_SIZE }
+    }
+
+    /// Sets the number of wires per segment.
+    ///
+    /// # Parameters
+    /// - `segment_size`: The number of wires buffered before a segment is written.
+    ///
+    /// # Returns
+    /// - `Self`: The generator with the new segment size.
+    pub fn with_segment_size(mut self, segment_size: usize) -> Self {
+        self.segment_size = segment_size.max(1);
+        self
+    }
+
+    /// Assigns the inputs, evaluates every gate and streams the wire values to a sink.
+    ///
+    /// # Parameters
+    /// - `inputs`: The values of the input wires, in declaration order.
+    /// - `sink`: The sink receiving the witness segments.
+    ///
+    /// # Returns
+    /// - `Result<usize>`: The number of wires written, or an error if the number of inputs
+    ///   does not match, a gate is out of order or the sink fails.
+    pub fn generate<S: WitnessSink>(&self, inputs: &[FieldElement], sink: &mut S) -> Result<usize> {
+        let circuit = self.circuit;
+        if inputs.len() != circuit.num_inputs {
+            return Err(Error::Input(format!("Expected {} inputs, got {}", circuit.num_inputs, inputs.len())));
+        }
+
+        // The index of the last gate reading each wire; wires never read are not kept
+        let mut last_use: Vec<Option<usize>> = vec![None; circuit.num_inputs + circuit.gates.len()];
+        for (index, gate) in circuit.gates.iter().enumerate() {
+            for wire in gate.inputs() {
+                if let Some(last) = last_use.get_mut(wire) {
+                    *last = Some(index);
+                }
+            }
+        }
+
+        let mut live: HashMap<usize, FieldElement> = HashMap::new();
+        let mut segment = Segment::new(self.segment_size);
+        for (wire, value) in inputs.iter().enumerate() {
+            if last_use[wire].is_some() {
+                live.insert(wire, value.clone());
+            }
+            segment.push(value.clone(), sink)?;
+        }
+
+        for (index, gate) in circuit.gates.iter().enumerate() {
+            let output = gate.output();
+            if output != segment.next_wire() || gate.inputs().any(|wire| wire >= output) {
+                return Err(Error::Compile(format!(
+                    "Gate {} is out of order: gates must define consecutive wires from {} and only read earlier ones",
+                    index, circuit.num_inputs
+                )));
+            }
+            let value = gate.evaluate_with(|wire| &live[&wire]);
+            for wire in gate.inputs() {
+                if last_use[wire] == Some(index) {
+                    live.remove(&wire);
+                }
+            }
+            if last_use[output].is_some() {
+                live.insert(output, value.clone());
+            }
+            segment.push(value, sink)?;
+        }
+        segment.flush(sink)
+    }
+
+    /// Streams the witness to segment files in a directory.
+    ///
+    /// # Parameters
+    /// - `inputs`: The values of the input wires, in declaration order.
+    /// - `dir`: The directory the segments are written to.
+    /// - `name`: The name of the witness, which prefixes the segment files.
+    ///
+    /// # Returns
+    /// - `Result<Vec<PathBuf>>`: The paths of the segments, in order.
+    pub fn generate_to_dir(&self, inputs: &[FieldElement], dir: &Path, name: &str) -> Result<Vec<PathBuf>> {
+        std::fs::create_dir_all(dir)?;
+        let mut files = SegmentFiles::new(dir, name, Compression::None);
+        self.generate(inputs, &mut files)?;
+        Ok(files.paths)
+    }
+}
+
+/// Buffers wire values until a full segment can be written to the sink.
+struct Segment {
+    values: Vec<FieldElement>,
+    size: usize,
+    start: usize,
+}
+
+impl Segment {
+    /// Creates an empty buffer for segments of the given size.
+    fn new(size: usize) -> Self {
+        Segment { values: Vec::with_capacity(size), size, start: 0 }
+    }
+
+    /// Gets the index of the next wire to be pushed.
+    fn next_wire(&self) -> usize {
+        self.start + self.values.len()
+    }
+
+    /// Appends a wire value, writing the segment once it is full.
+    fn push<S: WitnessSink>(&mut self, value: FieldElement, sink: &mut S) -> Result<()> {
+        self.values.push(value);
+        if self.values.len() == self.size {
+            self.flush(sink)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered values, if any, and returns the number of wires written so far.
+    fn flush<S: WitnessSink>(&mut self, sink: &mut S) -> Result<usize> {
+        if !self.values.is_empty() {
+            sink.write_segment(self.start, &self.values)?;
+            self.start += self.values.len();
+            self.values.clear();
+        }
+        Ok(self.start)
+    }
+}
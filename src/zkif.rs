@@ -0,0 +1,440 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use flatbuffers::{FlatBufferBuilder, TableFinishedWIPOffset, WIPOffset};
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::One;
+use crate::circom::{CircomCircuit, CircomConstraint, LinearCombination};
+use crate::error::{Error, Result};
+use crate::field::{FieldElement, ModulusPolicy};
+
+/// The file identifier of zkInterface messages.
+const IDENTIFIER: &[u8; 4] = b"zkif";
+
+/// The union tags of the `Message` types.
+const MESSAGE_CIRCUIT_HEADER: u8 = 1;
+const MESSAGE_CONSTRAINT_SYSTEM: u8 = 2;
+const MESSAGE_WITNESS: u8 = 3;
+
+/// The vtable slots of `Root`.
+const ROOT_MESSAGE_TYPE: u16 = 4;
+const ROOT_MESSAGE: u16 = 6;
+/// The vtable slots of `CircuitHeader`.
+const HEADER_INSTANCE_VARIABLES: u16 = 4;
+const HEADER_FREE_VARIABLE_ID: u16 = 6;
+const HEADER_FIELD_MAXIMUM: u16 = 8;
+/// The vtable slots of `ConstraintSystem`.
+const SYSTEM_CONSTRAINTS: u16 = 4;
+/// The vtable slots of `BilinearConstraint`.
+const CONSTRAINT_A: u16 = 4;
+const CONSTRAINT_B: u16 = 6;
+const CONSTRAINT_C: u16 = 8;
+/// The vtable slots of `Witness`.
+const WITNESS_ASSIGNED_VARIABLES: u16 = 4;
+/// The vtable slots of `Variables`.
+const VARIABLES_IDS: u16 = 4;
+const VARIABLES_VALUES: u16 = 6;
+
+/// Encodes a constraint system as zkInterface messages.
+///
+/// The output is a `CircuitHeader`, a `ConstraintSystem` and, given a witness,
+/// a `Witness` message, each size-prefixed as in `.zkif` files. Wire 0 is the
+/// constant one and the public outputs and inputs become the instance variables.
+///
+/// # Parameters
+/// - `circuit`: The constraint system.
+/// - `witness`: The full witness, starting with the constant one, or `None` to
+///   export the constraints only.
+///
+/// # Returns
+/// - `Result<Vec<u8>>`: The encoded messages, or an error if the witness has the wrong length.
+pub fn write_zkif(circuit: &CircomCircuit, witness: Option<&[FieldElement]>) -> Result<Vec<u8>> {
+    if let Some(witness) = witness {
+        if witness.len() != circuit.num_wires {
+            return Err(Error::Input(format!("Expected {} witness values, got {}", circuit.num_wires, witness.len())));
+        }
+    }
+    let num_public = circuit.num_public_outputs + circuit.num_public_inputs;
+    let field_maximum = &circuit.prime - BigInt::one();
+    let size = field_maximum.to_bytes_le().1.len();
+    let values = |wires: &[usize]| witness.map(|witness| wires.iter().map(|wire| witness[*wire].get_value()).collect::<Vec<_>>());
+
+    let public: Vec<usize> = (1..=num_public).collect();
+    let mut bytes = message(MESSAGE_CIRCUIT_HEADER, |fbb| {
+        let instance = variables(fbb, &public, values(&public).as_deref(), size);
+        let field_maximum = fbb.create_vector(&little_endian(&field_maximum, size));
+        let start = fbb.start_table();
+        fbb.push_slot_always(HEADER_INSTANCE_VARIABLES, instance);
+        fbb.push_slot::<u64>(HEADER_FREE_VARIABLE_ID, circuit.num_wires as u64, 0);
+        fbb.push_slot_always(HEADER_FIELD_MAXIMUM, field_maximum);
+        fbb.end_table(start)
+    });
+
+    bytes.extend(message(MESSAGE_CONSTRAINT_SYSTEM, |fbb| {
+        let constraints: Vec<_> = circuit.constraints.iter().map(|constraint| {
+            let [a, b, c] = [&constraint.a, &constraint.b, &constraint.c].map(|lc| {
+                let wires: Vec<usize> = lc.iter().map(|(wire, _)| *wire).collect();
+                let coeffs: Vec<BigInt> = lc.iter().map(|(_, coeff)| coeff.mod_floor(&circuit.prime)).collect();
+                variables(fbb, &wires, Some(&coeffs), size)
+            });
+            let start = fbb.start_table();
+            fbb.push_slot_always(CONSTRAINT_A, a);
+            fbb.push_slot_always(CONSTRAINT_B, b);
+            fbb.push_slot_always(CONSTRAINT_C, c);
+            fbb.end_table(start)
+        }).collect();
+        let constraints = fbb.create_vector(&constraints);
+        let start = fbb.start_table();
+        fbb.push_slot_always(SYSTEM_CONSTRAINTS, constraints);
+        fbb.end_table(start)
+    }));
+
+    if witness.is_some() {
+        let private: Vec<usize> = (num_public + 1..circuit.num_wires).collect();
+        bytes.extend(message(MESSAGE_WITNESS, |fbb| {
+            let assigned = variables(fbb, &private, values(&private).as_deref(), size);
+            let start = fbb.start_table();
+            fbb.push_slot_always(WITNESS_ASSIGNED_VARIABLES, assigned);
+            fbb.end_table(start)
+        }));
+    }
+    Ok(bytes)
+}
+
+/// Decodes zkInterface messages into a constraint system and, if present, its witness.
+///
+/// Variable 0 is the constant one, the instance variables become the leading
+/// public inputs in header order and the remaining variables follow in id
+/// order. `Command` messages are ignored.
+///
+/// # Parameters
+/// - `data`: A sequence of size-prefixed zkInterface messages.
+/// - `policy`: The requirements on the prime, `field_maximum + 1`, of the header.
+///
+/// # Returns
+/// - `Result<(CircomCircuit, Option<Vec<FieldElement>>)>`: The constraint system and the
+///   full witness, starting with the constant one, when the messages assign every variable,
+///   or an error if the prime fails the policy.
+pub fn read_zkif(data: &[u8], policy: &ModulusPolicy) -> Result<(CircomCircuit, Option<Vec<FieldElement>>)> {
+    let mut header = None;
+    let mut constraints = Vec::new();
+    let mut assigned: Option<Assignment> = None;
+
+    let mut position = 0;
+    while position < data.len() {
+        let len = read_u32(data, position)? as usize;
+        let buf = data.get(position + 4..position + 4 + len)
+            .ok_or_else(|| malformed("message extends past the end of the data"))?;
+        position += 4 + len;
+
+        if buf.get(4..8) != Some(&IDENTIFIER[..]) {
+            return Err(malformed("message lacks the zkif file identifier"));
+        }
+        let root = Table::new(buf, read_u32(buf, 0)? as usize)?;
+        let body = match root.table(ROOT_MESSAGE)? {
+            Some(body) => body,
+            None => continue,
+        };
+        match root.u8(ROOT_MESSAGE_TYPE)? {
+            MESSAGE_CIRCUIT_HEADER => header = Some(body),
+            MESSAGE_CONSTRAINT_SYSTEM => constraints.extend(body.tables(SYSTEM_CONSTRAINTS)?),
+            MESSAGE_WITNESS => {
+                let variables = body.table(WITNESS_ASSIGNED_VARIABLES)?.ok_or_else(|| malformed("witness without variables"))?;
+                assigned.get_or_insert_with(Vec::new).extend(read_variables(&variables)?);
+            },
+            _ => {},
+        }
+    }
+
+    let header = header.ok_or_else(|| malformed("no circuit header"))?;
+    let field_maximum = header.bytes(HEADER_FIELD_MAXIMUM)?.ok_or_else(|| malformed("header without field_maximum"))?;
+    let prime = BigInt::from_bytes_le(Sign::Plus, field_maximum) + BigInt::one();
+    policy.check(&prime)?;
+    let free_variable_id = header.u64(HEADER_FREE_VARIABLE_ID)?;
+    if free_variable_id == 0 {
+        return Err(malformed("free_variable_id leaves no room for the constant one"));
+    }
+    let num_wires = usize::try_from(free_variable_id).map_err(|_| malformed("free_variable_id is too large"))?;
+    let instance = match header.table(HEADER_INSTANCE_VARIABLES)? {
+        Some(variables) => read_variables(&variables)?,
+        None => Vec::new(),
+    };
+
+    // Number the wires with the one first, then the instance, then the rest in id order
+    let mut public: HashMap<u64, usize> = HashMap::new();
+    for (id, _) in &instance {
+        if *id == 0 || *id >= free_variable_id || public.insert(*id, public.len() + 1).is_some() {
+            return Err(malformed(&format!("invalid instance variable {}", id)));
+        }
+    }
+    let mut public_ids: Vec<u64> = public.keys().copied().collect();
+    public_ids.sort_unstable();
+    let wire = |id: u64| -> Result<usize> {
+        if id >= free_variable_id {
+            return Err(malformed(&format!("variable {} is not below free_variable_id", id)));
+        }
+        if id == 0 {
+            return Ok(0);
+        }
+        if let Some(index) = public.get(&id) {
+            return Ok(*index);
+        }
+        let public_below = public_ids.partition_point(|public| *public < id);
+        Ok((id as usize - public_below) + public_ids.len())
+    };
+
+    let combination = |constraint: &Table, slot: u16| -> Result<LinearCombination> {
+        match constraint.table(slot)? {
+            Some(variables) => read_variables(&variables)?.into_iter()
+                .map(|(id, coeff)| Ok((wire(id)?, coeff.ok_or_else(|| malformed("constraint term without a coefficient"))?)))
+                .collect(),
+            None => Ok(Vec::new()),
+        }
+    };
+    let constraints = constraints.iter().map(|constraint| Ok(CircomConstraint {
+        a: combination(constraint, CONSTRAINT_A)?,
+        b: combination(constraint, CONSTRAINT_B)?,
+        c: combination(constraint, CONSTRAINT_C)?,
+    })).collect::<Result<Vec<_>>>()?;
+
+    // Assemble the witness only when the header and witness messages carry every value
+    let witness = match assigned {
+        Some(assigned) => {
+            if 1 + instance.len() + assigned.len() != num_wires {
+                return Err(malformed(&format!("{} of {} variables are assigned", 1 + instance.len() + assigned.len(), num_wires)));
+            }
+            let mut values = vec![None; num_wires];
+            values[0] = Some(BigInt::one());
+            for (id, value) in instance.iter().cloned().chain(assigned) {
+                values[wire(id)?] = Some(value.ok_or_else(|| malformed(&format!("variable {} has no value", id)))?);
+            }
+            let witness = values.into_iter().enumerate()
+                .map(|(index, value)| value
+                    .map(|value| FieldElement::new_with_modulus(value, prime.clone()))
+                    .ok_or_else(|| malformed(&format!("wire {} is not assigned", index))))
+                .collect::<Result<Vec<_>>>()?;
+            Some(witness)
+        },
+        None => None,
+    };
+
+    let circuit = CircomCircuit {
+        prime,
+        num_wires,
+        num_public_outputs: 0,
+        num_public_inputs: instance.len(),
+        num_private_inputs: num_wires.saturating_sub(1 + instance.len()),
+        constraints,
+    };
+    Ok((circuit, witness))
+}
+
+/// Represents a variable id with its value, if the message carried one.
+type Assignment = Vec<(u64, Option<BigInt>)>;
+
+/// Builds a size-prefixed `Root` message around a body table.
+fn message<F>(kind: u8, build: F) -> Vec<u8>
+where
+    F: FnOnce(&mut FlatBufferBuilder) -> WIPOffset<TableFinishedWIPOffset>,
+{
+    let mut fbb = FlatBufferBuilder::new();
+    let body = build(&mut fbb);
+    let start = fbb.start_table();
+    fbb.push_slot_always(ROOT_MESSAGE, body);
+    fbb.push_slot::<u8>(ROOT_MESSAGE_TYPE, kind, 0);
+    let root = fbb.end_table(start);
+    fbb.finish_size_prefixed(root, Some("zkif"));
+    fbb.finished_data().to_vec()
+}
+
+/// Builds a `Variables` table, with the values as fixed-size little-endian elements.
+fn variables(fbb: &mut FlatBufferBuilder, wires: &[usize], values: Option<&[BigInt]>, size: usize) -> WIPOffset<TableFinishedWIPOffset> {
+    let ids: Vec<u64> = wires.iter().map(|wire| *wire as u64).collect();
+    let ids = fbb.create_vector(&ids);
+    let values = values.map(|values| {
+        let bytes: Vec<u8> = values.iter().flat_map(|value| little_endian(value, size)).collect();
+        fbb.create_vector(&bytes)
+    });
+    let start = fbb.start_table();
+    fbb.push_slot_always(VARIABLES_IDS, ids);
+    if let Some(values) = values {
+        fbb.push_slot_always(VARIABLES_VALUES, values);
+    }
+    fbb.end_table(start)
+}
+
+/// Reads a `Variables` table into ids and, when present, values.
+fn read_variables(variables: &Table) -> Result<Assignment> {
+    let ids = variables.u64s(VARIABLES_IDS)?;
+    let values = variables.bytes(VARIABLES_VALUES)?.unwrap_or_default();
+    if values.is_empty() {
+        return Ok(ids.into_iter().map(|id| (id, None)).collect());
+    }
+    if ids.is_empty() || values.len() % ids.len() != 0 {
+        return Err(malformed("values are not evenly split between the variables"));
+    }
+    let size = values.len() / ids.len();
+    Ok(ids.into_iter()
+        .zip(values.chunks(size))
+        .map(|(id, value)| (id, Some(BigInt::from_bytes_le(Sign::Plus, value))))
+        .collect())
+}
+
+/// Encodes a non-negative integer as exactly `size` little-endian bytes.
+fn little_endian(value: &BigInt, size: usize) -> Vec<u8> {
+    let mut bytes = value.to_bytes_le().1;
+    bytes.resize(size, 0);
+    bytes
+}
+
+/// Builds the error for malformed zkInterface data.
+fn malformed(reason: &str) -> Error {
+    Error::Serialization(format!("Malformed zkInterface data: {}", reason))
+}
+
+/// Reads a little-endian `u32` at a position, checking bounds.
+fn read_u32(buf: &[u8], position: usize) -> Result<u32> {
+    let bytes = buf.get(position..position + 4).ok_or_else(|| malformed("offset out of bounds"))?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Reads a little-endian `u16` at a position, checking bounds.
+fn read_u16(buf: &[u8], position: usize) -> Result<u16> {
+    let bytes = buf.get(position..position + 2).ok_or_else(|| malformed("offset out of bounds"))?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Represents a FlatBuffers table, read without generated code by following
+/// its vtable and checking every offset against the buffer.
+struct Table<'a> {
+    /// The buffer holding the table.
+    buf: &'a [u8],
+    /// The position of the table.
+    position: usize,
+    /// The position of the table's vtable.
+    vtable: usize,
+}
+
+impl<'a> Table<'a> {
+    /// Opens the table at a position.
+    fn new(buf: &'a [u8], position: usize) -> Result<Self> {
+        let soffset = read_u32(buf, position)? as i32 as i64;
+        let vtable = usize::try_from(position as i64 - soffset).map_err(|_| malformed("vtable out of bounds"))?;
+        read_u16(buf, vtable)?;
+        Ok(Table { buf, position, vtable })
+    }
+
+    /// Gets the position of a field, or `None` if it is absent.
+    fn field(&self, slot: u16) -> Result<Option<usize>> {
+        if slot >= read_u16(self.buf, self.vtable)? {
+            return Ok(None);
+        }
+        match read_u16(self.buf, self.vtable + slot as usize)? {
+            0 => Ok(None),
+            offset => Ok(Some(self.position + offset as usize)),
+        }
+    }
+
+    /// Follows the offset stored in a field to the object it references.
+    fn target(&self, slot: u16) -> Result<Option<usize>> {
+        match self.field(slot)? {
+            Some(position) => Ok(Some(position + read_u32(self.buf, position)? as usize)),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads a `ubyte` field, defaulting to zero.
+    fn u8(&self, slot: u16) -> Result<u8> {
+        match self.field(slot)? {
+            Some(position) => self.buf.get(position).copied().ok_or_else(|| malformed("offset out of bounds")),
+            None => Ok(0),
+        }
+    }
+
+    /// Reads a `uint64` field, defaulting to zero.
+    fn u64(&self, slot: u16) -> Result<u64> {
+        match self.field(slot)? {
+            Some(position) => {
+                let low = read_u32(self.buf, position)? as u64;
+                let high = read_u32(self.buf, position + 4)? as u64;
+                Ok(high << 32 | low)
+            },
+            None => Ok(0),
+        }
+    }
+
+    /// Reads a table field.
+    fn table(&self, slot: u16) -> Result<Option<Table<'a>>> {
+        match self.target(slot)? {
+            Some(position) => Ok(Some(Table::new(self.buf, position)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads a vector field as its element count and the position of its first element.
+    fn vector(&self, slot: u16, element_size: usize) -> Result<Option<(usize, usize)>> {
+        let position = match self.target(slot)? {
+            Some(position) => position,
+            None => return Ok(None),
+        };
+        let len = read_u32(self.buf, position)? as usize;
+        let end = len.checked_mul(element_size).and_then(|size| (position + 4).checked_add(size));
+        if !matches!(end, Some(end) if end <= self.buf.len()) {
+            return Err(malformed("vector extends past the end of the message"));
+        }
+        Ok(Some((len, position + 4)))
+    }
+
+    /// Reads a `[ubyte]` field.
+    fn bytes(&self, slot: u16) -> Result<Option<&'a [u8]>> {
+        Ok(self.vector(slot, 1)?.map(|(len, start)| &self.buf[start..start + len]))
+    }
+
+    /// Reads a `[uint64]` field, empty if absent.
+    fn u64s(&self, slot: u16) -> Result<Vec<u64>> {
+        let (len, start) = self.vector(slot, 8)?.unwrap_or((0, 0));
+        Ok(self.buf[start..start + 8 * len].chunks(8).map(|chunk| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(chunk);
+            u64::from_le_bytes(bytes)
+        }).collect())
+    }
+
+    /// Reads a vector of tables, empty if absent.
+    fn tables(&self, slot: u16) -> Result<Vec<Table<'a>>> {
+        let (len, start) = self.vector(slot, 4)?.unwrap_or((0, 0));
+        (0..len).map(|index| {
+            let position = start + 4 * index;
+            Table::new(self.buf, position + read_u32(self.buf, position)? as usize)
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a circuit without constraints over the given prime.
+    fn empty_circuit(prime: BigInt) -> CircomCircuit {
+        CircomCircuit { prime, num_wires: 2, num_public_outputs: 0, num_public_inputs: 1, num_private_inputs: 0, constraints: Vec::new() }
+    }
+
+    #[test]
+    fn primes_are_checked_against_the_policy() {
+        let bn254: BigInt = "21888242871839275222246405745257275088548364400416034343698204186575808495617".parse().unwrap();
+        let bytes = write_zkif(&empty_circuit(bn254.clone()), None).unwrap();
+        assert_eq!(read_zkif(&bytes, &ModulusPolicy::default()).unwrap().0.prime, bn254);
+
+        let bytes = write_zkif(&empty_circuit(FieldElement::default_modulus()), None).unwrap();
+        assert!(read_zkif(&bytes, &ModulusPolicy::default()).is_err());
+        assert!(read_zkif(&bytes, &ModulusPolicy::toy()).is_ok());
+    }
+
+    #[test]
+    fn zero_and_composite_primes_are_rejected() {
+        for prime in [BigInt::one(), BigInt::from(1_000_000_008)].iter() {
+            let bytes = write_zkif(&empty_circuit(prime.clone()), None).unwrap();
+            assert!(read_zkif(&bytes, &ModulusPolicy::toy()).is_err(), "{} accepted", prime);
+        }
+    }
+}